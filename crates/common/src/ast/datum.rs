@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+use pluine_lex::span::{Span, Spanned};
+use pluine_number::Number;
+use pluine_parser::{DatumGraph, Node, Reader, SimpleDatum};
+
+use crate::*;
+
+/// External representation of a Scheme value, the input of lowering to the AST.
+///
+/// Unlike the data of `pluine-parser` it owns its contents, allowing it to be built by macro
+/// expansion. Abbreviations such as `'a` are read as two element lists.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Datum {
+    /// See [`DatumKind`]
+    pub kind: DatumKind,
+    /// Source region of the datum, abbreviation prefixes included
+    pub span: Span,
+}
+
+/// See [`Datum`]
+#[derive(Debug, PartialEq, Clone)]
+pub enum DatumKind {
+    /// `#t` or `#false`
+    Boolean(bool),
+    /// Number literal value, exact or not
+    Number(Number),
+    /// `#\a`
+    Character(char),
+    /// String with its escapes resolved
+    String(Box<str>),
+    /// Identifier read as a datum
+    Symbol(Box<str>),
+    /// Proper or dotted list
+    List {
+        /// Elements preceding the dot, if any
+        elements: Vec<Datum>,
+        /// Datum following the dot, `None` for proper lists
+        tail: Option<Box<Datum>>,
+    },
+    /// `#( <Datum>* )`
+    Vector(Vec<Datum>),
+    /// `#u8( <Byte>* )`
+    ByteVector(Vec<u8>),
+}
+
+impl Datum {
+    /// Symbol name, if the datum is a symbol
+    pub fn as_symbol(&self) -> Option<&str> {
+        match &self.kind {
+            DatumKind::Symbol(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Elements of a proper list, `None` for any other datum.
+    pub fn as_list(&self) -> Option<&[Datum]> {
+        match &self.kind {
+            DatumKind::List { elements, tail: None } => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+impl Datum {
+    /// Reads `src`, which must consist of a single datum.
+    pub fn parse_str(src: &str) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(src);
+        let datum = reader.next().ok_or(ParseError::UnexpectedEnd)??;
+
+        match reader.next().transpose()? {
+            Some(unexpected) => Err(ParseError::UnexpectedDatum(unexpected.span())),
+            None => Datum::from_graph(&DatumGraph::new(src, datum)),
+        }
+    }
+
+    /// Reads every datum of `src`.
+    pub fn parse_str_all(src: &str) -> Result<Vec<Self>, ParseError> {
+        Reader::new(src)
+            .map(|datum| Datum::from_graph(&DatumGraph::new(src, datum?)))
+            .collect()
+    }
+
+    /// Owned copy of a datum read by `pluine-parser`, number literals converted to their value.
+    ///
+    /// Data shared through labels are copied, whereas circular ones are rejected.
+    pub fn from_graph(graph: &DatumGraph) -> Result<Self, ParseError> {
+        // Explicit stacks rather than recursion, nodes being visited before their elements are
+        // built and then themselves built from the data built last.
+        let mut pending = vec![(graph.root(), false)];
+        let mut path = HashSet::new();
+        let mut built = Vec::new();
+
+        while let Some((id, visited)) = pending.pop() {
+            let span = graph.span(id);
+
+            let kind = match (graph.node(id), visited) {
+                (Node::Simple(simple_datum), _) => simple(simple_datum)?,
+                (_, false) => {
+                    if !path.insert(id) {
+                        return Err(ParseError::CircularDatum(span));
+                    }
+
+                    pending.push((id, true));
+                    pending.extend(graph.children(id).rev().map(|element| (element, false)));
+                    continue;
+                }
+                (Node::List { elements, tail }, true) => {
+                    path.remove(&id);
+                    let tail = tail.map(|_| Box::new(built.pop().expect("tail built last")));
+                    DatumKind::List { elements: built.split_off(built.len() - elements.len()), tail }
+                }
+                (Node::Vector { elements }, true) => {
+                    path.remove(&id);
+                    DatumKind::Vector(built.split_off(built.len() - elements.len()))
+                }
+            };
+
+            built.push(Datum { kind, span });
+        }
+
+        Ok(built.pop().expect("root built last"))
+    }
+}
+
+fn simple(simple_datum: &SimpleDatum) -> Result<DatumKind, ParseError> {
+    Ok(match simple_datum {
+        SimpleDatum::Boolean(boolean) => DatumKind::Boolean(boolean.value()),
+        SimpleDatum::Number(number) => {
+            DatumKind::Number(Number::try_from(number).map_err(|error| ParseError::InvalidNumber(error, number.span()))?)
+        }
+        SimpleDatum::Character(character) => DatumKind::Character(character.value()),
+        SimpleDatum::String(string) => DatumKind::String(string.value().into()),
+        SimpleDatum::Symbol(symbol) => DatumKind::Symbol(symbol.name().into()),
+        SimpleDatum::ByteVector(byte_vector) => DatumKind::ByteVector(byte_vector.to_vec()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pluine_number::NumberError;
+    use pluine_parser::ReadError;
+
+    use super::*;
+
+    /// Writes the datum back with single spaces, strings debug formatted.
+    fn write(datum: &Datum) -> String {
+        let write_all = |data: &[Datum]| data.iter().map(write).collect::<Vec<_>>().join(" ");
+
+        match &datum.kind {
+            DatumKind::Boolean(value) => if *value { "#t" } else { "#f" }.to_string(),
+            DatumKind::Number(number) => number.to_string(),
+            DatumKind::Character(char) => format!("{char:?}"),
+            DatumKind::String(string) => format!("{string:?}"),
+            DatumKind::Symbol(name) => name.to_string(),
+            DatumKind::List { elements, tail: Some(tail) } => format!("({} . {})", write_all(elements), write(tail)),
+            DatumKind::List { elements, tail: None } => format!("({})", write_all(elements)),
+            DatumKind::Vector(elements) => format!("#({})", write_all(elements)),
+            DatumKind::ByteVector(bytes) => format!("#u8{bytes:?}"),
+        }
+    }
+
+    fn read(src: &str) -> String {
+        write(&Datum::parse_str(src).unwrap())
+    }
+
+    #[test]
+    fn simple_data() {
+        assert_eq!("(#t 1 'a' \"b\" c)", read(r#"(#true 1 #\a "b" |c|)"#));
+    }
+
+    #[test]
+    fn compound_data() {
+        assert_eq!("(a (b . c) #(1) #u8[0, 255])", read("(a (b . c) #(#d1) #u8(0 #xFF))"));
+    }
+
+    #[test]
+    fn abbreviations() {
+        assert_eq!("(quote (quasiquote (unquote (unquote-splicing a))))", read("'`,,@a"));
+    }
+
+    #[test]
+    fn datum_span() {
+        let datum = Datum::parse_str(" '(a)").unwrap();
+        assert_eq!((1, 5), (datum.span.start(), datum.span.end()));
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!("((a) (a))", read("(#0=(a) #0#)"));
+        assert_eq!("#(1 1)", read("#(#0=1 #0#)"));
+    }
+
+    #[test]
+    fn deeply_nested_data() {
+        let src = format!("{}a", "'".repeat(10_000));
+        assert!(Datum::parse_str(&src).is_ok());
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(
+            Datum::parse_str("(. a)"),
+            Err(ParseError::Read(ReadError::UnexpectedDot(_)))
+        ));
+        assert!(matches!(
+            Datum::parse_str("#u8(256)"),
+            Err(ParseError::Read(ReadError::ByteOutOfRange(_)))
+        ));
+        assert!(matches!(
+            Datum::parse_str(")"),
+            Err(ParseError::Read(ReadError::UnexpectedCloseParenthesis(_)))
+        ));
+        assert!(matches!(Datum::parse_str("a b"), Err(ParseError::UnexpectedDatum(span)) if span.start() == 2));
+        assert_eq!(Err(ParseError::UnexpectedEnd), Datum::parse_str("#;a"));
+        // unlike `read`, program source can't hold circular data
+        assert!(matches!(Datum::parse_str("#0=(a . #0#)"), Err(ParseError::CircularDatum(span)) if span.start() == 3));
+        assert!(matches!(Datum::parse_str("(b #0=#(#0#))"), Err(ParseError::CircularDatum(span)) if span.start() == 6));
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        assert!(matches!(
+            Datum::parse_str("#e+inf.0"),
+            Err(ParseError::InvalidNumber(NumberError::NotRational, _))
+        ));
+        assert!(
+            matches!(Datum::parse_str("(#e1e100000000)"), Err(ParseError::InvalidNumber(NumberError::Overflow, span)) if span.start() == 1)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// Top-level element of a program or body
+#[derive(Debug, PartialEq, Clone)]
+pub enum Form {
+    /// See [`Definition`]
+    Definition(Definition),
+    /// See [`Expression`]
+    Expression(Expression),
+}
+
+impl Form {
+    /// Source region of the form
+    pub fn span(&self) -> Span {
+        match self {
+            Form::Definition(definition) => definition.span(),
+            Form::Expression(expression) => expression.span(),
+        }
+    }
+}
+
+/// EBNF: `<definition>`, see R7RS 5.3 to 5.6
+#[derive(Debug, PartialEq, Clone)]
+pub enum Definition {
+    /// See [`VariableDefinition`]
+    Variable(VariableDefinition),
+    /// See [`ValuesDefinition`]
+    Values(ValuesDefinition),
+    /// See [`RecordType`]
+    RecordType(RecordType),
+    /// See [`SyntaxDefinition`]
+    Syntax(SyntaxDefinition),
+    /// `(begin <definition>*)`
+    Begin {
+        /// Spliced into the enclosing body or program
+        definitions: Vec<Definition>,
+        /// From the opening to the closing parenthesis
+        span: Span,
+    },
+}
+
+impl Definition {
+    /// Source region of the definition
+    pub fn span(&self) -> Span {
+        match self {
+            Definition::Variable(definition) => definition.span,
+            Definition::Values(definition) => definition.span,
+            Definition::RecordType(record_type) => record_type.span,
+            Definition::Syntax(definition) => definition.span,
+            Definition::Begin { span, .. } => *span,
+        }
+    }
+}
+
+/// EBNF: `(define <identifier> <expression>) | (define (<identifier> <def formals>) <body>)`
+///
+/// The procedure shorthand is lowered to a [`Lambda`] value.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariableDefinition {
+    /// Defined variable
+    pub variable: Identifier,
+    /// Initial value
+    pub value: Box<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(define-values <formals> <expression>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ValuesDefinition {
+    /// Bound to the returned values
+    pub formals: Formals,
+    /// Expression returning the values
+    pub value: Box<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(define-record-type <identifier> <constructor>? <identifier> <field spec>*)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordType {
+    /// Bound to the record type descriptor
+    pub name: Identifier,
+    /// See [`RecordConstructor`], absent when written as `#f`
+    pub constructor: Option<RecordConstructor>,
+    /// Type predicate name
+    pub predicate: Identifier,
+    /// See [`RecordField`]
+    pub fields: Vec<RecordField>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<identifier> <field name>*) | <identifier>`
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordConstructor {
+    /// Constructor procedure name
+    pub name: Identifier,
+    /// Fields initialized by the arguments, all fields if the constructor is a bare identifier
+    pub fields: Vec<Identifier>,
+}
+
+/// EBNF: `(<field name> <accessor>) | (<field name> <accessor> <modifier>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct RecordField {
+    /// Field name
+    pub name: Identifier,
+    /// Accessor procedure name
+    pub accessor: Identifier,
+    /// Modifier procedure name
+    pub modifier: Option<Identifier>,
+}
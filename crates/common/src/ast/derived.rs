@@ -0,0 +1,226 @@
+//! Derived expressions of R7RS 4.2
+
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// EBNF: `(cond <cond clause>+) | (cond <cond clause>* (else <sequence>))`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cond {
+    /// See [`CondClause`]
+    pub clauses: Vec<CondClause>,
+    /// `else` clause expressions
+    pub else_clause: Option<Vec<Expression>>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<test> <sequence>) | (<test>) | (<test> => <recipient>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct CondClause {
+    /// Clause is selected when true
+    pub test: Expression,
+    /// See [`ClauseBody`]
+    pub body: ClauseBody,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// Consequent of a `cond`, `case` or `guard` clause
+#[derive(Debug, PartialEq, Clone)]
+pub enum ClauseBody {
+    /// Expressions evaluated in order, empty for `(<test>)` which yields the test value
+    Sequence(Vec<Expression>),
+    /// `=> <recipient>`, the procedure being called with the test value or key
+    Arrow(Box<Expression>),
+}
+
+/// EBNF: `(case <expression> <case clause>+ <else clause>?)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Case {
+    /// Compared to the clause data with `eqv?`
+    pub key: Box<Expression>,
+    /// See [`CaseClause`]
+    pub clauses: Vec<CaseClause>,
+    /// `(else <sequence>)` or `(else => <recipient>)`
+    pub else_clause: Option<ClauseBody>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `((<datum>*) <sequence>) | ((<datum>*) => <recipient>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct CaseClause {
+    /// Data compared to the key
+    pub data: Vec<Datum>,
+    /// See [`ClauseBody`], never an empty sequence
+    pub body: ClauseBody,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(when <test> <sequence>)`, also used by `unless`
+#[derive(Debug, PartialEq, Clone)]
+pub struct When {
+    /// Condition of the evaluation
+    pub test: Box<Expression>,
+    /// Evaluated when the test is true for `when`, false for `unless`
+    pub body: Vec<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// Binding keyword of a [`Let`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LetKind {
+    /// `let`, possibly named
+    Let,
+    /// `let*`
+    LetStar,
+    /// `letrec`
+    Letrec,
+    /// `letrec*`
+    LetrecStar,
+}
+
+/// EBNF: `(<let keyword> <identifier>? (<binding spec>*) <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Let {
+    /// See [`LetKind`]
+    pub kind: LetKind,
+    /// Name of a named `let`, bound to the body as a procedure
+    pub name: Option<Identifier>,
+    /// See [`Binding`]
+    pub bindings: Vec<Binding>,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<identifier> <expression>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Binding {
+    /// Bound variable
+    pub variable: Identifier,
+    /// Initial value
+    pub init: Expression,
+}
+
+/// EBNF: `(let-values (<mv binding spec>*) <body>) | (let*-values (<mv binding spec>*) <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct LetValues {
+    /// Whether each binding is in scope of the following ones, as with `let*-values`
+    pub sequential: bool,
+    /// `(<formals> <expression>)`, the formals being bound to the returned values
+    pub bindings: Vec<(Formals, Expression)>,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(do (<iteration spec>*) (<test> <do result>) <command>*)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Do {
+    /// See [`Iteration`]
+    pub iterations: Vec<Iteration>,
+    /// Ends the loop when true
+    pub test: Box<Expression>,
+    /// Evaluated once the loop ends, unspecified value if empty
+    pub result: Vec<Expression>,
+    /// Evaluated at each step
+    pub commands: Vec<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<identifier> <init> <step>?)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Iteration {
+    /// Loop variable
+    pub variable: Identifier,
+    /// Initial value
+    pub init: Expression,
+    /// Next value, the variable being left unchanged if absent
+    pub step: Option<Expression>,
+}
+
+/// EBNF: `(delay <expression>) | (delay-force <expression>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Delay {
+    /// Evaluated when the promise is forced
+    pub expression: Box<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(parameterize ((<expression> <expression>)*) <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameterize {
+    /// Parameter objects along with their new value
+    pub bindings: Vec<(Expression, Expression)>,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(guard (<identifier> <cond clause>*) <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Guard {
+    /// Bound to the raised object within the clauses
+    pub variable: Identifier,
+    /// See [`CondClause`]
+    pub clauses: Vec<CondClause>,
+    /// `else` clause expressions, the object being re-raised if absent and no clause matches
+    pub else_clause: Option<Vec<Expression>>,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `` `<qq template 1> | (quasiquote <qq template 1>) ``
+#[derive(Debug, PartialEq, Clone)]
+pub struct Quasiquote {
+    /// See [`Template`]
+    pub template: Template,
+    /// Source region of the quasiquotation, prefix or `quasiquote` form included
+    pub span: Span,
+}
+
+/// Quasiquotation template, nested quasiquotations being kept as data with their own unquotations
+/// resolved to the matching depth.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Template {
+    /// Datum without unquotation at the outermost depth, quoted as is
+    Datum(Datum),
+    /// `,<expression>` at the outermost depth
+    Unquote(Box<Expression>),
+    /// List template
+    List {
+        /// See [`TemplateElement`]
+        elements: Vec<TemplateElement>,
+        /// Template following the dot, if any
+        tail: Option<Box<Template>>,
+        /// From the opening to the closing parenthesis
+        span: Span,
+    },
+    /// Vector template
+    Vector {
+        /// See [`TemplateElement`]
+        elements: Vec<TemplateElement>,
+        /// From the opening to the closing parenthesis
+        span: Span,
+    },
+}
+
+/// Element of a list or vector [`Template`]
+#[derive(Debug, PartialEq, Clone)]
+pub enum TemplateElement {
+    /// Single element
+    Template(Template),
+    /// `,@<expression>` at the outermost depth, spliced into the enclosing list or vector
+    Splice(Expression),
+}
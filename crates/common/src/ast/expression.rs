@@ -1,84 +1,231 @@
 use std::str::FromStr;
 
-use chumsky::prelude::*;
-use text::TextParser;
+use pluine_lex::span::Span;
 
 use crate::*;
 
-#[derive(Debug, PartialEq)]
-pub enum Value {
-    Atom(Atom),
-    Expression(Expression),
+/// EBNF: `<expression>`, see R7RS 7.1.3
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    /// Variable reference
+    Variable(Identifier),
+    /// See [`Literal`]
+    Literal(Literal),
+    /// See [`Call`]
+    Call(Call),
+    /// See [`Lambda`]
+    Lambda(Lambda),
+    /// See [`If`]
+    If(If),
+    /// See [`Set`]
+    Set(Set),
+    /// See [`Include`]
+    Include(Include),
+    /// See [`Cond`]
+    Cond(Cond),
+    /// See [`Case`]
+    Case(Case),
+    /// `(and <test>*)`
+    And(Sequence),
+    /// `(or <test>*)`
+    Or(Sequence),
+    /// `(when <test> <expression>+)`
+    When(When),
+    /// `(unless <test> <expression>+)`
+    Unless(When),
+    /// See [`Let`]
+    Let(Let),
+    /// See [`LetValues`]
+    LetValues(LetValues),
+    /// `(begin <expression>+)`
+    Begin(Sequence),
+    /// See [`Do`]
+    Do(Do),
+    /// `(delay <expression>)`
+    Delay(Delay),
+    /// `(delay-force <expression>)`
+    DelayForce(Delay),
+    /// See [`Parameterize`]
+    Parameterize(Parameterize),
+    /// See [`Guard`]
+    Guard(Guard),
+    /// See [`Quasiquote`]
+    Quasiquote(Quasiquote),
+    /// See [`CaseLambda`]
+    CaseLambda(CaseLambda),
+    /// See [`LetSyntax`]
+    LetSyntax(LetSyntax),
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Expression {
-    pub symbol: Box<str>,
-    pub list: Box<[Value]>,
+impl Expression {
+    /// Source region of the expression
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Variable(identifier) => identifier.span,
+            Expression::Literal(literal) => literal.span(),
+            Expression::Call(call) => call.span,
+            Expression::Lambda(lambda) => lambda.span,
+            Expression::If(if_expression) => if_expression.span,
+            Expression::Set(set) => set.span,
+            Expression::Include(include) => include.span,
+            Expression::Cond(cond) => cond.span,
+            Expression::Case(case) => case.span,
+            Expression::And(sequence) | Expression::Or(sequence) | Expression::Begin(sequence) => sequence.span,
+            Expression::When(when) | Expression::Unless(when) => when.span,
+            Expression::Let(let_expression) => let_expression.span,
+            Expression::LetValues(let_values) => let_values.span,
+            Expression::Do(do_expression) => do_expression.span,
+            Expression::Delay(delay) | Expression::DelayForce(delay) => delay.span,
+            Expression::Parameterize(parameterize) => parameterize.span,
+            Expression::Guard(guard) => guard.span,
+            Expression::Quasiquote(quasiquote) => quasiquote.span,
+            Expression::CaseLambda(case_lambda) => case_lambda.span,
+            Expression::LetSyntax(let_syntax) => let_syntax.span,
+        }
+    }
 }
 
 // TODO: deprecate?
 impl FromStr for Expression {
-    type Err = Vec<Simple<char>>;
+    type Err = ParseError;
 
+    /// Reads a single datum and lowers it to an expression.
     fn from_str(source_code_string: &str) -> Result<Self, Self::Err> {
-        Expression::parser().parse(source_code_string)
+        let datum = Datum::parse_str(source_code_string)?;
+        Ok(Expression::from_datum(&datum)?)
     }
 }
 
-impl RuineParser for Expression {
-    fn parser() -> impl Parser<char, Self, Error = Simple<char>> {
-        let expression = recursive(|expression| {
-            // XXX: check scheme spec for which symbol values to allow
-            let symbol = text::ident().or(just('+').to('+'.to_string())).padded();
+/// EBNF: `(<operator> <operand>*)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Call {
+    /// Any expression, not only identifiers
+    pub operator: Box<Expression>,
+    /// Possibly empty arguments
+    pub operands: Vec<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
 
-            let nested_expression = expression.delimited_by(just('('), just(')')).map(Value::Expression);
-            let atom = || Atom::parser().map(Value::Atom);
+/// EBNF: `(if <test> <consequent> <alternate>?)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct If {
+    /// Condition, only `#f` being false
+    pub test: Box<Expression>,
+    /// Evaluated when the test is true
+    pub consequent: Box<Expression>,
+    /// Evaluated when the test is false, unspecified value if absent
+    pub alternate: Option<Box<Expression>>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
 
-            let list = nested_expression.or(atom()).padded().repeated().at_least(1);
+/// EBNF: `(set! <identifier> <expression>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Set {
+    /// Variable being assigned
+    pub variable: Identifier,
+    /// New value
+    pub value: Box<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
 
-            symbol
-                .then(list)
-                .map(|(symbol, list)| Expression { symbol: symbol.into(), list: list.into_boxed_slice() })
-        })
-        .delimited_by(just('('), just(')'));
+/// EBNF: `(include <string>+) | (include-ci <string>+)`
+///
+/// Files are left unread, their resolution being up to the consumer of the AST.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Include {
+    /// Whether the included files are read as if prefixed by `#!fold-case`
+    pub case_insensitive: bool,
+    /// File names, along with the span of their string literal
+    pub files: Vec<(Box<str>, Span)>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
 
-        expression.padded().then_ignore(end())
-    }
+/// Keyword followed by expressions, such as `(begin <expression>+)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Sequence {
+    /// Expressions in evaluation order
+    pub expressions: Vec<Expression>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
 }
 
 #[cfg(test)]
 mod tests {
+    use pluine_number::Number;
+    use pluine_parser::ReadError;
+
     use super::*;
 
+    /// Expression stripped of its spans, so that its shape can be compared
+    #[derive(Debug, PartialEq)]
+    enum Node {
+        Variable(Box<str>),
+        Number(Number),
+        String(Box<str>),
+        Call(Box<Node>, Vec<Node>),
+    }
+
+    impl From<&Expression> for Node {
+        fn from(expression: &Expression) -> Self {
+            match expression {
+                Expression::Variable(identifier) => Node::Variable(identifier.name.clone()),
+                Expression::Literal(Literal::Number { value, .. }) => Node::Number(value.clone()),
+                Expression::Literal(Literal::SelfEvaluating(Datum { kind: DatumKind::String(value), .. })) => Node::String(value.clone()),
+                Expression::Call(call) => Node::Call(Box::new((&*call.operator).into()), call.operands.iter().map(Node::from).collect()),
+                other => panic!("unexpected expression {other:?}"),
+            }
+        }
+    }
+
     mod value {
         use super::*;
 
         #[test]
         fn mixed_literals() {
-            assert_expression(
-                literal_expression([Literal::String("a".into()), Literal::Integer(Integer::Unsigned(1))]),
-                r#"(+ "a" 1)"#,
-            );
+            assert_expression(simple_expression([Node::String("a".into()), integer(1)]), r#"(+ "a" 1)"#);
         }
 
         #[test]
         fn nested_expressions() {
             assert_expression(
-                nested_expression([unsigned_expression([1, 2]), unsigned_expression([3, 4])]),
+                simple_expression([integer_expression([1, 2]), integer_expression([3, 4])]),
                 "(+ (+ 1 2) (+ 3 4))",
             );
         }
 
         #[test]
         fn expression_and_literal() {
-            assert_expression(
-                simple_expression([
-                    Value::Expression(unsigned_expression([1, 2])),
-                    Value::Atom(Atom::Literal(Literal::Integer(Integer::Unsigned(3)))),
-                ]),
-                "(+ (+ 1 2) 3)",
-            );
+            assert_expression(simple_expression([integer_expression([1, 2]), integer(3)]), "(+ (+ 1 2) 3)");
+        }
+
+        #[test]
+        fn signed_integers() {
+            assert_expression(integer_expression([-1, 2, 0]), "(+ -1 +2 -0)");
+        }
+
+        #[test]
+        fn lexer_identifiers() {
+            assert_expression(call(variable("string-append"), [Node::String("a".into())]), "(string-append \"a\")");
+            assert_expression(call(variable("a b"), [integer(1)]), "(|a b| 1)");
+        }
+
+        #[test]
+        fn zero_argument_call() {
+            assert_expression(simple_expression([]), "(+)");
+        }
+
+        #[test]
+        fn non_identifier_operator() {
+            assert_expression(call(call(variable("f"), []), [integer(1)]), "((f) 1)");
+        }
+
+        #[test]
+        fn variable_reference() {
+            assert_expression(variable("a"), " a ");
         }
     }
 
@@ -114,8 +261,33 @@ mod tests {
             assert_normalized("(+ 1 2)\n");
         }
 
+        #[test]
+        fn comments() {
+            assert_normalized("; comment\n(+ 1 #| 3 |# 2)");
+            assert_normalized("(+ 1 #;(+ 3 4) 2)");
+        }
+
         fn assert_normalized(expression_string: &str) {
-            assert_expression(unsigned_expression([1, 2]), expression_string);
+            assert_expression(integer_expression([1, 2]), expression_string);
+        }
+    }
+
+    mod spans {
+        use super::*;
+
+        #[test]
+        fn expression_spans() {
+            let Ok(Expression::Call(call)) = " (+ (+ 1 2) \"a\")".parse() else {
+                panic!("expected a call");
+            };
+            assert_eq!((1, 16), (call.span.start(), call.span.end()));
+
+            let spans = call
+                .operands
+                .iter()
+                .map(|operand| (operand.span().start(), operand.span().end()))
+                .collect::<Vec<_>>();
+            assert_eq!(vec![(4, 11), (12, 15)], spans);
         }
     }
 
@@ -128,8 +300,22 @@ mod tests {
         }
 
         #[test]
-        fn missing_list() {
-            assert_error("(+)");
+        fn empty_combination() {
+            assert_error("()");
+        }
+
+        #[test]
+        fn error_locations() {
+            let Err(ParseError::UnexpectedDatum(span)) = "(+ 1 2) a".parse::<Expression>() else {
+                panic!("expected an unexpected datum error");
+            };
+            assert_eq!((8, 9), (span.start(), span.end()));
+
+            assert_eq!(Err(ParseError::UnexpectedEnd), "; comment".parse::<Expression>());
+            assert!(matches!(
+                "(+ 1".parse::<Expression>(),
+                Err(ParseError::Read(ReadError::UnclosedList(_)))
+            ));
         }
 
         fn assert_error(expression_string: &str) {
@@ -137,35 +323,28 @@ mod tests {
         }
     }
 
-    fn assert_expression(expected_expression: Expression, expression_string: &str) {
-        let expression = expression_string.parse().unwrap();
-        assert_eq!(expected_expression, expression);
-    }
-
-    fn simple_expression(values: impl IntoIterator<Item = Value>) -> Expression {
-        Expression {
-            symbol: "+".into(),
-            list: values.into_iter().collect::<Vec<_>>().into_boxed_slice(),
-        }
+    fn assert_expression(expected_expression: Node, expression_string: &str) {
+        let expression: Expression = expression_string.parse().unwrap();
+        assert_eq!(expected_expression, Node::from(&expression));
     }
 
-    fn nested_expression(expressions: impl IntoIterator<Item = Expression>) -> Expression {
-        simple_expression(expressions.into_iter().map(Value::Expression))
+    fn variable(name: &str) -> Node {
+        Node::Variable(name.into())
     }
 
-    fn atomic_expression(atoms: impl IntoIterator<Item = Atom>) -> Expression {
-        simple_expression(atoms.into_iter().map(Value::Atom))
+    fn integer(number: i64) -> Node {
+        Node::Number(number.into())
     }
 
-    fn literal_expression(literals: impl IntoIterator<Item = Literal>) -> Expression {
-        atomic_expression(literals.into_iter().map(Atom::Literal))
+    fn call(operator: Node, operands: impl IntoIterator<Item = Node>) -> Node {
+        Node::Call(Box::new(operator), operands.into_iter().collect())
     }
 
-    fn integer_expression(numbers: impl IntoIterator<Item = Integer>) -> Expression {
-        literal_expression(numbers.into_iter().map(Literal::Integer))
+    fn simple_expression(values: impl IntoIterator<Item = Node>) -> Node {
+        call(variable("+"), values)
     }
 
-    fn unsigned_expression(numbers: impl IntoIterator<Item = u64>) -> Expression {
-        integer_expression(numbers.into_iter().map(Integer::Unsigned))
+    fn integer_expression(numbers: impl IntoIterator<Item = i64>) -> Node {
+        simple_expression(numbers.into_iter().map(integer))
     }
 }
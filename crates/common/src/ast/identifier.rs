@@ -0,0 +1,10 @@
+use pluine_lex::span::Span;
+
+/// Variable name or syntactic keyword
+#[derive(Debug, PartialEq, Clone)]
+pub struct Identifier {
+    /// Name with vertical line escapes resolved
+    pub name: Box<str>,
+    /// Source region of the identifier
+    pub span: Span,
+}
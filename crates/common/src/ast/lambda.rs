@@ -0,0 +1,46 @@
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// EBNF: `(lambda <formals> <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lambda {
+    /// See [`Formals`]
+    pub formals: Formals,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<identifier>*) | <identifier> | (<identifier>+ . <identifier>)`
+///
+/// Optional arguments are collected by the rest parameter, a bare `<identifier>` having no
+/// required parameters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Formals {
+    /// Parameters bound to the first arguments
+    pub required: Vec<Identifier>,
+    /// Parameter bound to a list of the remaining arguments
+    pub rest: Option<Identifier>,
+    /// Source region of the formals
+    pub span: Span,
+}
+
+/// EBNF: `<definition>* <expression>+`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Body {
+    /// Internal definitions, all preceding the expressions
+    pub definitions: Vec<Definition>,
+    /// Evaluated in order, the last one in tail position
+    pub expressions: Vec<Expression>,
+}
+
+/// EBNF: `(case-lambda (<formals> <body>)*)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct CaseLambda {
+    /// Clauses, the first accepting the argument count being selected
+    pub clauses: Vec<Lambda>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
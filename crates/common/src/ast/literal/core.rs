@@ -1,34 +1,98 @@
-use chumsky::prelude::*;
+use pluine_lex::span::Span;
+use pluine_number::Number;
 
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+/// EBNF: `<self-evaluating> | <quotation>`
+#[derive(Debug, PartialEq, Clone)]
 pub enum Literal {
-    Integer(Integer),
-    String(Box<str>),
-    // TODO: boolean?
+    /// Number of any exactness, see [`Number`]
+    Number {
+        /// Value of the number literal
+        value: Number,
+        /// Source region of the number literal
+        span: Span,
+    },
+    /// Boolean, character, string, vector or bytevector
+    SelfEvaluating(Datum),
+    /// See [`Quotation`]
+    Quotation(Quotation),
 }
 
-impl RuineParser for Literal {
-    fn parser() -> impl Parser<char, Self, Error = chumsky::prelude::Simple<char>> {
-        // IMPROVEMENT: separate to a `StringLiteral`?
-        let escape = just('\\').ignore_then(one_of("\\\""));
-
-        let string_literal = none_of("\\\"")
-            .or(escape)
-            .repeated()
-            .collect()
-            .delimited_by(just('"'), just('"'))
-            .map(Literal::String);
-
-        Integer::parser().map(Literal::Integer).or(string_literal)
+impl Literal {
+    /// Source region of the literal
+    pub fn span(&self) -> Span {
+        match self {
+            Literal::Number { span, .. } => *span,
+            Literal::SelfEvaluating(datum) => datum.span,
+            Literal::Quotation(quotation) => quotation.span,
+        }
     }
 }
 
+/// EBNF: `'<datum> | (quote <datum>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct Quotation {
+    /// Quoted datum
+    pub datum: Datum,
+    /// Source region of the quotation, prefix or `quote` form included
+    pub span: Span,
+}
+
 #[cfg(test)]
 mod tests {
+    use pluine_parser::ReadError;
+
     use super::*;
 
+    fn number(src: &str) -> Option<String> {
+        match src.parse::<Expression>() {
+            Ok(Expression::Literal(Literal::Number { value, .. })) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn integer_parsing() {
+        assert_eq!(Some("1".to_string()), number("01"));
+        assert_eq!(Some("0".to_string()), number("-0"));
+        assert_eq!(Some("-10".to_string()), number("-10"));
+        assert_eq!(Some("1".to_string()), number("+1"));
+        assert_eq!(Some("31".to_string()), number("#x1F"));
+        assert_eq!(Some("-5".to_string()), number("#b-101"));
+        assert_eq!(Some("100".to_string()), number("#e1e2"));
+    }
+
+    #[test]
+    fn integers_of_any_size() {
+        assert_eq!(
+            Some("170141183460469231731687303715884105728".to_string()),
+            number("170141183460469231731687303715884105728")
+        );
+    }
+
+    #[test]
+    fn exact_rationals_and_inexact_numbers() {
+        assert_eq!(Some("2".to_string()), number("4/2"));
+        assert_eq!(Some("1/3".to_string()), number("2/6"));
+        assert_eq!(Some("2.0".to_string()), number("2.0"));
+        assert_eq!(Some("1+2i".to_string()), number("1+2i"));
+    }
+
+    #[test]
+    fn minus_character_not_repeatable() {
+        // `--1` is an identifier
+        assert_eq!(None, number("--1"));
+    }
+
+    #[test]
+    fn number_span() {
+        let Ok(Expression::Literal(literal)) = " 42".parse::<Expression>() else {
+            panic!("expected a literal");
+        };
+        assert_eq!((1, 3), (literal.span().start(), literal.span().end()));
+    }
+
     #[test]
     fn string_literal_parsing() {
         assert_string_literal("a", "\"a\"");
@@ -39,17 +103,43 @@ mod tests {
         assert_string_literal("\"test\"", r#""\"test\"""#);
         assert_string_literal("\\", r#""\\""#);
         assert_string_literal("\"", r#""\"""#);
+        assert_string_literal("\u{7}\n", r#""\a\n""#);
+        assert_string_literal("λ", r#""\x3BB;""#);
     }
 
     #[test]
     fn escape_character_must_be_known() {
-        assert!(Literal::parser().parse("\\a").is_err());
+        assert!(matches!(
+            r#""\q""#.parse::<Expression>(),
+            Err(ParseError::Read(ReadError::Tokenize(_)))
+        ));
+    }
+
+    #[test]
+    fn string_literal_span() {
+        let Ok(Expression::Literal(literal)) = "\"a\" ".parse::<Expression>() else {
+            panic!("expected a literal");
+        };
+        assert_eq!((0, 3), (literal.span().start(), literal.span().end()));
+    }
+
+    #[test]
+    fn quotations() {
+        for src in ["'(a b)", "(quote (a b))"] {
+            let Ok(Expression::Literal(Literal::Quotation(quotation))) = src.parse::<Expression>() else {
+                panic!("expected a quotation");
+            };
+            assert_eq!(2, quotation.datum.as_list().unwrap().len());
+            assert_eq!((0, src.len()), (quotation.span.start(), quotation.span.end()));
+        }
     }
 
     fn assert_string_literal(expected_str: &str, string_literal: &str) {
-        assert_eq!(
-            Literal::String(expected_str.into()),
-            Literal::parser().parse(string_literal).unwrap()
-        );
+        let Ok(Expression::Literal(Literal::SelfEvaluating(Datum { kind: DatumKind::String(value), .. }))) =
+            string_literal.parse::<Expression>()
+        else {
+            panic!("expected a string literal");
+        };
+        assert_eq!(expected_str, &*value);
     }
 }
@@ -1,5 +1,2 @@
-mod integer;
-pub(crate) use core::Literal;
-
 mod core;
-pub(crate) use integer::Integer;
+pub use core::{Literal, Quotation};
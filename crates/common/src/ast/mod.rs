@@ -1,8 +1,23 @@
-mod expression;
-pub(crate) use expression::{Expression, Value};
+mod datum;
+pub use datum::{Datum, DatumKind};
 
-mod atom;
-pub(crate) use atom::Atom;
+mod identifier;
+pub use identifier::Identifier;
 
 mod literal;
-pub(crate) use literal::*;
+pub use literal::*;
+
+mod expression;
+pub use expression::{Call, Expression, If, Include, Sequence, Set};
+
+mod lambda;
+pub use lambda::{Body, CaseLambda, Formals, Lambda};
+
+mod derived;
+pub use derived::*;
+
+mod definition;
+pub use definition::{Definition, Form, RecordConstructor, RecordField, RecordType, ValuesDefinition, VariableDefinition};
+
+mod syntax;
+pub use syntax::{LetSyntax, SyntaxDefinition, SyntaxRule, SyntaxRules};
@@ -0,0 +1,51 @@
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// EBNF: `(define-syntax <keyword> <transformer spec>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxDefinition {
+    /// Defined keyword
+    pub keyword: Identifier,
+    /// See [`SyntaxRules`]
+    pub transformer: SyntaxRules,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(syntax-rules <ellipsis>? (<literal>*) <syntax rule>*)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxRules {
+    /// Custom ellipsis identifier, `...` being used if absent
+    pub ellipsis: Option<Identifier>,
+    /// Identifiers matched literally by the patterns
+    pub literals: Vec<Identifier>,
+    /// See [`SyntaxRule`]
+    pub rules: Vec<SyntaxRule>,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
+
+/// EBNF: `(<pattern> <template>)`
+///
+/// Patterns and templates are kept as data, their validation being part of macro expansion.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SyntaxRule {
+    /// Matched against the macro use, the keyword position being ignored
+    pub pattern: Datum,
+    /// Expansion of the macro use
+    pub template: Datum,
+}
+
+/// EBNF: `(let-syntax (<syntax spec>*) <body>) | (letrec-syntax (<syntax spec>*) <body>)`
+#[derive(Debug, PartialEq, Clone)]
+pub struct LetSyntax {
+    /// Whether the transformers are in scope of each other, as with `letrec-syntax`
+    pub recursive: bool,
+    /// `(<keyword> <transformer spec>)`
+    pub bindings: Vec<(Identifier, SyntaxRules)>,
+    /// See [`Body`]
+    pub body: Body,
+    /// From the opening to the closing parenthesis
+    pub span: Span,
+}
@@ -0,0 +1,402 @@
+//! Libraries and import sets of R7RS 5.2 and 5.6.
+//!
+//! Library `(foo bar)` is read from the file `foo/bar.sld` of the first library path having it,
+//! the first time it is imported. Its declarations are processed in order, each `begin` body being
+//! expanded within the top level of the library, which only binds the identifiers it imports.
+
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
+
+use pluine_lex::Lexer;
+use pluine_number::{Number, Real};
+use pluine_parser::{DatumGraph, Reader};
+
+use super::*;
+
+/// Feature identifiers of `cond-expand` requirements
+const FEATURES: &[&str] = &["r7rs", "exact-closed", "ratios", "full-unicode", "pluine"];
+
+/// Library defined while expanding a form, whose body must be evaluated before the form itself.
+///
+/// Libraries are loaded after the ones they import, and their global variables are named after
+/// the library, such as `(foo bar) baz`.
+#[derive(Debug)]
+pub struct LoadedLibrary {
+    /// Library name, such as `(foo bar)`
+    pub name: Box<str>,
+    /// File of the library, to which the spans of its body point, `None` if defined by the program
+    pub path: Option<PathBuf>,
+    /// Top-level forms of the `begin` and `include` declarations, in order
+    pub forms: Vec<Form>,
+}
+
+/// Declarations of the library being defined
+#[derive(Default)]
+struct Declarations {
+    /// Internal name, name exported and span of each export specification
+    exports: Vec<(Rc<str>, Rc<str>, Span)>,
+    /// See [`LoadedLibrary::forms`]
+    forms: Vec<Form>,
+}
+
+impl Expander {
+    /// Appends a directory to search for library files.
+    pub fn add_library_path(&mut self, path: impl Into<PathBuf>) {
+        self.library_paths.push(path.into());
+    }
+
+    /// Defines a library of identifiers built into the consumer of the expander, named by the
+    /// parts of `name` such as `["scheme", "base"]`.
+    ///
+    /// Exports named after a syntactic keyword refer to that keyword, any other to the global
+    /// variable of that name.
+    pub fn add_builtin_library(&mut self, name: &[&str], exports: &[&str]) {
+        let exports = exports
+            .iter()
+            .map(|export| {
+                let binding = match KEYWORDS.iter().find(|keyword| *keyword == export) {
+                    Some(keyword) => Binding::Keyword(keyword),
+                    None => Binding::Global(Rc::from(*export)),
+                };
+                (Rc::from(*export), binding)
+            })
+            .collect();
+        self.libraries.insert(format!("({})", name.join(" ")).into(), exports);
+    }
+
+    /// Libraries defined since the last call, in the order their bodies must be evaluated
+    pub fn take_loaded(&mut self) -> Vec<LoadedLibrary> {
+        std::mem::take(&mut self.loaded)
+    }
+
+    /// `(import <import set>+)`, binding the imported identifiers at the top level being expanded
+    pub(super) fn import(&mut self, declaration: &Datum) -> Result<()> {
+        let sets = match declaration.as_list() {
+            Some([_, sets @ ..]) if !sets.is_empty() => sets,
+            _ => return Err(SyntaxError::MalformedForm("import", declaration.span)),
+        };
+
+        for set in sets {
+            let imported = self.import_set(set)?.into_iter().map(|(name, binding)| match binding {
+                Binding::Global(global) => (name, Binding::Import(global)),
+                binding => (name, binding),
+            });
+            self.top_level.bindings.borrow_mut().extend(imported);
+        }
+        Ok(())
+    }
+
+    /// `(define-library <library name> <library declaration>*)`, read from `path` if any
+    pub(super) fn define_library(&mut self, form: &Datum, path: Option<&Path>) -> Result<()> {
+        let malformed = || SyntaxError::MalformedForm("define-library", form.span);
+        let Some([keyword, name, declarations @ ..]) = form.as_list() else {
+            return Err(malformed());
+        };
+        if keyword.as_symbol() != Some("define-library") {
+            return Err(malformed());
+        }
+        let (name, _) = library_name(name)?;
+
+        let top_level = TopLevel { bindings: RefCell::default(), library: Some(name.clone()) };
+        let outer = std::mem::replace(&mut self.top_level, Rc::new(top_level));
+        self.defining.push(name.clone());
+        let mut library = Declarations::default();
+        let result = self.declarations(declarations, path.and_then(Path::parent), &mut library);
+        self.defining.pop();
+        let top_level = std::mem::replace(&mut self.top_level, outer);
+        result?;
+
+        let bindings = top_level.bindings.borrow();
+        let exports = library
+            .exports
+            .into_iter()
+            .map(|(internal, external, span)| match bindings.get(&internal) {
+                Some(binding) => Ok((external, binding.clone())),
+                None => Err(SyntaxError::UnboundExport((*internal).into(), span)),
+            })
+            .collect::<Result<_>>()?;
+
+        self.libraries.insert(name.clone(), exports);
+        self.loaded.push(LoadedLibrary {
+            name: (*name).into(),
+            path: path.map(Path::to_path_buf),
+            forms: library.forms,
+        });
+        Ok(())
+    }
+
+    /// Library declarations, files being included relative to `directory`
+    fn declarations(&mut self, declarations: &[Datum], directory: Option<&Path>, library: &mut Declarations) -> Result<()> {
+        for declaration in declarations {
+            let malformed = |keyword| SyntaxError::MalformedForm(keyword, declaration.span);
+            let Some([keyword, operands @ ..]) = declaration.as_list() else {
+                return Err(malformed("define-library"));
+            };
+
+            match keyword.as_symbol() {
+                Some("export") => {
+                    for specification in operands {
+                        let names = match (specification.as_symbol(), specification.as_list()) {
+                            (Some(name), _) => (name, name),
+                            (_, Some([rename, internal, external])) if rename.as_symbol() == Some("rename") => {
+                                match (internal.as_symbol(), external.as_symbol()) {
+                                    (Some(internal), Some(external)) => (internal, external),
+                                    _ => return Err(malformed("export")),
+                                }
+                            }
+                            _ => return Err(malformed("export")),
+                        };
+                        library.exports.push((names.0.into(), names.1.into(), specification.span));
+                    }
+                }
+                Some("import") => self.import(declaration)?,
+                Some("begin") => {
+                    for form in operands {
+                        library.forms.push(self.library_form(form)?);
+                    }
+                }
+                Some(name @ ("include" | "include-ci")) => {
+                    let keyword = if name == "include" { "include" } else { "include-ci" };
+                    for (path, data) in self.included(declaration, directory, keyword)? {
+                        for datum in &data {
+                            let form = self.library_form(datum).map_err(|error| in_file(&path, error))?;
+                            library.forms.push(form);
+                        }
+                    }
+                }
+                Some("include-library-declarations") => {
+                    for (path, data) in self.included(declaration, directory, "include-library-declarations")? {
+                        self.declarations(&data, path.parent(), library)
+                            .map_err(|error| in_file(&path, error))?;
+                    }
+                }
+                Some("cond-expand") => {
+                    for clause in operands {
+                        let Some([requirement, declarations @ ..]) = clause.as_list() else {
+                            return Err(SyntaxError::MalformedForm("cond-expand", clause.span));
+                        };
+                        if requirement.as_symbol() == Some("else") || self.requirement(requirement)? {
+                            self.declarations(declarations, directory, library)?;
+                            break;
+                        }
+                    }
+                }
+                _ => return Err(malformed("define-library")),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Top-level form of the body of the library being defined
+    fn library_form(&mut self, datum: &Datum) -> Result<Form> {
+        let syntax = self.run(Part::TopLevel(Syntax::from_datum(datum)))?;
+        Form::from_datum(&syntax.output())
+    }
+
+    /// `(begin <datum> ...)` of the data of the files included by an `include` or `include-ci`
+    /// form of the program, read from the working directory
+    pub(super) fn include(&self, form: &Syntax, keyword: &'static str) -> Result<Syntax> {
+        // bound to `begin` whatever the environment of the form binds
+        let begin = Name::Alias(Rc::new(Alias {
+            name: Name::Symbol("begin".into()),
+            environment: None,
+            top_level: self.top_level.clone(),
+            binding: OnceCell::from(Binding::Keyword("begin")),
+            bound: Cell::new(false),
+        }));
+
+        let mut elements = vec![Syntax::Identifier(begin, form.span())];
+        for (_, data) in self.included(&form.to_datum(), None, keyword)? {
+            elements.extend(data.iter().map(Syntax::from_datum));
+        }
+        Ok(Syntax::list(elements, None, form.span()))
+    }
+
+    /// Data of each file named by the string literals of an `include` declaration, along with its
+    /// path. `include-ci` reads them as if they started with a `#!fold-case` directive.
+    fn included(&self, declaration: &Datum, directory: Option<&Path>, keyword: &'static str) -> Result<Vec<(PathBuf, Vec<Datum>)>> {
+        let files = match declaration.as_list() {
+            Some([_, files @ ..]) if !files.is_empty() => files,
+            _ => return Err(SyntaxError::MalformedForm(keyword, declaration.span)),
+        };
+
+        files
+            .iter()
+            .map(|file| {
+                let DatumKind::String(name) = &file.kind else {
+                    return Err(SyntaxError::MalformedForm(keyword, file.span));
+                };
+                let path = directory.map_or_else(|| PathBuf::from(&**name), |directory| directory.join(&**name));
+                let data = read(&path, file.span, keyword == "include-ci")?;
+                Ok((path, data))
+            })
+            .collect()
+    }
+
+    /// Whether a `cond-expand` feature requirement is met
+    fn requirement(&self, requirement: &Datum) -> Result<bool> {
+        if let Some(feature) = requirement.as_symbol() {
+            return Ok(FEATURES.contains(&feature));
+        }
+
+        let malformed = || SyntaxError::MalformedForm("cond-expand", requirement.span);
+        let Some([keyword, operands @ ..]) = requirement.as_list() else {
+            return Err(malformed());
+        };
+        match (keyword.as_symbol(), operands) {
+            (Some("library"), [name]) => {
+                let (name, parts) = library_name(name)?;
+                Ok(self.libraries.contains_key(&name) || self.library_file(&parts).is_some())
+            }
+            (Some("and"), _) => {
+                for operand in operands {
+                    if !self.requirement(operand)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            (Some("or"), _) => {
+                for operand in operands {
+                    if self.requirement(operand)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            (Some("not"), [operand]) => Ok(!self.requirement(operand)?),
+            _ => Err(malformed()),
+        }
+    }
+
+    /// Identifiers of an import set, along with their binding
+    fn import_set(&mut self, set: &Datum) -> Result<HashMap<Rc<str>, Binding>> {
+        let malformed = |datum: &Datum| SyntaxError::MalformedForm("import", datum.span);
+        let elements = set.as_list().ok_or_else(|| malformed(set))?;
+
+        // library names are made of identifiers and integers, an inner list being an import set
+        let (modifier, inner, operands) = match elements {
+            [modifier, inner @ Datum { kind: DatumKind::List { .. }, .. }, operands @ ..] => (modifier.as_symbol(), inner, operands),
+            _ => return self.library(set),
+        };
+        let mut imported = self.import_set(inner)?;
+        let identifier = |datum: &Datum| datum.as_symbol().map(Rc::<str>::from).ok_or_else(|| malformed(datum));
+        let missing = |name: &Rc<str>, datum: &Datum| SyntaxError::MissingImport((**name).into(), datum.span);
+
+        match modifier {
+            Some("only") => operands
+                .iter()
+                .map(|operand| {
+                    let name = identifier(operand)?;
+                    let binding = imported.remove(&name).ok_or_else(|| missing(&name, operand))?;
+                    Ok((name, binding))
+                })
+                .collect(),
+            Some("except") => {
+                for operand in operands {
+                    let name = identifier(operand)?;
+                    imported.remove(&name).ok_or_else(|| missing(&name, operand))?;
+                }
+                Ok(imported)
+            }
+            Some("prefix") => {
+                let [prefix] = operands else {
+                    return Err(malformed(set));
+                };
+                let prefix = identifier(prefix)?;
+                Ok(imported
+                    .into_iter()
+                    .map(|(name, binding)| (format!("{prefix}{name}").into(), binding))
+                    .collect())
+            }
+            Some("rename") => {
+                // all renamed at once, so that identifiers can be swapped
+                let mut renamed = Vec::new();
+                for operand in operands {
+                    let Some([from, to]) = operand.as_list() else {
+                        return Err(malformed(operand));
+                    };
+                    let name = identifier(from)?;
+                    let binding = imported.remove(&name).ok_or_else(|| missing(&name, from))?;
+                    renamed.push((identifier(to)?, binding));
+                }
+                imported.extend(renamed);
+                Ok(imported)
+            }
+            _ => Err(malformed(set)),
+        }
+    }
+
+    /// Exports of the library named by `name`, loading it from its file if not defined yet
+    fn library(&mut self, name: &Datum) -> Result<HashMap<Rc<str>, Binding>> {
+        let (library, parts) = library_name(name)?;
+        if let Some(exports) = self.libraries.get(&library) {
+            return Ok(exports.clone());
+        }
+        if self.defining.contains(&library) {
+            return Err(SyntaxError::ImportCycle((*library).into(), name.span));
+        }
+
+        let unknown = || SyntaxError::UnknownLibrary((*library).into(), name.span);
+        let path = self.library_file(&parts).ok_or_else(unknown)?;
+        for form in &read(&path, name.span, false)? {
+            self.define_library(form, Some(&path)).map_err(|error| in_file(&path, error))?;
+        }
+
+        self.libraries.get(&library).cloned().ok_or_else(unknown)
+    }
+
+    /// First library file named after the parts of a library name
+    fn library_file(&self, parts: &[String]) -> Option<PathBuf> {
+        let (last, directories) = parts.split_last()?;
+        self.library_paths
+            .iter()
+            .map(|directory| {
+                let mut path = directory.join(directories.iter().collect::<PathBuf>());
+                path.push(format!("{last}.sld"));
+                path
+            })
+            .find(|path| path.is_file())
+    }
+}
+
+/// Name of a library as written, such as `(foo bar)`, along with its parts
+fn library_name(name: &Datum) -> Result<(Rc<str>, Vec<String>)> {
+    let malformed = || SyntaxError::MalformedForm("library name", name.span);
+    let parts = name
+        .as_list()
+        .filter(|parts| !parts.is_empty())
+        .ok_or_else(malformed)?
+        .iter()
+        .map(|part| match &part.kind {
+            DatumKind::Symbol(symbol) => Ok(symbol.to_string()),
+            DatumKind::Number(number @ Number::Real(real @ Real::Integer(_))) if real.signum() != Some(Ordering::Less) => {
+                Ok(number.to_string())
+            }
+            _ => Err(malformed()),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((format!("({})", parts.join(" ")).into(), parts))
+}
+
+/// Data of a file, `span` pointing to what named it, read in fold-case mode if `fold_case`
+fn read(path: &Path, span: Span, fold_case: bool) -> Result<Vec<Datum>> {
+    let src = std::fs::read_to_string(path).map_err(|error| SyntaxError::UnreadableFile {
+        path: path.display().to_string().into(),
+        reason: error.to_string().into(),
+        span,
+    })?;
+    Reader::from_lexer(Lexer::new(&src).with_fold_case(fold_case))
+        .map(|datum| Datum::from_graph(&DatumGraph::new(&src, datum?)))
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|error| in_file(path, error))
+}
+
+/// `error` located in the file at `path`
+fn in_file(path: &Path, error: impl Into<ParseError>) -> SyntaxError {
+    SyntaxError::InFile { path: path.display().to_string().into(), error: Box::new(error.into()) }
+}
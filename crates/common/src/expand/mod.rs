@@ -0,0 +1,1387 @@
+//! Hygienic expansion of macro uses, between reading data and lowering them to the AST.
+//!
+//! Each top-level form is rewritten to one made of core and derived forms only. Identifiers
+//! inserted by a `syntax-rules` template become aliases, resolved in the environment of the macro
+//! definition rather than the one of its use. Local variables keep their name in the output unless
+//! it would capture a reference meant for another binding, in which case they are renamed.
+//!
+//! Data inserted by a template take the span of the macro use, so that diagnostics about the
+//! expansion point at the use site, while the parts of the use matched by pattern variables keep
+//! their own span.
+//!
+//! Libraries have a top level of their own, their global variables being renamed after the library
+//! so that they can't clash with those of the program or of other libraries.
+//!
+//! Forms are expanded with an explicit stack of [`Part`]s rather than recursion, each step
+//! expanding a single form and leaving its subforms for later steps, as deeply nested forms and
+//! recursive macros are common in generated code.
+
+mod library;
+pub use library::LoadedLibrary;
+
+mod transformer;
+
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    rc::Rc,
+};
+
+use pluine_lex::span::Span;
+use transformer::Transformer;
+
+use crate::*;
+
+type Result<T> = std::result::Result<T, SyntaxError>;
+
+/// Expander of top-level forms, keeping the macros and libraries defined from one form to the next
+pub struct Expander {
+    /// Top level of the program, or of the library being defined
+    top_level: Rc<TopLevel>,
+    /// Number of the last local variable, distinguishing renamed variables
+    locals: usize,
+    /// Exported identifiers of the libraries defined so far, by name such as `(foo bar)`
+    libraries: HashMap<Rc<str>, HashMap<Rc<str>, Binding>>,
+    /// Libraries being defined, innermost last, importing any of them closing a cycle
+    defining: Vec<Rc<str>>,
+    /// Directories searched in order for library files
+    library_paths: Vec<PathBuf>,
+    /// See [`LoadedLibrary`]
+    loaded: Vec<LoadedLibrary>,
+}
+
+/// Bindings of the top level of a program or library, unbound names referring to global variables
+struct TopLevel {
+    /// Keywords, macros and imported identifiers, along with the global variables defined so far
+    bindings: RefCell<HashMap<Rc<str>, Binding>>,
+    /// Name of the library, prefixing the names of its global variables, `None` for the program
+    library: Option<Rc<str>>,
+}
+
+/// Identifier during expansion
+#[derive(Clone)]
+enum Name {
+    /// Symbol as read, or naming a global variable or keyword in the output
+    Symbol(Rc<str>),
+    /// See [`Alias`]
+    Alias(Rc<Alias>),
+    /// Local variable of the output, see [`Local`]
+    Local(Rc<Local>),
+}
+
+/// Identifier inserted by a template, each expansion creating its own aliases
+struct Alias {
+    /// Template identifier
+    name: Name,
+    /// Environment of the macro definition, in which the alias is resolved if not bound by the
+    /// expansion itself
+    environment: Environment,
+    /// Top level of the macro definition, which may be another library than the one of the use
+    top_level: Rc<TopLevel>,
+    /// Binding of the template identifier in the environment of the macro definition, resolved
+    /// once
+    binding: OnceCell<Binding>,
+    /// Set once bound by a form of the expansion, scopes only being searched for bound aliases
+    bound: Cell<bool>,
+}
+
+/// Local variable bound by a form of the output
+struct Local {
+    name: Rc<str>,
+    /// Unique to the local, distinguishing it once renamed
+    number: usize,
+    /// Set when the name would capture references to other bindings of the same name
+    renamed: Cell<bool>,
+}
+
+/// What an identifier refers to
+#[derive(Clone)]
+enum Binding {
+    /// Core or derived form, or auxiliary syntax
+    Keyword(&'static str),
+    /// See [`Transformer`]
+    Macro(Rc<Transformer>),
+    /// See [`Local`]
+    Local(Rc<Local>),
+    /// Global variable of that name, defined or not
+    Global(Rc<str>),
+    /// Global variable of that name imported from a library, which can't be assigned
+    Import(Rc<str>),
+}
+
+/// Innermost scope, the top level being `None`
+type Environment = Option<Rc<Scope>>;
+
+/// Region of a binding form, such as the parameters of a `lambda`
+struct Scope {
+    bindings: RefCell<Vec<(Name, Binding)>>,
+    parent: Environment,
+}
+
+/// Datum being expanded
+#[derive(Clone)]
+enum Syntax {
+    /// See [`Name`]
+    Identifier(Name, Span),
+    /// Proper or dotted list, the tail never being a list itself
+    List {
+        /// Shared with the copies of the list, which parts of the expansion make at every level
+        elements: Rc<[Syntax]>,
+        tail: Option<Box<Syntax>>,
+        span: Span,
+    },
+    /// `#( <Datum>* )`
+    Vector(Vec<Syntax>, Span),
+    /// Any other datum, or quoted data of the output
+    Datum(Datum),
+}
+
+/// Form being expanded, parts of which are left for later steps
+enum Part {
+    /// Expanded syntax
+    Done(Syntax),
+    /// Expression to expand within an environment
+    Expression(Syntax, Environment),
+    /// Form of the top level being expanded
+    TopLevel(Syntax),
+    /// Definition of a body or of the top level, its variables being bound beforehand
+    Definition(&'static str, Syntax, Environment),
+    /// Forms of a body within a new scope, spliced into the enclosing list
+    Body(Vec<Syntax>, Environment),
+    /// Quasiquotation template at the given nesting depth
+    Quasiquote(Syntax, usize, Environment),
+    /// Proper or dotted list of parts
+    List(Vec<Part>, Option<Box<Part>>, Span),
+    /// Vector of parts
+    Vector(Vec<Part>, Span),
+}
+
+/// Step of [`Expander::run`]
+enum Task {
+    Part(Part),
+    /// Replaces the outputs pushed since `base` by the list they make, the last one being the
+    /// tail if `dotted`
+    List {
+        base: usize,
+        dotted: bool,
+        span: Span,
+    },
+    /// Replaces the outputs pushed since `base` by the vector they make
+    Vector {
+        base: usize,
+        span: Span,
+    },
+}
+
+impl Expander {
+    /// Expander with no macro nor library defined, nor any directory to search for library files
+    ///
+    /// The top level of the program binds every syntactic keyword, as if importing them.
+    pub fn new() -> Self {
+        let bindings = KEYWORDS
+            .iter()
+            .map(|keyword| (Rc::from(*keyword), Binding::Keyword(keyword)))
+            .collect();
+
+        Expander {
+            top_level: Rc::new(TopLevel { bindings: RefCell::new(bindings), library: None }),
+            locals: 0,
+            libraries: HashMap::new(),
+            defining: Vec::new(),
+            library_paths: Vec::new(),
+            loaded: Vec::new(),
+        }
+    }
+
+    /// Expands the macro uses of a top-level form, then lowers it.
+    ///
+    /// Macros defined by the form are kept for the following ones, the definition itself being
+    /// lowered to an empty `begin`. So are `import` declarations and `define-library` forms, the
+    /// libraries they load being left for [`Expander::take_loaded`].
+    pub fn expand(&mut self, datum: &Datum) -> Result<Form> {
+        let syntax = match datum.as_list().and_then(|elements| elements.first()?.as_symbol()) {
+            Some("import") => {
+                self.import(datum)?;
+                Syntax::list(vec![Syntax::keyword("begin", datum.span)], None, datum.span)
+            }
+            Some("define-library") => {
+                self.define_library(datum, None)?;
+                Syntax::list(vec![Syntax::keyword("begin", datum.span)], None, datum.span)
+            }
+            _ => self.run(Part::TopLevel(Syntax::from_datum(datum)))?,
+        };
+        Form::from_datum(&syntax.output())
+    }
+
+    /// Expands `part` with an explicit stack of tasks, in order and depth first.
+    ///
+    /// Each expanded part pushes its syntax onto the outputs, bodies pushing as many as they have
+    /// forms.
+    fn run(&mut self, part: Part) -> Result<Syntax> {
+        let mut tasks = vec![Task::Part(part)];
+        let mut outputs = Vec::new();
+
+        while let Some(task) = tasks.pop() {
+            let part = match task {
+                Task::Part(part) => part,
+                Task::List { base, dotted, span } => {
+                    let mut elements = outputs.split_off(base);
+                    let tail = if dotted { elements.pop() } else { None };
+                    outputs.push(Syntax::list(elements, tail, span));
+                    continue;
+                }
+                Task::Vector { base, span } => {
+                    let elements = outputs.split_off(base);
+                    outputs.push(Syntax::Vector(elements, span));
+                    continue;
+                }
+            };
+
+            let next = match part {
+                Part::Done(syntax) => {
+                    outputs.push(syntax);
+                    continue;
+                }
+                Part::Expression(form, environment) => self.expression(form, &environment)?,
+                Part::TopLevel(form) => self.top_level_form(form)?,
+                Part::Definition(keyword, form, environment) => self.definition(keyword, &form, &environment)?,
+                Part::Quasiquote(template, depth, environment) => self.quasiquote(&template, depth, &environment)?,
+                Part::Body(forms, environment) => {
+                    tasks.extend(self.body(&forms, &environment)?.into_iter().rev().map(Task::Part));
+                    continue;
+                }
+                Part::List(parts, tail, span) => {
+                    tasks.push(Task::List { base: outputs.len(), dotted: tail.is_some(), span });
+                    tasks.extend(tail.map(|tail| Task::Part(*tail)));
+                    tasks.extend(parts.into_iter().rev().map(Task::Part));
+                    continue;
+                }
+                Part::Vector(parts, span) => {
+                    tasks.push(Task::Vector { base: outputs.len(), span });
+                    tasks.extend(parts.into_iter().rev().map(Task::Part));
+                    continue;
+                }
+            };
+            tasks.push(Task::Part(next));
+        }
+
+        Ok(outputs.pop().expect("every part has an output"))
+    }
+
+    fn top_level_form(&mut self, form: Syntax) -> Result<Part> {
+        let form = self.head_expand(form, &None)?;
+        let keyword = match self.head(&form, &None) {
+            Some(Binding::Keyword(keyword)) => keyword,
+            _ => return Ok(Part::Expression(form, None)),
+        };
+
+        match (keyword, form.operands()) {
+            ("begin", Some(operands)) => {
+                let elements = std::iter::once(Part::Done(form.head_keyword(keyword)))
+                    .chain(operands.iter().map(|operand| Part::TopLevel(operand.clone())))
+                    .collect();
+                Ok(Part::List(elements, None, form.span()))
+            }
+            ("define-syntax", Some([keyword_syntax, transformer])) => {
+                let name = keyword_syntax
+                    .identifier()
+                    .ok_or(SyntaxError::MalformedForm(keyword, keyword_syntax.span()))?;
+                let transformer = self.transformer(transformer, &None)?;
+                let mut bindings = self.top_level.bindings.borrow_mut();
+                bindings.insert(name.symbol().clone(), Binding::Macro(transformer));
+
+                Ok(Part::Done(Syntax::list(vec![form.head_keyword("begin")], None, form.span())))
+            }
+            ("define-syntax", _) => Err(SyntaxError::MalformedForm(keyword, form.span())),
+            ("define" | "define-values" | "define-record-type", _) => {
+                // a variable definition replaces any macro or import of the same name
+                for identifier in defined_identifiers(keyword, &form)? {
+                    self.top_level
+                        .define(identifier.identifier().expect("checked by defined_identifiers").symbol());
+                }
+                self.definition(keyword, &form, &None)
+            }
+            _ => Ok(Part::Expression(form, None)),
+        }
+    }
+
+    /// Binding of `name` within `environment`
+    fn resolve(&self, name: &Name, environment: &Environment) -> Binding {
+        resolve(name, environment, &self.top_level)
+    }
+
+    /// Binding of the identifier heading the form, if any
+    fn head(&self, form: &Syntax, environment: &Environment) -> Option<Binding> {
+        match form {
+            Syntax::List { elements, .. } => Some(self.resolve(elements.first()?.identifier()?, environment)),
+            _ => None,
+        }
+    }
+
+    /// Expands the form until it is neither a macro use nor an inclusion.
+    fn head_expand(&mut self, mut form: Syntax, environment: &Environment) -> Result<Syntax> {
+        loop {
+            form = match self.head(&form, environment) {
+                Some(Binding::Macro(transformer)) => self.transcribe(&transformer, &form, environment)?,
+                Some(Binding::Keyword(keyword @ ("include" | "include-ci"))) => self.include(&form, keyword)?,
+                _ => return Ok(form),
+            };
+        }
+    }
+
+    /// Expands the form itself, leaving its subforms as parts.
+    fn expression(&mut self, form: Syntax, environment: &Environment) -> Result<Part> {
+        let form = self.head_expand(form, environment)?;
+
+        match form {
+            Syntax::Identifier(name, span) => Ok(Part::Done(self.variable(&name, span, environment)?)),
+            Syntax::List { elements, tail: None, span } if !elements.is_empty() => {
+                if let Some(Binding::Keyword(keyword)) = elements[0].identifier().map(|name| self.resolve(name, environment)) {
+                    let operands = self.special_form(keyword, &elements[1..], span, environment)?;
+                    return Ok(match operands {
+                        SpecialForm::Operands(operands) => {
+                            let head = Part::Done(Syntax::keyword(keyword, elements[0].span()));
+                            Part::List(std::iter::once(head).chain(operands).collect(), None, span)
+                        }
+                        SpecialForm::Expanded(part) => part,
+                    });
+                }
+
+                Ok(Part::List(expressions(&elements, environment), None, span))
+            }
+            // self-evaluating data, along with empty combinations and dotted calls left for lowering
+            other => Ok(Part::Done(Syntax::Datum(other.to_datum()))),
+        }
+    }
+
+    /// Reference to a variable, renaming the local variables in between that would capture it
+    fn variable(&mut self, name: &Name, span: Span, environment: &Environment) -> Result<Syntax> {
+        let (target, name) = match self.resolve(name, environment) {
+            Binding::Local(local) => (Some(local.clone()), local.name.clone()),
+            Binding::Global(name) | Binding::Import(name) => (None, name),
+            Binding::Keyword(_) | Binding::Macro(_) => return Err(SyntaxError::MisplacedKeyword(span)),
+        };
+
+        let mut scope = environment.clone();
+        'scopes: while let Some(current) = scope {
+            for (_, binding) in current.bindings.borrow().iter().rev() {
+                let Binding::Local(local) = binding else { continue };
+                if target.as_ref().is_some_and(|target| Rc::ptr_eq(target, local)) {
+                    break 'scopes;
+                }
+                if local.name == name {
+                    local.renamed.set(true);
+                }
+            }
+            scope = current.parent.clone();
+        }
+
+        Ok(Syntax::Identifier(target.map_or(Name::Symbol(name), Name::Local), span))
+    }
+
+    /// Operands of a core or derived form, their subforms being left as parts within their scope
+    fn special_form(&mut self, keyword: &'static str, operands: &[Syntax], span: Span, environment: &Environment) -> Result<SpecialForm> {
+        let malformed = || SyntaxError::MalformedForm(keyword, span);
+
+        let operands = match (keyword, operands) {
+            ("quote", _) => operands
+                .iter()
+                .map(|operand| Part::Done(Syntax::Datum(operand.to_datum())))
+                .collect(),
+            ("quasiquote", [template]) => vec![Part::Quasiquote(template.clone(), 1, environment.clone())],
+            ("lambda", [formals, body @ ..]) => {
+                let scope = Scope::new(environment);
+                let formals = self.formals(formals, &scope, keyword)?;
+                vec![Part::Done(formals), Part::Body(body.to_vec(), Some(scope))]
+            }
+            ("set!", [Syntax::Identifier(name, span), ..]) if matches!(self.resolve(name, environment), Binding::Import(_)) => {
+                return Err(SyntaxError::ImportedAssignment((**name.symbol()).into(), *span))
+            }
+            ("if" | "set!" | "and" | "or" | "when" | "unless" | "begin" | "delay" | "delay-force", _) => expressions(operands, environment),
+            ("cond", _) => operands
+                .iter()
+                .map(|clause| self.clause(clause, keyword, environment))
+                .collect::<Result<_>>()?,
+            ("case", [key, clauses @ ..]) => {
+                let mut operands = vec![Part::Expression(key.clone(), environment.clone())];
+                for clause in clauses {
+                    let Some([data, rest @ ..]) = clause.as_list() else {
+                        return Err(SyntaxError::MalformedForm(keyword, clause.span()));
+                    };
+                    let data = match self.auxiliary(data, environment) {
+                        Some(auxiliary) => Syntax::keyword(auxiliary, data.span()),
+                        None => Syntax::Datum(data.to_datum()),
+                    };
+                    let elements =
+                        std::iter::once(Part::Done(data)).chain(rest.iter().map(|element| self.clause_element(element, environment)));
+                    operands.push(Part::List(elements.collect(), None, clause.span()));
+                }
+                operands
+            }
+            ("let" | "let*" | "letrec" | "letrec*", _) => self.let_form(keyword, operands, span, environment)?,
+            ("let-values" | "let*-values", [bindings, body @ ..]) => {
+                let sequential = keyword == "let*-values";
+                let mut scope = Scope::new(environment);
+                let mut outer = environment.clone();
+                let mut output = Vec::new();
+
+                for binding in list(bindings, keyword)? {
+                    let [formals, init] = list(binding, keyword)? else {
+                        return Err(SyntaxError::MalformedForm(keyword, binding.span()));
+                    };
+                    if sequential {
+                        scope = Scope::new(&outer);
+                    }
+                    let init = Part::Expression(init.clone(), outer.clone());
+                    let formals = self.formals(formals, &scope, keyword)?;
+                    output.push(Part::List(vec![Part::Done(formals), init], None, binding.span()));
+                    if sequential {
+                        outer = Some(scope.clone());
+                    }
+                }
+
+                let environment = if sequential { outer } else { Some(scope) };
+                vec![Part::List(output, None, bindings.span()), Part::Body(body.to_vec(), environment)]
+            }
+            ("do", [iterations, exit, commands @ ..]) => {
+                let scope = Scope::new(environment);
+                let mut specs = Vec::new();
+                for iteration in list(iterations, keyword)? {
+                    let [variable, init, step @ ..] = list(iteration, keyword)? else {
+                        return Err(SyntaxError::MalformedForm(keyword, iteration.span()));
+                    };
+                    let init = Part::Expression(init.clone(), environment.clone());
+                    specs.push((self.bind(&scope, variable, keyword)?, init, step, iteration.span()));
+                }
+
+                let inner = Some(scope);
+                let mut output = Vec::new();
+                for (variable, init, step, span) in specs {
+                    let mut elements = vec![Part::Done(variable), init];
+                    elements.extend(expressions(step, &inner));
+                    output.push(Part::List(elements, None, span));
+                }
+
+                let exit = Part::List(expressions(list(exit, keyword)?, &inner), None, exit.span());
+                let mut operands = vec![Part::List(output, None, iterations.span()), exit];
+                operands.extend(expressions(commands, &inner));
+                operands
+            }
+            ("parameterize", [bindings, body @ ..]) => {
+                let parameters = list(bindings, keyword)?
+                    .iter()
+                    .map(|binding| Ok(Part::List(expressions(list(binding, keyword)?, environment), None, binding.span())))
+                    .collect::<Result<_>>()?;
+                vec![
+                    Part::List(parameters, None, bindings.span()),
+                    Part::Body(body.to_vec(), environment.clone()),
+                ]
+            }
+            ("guard", [specification, body @ ..]) => {
+                let Some([variable, clauses @ ..]) = specification.as_list() else {
+                    return Err(SyntaxError::MalformedForm(keyword, specification.span()));
+                };
+                let scope = Scope::new(environment);
+                let mut elements = vec![Part::Done(self.bind(&scope, variable, keyword)?)];
+                let inner = Some(scope);
+                for clause in clauses {
+                    elements.push(self.clause(clause, keyword, &inner)?);
+                }
+
+                vec![
+                    Part::List(elements, None, specification.span()),
+                    Part::Body(body.to_vec(), environment.clone()),
+                ]
+            }
+            ("case-lambda", _) => operands
+                .iter()
+                .map(|clause| {
+                    let Some([formals, body @ ..]) = clause.as_list() else {
+                        return Err(SyntaxError::MalformedForm(keyword, clause.span()));
+                    };
+                    let scope = Scope::new(environment);
+                    let formals = self.formals(formals, &scope, keyword)?;
+                    let elements = vec![Part::Done(formals), Part::Body(body.to_vec(), Some(scope))];
+                    Ok(Part::List(elements, None, clause.span()))
+                })
+                .collect::<Result<_>>()?,
+            // expanded to `(let () <body>)`, the keywords being bound in the scope of the body
+            ("let-syntax" | "letrec-syntax", [bindings, body @ ..]) => {
+                let scope = Scope::new(environment);
+                let inner = Some(scope.clone());
+                let transformer_environment = if keyword == "letrec-syntax" { &inner } else { environment };
+
+                for binding in list(bindings, keyword)? {
+                    let [keyword_syntax, transformer] = list(binding, keyword)? else {
+                        return Err(SyntaxError::MalformedForm(keyword, binding.span()));
+                    };
+                    let transformer = self.transformer(transformer, transformer_environment)?;
+                    scope.bind(keyword_syntax, Binding::Macro(transformer), keyword)?;
+                }
+
+                let elements = vec![
+                    Part::Done(Syntax::keyword("let", span)),
+                    Part::Done(Syntax::list(Vec::new(), None, bindings.span())),
+                    Part::Body(body.to_vec(), inner),
+                ];
+                return Ok(SpecialForm::Expanded(Part::List(elements, None, span)));
+            }
+            ("define" | "define-values" | "define-record-type" | "define-syntax", _) => {
+                return Err(SyntaxError::UnexpectedDefinition(span))
+            }
+            (
+                "quasiquote" | "lambda" | "case" | "let-values" | "let*-values" | "do" | "parameterize" | "guard" | "let-syntax"
+                | "letrec-syntax",
+                _,
+            ) => return Err(malformed()),
+            // auxiliary syntax, `syntax-rules` and unquotations outside of a quasiquotation
+            _ => return Err(SyntaxError::MisplacedKeyword(span)),
+        };
+
+        Ok(SpecialForm::Operands(operands))
+    }
+
+    fn let_form(&mut self, keyword: &'static str, operands: &[Syntax], span: Span, environment: &Environment) -> Result<Vec<Part>> {
+        let (name, operands) = match operands {
+            [name @ Syntax::Identifier(..), rest @ ..] if keyword == "let" => (Some(name), rest),
+            _ => (None, operands),
+        };
+        let [bindings, body @ ..] = operands else {
+            return Err(SyntaxError::MalformedForm(keyword, span));
+        };
+        let pairs = list(bindings, keyword)?
+            .iter()
+            .map(|binding| match list(binding, keyword)? {
+                [variable, init] => Ok((variable, init, binding.span())),
+                _ => Err(SyntaxError::MalformedForm(keyword, binding.span())),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut output = Vec::new();
+        let mut operands = Vec::new();
+        let inner = match keyword {
+            "let" => {
+                // the name of a named `let` is in scope of the body only
+                let outer = match name {
+                    Some(name) => {
+                        let scope = Scope::new(environment);
+                        operands.push(Part::Done(self.bind(&scope, name, keyword)?));
+                        Some(scope)
+                    }
+                    None => environment.clone(),
+                };
+                let scope = Scope::new(&outer);
+                for (variable, init, span) in pairs {
+                    let init = Part::Expression(init.clone(), environment.clone());
+                    output.push(Part::List(
+                        vec![Part::Done(self.bind(&scope, variable, keyword)?), init],
+                        None,
+                        span,
+                    ));
+                }
+                Some(scope)
+            }
+            "let*" => {
+                let mut inner = environment.clone();
+                for (variable, init, span) in pairs {
+                    let init = Part::Expression(init.clone(), inner.clone());
+                    let scope = Scope::new(&inner);
+                    output.push(Part::List(
+                        vec![Part::Done(self.bind(&scope, variable, keyword)?), init],
+                        None,
+                        span,
+                    ));
+                    inner = Some(scope);
+                }
+                inner
+            }
+            _ => {
+                let scope = Scope::new(environment);
+                let variables = pairs
+                    .iter()
+                    .map(|(variable, ..)| self.bind(&scope, variable, keyword))
+                    .collect::<Result<Vec<_>>>()?;
+                let inner = Some(scope);
+                for (variable, (_, init, span)) in variables.into_iter().zip(pairs) {
+                    let init = Part::Expression(init.clone(), inner.clone());
+                    output.push(Part::List(vec![Part::Done(variable), init], None, span));
+                }
+                inner
+            }
+        };
+
+        operands.push(Part::List(output, None, bindings.span()));
+        operands.push(Part::Body(body.to_vec(), inner));
+        Ok(operands)
+    }
+
+    /// Clause of `cond` or `guard`
+    fn clause(&mut self, clause: &Syntax, keyword: &'static str, environment: &Environment) -> Result<Part> {
+        let elements = clause.as_list().ok_or(SyntaxError::MalformedForm(keyword, clause.span()))?;
+        let elements = elements.iter().map(|element| self.clause_element(element, environment)).collect();
+        Ok(Part::List(elements, None, clause.span()))
+    }
+
+    /// `else`, `=>` or an expression
+    fn clause_element(&self, element: &Syntax, environment: &Environment) -> Part {
+        match self.auxiliary(element, environment) {
+            Some(auxiliary) => Part::Done(Syntax::keyword(auxiliary, element.span())),
+            None => Part::Expression(element.clone(), environment.clone()),
+        }
+    }
+
+    /// Keyword of an identifier referring to `else` or `=>`
+    fn auxiliary(&self, syntax: &Syntax, environment: &Environment) -> Option<&'static str> {
+        match self.resolve(syntax.identifier()?, environment) {
+            Binding::Keyword(keyword @ ("else" | "=>")) => Some(keyword),
+            _ => None,
+        }
+    }
+
+    /// Definitions followed by expressions, within a new scope.
+    ///
+    /// Macro uses are expanded until the first expression to find the definitions, which are all
+    /// bound before any of their values is expanded.
+    fn body(&mut self, forms: &[Syntax], environment: &Environment) -> Result<Vec<Part>> {
+        let scope = Scope::new(environment);
+        let environment = Some(scope.clone());
+
+        let mut pending = forms.iter().rev().cloned().collect::<Vec<_>>();
+        let mut scanned: Vec<(Option<&'static str>, Syntax)> = Vec::new();
+        while let Some(form) = pending.pop() {
+            if scanned.iter().any(|(keyword, _)| keyword.is_none()) {
+                scanned.push((None, form));
+                continue;
+            }
+
+            let form = self.head_expand(form, &environment)?;
+            match (self.head(&form, &environment), form.operands()) {
+                (Some(Binding::Keyword("begin")), Some(operands)) => pending.extend(operands.iter().rev().cloned()),
+                (Some(Binding::Keyword(keyword @ "define-syntax")), operands) => {
+                    let Some([keyword_syntax, transformer]) = operands else {
+                        return Err(SyntaxError::MalformedForm(keyword, form.span()));
+                    };
+                    let transformer = self.transformer(transformer, &environment)?;
+                    scope.bind(keyword_syntax, Binding::Macro(transformer), keyword)?;
+                }
+                (Some(Binding::Keyword(keyword @ ("define" | "define-values" | "define-record-type"))), _) => {
+                    for identifier in defined_identifiers(keyword, &form)? {
+                        self.bind(&scope, identifier, keyword)?;
+                    }
+                    scanned.push((Some(keyword), form));
+                }
+                _ => scanned.push((None, form)),
+            }
+        }
+
+        Ok(scanned
+            .into_iter()
+            .map(|(keyword, form)| match keyword {
+                Some(keyword) => Part::Definition(keyword, form, environment.clone()),
+                None => Part::Expression(form, environment.clone()),
+            })
+            .collect())
+    }
+
+    /// Definition whose variables were bound beforehand, or are global
+    fn definition(&mut self, keyword: &'static str, form: &Syntax, environment: &Environment) -> Result<Part> {
+        let malformed = || SyntaxError::MalformedForm(keyword, form.span());
+        let operands = form.operands().ok_or_else(malformed)?;
+
+        let operands = match (keyword, operands) {
+            ("define", [variable @ Syntax::Identifier(..), value]) => vec![
+                Part::Done(self.defined(variable, environment)),
+                Part::Expression(value.clone(), environment.clone()),
+            ],
+            // procedure shorthand
+            ("define", [Syntax::List { elements, tail, span }, body @ ..]) if !elements.is_empty() => {
+                let scope = Scope::new(environment);
+                let parameters = Syntax::list(elements[1..].to_vec(), tail.as_deref().cloned(), *span);
+                let formals = self.formals(&parameters, &scope, keyword)?;
+
+                let head = Syntax::list(vec![self.defined(&elements[0], environment)], Some(formals), *span);
+                vec![Part::Done(head), Part::Body(body.to_vec(), Some(scope))]
+            }
+            ("define-values", [formals, value]) => {
+                let formals = match formals {
+                    Syntax::List { elements, tail, span } => Syntax::list(
+                        elements.iter().map(|element| self.defined(element, environment)).collect(),
+                        tail.as_deref().map(|tail| self.defined(tail, environment)),
+                        *span,
+                    ),
+                    _ => self.defined(formals, environment),
+                };
+                vec![Part::Done(formals), Part::Expression(value.clone(), environment.clone())]
+            }
+            ("define-record-type", [name, constructor, predicate, fields @ ..]) => {
+                let constructor = match constructor {
+                    Syntax::Identifier(..) => self.defined(constructor, environment),
+                    Syntax::List { elements, tail: None, span } if !elements.is_empty() => {
+                        let field_names = elements[1..].iter().map(|field| Syntax::Datum(field.to_datum()));
+                        let elements = std::iter::once(self.defined(&elements[0], environment)).chain(field_names);
+                        Syntax::list(elements.collect(), None, *span)
+                    }
+                    _ => Syntax::Datum(constructor.to_datum()),
+                };
+
+                let mut operands = vec![self.defined(name, environment), constructor, self.defined(predicate, environment)];
+                for field in fields {
+                    operands.push(match field.as_list() {
+                        Some([name, procedures @ ..]) => {
+                            let procedures = procedures.iter().map(|procedure| self.defined(procedure, environment));
+                            let elements = std::iter::once(Syntax::Datum(name.to_datum())).chain(procedures);
+                            Syntax::list(elements.collect(), None, field.span())
+                        }
+                        _ => Syntax::Datum(field.to_datum()),
+                    });
+                }
+                operands.into_iter().map(Part::Done).collect()
+            }
+            _ => return Err(malformed()),
+        };
+
+        let head = Part::Done(form.head_keyword(keyword));
+        Ok(Part::List(std::iter::once(head).chain(operands).collect(), None, form.span()))
+    }
+
+    /// Output identifier of a variable being defined, bound beforehand in bodies and at the top
+    /// level being defined
+    fn defined(&self, identifier: &Syntax, environment: &Environment) -> Syntax {
+        let Syntax::Identifier(name, span) = identifier else {
+            // left for lowering to reject
+            return Syntax::Datum(identifier.to_datum());
+        };
+
+        // top-level definitions inserted by a template still define a variable of the top level
+        // being expanded
+        let binding = match environment {
+            Some(_) => self.resolve(name, environment),
+            None => self.top_level.get(name.symbol()),
+        };
+        let name = match binding {
+            Binding::Local(local) => Name::Local(local),
+            Binding::Global(name) | Binding::Import(name) => Name::Symbol(name),
+            // keywords can't be defined as variables, which lowering reports
+            Binding::Keyword(_) | Binding::Macro(_) => Name::Symbol(name.symbol().clone()),
+        };
+        Syntax::Identifier(name, *span)
+    }
+
+    /// Parameters of a procedure, bound in `scope`
+    fn formals(&mut self, formals: &Syntax, scope: &Rc<Scope>, keyword: &'static str) -> Result<Syntax> {
+        match formals {
+            Syntax::Identifier(..) => self.bind(scope, formals, keyword),
+            Syntax::List { elements, tail, span } => Ok(Syntax::List {
+                elements: elements
+                    .iter()
+                    .map(|parameter| self.bind(scope, parameter, keyword))
+                    .collect::<Result<_>>()?,
+                tail: tail
+                    .as_deref()
+                    .map(|rest| self.bind(scope, rest, keyword))
+                    .transpose()?
+                    .map(Box::new),
+                span: *span,
+            }),
+            _ => Err(SyntaxError::MalformedForm(keyword, formals.span())),
+        }
+    }
+
+    /// Binds `identifier` to a new local variable of `scope`, returning its output identifier.
+    fn bind(&mut self, scope: &Rc<Scope>, identifier: &Syntax, keyword: &'static str) -> Result<Syntax> {
+        let name = identifier
+            .identifier()
+            .ok_or(SyntaxError::MalformedForm(keyword, identifier.span()))?;
+
+        self.locals += 1;
+        let local = Rc::new(Local {
+            name: name.symbol().clone(),
+            number: self.locals,
+            renamed: Cell::new(false),
+        });
+        // another identifier of the same name, such as one inserted by a template, or a keyword
+        // name which lowering would reject
+        let shadowed = scope
+            .bindings
+            .borrow()
+            .iter()
+            .any(|(_, binding)| matches!(binding, Binding::Local(other) if other.name == local.name));
+        local.renamed.set(shadowed || KEYWORDS.contains(&&*local.name));
+
+        scope.bind(identifier, Binding::Local(local.clone()), keyword)?;
+        Ok(Syntax::Identifier(Name::Local(local), identifier.span()))
+    }
+
+    /// Quasiquotation template at the given nesting depth, one being the outermost
+    fn quasiquote(&mut self, template: &Syntax, depth: usize, environment: &Environment) -> Result<Part> {
+        let part = |syntax: &Syntax, depth| Part::Quasiquote(syntax.clone(), depth, environment.clone());
+
+        Ok(match template {
+            Syntax::List { elements, tail: None, span } if elements.len() == 2 => {
+                let keyword = elements[0].identifier().map(|name| &**name.symbol());
+                let depth = match keyword {
+                    Some("unquote" | "unquote-splicing") if depth == 1 => {
+                        let operand = Part::Expression(elements[1].clone(), environment.clone());
+                        return Ok(Part::List(
+                            vec![Part::Done(Syntax::Datum(elements[0].to_datum())), operand],
+                            None,
+                            *span,
+                        ));
+                    }
+                    Some("unquote" | "unquote-splicing") => depth - 1,
+                    Some("quasiquote") => depth + 1,
+                    _ => depth,
+                };
+
+                Part::List(vec![part(&elements[0], depth), part(&elements[1], depth)], None, *span)
+            }
+            // `(<qq template>+ . ,<expression>)`, read as a list ending with `unquote` and its operand
+            Syntax::List { elements, tail: None, span }
+                if elements.len() > 2
+                    && matches!(
+                        elements[elements.len() - 2].identifier().map(|name| &**name.symbol()),
+                        Some("quasiquote" | "unquote" | "unquote-splicing")
+                    ) =>
+            {
+                let (before, last) = elements.split_at(elements.len() - 2);
+                let tail = Syntax::List {
+                    elements: last.into(),
+                    tail: None,
+                    span: last[0].span().join(last[1].span()),
+                };
+                let elements = before.iter().map(|element| part(element, depth)).collect();
+                Part::List(elements, Some(Box::new(part(&tail, depth))), *span)
+            }
+            Syntax::List { elements, tail, span } => Part::List(
+                elements.iter().map(|element| part(element, depth)).collect(),
+                tail.as_deref().map(|tail| Box::new(part(tail, depth))),
+                *span,
+            ),
+            Syntax::Vector(elements, span) => Part::Vector(elements.iter().map(|element| part(element, depth)).collect(), *span),
+            other => Part::Done(Syntax::Datum(other.to_datum())),
+        })
+    }
+}
+
+impl Default for Expander {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Expansion of a core or derived form
+enum SpecialForm {
+    /// Operands following the keyword
+    Operands(Vec<Part>),
+    /// Whole form, the keyword being replaced
+    Expanded(Part),
+}
+
+/// Binding of `name` within `environment`, names bound by neither being looked up in `top_level`.
+///
+/// Only aliases bound by the expansion can be found in a scope, the others resolving to the
+/// binding of their template identifier, looked up once.
+fn resolve(name: &Name, environment: &Environment, top_level: &TopLevel) -> Binding {
+    let searched = match name {
+        Name::Alias(alias) => alias.bound.get(),
+        Name::Symbol(_) | Name::Local(_) => true,
+    };
+    let mut scope = if searched { environment.clone() } else { None };
+    while let Some(current) = scope {
+        if let Some((_, binding)) = current.bindings.borrow().iter().rev().find(|(bound, _)| bound == name) {
+            return binding.clone();
+        }
+        scope = current.parent.clone();
+    }
+
+    match name {
+        Name::Symbol(symbol) => top_level.get(symbol),
+        Name::Alias(alias) => alias
+            .binding
+            .get_or_init(|| resolve(&alias.name, &alias.environment, &alias.top_level))
+            .clone(),
+        Name::Local(local) => Binding::Local(local.clone()),
+    }
+}
+
+/// Expressions of a form within the same environment
+fn expressions(forms: &[Syntax], environment: &Environment) -> Vec<Part> {
+    forms
+        .iter()
+        .map(|form| Part::Expression(form.clone(), environment.clone()))
+        .collect()
+}
+
+/// Identifiers bound by a definition
+fn defined_identifiers<'a>(keyword: &'static str, form: &'a Syntax) -> Result<Vec<&'a Syntax>> {
+    let malformed = |syntax: &Syntax| SyntaxError::MalformedForm(keyword, syntax.span());
+    let operands = form.operands().ok_or_else(|| malformed(form))?;
+
+    let identifiers = match (keyword, operands) {
+        ("define", [variable @ Syntax::Identifier(..), ..]) => vec![variable],
+        ("define", [Syntax::List { elements, .. }, ..]) if !elements.is_empty() => vec![&elements[0]],
+        ("define-values", [Syntax::List { elements, tail, .. }, ..]) => elements.iter().chain(tail.as_deref()).collect(),
+        ("define-values", [formals @ Syntax::Identifier(..), ..]) => vec![formals],
+        ("define-record-type", [name, constructor, predicate, fields @ ..]) => {
+            let mut identifiers = vec![name, predicate];
+            match constructor {
+                Syntax::Identifier(..) => identifiers.push(constructor),
+                Syntax::List { elements, tail: None, .. } if !elements.is_empty() => identifiers.push(&elements[0]),
+                _ => {}
+            }
+            for field in fields {
+                let procedures = field.as_list().and_then(|field| field.get(1..)).ok_or_else(|| malformed(field))?;
+                identifiers.extend(procedures);
+            }
+            identifiers
+        }
+        _ => return Err(malformed(form)),
+    };
+
+    match identifiers.iter().find(|identifier| identifier.identifier().is_none()) {
+        Some(other) => Err(malformed(other)),
+        None => Ok(identifiers),
+    }
+}
+
+/// Elements of a proper list
+fn list<'a>(syntax: &'a Syntax, keyword: &'static str) -> Result<&'a [Syntax]> {
+    syntax.as_list().ok_or(SyntaxError::MalformedForm(keyword, syntax.span()))
+}
+
+impl TopLevel {
+    /// Binding of `symbol`, the global variable of that name if unbound
+    fn get(&self, symbol: &Rc<str>) -> Binding {
+        let bindings = self.bindings.borrow();
+        bindings
+            .get(symbol)
+            .cloned()
+            .unwrap_or_else(|| Binding::Global(self.global(symbol)))
+    }
+
+    /// Name of the global variable `symbol` refers to when defined at this top level
+    fn global(&self, symbol: &Rc<str>) -> Rc<str> {
+        match &self.library {
+            Some(library) => format!("{library} {symbol}").into(),
+            None => symbol.clone(),
+        }
+    }
+
+    /// Binds `symbol` to a global variable of this top level, replacing any macro or import of
+    /// that name. Keywords are left for lowering to reject.
+    fn define(&self, symbol: &Rc<str>) {
+        let mut bindings = self.bindings.borrow_mut();
+        if !matches!(bindings.get(symbol), Some(Binding::Keyword(_))) {
+            bindings.insert(symbol.clone(), Binding::Global(self.global(symbol)));
+        }
+    }
+}
+
+impl Scope {
+    fn new(parent: &Environment) -> Rc<Scope> {
+        Rc::new(Scope { bindings: RefCell::default(), parent: parent.clone() })
+    }
+
+    /// Binds `identifier` in the scope, which must not already bind it.
+    fn bind(&self, identifier: &Syntax, binding: Binding, keyword: &'static str) -> Result<()> {
+        let name = identifier
+            .identifier()
+            .ok_or(SyntaxError::MalformedForm(keyword, identifier.span()))?;
+
+        let mut bindings = self.bindings.borrow_mut();
+        if bindings.iter().any(|(bound, _)| bound == name) {
+            return Err(SyntaxError::DuplicateIdentifier(identifier.span()));
+        }
+        if let Name::Alias(alias) = name {
+            alias.bound.set(true);
+        }
+        bindings.push((name.clone(), binding));
+        Ok(())
+    }
+}
+
+impl Binding {
+    /// Whether both bindings are the same, as when comparing literals of `syntax-rules`
+    fn same(&self, other: &Binding) -> bool {
+        match (self, other) {
+            (Binding::Keyword(left), Binding::Keyword(right)) => left == right,
+            (Binding::Macro(left), Binding::Macro(right)) => Rc::ptr_eq(left, right),
+            (Binding::Local(left), Binding::Local(right)) => Rc::ptr_eq(left, right),
+            (Binding::Global(left) | Binding::Import(left), Binding::Global(right) | Binding::Import(right)) => left == right,
+            _ => false,
+        }
+    }
+}
+
+impl Name {
+    /// Name as written, aliases being stripped of their environment
+    fn symbol(&self) -> &Rc<str> {
+        match self {
+            Name::Symbol(symbol) => symbol,
+            Name::Alias(alias) => alias.name.symbol(),
+            Name::Local(local) => &local.name,
+        }
+    }
+}
+
+/// Symbols are the same identifier when of the same name, aliases and local variables only
+/// being equal to themselves.
+impl PartialEq for Name {
+    fn eq(&self, other: &Name) -> bool {
+        match (self, other) {
+            (Name::Symbol(left), Name::Symbol(right)) => left == right,
+            (Name::Alias(left), Name::Alias(right)) => Rc::ptr_eq(left, right),
+            (Name::Local(left), Name::Local(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Name {}
+
+impl Hash for Name {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Name::Symbol(symbol) => symbol.hash(state),
+            Name::Alias(alias) => Rc::as_ptr(alias).hash(state),
+            Name::Local(local) => Rc::as_ptr(local).hash(state),
+        }
+    }
+}
+
+impl Syntax {
+    fn from_datum(datum: &Datum) -> Syntax {
+        enum Step<'a> {
+            Datum(&'a Datum),
+            List { len: usize, dotted: bool, span: Span },
+            Vector { len: usize, span: Span },
+        }
+
+        let mut steps = vec![Step::Datum(datum)];
+        let mut outputs = Vec::new();
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Datum(datum) => match &datum.kind {
+                    DatumKind::Symbol(name) => outputs.push(Syntax::Identifier(Name::Symbol(Rc::from(&**name)), datum.span)),
+                    DatumKind::List { elements, tail } => {
+                        steps.push(Step::List { len: elements.len(), dotted: tail.is_some(), span: datum.span });
+                        steps.extend(tail.as_deref().map(Step::Datum));
+                        steps.extend(elements.iter().rev().map(Step::Datum));
+                    }
+                    DatumKind::Vector(elements) => {
+                        steps.push(Step::Vector { len: elements.len(), span: datum.span });
+                        steps.extend(elements.iter().rev().map(Step::Datum));
+                    }
+                    _ => outputs.push(Syntax::Datum(datum.clone())),
+                },
+                Step::List { len, dotted, span } => {
+                    let tail = if dotted { outputs.pop() } else { None };
+                    let elements = outputs.split_off(outputs.len() - len);
+                    outputs.push(Syntax::list(elements, tail, span));
+                }
+                Step::Vector { len, span } => {
+                    let elements = outputs.split_off(outputs.len() - len);
+                    outputs.push(Syntax::Vector(elements, span));
+                }
+            }
+        }
+
+        outputs.pop().expect("every datum has a syntax")
+    }
+
+    /// List of `elements` followed by `tail`, lists given as tail being appended.
+    fn list(mut elements: Vec<Syntax>, tail: Option<Syntax>, span: Span) -> Syntax {
+        match tail {
+            Some(Syntax::List { elements: rest, tail, .. }) => {
+                elements.extend(rest.iter().cloned());
+                Syntax::List { elements: elements.into(), tail, span }
+            }
+            Some(tail) if elements.is_empty() => tail,
+            tail => Syntax::List { elements: elements.into(), tail: tail.map(Box::new), span },
+        }
+    }
+
+    /// Keyword of the output, which lowering recognizes by name
+    fn keyword(keyword: &'static str, span: Span) -> Syntax {
+        Syntax::Identifier(Name::Symbol(Rc::from(keyword)), span)
+    }
+
+    /// `keyword` in place of the identifier heading the form
+    fn head_keyword(&self, keyword: &'static str) -> Syntax {
+        let span = match self {
+            Syntax::List { elements, .. } if !elements.is_empty() => elements[0].span(),
+            other => other.span(),
+        };
+        Syntax::keyword(keyword, span)
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Syntax::Identifier(_, span) | Syntax::List { span, .. } | Syntax::Vector(_, span) => *span,
+            Syntax::Datum(datum) => datum.span,
+        }
+    }
+
+    fn identifier(&self) -> Option<&Name> {
+        match self {
+            Syntax::Identifier(name, _) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Elements of a proper list
+    fn as_list(&self) -> Option<&[Syntax]> {
+        match self {
+            Syntax::List { elements, tail: None, .. } => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Elements following the head of a proper list
+    fn operands(&self) -> Option<&[Syntax]> {
+        self.as_list()?.get(1..)
+    }
+
+    /// Quoted datum, aliases being stripped of their environment
+    fn to_datum(&self) -> Datum {
+        self.datum(&HashMap::new())
+    }
+
+    /// Datum of the output, renamed local variables being given a name that no other identifier
+    /// of the output has, such as `x.2` for the second local variable or `x..2` if `x.2` is taken.
+    fn output(&self) -> Datum {
+        let mut identifiers = HashSet::new();
+        let mut renamed = Vec::new();
+        let mut pending = vec![self];
+        while let Some(syntax) = pending.pop() {
+            match syntax {
+                Syntax::Identifier(Name::Local(local), _) if local.renamed.get() => renamed.push(local.clone()),
+                Syntax::Identifier(name, _) => {
+                    identifiers.insert(name.symbol().clone());
+                }
+                Syntax::List { elements, tail, .. } => pending.extend(elements.iter().chain(tail.as_deref())),
+                Syntax::Vector(elements, _) => pending.extend(elements),
+                Syntax::Datum(_) => {}
+            }
+        }
+
+        let mut names = HashMap::new();
+        for local in renamed {
+            names.entry(local.number).or_insert_with(|| {
+                (1..)
+                    .map(|dots| Rc::from(format!("{}{}{}", local.name, ".".repeat(dots), local.number)))
+                    .find(|name| !identifiers.contains(name))
+                    .expect("names of the output are finitely many")
+            });
+        }
+
+        self.datum(&names)
+    }
+
+    /// Datum of the syntax, renamed local variables taking their name in `names` by number
+    fn datum(&self, names: &HashMap<usize, Rc<str>>) -> Datum {
+        enum Step<'a> {
+            Syntax(&'a Syntax),
+            List { len: usize, dotted: bool, span: Span },
+            Vector { len: usize, span: Span },
+        }
+
+        let mut steps = vec![Step::Syntax(self)];
+        let mut outputs: Vec<Datum> = Vec::new();
+        while let Some(step) = steps.pop() {
+            let (kind, span) = match step {
+                Step::Syntax(Syntax::Identifier(name, span)) => {
+                    let name = match name {
+                        Name::Local(local) if local.renamed.get() => names.get(&local.number).unwrap_or(&local.name),
+                        other => other.symbol(),
+                    };
+                    (DatumKind::Symbol((**name).into()), *span)
+                }
+                Step::Syntax(Syntax::List { elements, tail, span }) => {
+                    steps.push(Step::List { len: elements.len(), dotted: tail.is_some(), span: *span });
+                    steps.extend(tail.as_deref().map(Step::Syntax));
+                    steps.extend(elements.iter().rev().map(Step::Syntax));
+                    continue;
+                }
+                Step::Syntax(Syntax::Vector(elements, span)) => {
+                    steps.push(Step::Vector { len: elements.len(), span: *span });
+                    steps.extend(elements.iter().rev().map(Step::Syntax));
+                    continue;
+                }
+                Step::Syntax(Syntax::Datum(datum)) => {
+                    outputs.push(datum.clone());
+                    continue;
+                }
+                Step::List { len, dotted, span } => {
+                    let tail = if dotted { outputs.pop().map(Box::new) } else { None };
+                    let elements = outputs.split_off(outputs.len() - len);
+                    (DatumKind::List { elements, tail }, span)
+                }
+                Step::Vector { len, span } => (DatumKind::Vector(outputs.split_off(outputs.len() - len)), span),
+            };
+            outputs.push(Datum { kind, span });
+        }
+
+        outputs.pop().expect("every syntax has a datum")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expansion of the last form of `src`, the previous ones defining macros
+    fn expand(src: &str) -> Result<Expression> {
+        expand_with(&mut Expander::new(), src)
+    }
+
+    fn expand_with(expander: &mut Expander, src: &str) -> Result<Expression> {
+        let mut forms = Vec::new();
+        for datum in Datum::parse_str_all(src).unwrap() {
+            forms.push(expander.expand(&datum)?);
+        }
+        match forms.pop() {
+            Some(Form::Expression(expression)) => Ok(expression),
+            other => panic!("expected an expression, got {other:?}"),
+        }
+    }
+
+    fn span(src: &str, part: &str) -> (usize, usize) {
+        let start = src.rfind(part).unwrap();
+        (start, start + part.len())
+    }
+
+    #[test]
+    fn renaming() {
+        let src = "(define-syntax m (syntax-rules () ((_ e) (let ((t 1)) (+ t e))))) (lambda (t) (m t))";
+        let Expression::Lambda(lambda) = expand(src).unwrap() else {
+            panic!("expected a lambda")
+        };
+        assert_eq!("t", &*lambda.formals.required[0].name);
+        let Expression::Let(inner) = &lambda.body.expressions[0] else {
+            panic!("expected a let")
+        };
+        assert!(inner.bindings[0].variable.name.starts_with("t."));
+
+        // renamed apart from the identifiers of the program
+        let src = "(define-syntax m (syntax-rules () ((_ e) (let ((t 1)) (+ t e))))) (lambda (t) (m (+ t t.2)))";
+        let Expression::Lambda(lambda) = expand(src).unwrap() else {
+            panic!("expected a lambda")
+        };
+        let Expression::Let(inner) = &lambda.body.expressions[0] else {
+            panic!("expected a let")
+        };
+        assert_eq!("t..2", &*inner.bindings[0].variable.name);
+
+        // nothing captured, nothing renamed
+        let src = "(define-syntax m (syntax-rules () ((_ e) (let ((t 1)) (+ t e))))) (m 2)";
+        let Expression::Let(inner) = expand(src).unwrap() else {
+            panic!("expected a let")
+        };
+        assert_eq!("t", &*inner.bindings[0].variable.name);
+    }
+
+    #[test]
+    fn deep_expansion() {
+        let or = "(define-syntax my-or (syntax-rules () ((_) #f) ((_ e) e) ((_ e r ...) (let ((t e)) (if t t (my-or r ...))))))";
+        let src = format!("{or} (my-or {})", "#f ".repeat(800));
+        assert!(matches!(expand(&src), Ok(Expression::Let(_))));
+
+        let src = format!("{}x{}", "(let ((x 1)) (if x ".repeat(800), " x))".repeat(800));
+        assert!(matches!(expand(&src), Ok(Expression::Let(_))));
+    }
+
+    #[test]
+    fn provenance() {
+        let src = "(define-syntax m (syntax-rules () ((_ e) (list e 'x)))) (m (car 1))";
+        let Expression::Call(call) = expand(src).unwrap() else {
+            panic!("expected a call")
+        };
+        let use_span = span(src, "(m (car 1))");
+        assert_eq!(use_span, (call.span.start(), call.span.end()));
+        assert_eq!(use_span, (call.operator.span().start(), call.operator.span().end()));
+        assert_eq!(
+            span(src, "(car 1)"),
+            (call.operands[0].span().start(), call.operands[0].span().end())
+        );
+    }
+
+    #[test]
+    fn libraries() {
+        let mut expander = Expander::new();
+        expander.add_builtin_library(&["base"], &["define", "lambda", "+"]);
+        let src = "(define-library (m) (export (rename f g)) (import (base)) (begin (define n 1) (define (f x) (+ x n))))";
+        let form = expander.expand(&Datum::parse_str(src).unwrap()).unwrap();
+        assert!(matches!(form, Form::Definition(Definition::Begin { ref definitions, .. }) if definitions.is_empty()));
+
+        let [library] = &expander.take_loaded()[..] else {
+            panic!("expected one library")
+        };
+        assert_eq!("(m)", &*library.name);
+        let Form::Definition(Definition::Variable(VariableDefinition { variable, value, .. })) = &library.forms[1] else {
+            panic!("expected a definition")
+        };
+        assert_eq!("(m) f", &*variable.name);
+        let Expression::Lambda(lambda) = &**value else {
+            panic!("expected a lambda")
+        };
+        assert!(matches!(&lambda.body.expressions[0], Expression::Call(call)
+            if matches!(&call.operands[..], [Expression::Variable(x), Expression::Variable(n)] if &*x.name == "x" && &*n.name == "(m) n")));
+
+        let src = "(import (prefix (m) m:)) (m:g 1)";
+        let Expression::Call(call) = expand_with(&mut expander, src).unwrap() else {
+            panic!("expected a call")
+        };
+        assert!(matches!(&*call.operator, Expression::Variable(g) if &*g.name == "(m) f"));
+
+        // imported variables can't be assigned, unless shadowed or redefined
+        let src = "(set! m:g 2)";
+        let SyntaxError::ImportedAssignment(name, assigned) = expand_with(&mut expander, src).unwrap_err() else {
+            panic!("expected an imported assignment")
+        };
+        assert_eq!(("m:g", span(src, "m:g")), (&*name, (assigned.start(), assigned.end())));
+        assert!(expand_with(&mut expander, "(lambda (m:g) (set! m:g 2))").is_ok());
+        assert!(expand_with(&mut expander, "(define m:g 1) (set! m:g 2)").is_ok());
+
+        assert!(matches!(
+            expand_with(&mut expander, "(import (only (m) f))"),
+            Err(SyntaxError::MissingImport(..))
+        ));
+        assert!(matches!(
+            expand_with(&mut expander, "(import (n))"),
+            Err(SyntaxError::UnknownLibrary(..))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let src = "(define-syntax m (syntax-rules () ((_ a) a))) (m)";
+        let SyntaxError::NoMatchingRule(use_span) = expand(src).unwrap_err() else {
+            panic!("expected no matching rule")
+        };
+        assert_eq!(span(src, "(m)"), (use_span.start(), use_span.end()));
+
+        let src = "(define-syntax m (syntax-rules () ((_ a ...) a))) 1";
+        let SyntaxError::EllipsisDepth(depth_span) = expand(src).unwrap_err() else {
+            panic!("expected a depth error")
+        };
+        assert_eq!(span(src, "a)"), (depth_span.start(), depth_span.start() + 2));
+
+        let src = "(define-syntax m (syntax-rules () ((_ (a ...) (b ...)) '((a b) ...)))) (m (1 2) (3))";
+        assert!(matches!(expand(src), Err(SyntaxError::EllipsisLength(_))));
+        assert!(matches!(
+            expand("(define-syntax m (syntax-rules () ((_ a a) a))) 1"),
+            Err(SyntaxError::DuplicateIdentifier(..))
+        ));
+        assert!(matches!(
+            expand("(define-syntax m (syntax-rules (1) ((_) 1))) 1"),
+            Err(SyntaxError::MalformedForm { .. })
+        ));
+        assert!(matches!(
+            expand("(define-syntax m (syntax-rules () ((_) 1))) m"),
+            Err(SyntaxError::MisplacedKeyword(..))
+        ));
+    }
+}
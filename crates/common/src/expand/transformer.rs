@@ -0,0 +1,524 @@
+//! `syntax-rules` transformers, matching macro uses against patterns and instantiating templates
+
+// Names of locals hash by identity, their `renamed` flag never affecting the key
+#![allow(clippy::mutable_key_type)]
+
+use super::*;
+
+/// Macro defined by `syntax-rules`
+pub(super) struct Transformer {
+    /// Pattern and template of each rule, tried in order
+    rules: Vec<(Pattern, Template)>,
+    /// Environment of the macro definition, in which template identifiers are resolved
+    environment: Environment,
+    /// Top level of the macro definition, see [`Alias::top_level`]
+    top_level: Rc<TopLevel>,
+}
+
+/// Pattern of a rule, the keyword position being left out
+enum Pattern {
+    /// `_`, matching anything
+    Underscore,
+    /// Pattern variable, matching anything
+    Variable(Name),
+    /// Identifier of the literals, matching identifiers of the same binding
+    Literal(Binding),
+    /// Matching an equal datum
+    Datum(DatumKind),
+    /// Proper or dotted list, the tail matching the remaining elements
+    List { elements: Sequence, tail: Option<Box<Pattern>> },
+    /// `#( <pattern>* )`
+    Vector(Sequence),
+}
+
+/// Elements of a list or vector pattern
+struct Sequence {
+    /// Patterns preceding the ellipsis, if any
+    before: Vec<Pattern>,
+    /// Pattern followed by an ellipsis, along with the patterns after it
+    repeated: Option<(Box<Pattern>, Vec<Pattern>)>,
+}
+
+/// Template of a rule
+enum Template {
+    /// Replaced by the part of the use it matched
+    Variable(Name),
+    /// Inserted identifier, renamed to an alias by each expansion
+    Identifier(Name),
+    /// Elements along with the number of ellipses following them
+    List {
+        elements: Vec<(Template, usize)>,
+        tail: Option<Box<Template>>,
+    },
+    /// `#( <element>* )`
+    Vector(Vec<(Template, usize)>),
+    /// Inserted datum
+    Datum(Datum),
+}
+
+/// Part of a macro use matched by a pattern variable, nested once for each ellipsis following it
+#[derive(Clone)]
+enum Match {
+    One(Syntax),
+    Many(Vec<Match>),
+}
+
+type Bindings = HashMap<Name, Match>;
+
+/// Ellipsis and literals of the `syntax-rules` form being compiled
+struct Specification<'a> {
+    ellipsis: Binding,
+    literals: &'a [Name],
+    environment: &'a Environment,
+}
+
+impl Expander {
+    /// Compiles `(syntax-rules <ellipsis>? (<literal>*) <syntax rule>*)`, closed over
+    /// `environment`.
+    pub(super) fn transformer(&self, specification: &Syntax, environment: &Environment) -> Result<Rc<Transformer>> {
+        let malformed = |syntax: &Syntax| SyntaxError::MalformedForm("syntax-rules", syntax.span());
+
+        let Some([keyword, operands @ ..]) = specification.as_list() else {
+            return Err(malformed(specification));
+        };
+        if !matches!(
+            keyword.identifier().map(|name| self.resolve(name, environment)),
+            Some(Binding::Keyword("syntax-rules"))
+        ) {
+            return Err(malformed(specification));
+        }
+
+        let (ellipsis, literals, rules) = match operands {
+            [Syntax::Identifier(ellipsis, _), literals, rules @ ..] => (self.resolve(ellipsis, environment), literals, rules),
+            [literals, rules @ ..] => (Binding::Keyword("..."), literals, rules),
+            [] => return Err(malformed(specification)),
+        };
+        let literals = literals
+            .as_list()
+            .ok_or_else(|| malformed(literals))?
+            .iter()
+            .map(|literal| literal.identifier().cloned().ok_or_else(|| malformed(literal)))
+            .collect::<Result<Vec<_>>>()?;
+        let specification = Specification { ellipsis, literals: &literals, environment };
+
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let Some([Syntax::List { elements, tail, span }, template]) = rule.as_list() else {
+                    return Err(malformed(rule));
+                };
+                let pattern = Syntax::list(elements[1..].to_vec(), tail.as_deref().cloned(), *span);
+
+                let mut variables = Vec::new();
+                let pattern = self.pattern(&pattern, &specification, 0, &mut variables)?;
+                let template = self.template(template, &specification, &variables, 0, false)?;
+                Ok((pattern, template))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Rc::new(Transformer {
+            rules,
+            environment: environment.clone(),
+            top_level: self.top_level.clone(),
+        }))
+    }
+
+    /// Expands a use of the macro with the first rule whose pattern it matches.
+    pub(super) fn transcribe(&mut self, transformer: &Transformer, form: &Syntax, environment: &Environment) -> Result<Syntax> {
+        let Syntax::List { elements, tail, span } = form else {
+            unreachable!("macro uses are lists")
+        };
+        let operands = Syntax::list(elements[1..].to_vec(), tail.as_deref().cloned(), *span);
+
+        for (pattern, template) in &transformer.rules {
+            let mut bindings = HashMap::new();
+            if self.matches(pattern, &operands, environment, &mut bindings) {
+                return transformer.instantiate(template, &bindings, &mut HashMap::new(), *span);
+            }
+        }
+
+        Err(SyntaxError::NoMatchingRule(*span))
+    }
+
+    /// Whether the syntax is the ellipsis identifier, unless listed as a literal
+    fn is_ellipsis(&self, syntax: &Syntax, specification: &Specification) -> bool {
+        syntax.identifier().is_some_and(|name| {
+            !specification.literals.contains(name) && self.resolve(name, specification.environment).same(&specification.ellipsis)
+        })
+    }
+
+    /// Compiles a pattern, collecting its variables along with their ellipsis depth.
+    fn pattern(&self, syntax: &Syntax, specification: &Specification, depth: usize, variables: &mut Vec<(Name, usize)>) -> Result<Pattern> {
+        Ok(match syntax {
+            Syntax::Identifier(name, _) if specification.literals.contains(name) => {
+                Pattern::Literal(self.resolve(name, specification.environment))
+            }
+            Syntax::Identifier(_, span) if self.is_ellipsis(syntax, specification) => {
+                return Err(SyntaxError::MalformedForm("syntax-rules", *span));
+            }
+            Syntax::Identifier(name, span) => match self.resolve(name, specification.environment) {
+                Binding::Keyword("_") => Pattern::Underscore,
+                _ if variables.iter().any(|(variable, _)| variable == name) => return Err(SyntaxError::DuplicateIdentifier(*span)),
+                _ => {
+                    variables.push((name.clone(), depth));
+                    Pattern::Variable(name.clone())
+                }
+            },
+            Syntax::List { elements, tail, .. } => Pattern::List {
+                elements: self.sequence(elements, specification, depth, variables)?,
+                tail: tail
+                    .as_deref()
+                    .map(|tail| self.pattern(tail, specification, depth, variables))
+                    .transpose()?
+                    .map(Box::new),
+            },
+            Syntax::Vector(elements, _) => Pattern::Vector(self.sequence(elements, specification, depth, variables)?),
+            Syntax::Datum(datum) => Pattern::Datum(datum.kind.clone()),
+        })
+    }
+
+    /// Elements of a list or vector pattern, at most one of them being followed by an ellipsis
+    fn sequence(
+        &self,
+        elements: &[Syntax],
+        specification: &Specification,
+        depth: usize,
+        variables: &mut Vec<(Name, usize)>,
+    ) -> Result<Sequence> {
+        let mut sequence = Sequence { before: Vec::new(), repeated: None };
+
+        let mut index = 0;
+        while index < elements.len() {
+            match elements.get(index + 1) {
+                Some(ellipsis) if self.is_ellipsis(ellipsis, specification) => {
+                    if sequence.repeated.is_some() {
+                        return Err(SyntaxError::MalformedForm("syntax-rules", ellipsis.span()));
+                    }
+                    let repeated = self.pattern(&elements[index], specification, depth + 1, variables)?;
+                    sequence.repeated = Some((Box::new(repeated), Vec::new()));
+                    index += 2;
+                }
+                _ => {
+                    let pattern = self.pattern(&elements[index], specification, depth, variables)?;
+                    match &mut sequence.repeated {
+                        Some((_, after)) => after.push(pattern),
+                        None => sequence.before.push(pattern),
+                    }
+                    index += 1;
+                }
+            }
+        }
+
+        Ok(sequence)
+    }
+
+    /// Compiles a template, checking that pattern variables are used at their ellipsis depth.
+    ///
+    /// Ellipses are taken literally within an escaped template, written `(<ellipsis> <template>)`.
+    fn template(
+        &self,
+        syntax: &Syntax,
+        specification: &Specification,
+        variables: &[(Name, usize)],
+        depth: usize,
+        escaped: bool,
+    ) -> Result<Template> {
+        Ok(match syntax {
+            Syntax::Identifier(name, span) => match variables.iter().find(|(variable, _)| variable == name) {
+                Some((_, variable_depth)) if *variable_depth > depth => return Err(SyntaxError::EllipsisDepth(*span)),
+                Some(_) => Template::Variable(name.clone()),
+                None if !escaped && self.is_ellipsis(syntax, specification) => {
+                    return Err(SyntaxError::MalformedForm("syntax-rules", *span));
+                }
+                None => Template::Identifier(name.clone()),
+            },
+            Syntax::List { elements, tail, .. } => {
+                if let ([ellipsis, template], None, false) = (&elements[..], tail, escaped) {
+                    if self.is_ellipsis(ellipsis, specification) {
+                        return self.template(template, specification, variables, depth, true);
+                    }
+                }
+
+                Template::List {
+                    elements: self.template_elements(elements, specification, variables, depth, escaped)?,
+                    tail: tail
+                        .as_deref()
+                        .map(|tail| self.template(tail, specification, variables, depth, escaped))
+                        .transpose()?
+                        .map(Box::new),
+                }
+            }
+            Syntax::Vector(elements, _) => Template::Vector(self.template_elements(elements, specification, variables, depth, escaped)?),
+            Syntax::Datum(datum) => Template::Datum(datum.clone()),
+        })
+    }
+
+    fn template_elements(
+        &self,
+        elements: &[Syntax],
+        specification: &Specification,
+        variables: &[(Name, usize)],
+        depth: usize,
+        escaped: bool,
+    ) -> Result<Vec<(Template, usize)>> {
+        let mut templates = Vec::new();
+
+        let mut index = 0;
+        while index < elements.len() {
+            let element = &elements[index];
+            let ellipses = match escaped {
+                true => 0,
+                false => elements[index + 1..]
+                    .iter()
+                    .take_while(|next| self.is_ellipsis(next, specification))
+                    .count(),
+            };
+            let template = self.template(element, specification, variables, depth + ellipses, escaped)?;
+
+            // the deepest variable is repeated by every ellipsis
+            if ellipses > 0 {
+                let mut names = Vec::new();
+                template.variables(&mut names);
+                let deepest = names
+                    .iter()
+                    .filter_map(|name| variables.iter().find(|(variable, _)| variable == *name))
+                    .map(|(_, depth)| *depth)
+                    .max();
+                if deepest.is_none_or(|deepest| deepest < depth + ellipses) {
+                    return Err(SyntaxError::EllipsisDepth(element.span()));
+                }
+            }
+
+            templates.push((template, ellipses));
+            index += 1 + ellipses;
+        }
+
+        Ok(templates)
+    }
+
+    fn matches(&self, pattern: &Pattern, syntax: &Syntax, environment: &Environment, bindings: &mut Bindings) -> bool {
+        match (pattern, syntax) {
+            (Pattern::Underscore, _) => true,
+            (Pattern::Variable(name), _) => {
+                bindings.insert(name.clone(), Match::One(syntax.clone()));
+                true
+            }
+            (Pattern::Literal(binding), Syntax::Identifier(name, _)) => self.resolve(name, environment).same(binding),
+            (Pattern::Datum(kind), Syntax::Datum(datum)) => *kind == datum.kind,
+            (Pattern::List { elements, tail }, Syntax::List { elements: input, tail: input_tail, span }) => self.matches_sequence(
+                elements,
+                tail.as_deref(),
+                input,
+                input_tail.as_deref(),
+                *span,
+                environment,
+                bindings,
+            ),
+            (Pattern::Vector(elements), Syntax::Vector(input, span)) => {
+                self.matches_sequence(elements, None, input, None, *span, environment, bindings)
+            }
+            _ => false,
+        }
+    }
+
+    /// Matches the elements of a list or vector, the tail pattern matching the elements left and
+    /// the tail of the input.
+    #[allow(clippy::too_many_arguments)]
+    fn matches_sequence(
+        &self,
+        sequence: &Sequence,
+        tail: Option<&Pattern>,
+        input: &[Syntax],
+        input_tail: Option<&Syntax>,
+        span: Span,
+        environment: &Environment,
+        bindings: &mut Bindings,
+    ) -> bool {
+        let after_len = sequence.repeated.as_ref().map_or(0, |(_, after)| after.len());
+        if input.len() < sequence.before.len() + after_len {
+            return false;
+        }
+
+        let (before, mut rest) = input.split_at(sequence.before.len());
+        let matches_all = |patterns: &[Pattern], input: &[Syntax], bindings: &mut Bindings| {
+            patterns
+                .iter()
+                .zip(input)
+                .all(|(pattern, syntax)| self.matches(pattern, syntax, environment, bindings))
+        };
+        if !matches_all(&sequence.before, before, bindings) {
+            return false;
+        }
+
+        if let Some((repeated, after)) = &sequence.repeated {
+            let (repetitions, after_input) = rest.split_at(rest.len() - after.len());
+
+            let mut matches = Vec::with_capacity(repetitions.len());
+            for syntax in repetitions {
+                let mut repetition = HashMap::new();
+                if !self.matches(repeated, syntax, environment, &mut repetition) {
+                    return false;
+                }
+                matches.push(repetition);
+            }
+
+            let mut names = Vec::new();
+            repeated.variables(&mut names);
+            for name in names {
+                let sequence = matches
+                    .iter_mut()
+                    .map(|repetition| repetition.remove(name).expect("bound by every repetition"))
+                    .collect();
+                bindings.insert(name.clone(), Match::Many(sequence));
+            }
+
+            if !matches_all(after, after_input, bindings) {
+                return false;
+            }
+            rest = &[];
+        }
+
+        match tail {
+            Some(tail) => self.matches(tail, &Syntax::list(rest.to_vec(), input_tail.cloned(), span), environment, bindings),
+            None => rest.is_empty() && input_tail.is_none(),
+        }
+    }
+}
+
+impl Transformer {
+    /// Instantiates a template, inserted identifiers being renamed to the aliases of `renames`
+    /// and inserted data taking the span of the macro use.
+    fn instantiate(&self, template: &Template, bindings: &Bindings, renames: &mut HashMap<Name, Name>, span: Span) -> Result<Syntax> {
+        Ok(match template {
+            Template::Variable(name) => match &bindings[name] {
+                Match::One(syntax) => syntax.clone(),
+                Match::Many(_) => unreachable!("ellipsis depth checked when defining the macro"),
+            },
+            Template::Identifier(name) => {
+                let alias = renames.entry(name.clone()).or_insert_with(|| {
+                    Name::Alias(Rc::new(Alias {
+                        name: name.clone(),
+                        environment: self.environment.clone(),
+                        top_level: self.top_level.clone(),
+                        binding: OnceCell::new(),
+                        bound: Cell::new(false),
+                    }))
+                });
+                Syntax::Identifier(alias.clone(), span)
+            }
+            Template::List { elements, tail } => {
+                let elements = self.instantiate_elements(elements, bindings, renames, span)?;
+                let tail = tail
+                    .as_deref()
+                    .map(|tail| self.instantiate(tail, bindings, renames, span))
+                    .transpose()?;
+                Syntax::list(elements, tail, span)
+            }
+            Template::Vector(elements) => Syntax::Vector(self.instantiate_elements(elements, bindings, renames, span)?, span),
+            Template::Datum(datum) => Syntax::Datum(Datum { kind: datum.kind.clone(), span }),
+        })
+    }
+
+    fn instantiate_elements(
+        &self,
+        elements: &[(Template, usize)],
+        bindings: &Bindings,
+        renames: &mut HashMap<Name, Name>,
+        span: Span,
+    ) -> Result<Vec<Syntax>> {
+        let mut output = Vec::new();
+        for (template, ellipses) in elements {
+            self.repeat(template, *ellipses, bindings, renames, span, &mut output)?;
+        }
+
+        Ok(output)
+    }
+
+    /// Instantiates a template followed by `ellipses` ellipses, once for each element of the
+    /// sequences matched by its variables.
+    fn repeat(
+        &self,
+        template: &Template,
+        ellipses: usize,
+        bindings: &Bindings,
+        renames: &mut HashMap<Name, Name>,
+        span: Span,
+        output: &mut Vec<Syntax>,
+    ) -> Result<()> {
+        if ellipses == 0 {
+            output.push(self.instantiate(template, bindings, renames, span)?);
+            return Ok(());
+        }
+
+        let mut names = Vec::new();
+        template.variables(&mut names);
+        let sequences = names
+            .into_iter()
+            .filter_map(|name| match &bindings[name] {
+                Match::Many(matches) => Some((name, matches)),
+                Match::One(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let len = sequences.first().map_or(0, |(_, matches)| matches.len());
+        if sequences.iter().any(|(_, matches)| matches.len() != len) {
+            return Err(SyntaxError::EllipsisLength(span));
+        }
+
+        // the sequences are replaced by one of their elements in each repetition, and left out of
+        // the copy of the other bindings
+        let mut repetition = bindings
+            .iter()
+            .filter(|(name, _)| !sequences.iter().any(|(sequence, _)| sequence == name))
+            .map(|(name, matched)| (name.clone(), matched.clone()))
+            .collect::<Bindings>();
+        for index in 0..len {
+            for (name, matches) in &sequences {
+                repetition.insert((*name).clone(), matches[index].clone());
+            }
+            self.repeat(template, ellipses - 1, &repetition, renames, span, output)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Pattern {
+    fn variables<'a>(&'a self, names: &mut Vec<&'a Name>) {
+        let sequence_variables = |sequence: &'a Sequence, names: &mut Vec<&'a Name>| {
+            let after = sequence
+                .repeated
+                .iter()
+                .flat_map(|(repeated, after)| std::iter::once(&**repeated).chain(after));
+            sequence.before.iter().chain(after).for_each(|pattern| pattern.variables(names));
+        };
+
+        match self {
+            Pattern::Variable(name) => names.push(name),
+            Pattern::List { elements, tail } => {
+                sequence_variables(elements, names);
+                if let Some(tail) = tail {
+                    tail.variables(names);
+                }
+            }
+            Pattern::Vector(elements) => sequence_variables(elements, names),
+            Pattern::Underscore | Pattern::Literal(_) | Pattern::Datum(_) => {}
+        }
+    }
+}
+
+impl Template {
+    fn variables<'a>(&'a self, names: &mut Vec<&'a Name>) {
+        match self {
+            Template::Variable(name) => names.push(name),
+            Template::List { elements, tail } => {
+                elements.iter().for_each(|(element, _)| element.variables(names));
+                if let Some(tail) = tail {
+                    tail.variables(names);
+                }
+            }
+            Template::Vector(elements) => elements.iter().for_each(|(element, _)| element.variables(names)),
+            Template::Identifier(_) | Template::Datum(_) => {}
+        }
+    }
+}
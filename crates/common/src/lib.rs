@@ -1,7 +1,14 @@
 //! Pluine Language parsing to an AST.
 
 mod parser;
-pub(crate) use parser::RuineParser;
+pub use parser::ParseError;
 
 mod ast;
-pub(crate) use ast::*;
+pub use ast::*;
+
+mod lower;
+pub use lower::SyntaxError;
+pub(crate) use lower::KEYWORDS;
+
+mod expand;
+pub use expand::{Expander, LoadedLibrary};
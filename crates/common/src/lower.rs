@@ -0,0 +1,1292 @@
+//! Lowering of data to the AST, following the grammar of R7RS 7.1.3 and 7.1.6.
+
+use std::collections::HashSet;
+
+use pluine_lex::{
+    diagnostic::{Diagnostic, ToDiagnostic},
+    span::Span,
+};
+use thiserror::Error;
+
+use crate::*;
+
+/// Syntactic keywords and auxiliary syntax, which can't be used as variables.
+pub(crate) const KEYWORDS: &[&str] = &[
+    "quote",
+    "quasiquote",
+    "unquote",
+    "unquote-splicing",
+    "lambda",
+    "if",
+    "set!",
+    "include",
+    "include-ci",
+    "cond",
+    "case",
+    "and",
+    "or",
+    "when",
+    "unless",
+    "let",
+    "let*",
+    "letrec",
+    "letrec*",
+    "let-values",
+    "let*-values",
+    "begin",
+    "do",
+    "delay",
+    "delay-force",
+    "parameterize",
+    "guard",
+    "case-lambda",
+    "let-syntax",
+    "letrec-syntax",
+    "syntax-rules",
+    "define",
+    "define-values",
+    "define-record-type",
+    "define-syntax",
+    "else",
+    "=>",
+    "...",
+    "_",
+];
+
+type Result<T> = std::result::Result<T, SyntaxError>;
+
+impl Expression {
+    /// Lowers a datum to an expression, definitions being rejected.
+    pub fn from_datum(datum: &Datum) -> Result<Self> {
+        Ok(run(Subform::Expression(datum))?.expression())
+    }
+}
+
+impl Form {
+    /// Lowers a datum to a definition or an expression.
+    pub fn from_datum(datum: &Datum) -> Result<Self> {
+        Ok(match run(subform(datum))? {
+            Node::Definition(definition) => Form::Definition(definition),
+            node => Form::Expression(node.expression()),
+        })
+    }
+
+    /// Reads and lowers every form of a program.
+    pub fn parse_all(src: &str) -> std::result::Result<Vec<Self>, ParseError> {
+        let data = Datum::parse_str_all(src)?;
+        Ok(data.iter().map(Form::from_datum).collect::<Result<_>>()?)
+    }
+}
+
+/// Subform left for [`run`] by the lowering of the form containing it
+#[derive(Clone, Copy)]
+enum Subform<'a> {
+    Expression(&'a Datum),
+    Definition(&'a Datum),
+    /// Forms of a body, along with the span of the enclosing form
+    Body(&'a [Datum], Span),
+}
+
+/// Lowered subform
+enum Node {
+    Expression(Expression),
+    Definition(Definition),
+    Body(Body),
+}
+
+/// Form checked against its syntax, built from the nodes of its subforms once [`run`] lowered
+/// them in the same order
+struct Lowering<'a, T> {
+    subforms: Vec<Subform<'a>>,
+    build: Box<dyn FnOnce(&mut Nodes) -> T + 'a>,
+}
+
+/// Nodes of the subforms of a [`Lowering`], taken in order by its `build` function
+struct Nodes(std::vec::IntoIter<Node>);
+
+/// Lowers a form with an explicit stack of lowerings rather than recursion, so that deeply nested
+/// forms such as those of macro expansions can't overflow the stack.
+///
+/// Subforms are lowered in order and depth first, each lowering being built once the nodes of
+/// all of its subforms are.
+fn run(subform: Subform) -> Result<Node> {
+    let mut stack = vec![(lower(subform)?, Vec::new())];
+    loop {
+        let (lowering, nodes) = stack.last_mut().expect("popped once built");
+        if let Some(&subform) = lowering.subforms.get(nodes.len()) {
+            stack.push((lower(subform)?, Vec::new()));
+            continue;
+        }
+
+        let (lowering, nodes) = stack.pop().expect("checked above");
+        let node = (lowering.build)(&mut Nodes(nodes.into_iter()));
+        match stack.last_mut() {
+            Some((_, parent)) => parent.push(node),
+            None => return Ok(node),
+        }
+    }
+}
+
+/// Lowers the subform itself, leaving its own subforms
+fn lower(subform: Subform) -> Result<Lowering<Node>> {
+    Ok(match subform {
+        Subform::Expression(datum) => expression(datum)?.map(Node::Expression),
+        Subform::Definition(datum) => definition(datum)?.map(Node::Definition),
+        Subform::Body(forms, span) => body(forms, span)?.map(Node::Body),
+    })
+}
+
+/// Definition or expression
+fn subform(datum: &Datum) -> Subform<'_> {
+    match keyword_form(datum).is_some_and(is_definition) {
+        true => Subform::Definition(datum),
+        false => Subform::Expression(datum),
+    }
+}
+
+impl<'a, T: 'a> Lowering<'a, T> {
+    fn new(subforms: Vec<Subform<'a>>, build: impl FnOnce(&mut Nodes) -> T + 'a) -> Self {
+        Lowering { subforms, build: Box::new(build) }
+    }
+
+    /// Form without subforms
+    fn done(value: T) -> Self {
+        Lowering::new(Vec::new(), move |_| value)
+    }
+
+    fn map<U: 'a>(self, f: impl FnOnce(T) -> U + 'a) -> Lowering<'a, U> {
+        let build = self.build;
+        Lowering::new(self.subforms, move |nodes| f(build(nodes)))
+    }
+
+    /// Both lowerings, the subforms of `self` coming first
+    fn zip<U: 'a>(self, other: Lowering<'a, U>) -> Lowering<'a, (T, U)> {
+        let (build, other_build) = (self.build, other.build);
+        let mut subforms = self.subforms;
+        subforms.extend(other.subforms);
+        Lowering::new(subforms, move |nodes| (build(nodes), other_build(nodes)))
+    }
+
+    /// Every lowering, in order
+    fn all(lowerings: Vec<Self>) -> Lowering<'a, Vec<T>> {
+        let mut subforms = Vec::new();
+        let mut builds = Vec::new();
+        for lowering in lowerings {
+            subforms.extend(lowering.subforms);
+            builds.push(lowering.build);
+        }
+        Lowering::new(subforms, move |nodes| builds.into_iter().map(|build| build(nodes)).collect())
+    }
+}
+
+impl Node {
+    fn expression(self) -> Expression {
+        match self {
+            Node::Expression(expression) => expression,
+            _ => unreachable!("expression subforms are lowered to expressions"),
+        }
+    }
+}
+
+impl Nodes {
+    fn expression(&mut self) -> Expression {
+        self.0.next().expect("one node per subform").expression()
+    }
+
+    fn boxed(&mut self) -> Box<Expression> {
+        Box::new(self.expression())
+    }
+
+    fn expressions(&mut self, len: usize) -> Vec<Expression> {
+        (0..len).map(|_| self.expression()).collect()
+    }
+
+    fn definitions(&mut self, len: usize) -> Vec<Definition> {
+        (0..len)
+            .map(|_| match self.0.next() {
+                Some(Node::Definition(definition)) => definition,
+                _ => unreachable!("definition subforms are lowered to definitions"),
+            })
+            .collect()
+    }
+
+    fn body(&mut self) -> Body {
+        match self.0.next() {
+            Some(Node::Body(body)) => body,
+            _ => unreachable!("bodies are lowered to bodies"),
+        }
+    }
+}
+
+/// Error returned when lowering a [`Datum`]
+#[derive(Debug, PartialEq, Error)]
+pub enum SyntaxError {
+    /// Keyword of the form, inner span points to the part of the form not matching its syntax
+    #[error("malformed {0} form")]
+    MalformedForm(&'static str, Span),
+    /// Inner span points to the `()`
+    #[error("empty combination, `()` is not an expression")]
+    EmptyCombination(Span),
+    /// Inner span points to the dotted list
+    #[error("procedure calls can't be dotted lists")]
+    ImproperCall(Span),
+    /// Inner span points to the keyword, or to the form it introduces
+    #[error("syntactic keyword used out of place")]
+    MisplacedKeyword(Span),
+    /// Inner span points to the definition
+    #[error("definition not allowed here")]
+    UnexpectedDefinition(Span),
+    /// Inner span points to the form whose body has no expression
+    #[error("body must end with at least one expression")]
+    MissingExpression(Span),
+    /// Inner span points to the repeated identifier
+    #[error("identifier bound more than once")]
+    DuplicateIdentifier(Span),
+    /// Inner span points to the macro use
+    #[error("no syntax rule matches the macro use")]
+    NoMatchingRule(Span),
+    /// Inner span points to the pattern variable, or to the subtemplate followed by an ellipsis
+    #[error("pattern variable used at the wrong ellipsis depth")]
+    EllipsisDepth(Span),
+    /// Inner span points to the macro use
+    #[error("pattern variables repeated by the same ellipsis matched sequences of different lengths")]
+    EllipsisLength(Span),
+    /// Library name, inner span points to it in the import set
+    #[error("library `{0}` not found")]
+    UnknownLibrary(Box<str>, Span),
+    /// Library name, inner span points to the import set closing the cycle
+    #[error("library `{0}` imports itself, directly or through other libraries")]
+    ImportCycle(Box<str>, Span),
+    /// Identifier name, inner span points to it in the `only`, `except` or `rename` import set
+    #[error("`{0}` is not imported by the inner import set")]
+    MissingImport(Box<str>, Span),
+    /// Identifier name, inner span points to it in the export specification
+    #[error("exported identifier `{0}` is neither defined nor imported by the library")]
+    UnboundExport(Box<str>, Span),
+    /// Identifier name, inner span points to the identifier in the `set!` form
+    #[error("`{0}` is imported and can't be assigned")]
+    ImportedAssignment(Box<str>, Span),
+    /// File to include, or of a library, that could not be read
+    #[error("can't read `{path}`: {reason}")]
+    UnreadableFile {
+        /// Path as resolved
+        path: Box<str>,
+        /// Description of the I/O error
+        reason: Box<str>,
+        /// Points to the file name in `include` declarations, or to the library name
+        span: Span,
+    },
+    /// Error within a file read by the expander, the spans of `error` pointing into that file
+    #[error("{error} (in `{path}`)")]
+    InFile {
+        /// Path of the included or library file
+        path: Box<str>,
+        /// See [`ParseError`]
+        error: Box<ParseError>,
+    },
+}
+
+/// Error codes:
+///
+/// | Code  | Error                                 |
+/// |-------|---------------------------------------|
+/// | C0201 | `SyntaxError::MalformedForm`          |
+/// | C0202 | `SyntaxError::EmptyCombination`       |
+/// | C0203 | `SyntaxError::ImproperCall`           |
+/// | C0204 | `SyntaxError::MisplacedKeyword`       |
+/// | C0205 | `SyntaxError::UnexpectedDefinition`   |
+/// | C0206 | `SyntaxError::MissingExpression`      |
+/// | C0207 | `SyntaxError::DuplicateIdentifier`    |
+/// | C0208 | `SyntaxError::NoMatchingRule`         |
+/// | C0209 | `SyntaxError::EllipsisDepth`          |
+/// | C0210 | `SyntaxError::EllipsisLength`         |
+/// | C0211 | `SyntaxError::UnknownLibrary`         |
+/// | C0212 | `SyntaxError::ImportCycle`            |
+/// | C0213 | `SyntaxError::MissingImport`          |
+/// | C0214 | `SyntaxError::UnboundExport`          |
+/// | C0215 | `SyntaxError::UnreadableFile`         |
+/// | C0216 | `SyntaxError::ImportedAssignment`     |
+///
+/// Errors within other files keep the code of the inner error, noting the file they occurred in.
+impl ToDiagnostic for SyntaxError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, span, label) = match self {
+            SyntaxError::InFile { path, error } => return error.to_diagnostic().with_note(format!("in `{path}`")),
+            SyntaxError::MalformedForm(_, span) => ("C0201", span, "malformed"),
+            SyntaxError::EmptyCombination(span) => ("C0202", span, "empty"),
+            SyntaxError::ImproperCall(span) => ("C0203", span, "dotted"),
+            SyntaxError::MisplacedKeyword(span) => ("C0204", span, "misplaced"),
+            SyntaxError::UnexpectedDefinition(span) => ("C0205", span, "definition"),
+            SyntaxError::MissingExpression(span) => ("C0206", span, "no expression"),
+            SyntaxError::DuplicateIdentifier(span) => ("C0207", span, "already bound"),
+            SyntaxError::NoMatchingRule(span) => ("C0208", span, "no matching rule"),
+            SyntaxError::EllipsisDepth(span) => ("C0209", span, "wrong depth"),
+            SyntaxError::EllipsisLength(span) => ("C0210", span, "expanded here"),
+            SyntaxError::UnknownLibrary(_, span) => ("C0211", span, "not found"),
+            SyntaxError::ImportCycle(_, span) => ("C0212", span, "imported here"),
+            SyntaxError::MissingImport(_, span) => ("C0213", span, "not imported"),
+            SyntaxError::UnboundExport(_, span) => ("C0214", span, "unbound"),
+            SyntaxError::UnreadableFile { span, .. } => ("C0215", span, "unreadable"),
+            SyntaxError::ImportedAssignment(_, span) => ("C0216", span, "imported"),
+        };
+
+        let diagnostic = Diagnostic::error(code, self.to_string()).with_primary_label(*span, Some(label));
+        match self {
+            SyntaxError::UnexpectedDefinition(_) => diagnostic.with_help("definitions must precede the expressions of a body"),
+            SyntaxError::EllipsisDepth(_) => {
+                diagnostic.with_help("pattern variables must be followed by at least as many ellipses in the template as in the pattern")
+            }
+            SyntaxError::UnknownLibrary(..) => {
+                diagnostic.with_help("library `(foo bar)` is read from `foo/bar.sld` under the library paths")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+/// Definition, as told by [`is_definition`]
+fn definition(datum: &Datum) -> Result<Lowering<'_, Definition>> {
+    let (keyword, operands) = keyword_form(datum).expect("checked by is_definition");
+    let span = datum.span;
+
+    Ok(match keyword {
+        "define" => define(operands, span)?.map(Definition::Variable),
+        "define-values" => match operands {
+            [formals_datum, value] => {
+                let formals = formals(formals_datum, keyword)?;
+                Lowering::new(vec![Subform::Expression(value)], move |nodes| {
+                    Definition::Values(ValuesDefinition { formals, value: nodes.boxed(), span })
+                })
+            }
+            _ => return Err(SyntaxError::MalformedForm(keyword, span)),
+        },
+        "define-record-type" => Lowering::done(Definition::RecordType(record_type(operands, span)?)),
+        "define-syntax" => match operands {
+            [keyword_datum, transformer] => Lowering::done(Definition::Syntax(SyntaxDefinition {
+                keyword: identifier(keyword_datum, keyword)?,
+                transformer: syntax_rules(transformer)?,
+                span,
+            })),
+            _ => return Err(SyntaxError::MalformedForm(keyword, span)),
+        },
+        // `(begin)` and any `begin` containing a definition splice definitions
+        _ => {
+            let subforms = operands
+                .iter()
+                .map(|operand| match subform(operand) {
+                    definition @ Subform::Definition(_) => Ok(definition),
+                    _ => Err(SyntaxError::MalformedForm(keyword, operand.span)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let len = subforms.len();
+            Lowering::new(subforms, move |nodes| Definition::Begin {
+                definitions: nodes.definitions(len),
+                span,
+            })
+        }
+    })
+}
+
+fn is_definition((keyword, operands): (&'static str, &[Datum])) -> bool {
+    match keyword {
+        "define" | "define-values" | "define-record-type" | "define-syntax" => true,
+        "begin" => operands.is_empty() || operands.iter().any(|operand| keyword_form(operand).is_some_and(is_definition)),
+        _ => false,
+    }
+}
+
+fn define(operands: &[Datum], span: Span) -> Result<Lowering<'_, VariableDefinition>> {
+    let keyword = "define";
+
+    match operands {
+        [variable @ Datum { kind: DatumKind::Symbol(_), .. }, value] => {
+            let variable = identifier(variable, keyword)?;
+            Ok(Lowering::new(vec![Subform::Expression(value)], move |nodes| VariableDefinition {
+                variable,
+                value: nodes.boxed(),
+                span,
+            }))
+        }
+        // procedure shorthand
+        [Datum { kind: DatumKind::List { elements, tail }, span: target_span }, body_forms @ ..] if !elements.is_empty() => {
+            let formals = formals_parts(&elements[1..], tail.as_deref(), *target_span, keyword)?;
+            let variable = identifier(&elements[0], keyword)?;
+
+            Ok(Lowering::new(vec![Subform::Body(body_forms, span)], move |nodes| {
+                VariableDefinition {
+                    variable,
+                    value: Box::new(Expression::Lambda(Lambda { formals, body: nodes.body(), span })),
+                    span,
+                }
+            }))
+        }
+        _ => Err(SyntaxError::MalformedForm(keyword, span)),
+    }
+}
+
+fn record_type(operands: &[Datum], span: Span) -> Result<RecordType> {
+    let keyword = "define-record-type";
+    let [name, constructor, predicate, field_specs @ ..] = operands else {
+        return Err(SyntaxError::MalformedForm(keyword, span));
+    };
+
+    let fields = field_specs
+        .iter()
+        .map(|field_spec| match list(field_spec, keyword)? {
+            [name, accessor] => Ok(RecordField {
+                name: identifier(name, keyword)?,
+                accessor: identifier(accessor, keyword)?,
+                modifier: None,
+            }),
+            [name, accessor, modifier] => Ok(RecordField {
+                name: identifier(name, keyword)?,
+                accessor: identifier(accessor, keyword)?,
+                modifier: Some(identifier(modifier, keyword)?),
+            }),
+            _ => Err(SyntaxError::MalformedForm(keyword, field_spec.span)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    check_distinct(fields.iter().map(|field| &field.name))?;
+
+    let constructor = match &constructor.kind {
+        DatumKind::Boolean(false) => None,
+        DatumKind::Symbol(_) => Some(RecordConstructor {
+            name: identifier(constructor, keyword)?,
+            fields: fields.iter().map(|field| field.name.clone()).collect(),
+        }),
+        DatumKind::List { elements, tail: None } if !elements.is_empty() => {
+            let field_names = elements[1..]
+                .iter()
+                .map(|field| identifier(field, keyword))
+                .collect::<Result<Vec<_>>>()?;
+            check_distinct(&field_names)?;
+
+            if let Some(unknown) = field_names
+                .iter()
+                .find(|field_name| fields.iter().all(|field| field.name.name != field_name.name))
+            {
+                return Err(SyntaxError::MalformedForm(keyword, unknown.span));
+            }
+
+            Some(RecordConstructor { name: identifier(&elements[0], keyword)?, fields: field_names })
+        }
+        _ => return Err(SyntaxError::MalformedForm(keyword, constructor.span)),
+    };
+
+    Ok(RecordType {
+        name: identifier(name, keyword)?,
+        constructor,
+        predicate: identifier(predicate, keyword)?,
+        fields,
+        span,
+    })
+}
+
+fn syntax_rules(datum: &Datum) -> Result<SyntaxRules> {
+    let keyword = "syntax-rules";
+    let operands = match keyword_form(datum) {
+        Some((name, operands)) if name == keyword => operands,
+        _ => return Err(SyntaxError::MalformedForm(keyword, datum.span)),
+    };
+
+    let (ellipsis, literals, rules) = match operands {
+        [ellipsis @ Datum { kind: DatumKind::Symbol(_), .. }, literals, rules @ ..] => (Some(symbol(ellipsis, keyword)?), literals, rules),
+        [literals, rules @ ..] => (None, literals, rules),
+        [] => return Err(SyntaxError::MalformedForm(keyword, datum.span)),
+    };
+
+    let literals = list(literals, keyword)?
+        .iter()
+        .map(|literal| symbol(literal, keyword))
+        .collect::<Result<_>>()?;
+    let rules = rules
+        .iter()
+        .map(|rule| match list(rule, keyword)? {
+            [pattern @ Datum { kind: DatumKind::List { .. }, .. }, template] => {
+                Ok(SyntaxRule { pattern: pattern.clone(), template: template.clone() })
+            }
+            _ => Err(SyntaxError::MalformedForm(keyword, rule.span)),
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(SyntaxRules { ellipsis, literals, rules, span: datum.span })
+}
+
+fn expression(datum: &Datum) -> Result<Lowering<'_, Expression>> {
+    let elements = match &datum.kind {
+        DatumKind::Symbol(_) => return Ok(Lowering::done(Expression::Variable(identifier(datum, "variable")?))),
+        DatumKind::Number(value) => {
+            return Ok(Lowering::done(Expression::Literal(Literal::Number {
+                value: value.clone(),
+                span: datum.span,
+            })))
+        }
+        DatumKind::List { tail: Some(_), .. } => return Err(SyntaxError::ImproperCall(datum.span)),
+        DatumKind::List { elements, tail: None } => elements,
+        _ => return Ok(Lowering::done(Expression::Literal(Literal::SelfEvaluating(datum.clone())))),
+    };
+
+    if let Some((keyword, operands)) = keyword_form(datum) {
+        return special_form(keyword, operands, datum.span);
+    }
+
+    if elements.is_empty() {
+        return Err(SyntaxError::EmptyCombination(datum.span));
+    }
+    let span = datum.span;
+    Ok(Lowering::new(expressions(elements), move |nodes| {
+        Expression::Call(Call {
+            operator: nodes.boxed(),
+            operands: nodes.expressions(elements.len() - 1),
+            span,
+        })
+    }))
+}
+
+fn special_form<'a>(keyword: &'static str, operands: &'a [Datum], span: Span) -> Result<Lowering<'a, Expression>> {
+    let malformed = || SyntaxError::MalformedForm(keyword, span);
+    let len = operands.len();
+
+    let lowering = match (keyword, operands) {
+        ("quote", [datum]) => Lowering::done(Expression::Literal(Literal::Quotation(Quotation { datum: datum.clone(), span }))),
+        ("quasiquote", [datum]) => template(datum, 1)?.map(move |template| Expression::Quasiquote(Quasiquote { template, span })),
+        ("lambda", [formals_datum, body_forms @ ..]) => {
+            let formals = formals(formals_datum, keyword)?;
+            Lowering::new(vec![Subform::Body(body_forms, span)], move |nodes| {
+                Expression::Lambda(Lambda { formals, body: nodes.body(), span })
+            })
+        }
+        ("if", [_, _, alternate @ ..]) if alternate.len() <= 1 => Lowering::new(expressions(operands), move |nodes| {
+            Expression::If(If {
+                test: nodes.boxed(),
+                consequent: nodes.boxed(),
+                alternate: (len == 3).then(|| nodes.boxed()),
+                span,
+            })
+        }),
+        ("set!", [variable, value]) => {
+            let variable = identifier(variable, keyword)?;
+            Lowering::new(vec![Subform::Expression(value)], move |nodes| {
+                Expression::Set(Set { variable, value: nodes.boxed(), span })
+            })
+        }
+        ("include" | "include-ci", [_, ..]) => {
+            let files = operands
+                .iter()
+                .map(|operand| match &operand.kind {
+                    DatumKind::String(file) => Ok((file.clone(), operand.span)),
+                    _ => Err(SyntaxError::MalformedForm(keyword, operand.span)),
+                })
+                .collect::<Result<_>>()?;
+            Lowering::done(Expression::Include(Include {
+                case_insensitive: keyword == "include-ci",
+                files,
+                span,
+            }))
+        }
+        ("cond", [_, ..]) => {
+            cond_clauses(operands, keyword)?.map(move |(clauses, else_clause)| Expression::Cond(Cond { clauses, else_clause, span }))
+        }
+        ("case", [key, clauses @ ..]) if !clauses.is_empty() => case(key, clauses, span)?,
+        ("and" | "or" | "begin", _) if keyword != "begin" || len > 0 => Lowering::new(expressions(operands), move |nodes| {
+            let sequence = Sequence { expressions: nodes.expressions(len), span };
+            match keyword {
+                "and" => Expression::And(sequence),
+                "or" => Expression::Or(sequence),
+                _ => Expression::Begin(sequence),
+            }
+        }),
+        ("when" | "unless", [_, body @ ..]) if !body.is_empty() => Lowering::new(expressions(operands), move |nodes| {
+            let when = When { test: nodes.boxed(), body: nodes.expressions(len - 1), span };
+            match keyword {
+                "when" => Expression::When(when),
+                _ => Expression::Unless(when),
+            }
+        }),
+        ("let" | "let*" | "letrec" | "letrec*", _) => let_expression(keyword, operands, span)?.map(Expression::Let),
+        ("let-values" | "let*-values", [bindings, body_forms @ ..]) => {
+            let mut formals_list = Vec::new();
+            let mut subforms = Vec::new();
+            for binding in list(bindings, keyword)? {
+                let [formals_datum, init] = list(binding, keyword)? else {
+                    return Err(SyntaxError::MalformedForm(keyword, binding.span));
+                };
+                formals_list.push(formals(formals_datum, keyword)?);
+                subforms.push(Subform::Expression(init));
+            }
+
+            let sequential = keyword == "let*-values";
+            if !sequential {
+                check_distinct(formals_list.iter().flat_map(|formals| formals.required.iter().chain(&formals.rest)))?;
+            }
+
+            subforms.push(Subform::Body(body_forms, span));
+            Lowering::new(subforms, move |nodes| {
+                let bindings = formals_list.into_iter().map(|formals| (formals, nodes.expression())).collect();
+                Expression::LetValues(LetValues { sequential, bindings, body: nodes.body(), span })
+            })
+        }
+        ("do", [iterations, exit, commands @ ..]) => {
+            let mut variables = Vec::new();
+            let mut subforms = Vec::new();
+            for iteration in list(iterations, keyword)? {
+                let [variable, init, step @ ..] = list(iteration, keyword)? else {
+                    return Err(SyntaxError::MalformedForm(keyword, iteration.span));
+                };
+                if step.len() > 1 {
+                    return Err(SyntaxError::MalformedForm(keyword, iteration.span));
+                }
+                variables.push((identifier(variable, keyword)?, !step.is_empty()));
+                subforms.push(Subform::Expression(init));
+                subforms.extend(expressions(step));
+            }
+            check_distinct(variables.iter().map(|(variable, _)| variable))?;
+
+            let exit = list(exit, keyword)?;
+            if exit.is_empty() {
+                return Err(SyntaxError::MalformedForm(keyword, operands[1].span));
+            }
+            subforms.extend(expressions(exit));
+            subforms.extend(expressions(commands));
+
+            Lowering::new(subforms, move |nodes| {
+                let iterations = variables
+                    .into_iter()
+                    .map(|(variable, stepped)| Iteration {
+                        variable,
+                        init: nodes.expression(),
+                        step: stepped.then(|| nodes.expression()),
+                    })
+                    .collect();
+                Expression::Do(Do {
+                    iterations,
+                    test: nodes.boxed(),
+                    result: nodes.expressions(exit.len() - 1),
+                    commands: nodes.expressions(commands.len()),
+                    span,
+                })
+            })
+        }
+        ("delay" | "delay-force", [operand]) => Lowering::new(vec![Subform::Expression(operand)], move |nodes| {
+            let delay = Delay { expression: nodes.boxed(), span };
+            match keyword {
+                "delay" => Expression::Delay(delay),
+                _ => Expression::DelayForce(delay),
+            }
+        }),
+        ("parameterize", [bindings, body_forms @ ..]) => {
+            let mut subforms = Vec::new();
+            for binding in list(bindings, keyword)? {
+                match list(binding, keyword)? {
+                    pair @ [_, _] => subforms.extend(expressions(pair)),
+                    _ => return Err(SyntaxError::MalformedForm(keyword, binding.span)),
+                }
+            }
+            let len = subforms.len() / 2;
+
+            subforms.push(Subform::Body(body_forms, span));
+            Lowering::new(subforms, move |nodes| {
+                let bindings = (0..len).map(|_| (nodes.expression(), nodes.expression())).collect();
+                Expression::Parameterize(Parameterize { bindings, body: nodes.body(), span })
+            })
+        }
+        ("guard", [specification, body_forms @ ..]) => {
+            let [variable, clauses @ ..] = list(specification, keyword)? else {
+                return Err(SyntaxError::MalformedForm(keyword, specification.span));
+            };
+            if clauses.is_empty() {
+                return Err(SyntaxError::MalformedForm(keyword, specification.span));
+            }
+            let variable = identifier(variable, keyword)?;
+
+            let body = Lowering::new(vec![Subform::Body(body_forms, span)], Nodes::body);
+            cond_clauses(clauses, keyword)?
+                .zip(body)
+                .map(move |((clauses, else_clause), body)| Expression::Guard(Guard { variable, clauses, else_clause, body, span }))
+        }
+        ("case-lambda", _) => {
+            let clauses = operands
+                .iter()
+                .map(|clause| match list(clause, keyword)? {
+                    [formals_datum, body_forms @ ..] => {
+                        let formals = formals(formals_datum, keyword)?;
+                        let span = clause.span;
+                        Ok(Lowering::new(vec![Subform::Body(body_forms, span)], move |nodes| Lambda {
+                            formals,
+                            body: nodes.body(),
+                            span,
+                        }))
+                    }
+                    [] => Err(SyntaxError::MalformedForm(keyword, clause.span)),
+                })
+                .collect::<Result<_>>()?;
+
+            Lowering::all(clauses).map(move |clauses| Expression::CaseLambda(CaseLambda { clauses, span }))
+        }
+        ("let-syntax" | "letrec-syntax", [bindings, body_forms @ ..]) => {
+            let bindings = list(bindings, keyword)?
+                .iter()
+                .map(|binding| match list(binding, keyword)? {
+                    [name, transformer] => Ok((identifier(name, keyword)?, syntax_rules(transformer)?)),
+                    _ => Err(SyntaxError::MalformedForm(keyword, binding.span)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            check_distinct(bindings.iter().map(|(name, _)| name))?;
+
+            Lowering::new(vec![Subform::Body(body_forms, span)], move |nodes| {
+                Expression::LetSyntax(LetSyntax {
+                    recursive: keyword == "letrec-syntax",
+                    bindings,
+                    body: nodes.body(),
+                    span,
+                })
+            })
+        }
+        ("define" | "define-values" | "define-record-type" | "define-syntax", _) => return Err(SyntaxError::UnexpectedDefinition(span)),
+        (
+            "lambda" | "if" | "set!" | "include" | "include-ci" | "cond" | "case" | "when" | "unless" | "let-values" | "let*-values"
+            | "begin" | "do" | "delay" | "delay-force" | "parameterize" | "guard" | "let-syntax" | "letrec-syntax" | "quote" | "quasiquote",
+            _,
+        ) => return Err(malformed()),
+        // auxiliary syntax, `syntax-rules` and unquotations outside of a quasiquotation
+        _ => return Err(SyntaxError::MisplacedKeyword(span)),
+    };
+
+    Ok(lowering)
+}
+
+/// Clauses of `cond` and `guard`, the `else` clause being returned separately.
+#[allow(clippy::type_complexity)]
+fn cond_clauses<'a>(clauses: &'a [Datum], keyword: &'static str) -> Result<Lowering<'a, (Vec<CondClause>, Option<Vec<Expression>>)>> {
+    let mut cond_clauses = Vec::new();
+    let mut else_clause = None;
+
+    for (index, clause) in clauses.iter().enumerate() {
+        let span = clause.span;
+        let (test, body) = match list(clause, keyword)? {
+            [first, sequence @ ..] if first.as_symbol() == Some("else") => {
+                if index + 1 != clauses.len() || sequence.is_empty() {
+                    return Err(SyntaxError::MalformedForm(keyword, span));
+                }
+                let len = sequence.len();
+                else_clause = Some(Lowering::new(expressions(sequence), move |nodes| nodes.expressions(len)));
+                break;
+            }
+            [test] => (test, Lowering::done(ClauseBody::Sequence(Vec::new()))),
+            [test, rest @ ..] => (test, clause_body(rest, keyword, span)?),
+            [] => return Err(SyntaxError::MalformedForm(keyword, span)),
+        };
+
+        let test = Lowering::new(vec![Subform::Expression(test)], Nodes::expression);
+        cond_clauses.push(test.zip(body).map(move |(test, body)| CondClause { test, body, span }));
+    }
+
+    let else_clause = Lowering::all(else_clause.into_iter().collect()).map(|mut else_clause| else_clause.pop());
+    Ok(Lowering::all(cond_clauses).zip(else_clause))
+}
+
+fn case<'a>(key: &'a Datum, clauses: &'a [Datum], span: Span) -> Result<Lowering<'a, Expression>> {
+    let keyword = "case";
+    let mut case_clauses = Vec::new();
+    let mut else_clause = None;
+
+    for (index, clause) in clauses.iter().enumerate() {
+        let [data, rest @ ..] = list(clause, keyword)? else {
+            return Err(SyntaxError::MalformedForm(keyword, clause.span));
+        };
+
+        if data.as_symbol() == Some("else") {
+            if index + 1 != clauses.len() {
+                return Err(SyntaxError::MalformedForm(keyword, clause.span));
+            }
+            else_clause = Some(clause_body(rest, keyword, clause.span)?);
+            break;
+        }
+
+        let data = list(data, keyword)?.to_vec();
+        let span = clause.span;
+        case_clauses.push(clause_body(rest, keyword, span)?.map(move |body| CaseClause { data, body, span }));
+    }
+
+    let key = Lowering::new(vec![Subform::Expression(key)], Nodes::boxed);
+    let else_clause = Lowering::all(else_clause.into_iter().collect()).map(|mut else_clause| else_clause.pop());
+    Ok(key
+        .zip(Lowering::all(case_clauses))
+        .zip(else_clause)
+        .map(move |((key, clauses), else_clause)| Expression::Case(Case { key, clauses, else_clause, span })))
+}
+
+/// Non-empty sequence or `=> <recipient>`
+fn clause_body<'a>(rest: &'a [Datum], keyword: &'static str, span: Span) -> Result<Lowering<'a, ClauseBody>> {
+    match rest {
+        [arrow, recipient] if arrow.as_symbol() == Some("=>") => Ok(Lowering::new(vec![Subform::Expression(recipient)], |nodes| {
+            ClauseBody::Arrow(nodes.boxed())
+        })),
+        [] => Err(SyntaxError::MalformedForm(keyword, span)),
+        _ => Ok(Lowering::new(expressions(rest), move |nodes| {
+            ClauseBody::Sequence(nodes.expressions(rest.len()))
+        })),
+    }
+}
+
+fn let_expression<'a>(keyword: &'static str, operands: &'a [Datum], span: Span) -> Result<Lowering<'a, Let>> {
+    let kind = match keyword {
+        "let" => LetKind::Let,
+        "let*" => LetKind::LetStar,
+        "letrec" => LetKind::Letrec,
+        _ => LetKind::LetrecStar,
+    };
+
+    let (name, operands) = match operands {
+        [name @ Datum { kind: DatumKind::Symbol(_), .. }, rest @ ..] if kind == LetKind::Let => (Some(identifier(name, keyword)?), rest),
+        _ => (None, operands),
+    };
+    let [bindings, body_forms @ ..] = operands else {
+        return Err(SyntaxError::MalformedForm(keyword, span));
+    };
+
+    let mut variables = Vec::new();
+    let mut subforms = Vec::new();
+    for binding in list(bindings, keyword)? {
+        let [variable, init] = list(binding, keyword)? else {
+            return Err(SyntaxError::MalformedForm(keyword, binding.span));
+        };
+        variables.push(identifier(variable, keyword)?);
+        subforms.push(Subform::Expression(init));
+    }
+
+    if kind != LetKind::LetStar {
+        check_distinct(&variables)?;
+    }
+
+    subforms.push(Subform::Body(body_forms, span));
+    Ok(Lowering::new(subforms, move |nodes| {
+        let bindings = variables
+            .into_iter()
+            .map(|variable| Binding { variable, init: nodes.expression() })
+            .collect();
+        Let { kind, name, bindings, body: nodes.body(), span }
+    }))
+}
+
+/// Definitions followed by at least one expression, `span` being the one of the enclosing form.
+fn body(forms: &[Datum], span: Span) -> Result<Lowering<'_, Body>> {
+    let mut subforms = Vec::new();
+    let mut definitions = 0;
+
+    for datum in forms {
+        match subform(datum) {
+            Subform::Definition(_) if definitions == subforms.len() => definitions += 1,
+            Subform::Definition(_) => return Err(SyntaxError::UnexpectedDefinition(datum.span)),
+            _ => {}
+        }
+        subforms.push(subform(datum));
+    }
+
+    if definitions == subforms.len() {
+        return Err(SyntaxError::MissingExpression(span));
+    }
+
+    let len = subforms.len() - definitions;
+    Ok(Lowering::new(subforms, move |nodes| Body {
+        definitions: nodes.definitions(definitions),
+        expressions: nodes.expressions(len),
+    }))
+}
+
+fn formals(datum: &Datum, keyword: &'static str) -> Result<Formals> {
+    match &datum.kind {
+        DatumKind::Symbol(_) => formals_parts(&[], Some(datum), datum.span, keyword),
+        DatumKind::List { elements, tail } => formals_parts(elements, tail.as_deref(), datum.span, keyword),
+        _ => Err(SyntaxError::MalformedForm(keyword, datum.span)),
+    }
+}
+
+fn formals_parts(required: &[Datum], rest: Option<&Datum>, span: Span, keyword: &'static str) -> Result<Formals> {
+    let formals = Formals {
+        required: required
+            .iter()
+            .map(|parameter| identifier(parameter, keyword))
+            .collect::<Result<_>>()?,
+        rest: rest.map(|parameter| identifier(parameter, keyword)).transpose()?,
+        span,
+    };
+    check_distinct(formals.required.iter().chain(&formals.rest))?;
+
+    Ok(formals)
+}
+
+fn expressions(data: &[Datum]) -> Vec<Subform<'_>> {
+    data.iter().map(Subform::Expression).collect()
+}
+
+/// Quasiquotation template at the given nesting depth, one being the outermost.
+fn template(datum: &Datum, depth: usize) -> Result<Lowering<'_, Template>> {
+    let (elements, tail) = match &datum.kind {
+        DatumKind::List { elements, tail } => (elements, tail),
+        DatumKind::Vector(elements) => {
+            let elements = elements
+                .iter()
+                .map(|element| template_element(element, depth))
+                .collect::<Result<Vec<_>>>()?;
+            let span = datum.span;
+            return Ok(Lowering::all(elements).map(move |elements| fold_template(Template::Vector { elements, span }, || datum.clone())));
+        }
+        _ => return Ok(Lowering::done(Template::Datum(datum.clone()))),
+    };
+
+    // `(<qq template>+ . ,<expression>)` is read as a list ending with `unquote` and its operand
+    let (elements, tail) = match (&elements[..], tail) {
+        ([before @ .., keyword, operand], None) if !before.is_empty() && is_quasiquotation_keyword(keyword) => {
+            let tail = unquotation(keyword, operand, keyword.span.join(operand.span), depth)?.expect("quasiquotation keyword");
+            (before, Some(tail))
+        }
+        ([keyword, operand], None) => match unquotation(keyword, operand, datum.span, depth)? {
+            Some(template) => return Ok(template),
+            None => (&elements[..], None),
+        },
+        _ => (&elements[..], tail.as_deref().map(|tail| template(tail, depth)).transpose()?),
+    };
+
+    let elements = elements
+        .iter()
+        .map(|element| template_element(element, depth))
+        .collect::<Result<Vec<_>>>()?;
+    let tail = Lowering::all(tail.into_iter().collect()).map(|mut tail| tail.pop().map(Box::new));
+    let span = datum.span;
+
+    Ok(Lowering::all(elements)
+        .zip(tail)
+        .map(move |(elements, tail)| fold_template(Template::List { elements, tail, span }, || datum.clone())))
+}
+
+/// Template of `(<keyword> <operand>)` if the keyword is `quasiquote`, `unquote` or
+/// `unquote-splicing`, `span` being the one of the list
+fn unquotation<'a>(keyword: &'a Datum, operand: &'a Datum, span: Span, depth: usize) -> Result<Option<Lowering<'a, Template>>> {
+    let depth = match keyword.as_symbol() {
+        Some("unquote") if depth == 1 => {
+            return Ok(Some(Lowering::new(vec![Subform::Expression(operand)], |nodes| {
+                Template::Unquote(nodes.boxed())
+            })))
+        }
+        Some("unquote-splicing") if depth == 1 => return Err(SyntaxError::MisplacedKeyword(span)),
+        Some("unquote" | "unquote-splicing") => depth - 1,
+        Some("quasiquote") => depth + 1,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(template(operand, depth)?.map(move |template| {
+        let elements = vec![
+            TemplateElement::Template(Template::Datum(keyword.clone())),
+            TemplateElement::Template(template),
+        ];
+        fold_template(Template::List { elements, tail: None, span }, || Datum {
+            kind: DatumKind::List { elements: vec![keyword.clone(), operand.clone()], tail: None },
+            span,
+        })
+    })))
+}
+
+fn is_quasiquotation_keyword(datum: &Datum) -> bool {
+    matches!(datum.as_symbol(), Some("quasiquote" | "unquote" | "unquote-splicing"))
+}
+
+fn template_element(datum: &Datum, depth: usize) -> Result<Lowering<'_, TemplateElement>> {
+    match datum.as_list() {
+        Some([keyword, operand]) if depth == 1 && keyword.as_symbol() == Some("unquote-splicing") => {
+            Ok(Lowering::new(vec![Subform::Expression(operand)], |nodes| {
+                TemplateElement::Splice(nodes.expression())
+            }))
+        }
+        _ => Ok(template(datum, depth)?.map(TemplateElement::Template)),
+    }
+}
+
+/// Compound templates without unquotation are kept as a single datum, the one they were read from.
+fn fold_template(template: Template, datum: impl FnOnce() -> Datum) -> Template {
+    let is_datum = |template: &Template| matches!(template, Template::Datum(_));
+    let is_datum_element = |element: &TemplateElement| matches!(element, TemplateElement::Template(template) if is_datum(template));
+
+    let constant = match &template {
+        Template::List { elements, tail, .. } => elements.iter().all(is_datum_element) && tail.as_deref().is_none_or(is_datum),
+        Template::Vector { elements, .. } => elements.iter().all(is_datum_element),
+        Template::Datum(_) | Template::Unquote(_) => false,
+    };
+
+    match constant {
+        true => Template::Datum(datum()),
+        false => template,
+    }
+}
+
+/// Keyword and operands if the datum is a proper list starting with a syntactic keyword.
+fn keyword_form(datum: &Datum) -> Option<(&'static str, &[Datum])> {
+    let (first, operands) = datum.as_list()?.split_first()?;
+    let name = first.as_symbol()?;
+    Some((KEYWORDS.iter().find(|keyword| **keyword == name)?, operands))
+}
+
+fn list<'a>(datum: &'a Datum, keyword: &'static str) -> Result<&'a [Datum]> {
+    datum.as_list().ok_or(SyntaxError::MalformedForm(keyword, datum.span))
+}
+
+/// Any symbol, keywords included
+fn symbol(datum: &Datum, keyword: &'static str) -> Result<Identifier> {
+    match datum.as_symbol() {
+        Some(name) => Ok(Identifier { name: name.into(), span: datum.span }),
+        None => Err(SyntaxError::MalformedForm(keyword, datum.span)),
+    }
+}
+
+/// Variable or keyword being bound or referenced, which may not be a syntactic keyword
+fn identifier(datum: &Datum, keyword: &'static str) -> Result<Identifier> {
+    let identifier = symbol(datum, keyword)?;
+
+    match KEYWORDS.contains(&&*identifier.name) {
+        true => Err(SyntaxError::MisplacedKeyword(identifier.span)),
+        false => Ok(identifier),
+    }
+}
+
+fn check_distinct<'a>(identifiers: impl IntoIterator<Item = &'a Identifier>) -> Result<()> {
+    let mut names = HashSet::new();
+
+    for identifier in identifiers {
+        if !names.insert(&identifier.name) {
+            return Err(SyntaxError::DuplicateIdentifier(identifier.span));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(src: &str) -> Expression {
+        src.parse().unwrap()
+    }
+
+    fn parse_definition(src: &str) -> Definition {
+        match Form::parse_all(src).unwrap().remove(0) {
+            Form::Definition(definition) => definition,
+            Form::Expression(expression) => panic!("expected a definition, got {expression:?}"),
+        }
+    }
+
+    fn error(src: &str) -> SyntaxError {
+        match Form::parse_all(src) {
+            Err(ParseError::Syntax(error)) => error,
+            other => panic!("expected a syntax error, got {other:?}"),
+        }
+    }
+
+    fn names(identifiers: &[Identifier]) -> Vec<&str> {
+        identifiers.iter().map(|identifier| &*identifier.name).collect()
+    }
+
+    #[test]
+    fn lambda_formals() {
+        for (src, required, rest) in [
+            ("(lambda (a b) a)", vec!["a", "b"], None),
+            ("(lambda args args)", vec![], Some("args")),
+            ("(lambda (a . rest) a)", vec!["a"], Some("rest")),
+        ] {
+            let Expression::Lambda(lambda) = parse(src) else {
+                panic!("expected a lambda");
+            };
+            assert_eq!(required, names(&lambda.formals.required));
+            assert_eq!(rest, lambda.formals.rest.as_ref().map(|rest| &*rest.name));
+        }
+    }
+
+    #[test]
+    fn bodies() {
+        let Expression::Lambda(lambda) = parse("(lambda () (define a 1) (begin (define b 2)) (+ a b))") else {
+            panic!("expected a lambda");
+        };
+        assert_eq!(2, lambda.body.definitions.len());
+        assert_eq!(1, lambda.body.expressions.len());
+
+        assert!(matches!(error("(lambda () 1 (define a 1) a)"), SyntaxError::UnexpectedDefinition(span) if span.start() == 13));
+        assert!(matches!(error("(lambda () (define a 1))"), SyntaxError::MissingExpression(_)));
+        assert!(matches!(error("(if (define a 1) 1)"), SyntaxError::UnexpectedDefinition(_)));
+    }
+
+    #[test]
+    fn conditionals() {
+        let Expression::If(if_expression) = parse("(if a b)") else {
+            panic!("expected an if");
+        };
+        assert_eq!(None, if_expression.alternate);
+
+        let Expression::Cond(cond) = parse("(cond ((assv b '((a 1))) => cadr) (a) (else 1 2))") else {
+            panic!("expected a cond");
+        };
+        assert!(matches!(cond.clauses[0].body, ClauseBody::Arrow(_)));
+        assert_eq!(ClauseBody::Sequence(Vec::new()), cond.clauses[1].body);
+        assert_eq!(Some(2), cond.else_clause.map(|expressions| expressions.len()));
+
+        let Expression::Case(case) = parse("(case x ((1 2) 'low) (else => f))") else {
+            panic!("expected a case");
+        };
+        assert_eq!(2, case.clauses[0].data.len());
+        assert!(matches!(case.else_clause, Some(ClauseBody::Arrow(_))));
+
+        assert!(matches!(error("(cond (else 1) (a 2))"), SyntaxError::MalformedForm("cond", _)));
+        assert!(matches!(error("(if a)"), SyntaxError::MalformedForm("if", _)));
+    }
+
+    #[test]
+    fn binding_forms() {
+        let Expression::Let(named_let) = parse("(let loop ((i 0)) (loop (+ i 1)))") else {
+            panic!("expected a let");
+        };
+        assert_eq!(Some("loop"), named_let.name.as_ref().map(|name| &*name.name));
+        assert_eq!(LetKind::Let, named_let.kind);
+
+        let Expression::Do(do_expression) = parse("(do ((i 0 (+ i 1)) (acc '())) ((= i 3) acc) (display i))") else {
+            panic!("expected a do");
+        };
+        assert!(do_expression.iterations[1].step.is_none());
+        assert_eq!(1, do_expression.commands.len());
+
+        assert!(matches!(
+            parse("(let*-values (((a . b) (values 1 2)) (c (f))) a)"),
+            Expression::LetValues(LetValues { sequential: true, .. })
+        ));
+        assert!(matches!(parse("(let* ((a 1) (a a)) a)"), Expression::Let(_)));
+        assert!(matches!(error("(let ((a 1) (a 2)) a)"), SyntaxError::DuplicateIdentifier(span) if span.start() == 13));
+    }
+
+    #[test]
+    fn definitions() {
+        let Definition::Variable(definition) = parse_definition("(define (f a . b) b)") else {
+            panic!("expected a variable definition");
+        };
+        assert_eq!("f", &*definition.variable.name);
+        assert!(matches!(*definition.value, Expression::Lambda(ref lambda) if lambda.formals.rest.is_some()));
+
+        let Definition::RecordType(record_type) =
+            parse_definition("(define-record-type point (make-point x) point? (x point-x) (y point-y set-y!))")
+        else {
+            panic!("expected a record type definition");
+        };
+        assert_eq!(vec!["x"], names(&record_type.constructor.unwrap().fields));
+        assert!(record_type.fields[1].modifier.is_some());
+
+        let Definition::Syntax(definition) =
+            parse_definition("(define-syntax swap! (syntax-rules ::: () ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))")
+        else {
+            panic!("expected a syntax definition");
+        };
+        assert_eq!(
+            Some(":::"),
+            definition.transformer.ellipsis.as_ref().map(|ellipsis| &*ellipsis.name)
+        );
+        assert_eq!(1, definition.transformer.rules.len());
+
+        assert!(matches!(
+            parse_definition("(define-values (a . b) (values 1 2))"),
+            Definition::Values(_)
+        ));
+        assert!(matches!(
+            error("(define-record-type p (make-p z) p? (x p-x))"),
+            SyntaxError::MalformedForm("define-record-type", _)
+        ));
+    }
+
+    #[test]
+    fn quasiquotation() {
+        let Expression::Quasiquote(quasiquote) = parse("`(1 ,a ,@b `(,c ,,d))") else {
+            panic!("expected a quasiquote");
+        };
+        let Template::List { elements, tail: None, .. } = quasiquote.template else {
+            panic!("expected a list template");
+        };
+        assert!(matches!(elements[0], TemplateElement::Template(Template::Datum(_))));
+        assert!(matches!(elements[1], TemplateElement::Template(Template::Unquote(_))));
+        assert!(matches!(elements[2], TemplateElement::Splice(_)));
+
+        // only `,,d` is evaluated within the nested quasiquotation
+        let TemplateElement::Template(Template::List { elements: nested, .. }) = &elements[3] else {
+            panic!("expected a nested list template");
+        };
+        let TemplateElement::Template(Template::List { elements: nested, .. }) = &nested[1] else {
+            panic!("expected a nested template");
+        };
+        assert!(matches!(nested[0], TemplateElement::Template(Template::Datum(_))));
+        assert!(
+            matches!(&nested[1], TemplateElement::Template(Template::List { elements, .. }) if matches!(elements[1], TemplateElement::Template(Template::Unquote(_))))
+        );
+
+        assert!(matches!(
+            parse("`(a `(b ,c))"),
+            Expression::Quasiquote(Quasiquote { template: Template::Datum(_), .. })
+        ));
+
+        // `(a . ,b)` reads as `(a unquote b)`
+        let Expression::Quasiquote(quasiquote) = parse("`(a . ,b)") else {
+            panic!("expected a quasiquote");
+        };
+        let Template::List { elements, tail: Some(tail), .. } = quasiquote.template else {
+            panic!("expected a dotted list template");
+        };
+        assert_eq!(1, elements.len());
+        assert!(matches!(*tail, Template::Unquote(_)));
+        assert!(matches!(error("`(a . ,@b)"), SyntaxError::MisplacedKeyword(_)));
+    }
+
+    #[test]
+    fn other_expressions() {
+        assert!(
+            matches!(parse("(include \"a.scm\" \"b.scm\")"), Expression::Include(Include { case_insensitive: false, ref files, .. }) if files.len() == 2)
+        );
+        assert!(matches!(parse("(guard (e ((string? e) e)) (raise 1))"), Expression::Guard(_)));
+        assert!(
+            matches!(parse("(case-lambda ((a) a) ((a b) b))"), Expression::CaseLambda(CaseLambda { ref clauses, .. }) if clauses.len() == 2)
+        );
+        assert!(matches!(parse("(parameterize ((p 1)) (p))"), Expression::Parameterize(_)));
+        assert!(matches!(parse("(delay-force (f))"), Expression::DelayForce(_)));
+        assert!(matches!(
+            parse("(letrec-syntax () 1)"),
+            Expression::LetSyntax(LetSyntax { recursive: true, .. })
+        ));
+        assert!(matches!(parse("(and)"), Expression::And(Sequence { ref expressions, .. }) if expressions.is_empty()));
+    }
+
+    #[test]
+    fn invalid_forms() {
+        assert!(matches!(error("(f . a)"), SyntaxError::ImproperCall(_)));
+        assert!(matches!(error("(f ())"), SyntaxError::EmptyCombination(span) if span.start() == 3));
+        assert!(matches!(error("(f else)"), SyntaxError::MisplacedKeyword(_)));
+        assert!(matches!(error(",a"), SyntaxError::MisplacedKeyword(_)));
+        assert!(matches!(error("(lambda (a a) a)"), SyntaxError::DuplicateIdentifier(_)));
+        assert!(matches!(error("(set! 1 2)"), SyntaxError::MalformedForm("set!", span) if span.start() == 6));
+    }
+
+    #[test]
+    fn programs() {
+        let forms = Form::parse_all("(define a 1) ; comment\n(display a) #;(ignored)").unwrap();
+        assert!(matches!(forms[..], [Form::Definition(_), Form::Expression(Expression::Call(_))]));
+        assert_eq!((23, 34), (forms[1].span().start(), forms[1].span().end()));
+
+        assert_eq!(Ok(Vec::new()), Form::parse_all(""));
+    }
+
+    #[test]
+    fn deeply_nested_forms() {
+        let src = format!("{}x{}", "(let ((x 1)) (if x (f ".repeat(1000), ")))".repeat(1000));
+        assert!(matches!(parse(&src), Expression::Let(_)));
+
+        let src = format!("(lambda () {}1{} 1)", "(define (f) ".repeat(1000), " 1)".repeat(1000));
+        assert!(matches!(parse(&src), Expression::Lambda(_)));
+    }
+
+    #[test]
+    fn error_codes() {
+        assert_eq!("C0201", error("(quote)").to_diagnostic().code);
+        assert_eq!("C0207", error("(lambda (a a) a)").to_diagnostic().code);
+    }
+}
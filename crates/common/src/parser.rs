@@ -1,5 +1,58 @@
-use chumsky::prelude::*;
+use pluine_lex::{
+    diagnostic::{Diagnostic, ToDiagnostic},
+    span::Span,
+};
+use pluine_number::NumberError;
+use pluine_parser::ReadError;
+use thiserror::Error;
 
-pub trait RuineParser: Sized {
-    fn parser() -> impl Parser<char, Self, Error = Simple<char>>;
+use crate::SyntaxError;
+
+/// Error returned by [`Datum::parse_str`](crate::Datum::parse_str) and
+/// [`Form::parse_all`](crate::Form::parse_all)
+#[derive(Debug, PartialEq, Error)]
+pub enum ParseError {
+    /// Reader error, lexer errors included
+    #[error(transparent)]
+    Read(#[from] ReadError),
+    /// Inner span points to the datum following the one expected
+    #[error("unexpected datum, expected a single one")]
+    UnexpectedDatum(Span),
+    /// Source without any datum
+    #[error("unexpected end of file")]
+    UnexpectedEnd,
+    /// Inner span points to the number literal, such as `#e+inf.0` which has no exact value
+    #[error("invalid number literal, {0}")]
+    InvalidNumber(NumberError, Span),
+    /// Inner span points to the labeled datum referring to itself, such as `#0=(a . #0#)`
+    #[error("circular datum, only acyclic data can be read as code")]
+    CircularDatum(Span),
+    /// Data not matching the syntax of expressions and definitions
+    #[error(transparent)]
+    Syntax(#[from] SyntaxError),
+}
+
+/// Error codes:
+///
+/// | Code  | Error                          |
+/// |-------|--------------------------------|
+/// | C0101 | `ParseError::UnexpectedDatum`  |
+/// | C0102 | `ParseError::UnexpectedEnd`    |
+/// | C0103 | `ParseError::InvalidNumber`    |
+/// | C0104 | `ParseError::CircularDatum`    |
+///
+/// Reader errors keep their `P` and `L` codes, and syntax errors their `C02` ones.
+impl ToDiagnostic for ParseError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, span, label) = match self {
+            ParseError::Read(error) => return error.to_diagnostic(),
+            ParseError::Syntax(error) => return error.to_diagnostic(),
+            ParseError::UnexpectedEnd => return Diagnostic::error("C0102", self.to_string()),
+            ParseError::UnexpectedDatum(span) => ("C0101", span, "unexpected"),
+            ParseError::InvalidNumber(_, span) => ("C0103", span, "invalid number"),
+            ParseError::CircularDatum(span) => ("C0104", span, "refers to itself"),
+        };
+
+        Diagnostic::error(code, self.to_string()).with_primary_label(*span, Some(label))
+    }
 }
@@ -0,0 +1,1067 @@
+//! Compilation of the AST to the [`Node`]s run by the machine.
+//!
+//! Variables are resolved ahead of time: local variables to a slot of an enclosing frame, others
+//! to a global cell which may be defined later on. Each frame holds the variables bound by a
+//! procedure call or a `let` family expression, along with those of the internal definitions of
+//! its body.
+//!
+//! Record types are made once their definition is compiled, their procedures being constants.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use pluine_common::{
+    self as ast, Binding, Body, ClauseBody, CondClause, Definition, Expression, Form, Formals, Identifier, LetKind, Literal, Template,
+    TemplateElement,
+};
+use pluine_lex::span::Span;
+
+use crate::{
+    procedure::ProcedureKind,
+    record::{RecordProcedure, RecordProcedureKind},
+    *,
+};
+
+type Result<T> = std::result::Result<T, EvalError>;
+
+/// Global cells by variable name, created on the first reference
+pub(crate) type Globals = HashMap<Rc<str>, Rc<Global>>;
+
+/// Expression compiled for the machine, cheap to clone
+#[derive(Clone)]
+pub(crate) enum Node {
+    Constant(Value),
+    Local(Local),
+    Global(Rc<Global>, Span),
+    SetLocal(Local, Rc<Node>),
+    SetGlobal(Rc<Global>, Rc<Node>, Span),
+    DefineGlobal(Rc<Global>, Rc<Node>),
+    DefineValues(Rc<DefineValues>),
+    If(Rc<If>),
+    Lambda(Rc<Lambda>),
+    CaseLambda(Rc<CaseLambda>),
+    /// Promise of the value of a thunk, which is itself a promise to force in turn if chained as
+    /// by `delay-force`
+    Delay(Rc<Lambda>, bool),
+    /// At least two nodes, the last one in tail position
+    Sequence(Rc<[Node]>),
+    Call(Rc<Call>),
+    Scope(Rc<Scope>),
+    /// At least two nodes evaluated until one is false, the last one in tail position
+    And(Rc<[Node]>),
+    /// At least two nodes evaluated until one is true, the last one in tail position
+    Or(Rc<[Node]>),
+    Arrow(Rc<Arrow>),
+    Case(Rc<Case>),
+    Guard(Rc<Guard>),
+    /// Raises the object bound by the clauses of a `guard` again, as none of them matched
+    Reraise(Span),
+}
+
+/// Slot of an enclosing frame
+#[derive(Clone)]
+pub(crate) struct Local {
+    /// Number of frames to go up, zero being the innermost frame
+    pub(crate) depth: usize,
+    pub(crate) index: usize,
+    pub(crate) name: Rc<str>,
+    pub(crate) span: Span,
+}
+
+/// Top-level variable, unbound until defined
+pub(crate) struct Global {
+    pub(crate) name: Rc<str>,
+    pub(crate) value: RefCell<Option<Value>>,
+}
+
+/// Variable bound by a definition, global at top level
+pub(crate) enum Variable {
+    Local(Local),
+    Global(Rc<Global>),
+}
+
+/// `define-values`, binding the variables to the values returned by `value`
+pub(crate) struct DefineValues {
+    pub(crate) value: Node,
+    pub(crate) arity: Arity,
+    /// Required variables, followed by the rest variable if any
+    pub(crate) variables: Vec<Variable>,
+    /// Points to the formals
+    pub(crate) span: Span,
+}
+
+pub(crate) struct If {
+    pub(crate) test: Node,
+    pub(crate) consequent: Node,
+    pub(crate) alternate: Node,
+}
+
+pub(crate) struct Lambda {
+    pub(crate) name: Option<Rc<str>>,
+    /// Required parameters occupy the first slots, followed by the rest parameter if any
+    pub(crate) arity: Arity,
+    pub(crate) frame_size: usize,
+    pub(crate) body: Node,
+}
+
+pub(crate) struct CaseLambda {
+    pub(crate) name: Option<Rc<str>>,
+    /// Accepting any argument count one of the clauses accepts, gaps included
+    pub(crate) arity: Arity,
+    /// Tried in order, the first one accepting the argument count being called
+    pub(crate) clauses: Vec<Rc<Lambda>>,
+}
+
+pub(crate) struct Call {
+    pub(crate) operator: Node,
+    pub(crate) operands: Vec<Node>,
+    pub(crate) span: Span,
+}
+
+/// New frame whose first slots are initialized by `inits`, evaluated in the enclosing frame
+pub(crate) struct Scope {
+    pub(crate) inits: Vec<Node>,
+    /// Formals of `let-values` each init is spread over, along with their span, empty if each
+    /// init initializes a single slot
+    pub(crate) formals: Vec<(Arity, Span)>,
+    pub(crate) frame_size: usize,
+    pub(crate) body: Node,
+}
+
+/// `cond` clause `(<test> => <recipient>)`, followed by the remaining clauses
+pub(crate) struct Arrow {
+    pub(crate) test: Node,
+    pub(crate) recipient: Node,
+    pub(crate) alternate: Node,
+    pub(crate) span: Span,
+}
+
+pub(crate) struct Case {
+    pub(crate) key: Node,
+    /// Data compared to the key with `eqv?`
+    pub(crate) clauses: Vec<(Vec<Value>, Clause)>,
+    pub(crate) else_clause: Clause,
+    pub(crate) span: Span,
+}
+
+/// Consequent of a `case` clause
+pub(crate) enum Clause {
+    Sequence(Node),
+    /// Procedure called with the key
+    Arrow(Node),
+}
+
+/// `guard` body, evaluated with a handler calling the clauses
+pub(crate) struct Guard {
+    pub(crate) body: Node,
+    /// Takes the raised object, followed by the continuation of the handler to re-raise it in
+    pub(crate) clauses: Rc<Lambda>,
+}
+
+/// Nodes are unlinked iteratively, as the default recursive drop would overflow the stack on
+/// deeply nested expressions.
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        self.unlink(&mut pending);
+        while let Some(mut node) = pending.pop() {
+            node.unlink(&mut pending);
+        }
+    }
+}
+
+impl Node {
+    /// Moves the nodes held by `self` alone to `pending`, nodes still referenced elsewhere being
+    /// left alone
+    fn unlink(&mut self, pending: &mut Vec<Node>) {
+        fn body(lambda: &mut Rc<Lambda>, take: &mut impl FnMut(&mut Node)) {
+            if let Some(lambda) = Rc::get_mut(lambda) {
+                take(&mut lambda.body);
+            }
+        }
+
+        let mut take = |node: &mut Node| pending.push(std::mem::replace(node, Node::Constant(Value::Unspecified)));
+
+        match self {
+            Node::Constant(_) | Node::Local(_) | Node::Global(..) | Node::Reraise(_) => {}
+            Node::SetLocal(_, value) | Node::SetGlobal(_, value, _) | Node::DefineGlobal(_, value) => {
+                Rc::get_mut(value).into_iter().for_each(take);
+            }
+            Node::DefineValues(definition) => {
+                if let Some(definition) = Rc::get_mut(definition) {
+                    take(&mut definition.value);
+                }
+            }
+            Node::If(if_node) => {
+                if let Some(If { test, consequent, alternate }) = Rc::get_mut(if_node) {
+                    [test, consequent, alternate].into_iter().for_each(take);
+                }
+            }
+            Node::Lambda(lambda) | Node::Delay(lambda, _) => body(lambda, &mut take),
+            Node::CaseLambda(case_lambda) => {
+                if let Some(case_lambda) = Rc::get_mut(case_lambda) {
+                    case_lambda.clauses.iter_mut().for_each(|lambda| body(lambda, &mut take));
+                }
+            }
+            Node::Sequence(nodes) | Node::And(nodes) | Node::Or(nodes) => {
+                Rc::get_mut(nodes).into_iter().flatten().for_each(take);
+            }
+            Node::Call(call) => {
+                if let Some(Call { operator, operands, .. }) = Rc::get_mut(call) {
+                    std::iter::once(operator).chain(operands).for_each(take);
+                }
+            }
+            Node::Scope(scope) => {
+                if let Some(Scope { inits, body, .. }) = Rc::get_mut(scope) {
+                    inits.iter_mut().chain([body]).for_each(take);
+                }
+            }
+            Node::Arrow(arrow) => {
+                if let Some(Arrow { test, recipient, alternate, .. }) = Rc::get_mut(arrow) {
+                    [test, recipient, alternate].into_iter().for_each(take);
+                }
+            }
+            Node::Case(case) => {
+                if let Some(Case { key, clauses, else_clause, .. }) = Rc::get_mut(case) {
+                    take(key);
+                    for clause in clauses.iter_mut().map(|(_, clause)| clause).chain([else_clause]) {
+                        let (Clause::Sequence(node) | Clause::Arrow(node)) = clause;
+                        take(node);
+                    }
+                }
+            }
+            Node::Guard(guard) => {
+                if let Some(Guard { body: guarded, clauses }) = Rc::get_mut(guard) {
+                    take(guarded);
+                    body(clauses, &mut take);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct Compiler<'a> {
+    globals: &'a mut Globals,
+    /// Variables bound by each enclosing frame, innermost last
+    scopes: Vec<Vec<Rc<str>>>,
+}
+
+/// Part of a form left for [`Compiler::run`] by the compilation of the form containing it,
+/// compiled once reached so that it sees the variables of the frames enclosing it
+enum Task<'e> {
+    Expression(&'e Expression),
+    /// Definition of a body, or top-level one binding global variables
+    Definition(&'e Definition),
+    /// Body of the innermost frame
+    Body(&'e Body),
+    Template(&'e Template),
+    /// Compiled by the function once reached, as parts resolving variables themselves are
+    Deferred(Deferred<'e>),
+    /// Compilation within a new frame binding the variables
+    Frame(Vec<Rc<str>>, Compilation<'e, Node>),
+    /// End of the innermost frame
+    Exit,
+}
+
+/// Function compiling a [`Task::Deferred`]
+type Deferred<'e> = Box<dyn FnOnce(&mut Compiler<'_>) -> Result<Compilation<'e, Node>> + 'e>;
+
+/// Compiled task
+enum Compiled {
+    Node(Node),
+    /// Node compiled within a frame, along with the final frame size
+    Frame(Node, usize),
+    /// Final size of a frame, once exited
+    Size(usize),
+}
+
+/// Part of a form whose tasks are left to compile, built from their nodes once [`Compiler::run`]
+/// compiled them in the same order
+struct Compilation<'e, T> {
+    tasks: Vec<Task<'e>>,
+    build: Box<dyn FnOnce(&mut Nodes) -> T + 'e>,
+}
+
+/// Nodes of the tasks of a [`Compilation`], taken in order by its `build` function
+struct Nodes(std::vec::IntoIter<Compiled>);
+
+impl<'a> Compiler<'a> {
+    pub(crate) fn new(globals: &'a mut Globals) -> Self {
+        Compiler { globals, scopes: Vec::new() }
+    }
+
+    /// Compiles a top-level form, definitions binding global variables.
+    pub(crate) fn form(&mut self, form: &Form) -> Result<Node> {
+        match form {
+            Form::Definition(definition) => self.run(Task::Definition(definition)),
+            Form::Expression(expression) => self.run(Task::Expression(expression)),
+        }
+    }
+
+    /// Compiles a task with an explicit stack of compilations rather than recursion, so that
+    /// deeply nested forms such as those of macro expansions can't overflow the stack.
+    ///
+    /// Tasks are compiled in order and depth first, each compilation being built once the nodes
+    /// of all of its tasks are.
+    fn run(&mut self, task: Task) -> Result<Node> {
+        let depth = self.scopes.len();
+        let root = Compilation::new(vec![task], |nodes| Compiled::Node(nodes.node()));
+        let mut stack = vec![(root.tasks.into_iter(), root.build, Vec::new())];
+        loop {
+            let (tasks, _, _) = stack.last_mut().expect("popped once built");
+            if let Some(task) = tasks.next() {
+                let compilation = self.task(task).inspect_err(|_| self.scopes.truncate(depth))?;
+                stack.push((compilation.tasks.into_iter(), compilation.build, Vec::new()));
+                continue;
+            }
+
+            let (_, build, compiled) = stack.pop().expect("checked above");
+            let compiled = build(&mut Nodes(compiled.into_iter()));
+            match stack.last_mut() {
+                Some((_, _, parent)) => parent.push(compiled),
+                None => return Ok(compiled.node()),
+            }
+        }
+    }
+
+    /// Compiles the task itself, leaving the tasks of its parts
+    fn task<'e>(&mut self, task: Task<'e>) -> Result<Compilation<'e, Compiled>> {
+        let compilation = match task {
+            Task::Expression(expression) => self.expression(expression)?,
+            Task::Definition(definition) => self.definition(definition)?,
+            Task::Body(body) => self.body(body)?,
+            Task::Template(nested) => template(nested),
+            Task::Deferred(compile) => compile(self)?,
+            Task::Frame(variables, Compilation { mut tasks, build }) => {
+                self.scopes.push(variables);
+                tasks.push(Task::Exit);
+                return Ok(Compilation::new(tasks, |nodes| {
+                    let node = build(nodes);
+                    Compiled::Frame(node, nodes.size())
+                }));
+            }
+            Task::Exit => {
+                return Ok(Compilation::done(Compiled::Size(
+                    self.scopes.pop().expect("pushed by the frame").len(),
+                )))
+            }
+        };
+
+        Ok(compilation.map(Compiled::Node))
+    }
+
+    /// Compiles a definition, its variables being those of the innermost frame within a body.
+    fn definition<'e>(&mut self, definition: &'e Definition) -> Result<Compilation<'e, Node>> {
+        Ok(match definition {
+            Definition::Variable(definition) => {
+                let variable = self.variable(&definition.variable);
+                named_value(&definition.variable, &definition.value).map(|value| define(variable, value))
+            }
+            Definition::Values(definition) => {
+                let ast::ValuesDefinition { formals, value, .. } = definition;
+                let variables = formals_variables(formals).map(|variable| self.variable(variable)).collect();
+                let (arity, span) = (formals_arity(formals), formals.span);
+                Compilation::expression(value).map(move |value| Node::DefineValues(Rc::new(DefineValues { value, arity, variables, span })))
+            }
+            Definition::RecordType(record_type) => Compilation::done(self.record_type(record_type)),
+            Definition::Syntax(definition) => return Err(EvalError::UnsupportedForm("define-syntax", definition.span)),
+            Definition::Begin { definitions, .. } => {
+                let len = definitions.len();
+                Compilation::new(definitions.iter().map(Task::Definition).collect(), move |nodes| {
+                    sequence(nodes.nodes(len))
+                })
+            }
+        })
+    }
+
+    /// Makes the record type, binding it along with its procedures.
+    fn record_type(&mut self, record_type: &ast::RecordType) -> Node {
+        let ast::RecordType { name, constructor, predicate, fields, .. } = record_type;
+        let record_type = Rc::new(RecordType {
+            name: Rc::from(&*name.name),
+            fields: fields.iter().map(|field| Rc::from(&*field.name.name)).collect(),
+        });
+
+        let index = |field: &Identifier| {
+            let index = fields.iter().position(|candidate| candidate.name.name == field.name);
+            index.expect("constructor fields are checked by the lowering")
+        };
+        let mut procedures = Vec::new();
+        if let Some(constructor) = constructor {
+            let indexes = constructor.fields.iter().map(index).collect();
+            procedures.push((&constructor.name, RecordProcedureKind::Constructor(indexes)));
+        }
+        procedures.push((predicate, RecordProcedureKind::Predicate));
+        for (index, field) in fields.iter().enumerate() {
+            procedures.push((&field.accessor, RecordProcedureKind::Accessor(index)));
+            procedures.extend(
+                field
+                    .modifier
+                    .iter()
+                    .map(|modifier| (modifier, RecordProcedureKind::Modifier(index))),
+            );
+        }
+
+        let mut nodes = vec![define(self.variable(name), Node::Constant(Value::RecordType(record_type.clone())))];
+        for (variable, kind) in procedures {
+            let procedure = RecordProcedure {
+                name: Rc::from(&*variable.name),
+                record_type: record_type.clone(),
+                kind,
+            };
+            let value = Value::Procedure(Procedure(ProcedureKind::Record(Rc::new(procedure))));
+            nodes.push(define(self.variable(variable), Node::Constant(value)));
+        }
+        sequence(nodes)
+    }
+
+    fn expression<'e>(&mut self, expression: &'e Expression) -> Result<Compilation<'e, Node>> {
+        Ok(match expression {
+            Expression::Variable(identifier) => Compilation::done(match self.local(identifier) {
+                Some(local) => Node::Local(local),
+                None => Node::Global(self.global(&identifier.name), identifier.span),
+            }),
+            Expression::Literal(literal) => Compilation::done(Node::Constant(match literal {
+                Literal::Number { value, .. } => Value::Number(value.clone()),
+                Literal::SelfEvaluating(datum) => Value::from_datum(datum),
+                Literal::Quotation(quotation) => Value::from_datum(&quotation.datum),
+            })),
+            Expression::Call(call) => {
+                let (len, span) = (call.operands.len(), call.span);
+                let tasks = std::iter::once(&*call.operator)
+                    .chain(&call.operands)
+                    .map(Task::Expression)
+                    .collect();
+                Compilation::new(tasks, move |nodes| {
+                    Node::Call(Rc::new(Call { operator: nodes.node(), operands: nodes.nodes(len), span }))
+                })
+            }
+            Expression::Lambda(form) => lambda(&form.formals, &form.body, None),
+            Expression::If(if_expression) => {
+                let ast::If { test, consequent, alternate, .. } = if_expression;
+                let tasks = [test, consequent]
+                    .into_iter()
+                    .chain(alternate)
+                    .map(|expression| Task::Expression(expression));
+                let has_alternate = alternate.is_some();
+                Compilation::new(tasks.collect(), move |nodes| {
+                    Node::If(Rc::new(If {
+                        test: nodes.node(),
+                        consequent: nodes.node(),
+                        alternate: match has_alternate {
+                            true => nodes.node(),
+                            false => Node::Constant(Value::Unspecified),
+                        },
+                    }))
+                })
+            }
+            Expression::Set(set) => {
+                let value = Compilation::expression(&set.value);
+                match self.local(&set.variable) {
+                    Some(local) => value.map(|value| Node::SetLocal(local, Rc::new(value))),
+                    None => {
+                        let (global, span) = (self.global(&set.variable.name), set.variable.span);
+                        value.map(move |value| Node::SetGlobal(global, Rc::new(value), span))
+                    }
+                }
+            }
+            Expression::Begin(begin) => Compilation::sequence(&begin.expressions),
+            Expression::Let(form) => let_expression(form),
+            Expression::Include(include) => {
+                return Err(EvalError::UnsupportedForm(
+                    if include.case_insensitive { "include-ci" } else { "include" },
+                    include.span,
+                ))
+            }
+            Expression::Cond(cond) => cond_clauses(&cond.clauses, &cond.else_clause, Node::Constant(Value::Unspecified)),
+            Expression::Case(form) => case(form),
+            Expression::And(and) => Compilation::expressions(&and.expressions).map(|nodes| junction(nodes, true, Node::And)),
+            Expression::Or(or) => Compilation::expressions(&or.expressions).map(|nodes| junction(nodes, false, Node::Or)),
+            Expression::When(when) => Compilation::expression(&when.test)
+                .zip(Compilation::sequence(&when.body))
+                .map(|(test, consequent)| Node::If(Rc::new(If { test, consequent, alternate: Node::Constant(Value::Unspecified) }))),
+            Expression::Unless(unless) => Compilation::expression(&unless.test)
+                .zip(Compilation::sequence(&unless.body))
+                .map(|(test, alternate)| Node::If(Rc::new(If { test, consequent: Node::Constant(Value::Unspecified), alternate }))),
+            Expression::LetValues(form) => let_values(form),
+            Expression::Do(form) => do_expression(form),
+            Expression::Delay(delay) => thunk(&delay.expression).map(|thunk| Node::Delay(thunk, false)),
+            Expression::DelayForce(delay) => thunk(&delay.expression).map(|thunk| Node::Delay(thunk, true)),
+            Expression::Parameterize(form) => parameterize(form),
+            Expression::Guard(form) => guard(form),
+            Expression::Quasiquote(quasiquote) => template(&quasiquote.template),
+            Expression::CaseLambda(form) => case_lambda(form, None),
+            Expression::LetSyntax(let_syntax) => {
+                return unsupported(if let_syntax.recursive { "letrec-syntax" } else { "let-syntax" }, expression)
+            }
+        })
+    }
+
+    /// Compiles a body within the innermost frame, which gets a slot for each variable bound by
+    /// an internal definition.
+    fn body<'e>(&mut self, body: &'e Body) -> Result<Compilation<'e, Node>> {
+        // definitions are in scope of each other, as with `letrec*`
+        let variables = defined_variables(&body.definitions)?;
+        let scope = self.scopes.last_mut().expect("bodies are compiled within a frame");
+        scope.extend(variables.into_iter().map(|variable| Rc::from(&*variable.name)));
+
+        let len = body.definitions.len();
+        let definitions = Compilation::new(body.definitions.iter().map(Task::Definition).collect(), move |nodes| {
+            nodes.nodes(len)
+        });
+        Ok(definitions
+            .zip(Compilation::expressions(&body.expressions))
+            .map(|(mut nodes, expressions)| {
+                nodes.extend(expressions);
+                sequence(nodes)
+            }))
+    }
+
+    /// Local variable of the innermost frame binding `identifier`, global variable otherwise
+    fn variable(&mut self, identifier: &Identifier) -> Variable {
+        match self.local(identifier) {
+            Some(local) => Variable::Local(local),
+            None => Variable::Global(self.global(&identifier.name)),
+        }
+    }
+
+    /// Innermost local binding of `identifier`, the last slot of a frame shadowing earlier ones
+    fn local(&self, identifier: &Identifier) -> Option<Local> {
+        self.scopes.iter().rev().enumerate().find_map(|(depth, variables)| {
+            let index = variables.iter().rposition(|variable| **variable == *identifier.name)?;
+            Some(Local { depth, index, name: variables[index].clone(), span: identifier.span })
+        })
+    }
+
+    fn global(&mut self, name: &str) -> Rc<Global> {
+        if let Some(global) = self.globals.get(name) {
+            return global.clone();
+        }
+
+        let name = Rc::<str>::from(name);
+        let global = Rc::new(Global { name: name.clone(), value: RefCell::new(None) });
+        self.globals.insert(name, global.clone());
+        global
+    }
+}
+
+impl<'e, T: 'e> Compilation<'e, T> {
+    fn new(tasks: Vec<Task<'e>>, build: impl FnOnce(&mut Nodes) -> T + 'e) -> Self {
+        Compilation { tasks, build: Box::new(build) }
+    }
+
+    /// Part without tasks
+    fn done(value: T) -> Self {
+        Compilation::new(Vec::new(), move |_| value)
+    }
+
+    fn map<U: 'e>(self, f: impl FnOnce(T) -> U + 'e) -> Compilation<'e, U> {
+        let build = self.build;
+        Compilation::new(self.tasks, move |nodes| f(build(nodes)))
+    }
+
+    /// Both compilations, the tasks of `self` coming first
+    fn zip<U: 'e>(self, other: Compilation<'e, U>) -> Compilation<'e, (T, U)> {
+        let (build, other_build) = (self.build, other.build);
+        let mut tasks = self.tasks;
+        tasks.extend(other.tasks);
+        Compilation::new(tasks, move |nodes| (build(nodes), other_build(nodes)))
+    }
+
+    /// Every compilation, in order
+    fn all(compilations: Vec<Self>) -> Compilation<'e, Vec<T>> {
+        let mut tasks = Vec::new();
+        let mut builds = Vec::new();
+        for compilation in compilations {
+            tasks.extend(compilation.tasks);
+            builds.push(compilation.build);
+        }
+        Compilation::new(tasks, move |nodes| builds.into_iter().map(|build| build(nodes)).collect())
+    }
+}
+
+impl<'e> Compilation<'e, Node> {
+    fn expression(expression: &'e Expression) -> Self {
+        Compilation::new(vec![Task::Expression(expression)], Nodes::node)
+    }
+
+    /// Single node evaluating the expressions in order
+    fn sequence(expressions: &'e [Expression]) -> Self {
+        Compilation::expressions(expressions).map(sequence)
+    }
+
+    fn body(body: &'e Body) -> Self {
+        Compilation::new(vec![Task::Body(body)], Nodes::node)
+    }
+
+    /// Compiled by `compile` once reached, within the frames enclosing it by then
+    fn deferred(compile: impl FnOnce(&mut Compiler<'_>) -> Result<Self> + 'e) -> Self {
+        Compilation::new(vec![Task::Deferred(Box::new(compile))], Nodes::node)
+    }
+}
+
+impl<'e> Compilation<'e, Vec<Node>> {
+    fn expressions(expressions: impl IntoIterator<Item = &'e Expression>) -> Self {
+        let tasks: Vec<_> = expressions.into_iter().map(Task::Expression).collect();
+        let len = tasks.len();
+        Compilation::new(tasks, move |nodes| nodes.nodes(len))
+    }
+}
+
+impl Compiled {
+    fn node(self) -> Node {
+        match self {
+            Compiled::Node(node) => node,
+            _ => unreachable!("expressions and bodies are compiled to nodes"),
+        }
+    }
+}
+
+impl Nodes {
+    fn node(&mut self) -> Node {
+        self.0.next().expect("one node per task").node()
+    }
+
+    fn nodes(&mut self, len: usize) -> Vec<Node> {
+        (0..len).map(|_| self.node()).collect()
+    }
+
+    fn frame(&mut self) -> (Node, usize) {
+        match self.0.next() {
+            Some(Compiled::Frame(node, frame_size)) => (node, frame_size),
+            _ => unreachable!("frames are compiled to nodes along with their size"),
+        }
+    }
+
+    fn size(&mut self) -> usize {
+        match self.0.next() {
+            Some(Compiled::Size(frame_size)) => frame_size,
+            _ => unreachable!("exiting a frame yields its size"),
+        }
+    }
+}
+
+/// Clauses nested as alternates of each other from the last one, `fallback` being evaluated if
+/// none matches and there is no `else` clause
+fn cond_clauses<'e>(clauses: &'e [CondClause], else_clause: &'e Option<Vec<Expression>>, fallback: Node) -> Compilation<'e, Node> {
+    let compiled = clauses
+        .iter()
+        .map(|clause| Compilation::expression(&clause.test).zip(clause_body(&clause.body)))
+        .collect();
+    let else_clause = match else_clause {
+        Some(expressions) => Compilation::sequence(expressions),
+        None => Compilation::done(fallback),
+    };
+
+    Compilation::all(compiled).zip(else_clause).map(|(compiled, mut node)| {
+        for (clause, (test, body)) in clauses.iter().zip(compiled).rev() {
+            node = match (&clause.body, body) {
+                // `(<test>)` yields the test value when true
+                (ClauseBody::Sequence(expressions), _) if expressions.is_empty() => Node::Or(Rc::new([test, node])),
+                (_, Clause::Sequence(consequent)) => Node::If(Rc::new(If { test, consequent, alternate: node })),
+                (_, Clause::Arrow(recipient)) => Node::Arrow(Rc::new(Arrow { test, recipient, alternate: node, span: clause.span })),
+            };
+        }
+        node
+    })
+}
+
+fn case(case: &ast::Case) -> Compilation<'_, Node> {
+    let clauses = case
+        .clauses
+        .iter()
+        .map(|clause| {
+            let data: Vec<_> = clause.data.iter().map(Value::from_datum).collect();
+            clause_body(&clause.body).map(|body| (data, body))
+        })
+        .collect();
+    let else_clause = match &case.else_clause {
+        Some(body) => clause_body(body),
+        None => Compilation::done(Clause::Sequence(Node::Constant(Value::Unspecified))),
+    };
+
+    let span = case.span;
+    Compilation::expression(&case.key)
+        .zip(Compilation::all(clauses))
+        .zip(else_clause)
+        .map(move |((key, clauses), else_clause)| Node::Case(Rc::new(Case { key, clauses, else_clause, span })))
+}
+
+fn clause_body(body: &ClauseBody) -> Compilation<'_, Clause> {
+    match body {
+        ClauseBody::Sequence(expressions) => Compilation::sequence(expressions).map(Clause::Sequence),
+        ClauseBody::Arrow(recipient) => Compilation::expression(recipient).map(Clause::Arrow),
+    }
+}
+
+fn guard(guard: &ast::Guard) -> Compilation<'_, Node> {
+    let body = scope(Vec::new(), Compilation::done(Vec::new()), Compilation::body(&guard.body));
+    let clauses = frame(
+        vec![Rc::from(&*guard.variable.name)],
+        cond_clauses(&guard.clauses, &guard.else_clause, Node::Reraise(guard.span)),
+    );
+
+    body.zip(clauses).map(|(body, (clauses, frame_size))| {
+        // clauses bind no other variable, leaving the second slot to the continuation
+        debug_assert_eq!(1, frame_size);
+        let clauses = Rc::new(Lambda { name: None, arity: Arity::exactly(2), frame_size: 2, body: clauses });
+        Node::Guard(Rc::new(Guard { body, clauses }))
+    })
+}
+
+fn lambda<'e>(formals: &Formals, body: &'e Body, name: Option<Rc<str>>) -> Compilation<'e, Node> {
+    formals_procedure(formals, body, name).map(Node::Lambda)
+}
+
+fn formals_procedure<'e>(formals: &Formals, body: &'e Body, name: Option<Rc<str>>) -> Compilation<'e, Rc<Lambda>> {
+    let parameters = formals_variables(formals).map(|parameter| Rc::from(&*parameter.name)).collect();
+    procedure(parameters, formals_arity(formals), body, name)
+}
+
+fn formals_arity(formals: &Formals) -> Arity {
+    match formals.rest {
+        Some(_) => Arity::at_least(formals.required.len()),
+        None => Arity::exactly(formals.required.len()),
+    }
+}
+
+/// Required variables, followed by the rest variable if any
+fn formals_variables(formals: &Formals) -> impl Iterator<Item = &Identifier> {
+    formals.required.iter().chain(&formals.rest)
+}
+
+fn procedure(parameters: Vec<Rc<str>>, arity: Arity, body: &Body, name: Option<Rc<str>>) -> Compilation<'_, Rc<Lambda>> {
+    frame(parameters, Compilation::body(body)).map(move |(body, frame_size)| Rc::new(Lambda { name, arity, frame_size, body }))
+}
+
+/// Procedure without parameters evaluating `expression`, as delayed by a promise
+fn thunk(expression: &Expression) -> Compilation<'_, Rc<Lambda>> {
+    frame(Vec::new(), Compilation::expression(expression))
+        .map(|(body, frame_size)| Rc::new(Lambda { name: None, arity: Arity::exactly(0), frame_size, body }))
+}
+
+fn case_lambda(case_lambda: &ast::CaseLambda, name: Option<Rc<str>>) -> Compilation<'_, Node> {
+    let clauses = case_lambda
+        .clauses
+        .iter()
+        .map(|clause| formals_procedure(&clause.formals, &clause.body, name.clone()))
+        .collect();
+
+    Compilation::all(clauses).map(|clauses| {
+        let min = clauses.iter().map(|lambda| lambda.arity.min).min().unwrap_or(0);
+        let max = clauses.iter().try_fold(0, |max, lambda| Some(max.max(lambda.arity.max?)));
+        let arity = Arity { min, max };
+
+        Node::CaseLambda(Rc::new(CaseLambda { name, arity, clauses }))
+    })
+}
+
+/// `(let <loop> ((<variable> <init>)*) (if <test> (begin <result>*) (begin <command>* (<loop>
+/// <step>*))))`, the loop procedure being bound to `do`, which no variable can be named after
+fn do_expression(do_expression: &ast::Do) -> Compilation<'_, Node> {
+    let ast::Do { iterations, test, result, commands, span } = do_expression;
+    let span = *span;
+    let name = Identifier { name: "do".into(), span };
+    let variables = iterations.iter().map(|iteration| Rc::from(&*iteration.variable.name)).collect();
+
+    let loop_name = name.clone();
+    let body = Compilation::deferred(move |compiler| {
+        let operator = Node::Local(compiler.local(&loop_name).expect("bound by the enclosing frame"));
+        let steps = iterations
+            .iter()
+            .map(|iteration| match &iteration.step {
+                Some(step) => Compilation::expression(step),
+                None => Compilation::done(Node::Local(compiler.local(&iteration.variable).expect("bound by the loop"))),
+            })
+            .collect();
+
+        Ok(Compilation::expression(test)
+            .zip(Compilation::sequence(result))
+            .zip(Compilation::expressions(commands))
+            .zip(Compilation::all(steps))
+            .map(move |(((test, result), mut nodes), operands)| {
+                nodes.push(Node::Call(Rc::new(Call { operator, operands, span })));
+                Node::If(Rc::new(If { test, consequent: result, alternate: sequence(nodes) }))
+            }))
+    });
+    let arity = Arity::exactly(iterations.len());
+    let procedure = frame(variables, body).map(move |(body, frame_size)| Lambda { name: None, arity, frame_size, body });
+
+    let inits = Compilation::expressions(iterations.iter().map(|iteration| &iteration.init));
+    scope(
+        vec![Rc::from("do")],
+        Compilation::done(Vec::new()),
+        Compilation::deferred(move |compiler| {
+            let local = compiler.local(&name).expect("bound by the innermost frame");
+            Ok(procedure.zip(inits).map(move |(procedure, operands)| {
+                sequence(vec![
+                    Node::SetLocal(local.clone(), Rc::new(Node::Lambda(Rc::new(procedure)))),
+                    Node::Call(Rc::new(Call { operator: Node::Local(local), operands, span })),
+                ])
+            }))
+        }),
+    )
+}
+
+/// Call to the `parameterize` primitive with each parameter and its value, then the thunk of the
+/// body
+fn parameterize(parameterize: &ast::Parameterize) -> Compilation<'_, Node> {
+    let bindings = parameterize.bindings.iter().flat_map(|(parameter, value)| [parameter, value]);
+    let body = procedure(Vec::new(), Arity::exactly(0), &parameterize.body, None);
+
+    let span = parameterize.span;
+    Compilation::expressions(bindings).zip(body).map(move |(mut operands, body)| {
+        operands.push(Node::Lambda(body));
+        let operator = Node::Constant(Value::Procedure((&primitive::PARAMETERIZE).into()));
+        Node::Call(Rc::new(Call { operator, operands, span }))
+    })
+}
+
+/// Quasiquotation template, lists being built by `list` and `append`, vectors by converting such
+/// a list
+fn template(template: &Template) -> Compilation<'_, Node> {
+    match template {
+        Template::Datum(datum) => Compilation::done(Node::Constant(Value::from_datum(datum))),
+        Template::Unquote(expression) => Compilation::expression(expression),
+        Template::List { elements, tail, span } => {
+            let span = *span;
+            let mut tasks = template_tasks(elements);
+            tasks.extend(tail.as_deref().map(Task::Template));
+            let has_tail = tail.is_some();
+            Compilation::new(tasks, move |nodes| {
+                let lists = template_elements(elements, nodes, span);
+                let tail = has_tail.then(|| nodes.node());
+                append(lists, tail, span)
+            })
+        }
+        Template::Vector { elements, span } => {
+            let span = *span;
+            Compilation::new(template_tasks(elements), move |nodes| {
+                let list = append(template_elements(elements, nodes, span), None, span);
+                builtin_call("list->vector", vec![list], span)
+            })
+        }
+    }
+}
+
+fn template_tasks(elements: &[TemplateElement]) -> Vec<Task<'_>> {
+    elements
+        .iter()
+        .map(|element| match element {
+            TemplateElement::Template(template) => Task::Template(template),
+            TemplateElement::Splice(expression) => Task::Expression(expression),
+        })
+        .collect()
+}
+
+/// Lists to append for the elements, spliced ones being shared or copied as `append` does, along
+/// with the trailing run of single elements
+fn template_elements(elements: &[TemplateElement], nodes: &mut Nodes, span: Span) -> (Vec<Node>, Vec<Node>) {
+    let mut lists = Vec::new();
+    let mut run = Vec::new();
+    for element in elements {
+        match element {
+            TemplateElement::Template(_) => run.push(nodes.node()),
+            TemplateElement::Splice(_) => {
+                if !run.is_empty() {
+                    lists.push(builtin_call("list", std::mem::take(&mut run), span));
+                }
+                lists.push(nodes.node());
+            }
+        }
+    }
+
+    (lists, run)
+}
+
+/// List of the elements, spliced ones included, ending with `tail`
+fn append((mut lists, run): (Vec<Node>, Vec<Node>), tail: Option<Node>, span: Span) -> Node {
+    if lists.is_empty() && tail.is_none() {
+        return builtin_call("list", run, span);
+    }
+    if !run.is_empty() {
+        lists.push(builtin_call("list", run, span));
+    }
+    lists.extend(tail);
+    builtin_call("append", lists, span)
+}
+
+fn let_expression(let_expression: &ast::Let) -> Compilation<'_, Node> {
+    let ast::Let { kind, name, bindings, body, span } = let_expression;
+    let variables = || bindings.iter().map(|binding| Rc::from(&*binding.variable.name)).collect::<Vec<_>>();
+    let inits = || Compilation::expressions(bindings.iter().map(|binding| &binding.init));
+
+    match (kind, name) {
+        (LetKind::Let, None) => scope(variables(), inits(), Compilation::body(body)),
+        // ((letrec ((<name> (lambda (<variable>*) <body>))) <name>) <init>*)
+        (LetKind::Let, Some(name)) => {
+            let procedure = procedure(variables(), Arity::exactly(bindings.len()), body, Some(Rc::from(&*name.name)));
+            let operator = scope(
+                vec![Rc::from(&*name.name)],
+                Compilation::done(Vec::new()),
+                Compilation::deferred(move |compiler| {
+                    let local = compiler.local(name).expect("bound by the innermost frame");
+                    Ok(procedure.map(move |procedure| {
+                        sequence(vec![
+                            Node::SetLocal(local.clone(), Rc::new(Node::Lambda(procedure))),
+                            Node::Local(local),
+                        ])
+                    }))
+                }),
+            );
+
+            let span = *span;
+            inits()
+                .zip(operator)
+                .map(move |(operands, operator)| Node::Call(Rc::new(Call { operator, operands, span })))
+        }
+        (LetKind::LetStar, _) => let_star(bindings, body),
+        (LetKind::Letrec | LetKind::LetrecStar, _) => scope(
+            variables(),
+            Compilation::done(Vec::new()),
+            Compilation::deferred(move |compiler| {
+                let values = bindings.iter().map(|Binding { variable, init }| {
+                    let local = compiler.local(variable).expect("bound by the innermost frame");
+                    named_value(variable, init).map(|value| Node::SetLocal(local, Rc::new(value)))
+                });
+
+                Ok(Compilation::all(values.collect())
+                    .zip(Compilation::body(body))
+                    .map(|(mut nodes, body)| {
+                        nodes.push(body);
+                        sequence(nodes)
+                    }))
+            }),
+        ),
+    }
+}
+
+/// Nested frames of a single variable each, the inner ones being compiled once reached
+fn let_star<'e>(bindings: &'e [Binding], body: &'e Body) -> Compilation<'e, Node> {
+    let Some((first, rest)) = bindings.split_first() else {
+        return scope(Vec::new(), Compilation::done(Vec::new()), Compilation::body(body));
+    };
+
+    let inner = match rest.is_empty() {
+        true => Compilation::body(body),
+        false => Compilation::deferred(|_| Ok(let_star(rest, body))),
+    };
+    scope(
+        vec![Rc::from(&*first.variable.name)],
+        Compilation::expressions([&first.init]),
+        inner,
+    )
+}
+
+/// New frame binding `variables`, the first ones to `inits` evaluated in the enclosing frame
+fn scope<'e>(variables: Vec<Rc<str>>, inits: Compilation<'e, Vec<Node>>, body: Compilation<'e, Node>) -> Compilation<'e, Node> {
+    inits
+        .zip(frame(variables, body))
+        .map(|(inits, (body, frame_size))| Node::Scope(Rc::new(Scope { inits, formals: Vec::new(), frame_size, body })))
+}
+
+fn let_values(let_values: &ast::LetValues) -> Compilation<'_, Node> {
+    let ast::LetValues { sequential, bindings, body, .. } = let_values;
+    match sequential {
+        false => values_scope(bindings, Compilation::body(body)),
+        true => let_star_values(bindings, body),
+    }
+}
+
+/// Nested frames of the formals of a single binding each, as with `let*`
+fn let_star_values<'e>(bindings: &'e [(Formals, Expression)], body: &'e Body) -> Compilation<'e, Node> {
+    let inner = match bindings.len() {
+        0 | 1 => Compilation::body(body),
+        _ => Compilation::deferred(|_| Ok(let_star_values(&bindings[1..], body))),
+    };
+    values_scope(&bindings[..bindings.len().min(1)], inner)
+}
+
+/// New frame binding the formals of each binding to the values of its init
+fn values_scope<'e>(bindings: &'e [(Formals, Expression)], body: Compilation<'e, Node>) -> Compilation<'e, Node> {
+    let variables = bindings
+        .iter()
+        .flat_map(|(formals, _)| formals_variables(formals))
+        .map(|variable| Rc::from(&*variable.name))
+        .collect();
+    let formals = bindings.iter().map(|(formals, _)| (formals_arity(formals), formals.span)).collect();
+
+    Compilation::expressions(bindings.iter().map(|(_, init)| init))
+        .zip(frame(variables, body))
+        .map(|(inits, (body, frame_size))| Node::Scope(Rc::new(Scope { inits, formals, frame_size, body })))
+}
+
+/// Compiles within a new frame binding `variables`, along with the final frame size.
+fn frame(variables: Vec<Rc<str>>, compilation: Compilation<'_, Node>) -> Compilation<'_, (Node, usize)> {
+    Compilation::new(vec![Task::Frame(variables, compilation)], Nodes::frame)
+}
+
+/// Lambda expressions bound to a variable are named after it.
+fn named_value<'e>(variable: &Identifier, value: &'e Expression) -> Compilation<'e, Node> {
+    match value {
+        Expression::Lambda(form) => lambda(&form.formals, &form.body, Some(Rc::from(&*variable.name))),
+        Expression::CaseLambda(form) => case_lambda(form, Some(Rc::from(&*variable.name))),
+        _ => Compilation::expression(value),
+    }
+}
+
+/// Single node evaluating `nodes` in order, unspecified if empty
+fn sequence(mut nodes: Vec<Node>) -> Node {
+    match nodes.len() {
+        0 => Node::Constant(Value::Unspecified),
+        1 => nodes.pop().expect("one node"),
+        _ => Node::Sequence(nodes.into()),
+    }
+}
+
+/// Call to the primitive named `name`, whatever the program binds the name to
+fn builtin_call(name: &str, operands: Vec<Node>, span: Span) -> Node {
+    let operator = Node::Constant(primitive::builtin(name));
+    Node::Call(Rc::new(Call { operator, operands, span }))
+}
+
+/// `and` or `or` of `nodes`, `empty` being the value without any node
+fn junction(mut nodes: Vec<Node>, empty: bool, junction: fn(Rc<[Node]>) -> Node) -> Node {
+    match nodes.len() {
+        0 => Node::Constant(Value::Boolean(empty)),
+        1 => nodes.pop().expect("one node"),
+        _ => junction(nodes.into()),
+    }
+}
+
+/// Variables bound by the definitions, those of `begin` included
+fn defined_variables(definitions: &[Definition]) -> Result<Vec<&Identifier>> {
+    let mut variables = Vec::new();
+    let mut pending: Vec<_> = definitions.iter().rev().collect();
+    while let Some(definition) = pending.pop() {
+        match definition {
+            Definition::Variable(definition) => variables.push(&definition.variable),
+            Definition::Values(definition) => variables.extend(formals_variables(&definition.formals)),
+            Definition::RecordType(record_type) => {
+                let ast::RecordType { name, constructor, predicate, fields, .. } = record_type;
+                variables.push(name);
+                variables.extend(constructor.iter().map(|constructor| &constructor.name));
+                variables.push(predicate);
+                for field in fields {
+                    variables.push(&field.accessor);
+                    variables.extend(&field.modifier);
+                }
+            }
+            Definition::Syntax(definition) => return Err(EvalError::UnsupportedForm("define-syntax", definition.span)),
+            Definition::Begin { definitions, .. } => pending.extend(definitions.iter().rev()),
+        }
+    }
+
+    Ok(variables)
+}
+
+/// Binds `variable` to the value of `value`.
+fn define(variable: Variable, value: Node) -> Node {
+    match variable {
+        Variable::Local(local) => Node::SetLocal(local, Rc::new(value)),
+        Variable::Global(global) => Node::DefineGlobal(global, Rc::new(value)),
+    }
+}
+
+fn unsupported<T>(keyword: &'static str, expression: &Expression) -> Result<T> {
+    Err(EvalError::UnsupportedForm(keyword, expression.span()))
+}
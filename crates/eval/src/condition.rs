@@ -0,0 +1,87 @@
+//! Error objects of R7RS 6.11
+
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use pluine_lex::{diagnostic::ToDiagnostic, span::Span};
+
+use crate::*;
+
+/// Object raised by `error`, by `read` given malformed input, by file operations, or in place
+/// of an [`EvalError`] when the program installed an exception handler
+pub struct ErrorObject {
+    kind: ErrorKind,
+    message: Box<str>,
+    irritants: Vec<Value>,
+    span: Option<Span>,
+    /// See [`ErrorObject::input`]
+    input: Option<Rc<str>>,
+}
+
+/// Condition type of an [`ErrorObject`], as told apart by `read-error?` and `file-error?`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ErrorKind {
+    /// Raised by `error` or by the evaluator
+    Error,
+    /// Malformed input given to `read`
+    Read,
+    /// File which could not be opened
+    File,
+}
+
+impl ErrorObject {
+    pub(crate) fn new(kind: ErrorKind, message: impl Into<Box<str>>, irritants: Vec<Value>, span: Option<Span>) -> Self {
+        ErrorObject { kind, message: message.into(), irritants, span, input: None }
+    }
+
+    /// Error of `kind` described by a diagnosable error, located at its primary label
+    pub(crate) fn from_error(kind: ErrorKind, error: &(impl ToDiagnostic + Display)) -> Self {
+        let span = error.to_diagnostic().labels.first().map(|label| label.span);
+        ErrorObject::new(kind, error.to_string(), Vec::new(), span)
+    }
+
+    /// Same error, its span pointing into `input` rather than into the program
+    pub(crate) fn in_input(self, input: Rc<str>) -> Self {
+        ErrorObject { input: Some(input), ..self }
+    }
+
+    /// See [`ErrorKind`]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// R7RS `error-object-message`
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// R7RS `error-object-irritants`
+    pub fn irritants(&self) -> &[Value] {
+        &self.irritants
+    }
+
+    /// Source region the error originates from, within [`ErrorObject::input`] if any, within the
+    /// program otherwise. `None` for errors raised by `error`.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Text of the input port a read error occurred in, which its span points into
+    pub fn input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+}
+
+/// Message followed by the written irritants
+impl Display for ErrorObject {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)?;
+        for irritant in &self.irritants {
+            write!(formatter, " {}", irritant.written())?;
+        }
+
+        Ok(())
+    }
+}
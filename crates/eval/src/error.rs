@@ -0,0 +1,136 @@
+use pluine_common::ParseError;
+use pluine_lex::{
+    diagnostic::{Diagnostic, ToDiagnostic},
+    span::Span,
+};
+use thiserror::Error;
+
+use crate::{primitive::PrimitiveError, *};
+
+/// Error returned when evaluating a program
+#[derive(Debug, PartialEq, Error)]
+pub enum EvalError {
+    /// See [`ParseError`]
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// Keyword of the form, inner span points to the form
+    #[error("`{0}` is not supported by the evaluator yet")]
+    UnsupportedForm(&'static str, Span),
+    /// Variable name, inner span points to the reference or assignment
+    #[error("unbound variable `{0}`")]
+    UnboundVariable(Box<str>, Span),
+    /// Variable name, inner span points to the reference
+    #[error("variable `{0}` used before its initialization")]
+    UninitializedVariable(Box<str>, Span),
+    /// Inner span points to the call
+    #[error("attempt to call a non-procedure")]
+    NotProcedure(Span),
+    /// Wrong number of arguments given to a procedure
+    #[error("wrong number of arguments, expected {expected} but got {found}")]
+    ArityMismatch {
+        /// Arguments accepted by the procedure
+        expected: Arity,
+        /// Arguments given
+        found: usize,
+        /// Points to the call
+        span: Span,
+    },
+    /// Wrong number of values returned to `let-values`, `define-values` or the like
+    #[error("wrong number of values, expected {expected} but got {found}")]
+    ValuesMismatch {
+        /// Values accepted by the formals
+        expected: Arity,
+        /// Values returned
+        found: usize,
+        /// Points to the formals
+        span: Span,
+    },
+    /// Procedure defined by `define-record-type` given an object not of its record type
+    #[error("{procedure}: argument 1 must be a record of type `{record_type}`")]
+    NotRecord {
+        /// Name of the procedure
+        procedure: Box<str>,
+        /// Name of the record type
+        record_type: Box<str>,
+        /// Points to the call
+        span: Span,
+    },
+    /// Object raised without any handler installed, described by its message and irritants if
+    /// an error object, written otherwise. Inner span points to the call raising it.
+    #[error("uncaught exception: {0}")]
+    Raised(Box<str>, Span),
+    /// Inner span points to the call to `raise`
+    #[error("exception handler returned from a non-continuable `raise`")]
+    HandlerReturned(Span),
+    /// Error raised by a primitive procedure
+    #[error("{name}: {error}")]
+    Primitive {
+        /// Name of the primitive
+        name: &'static str,
+        /// See [`PrimitiveError`]
+        error: PrimitiveError,
+        /// Points to the call
+        span: Span,
+    },
+    /// Error while evaluating the body of a library
+    #[error("{error} (in library `{name}`)")]
+    InLibrary {
+        /// Library name, such as `(foo bar)`
+        name: Box<str>,
+        /// File of the library, to which the spans of `error` point, `None` if defined by the
+        /// program
+        path: Option<Box<str>>,
+        /// See [`EvalError`]
+        error: Box<EvalError>,
+    },
+}
+
+/// Error codes:
+///
+/// | Code  | Error                                 |
+/// |-------|---------------------------------------|
+/// | E0101 | `EvalError::UnsupportedForm`          |
+/// | E0201 | `EvalError::UnboundVariable`          |
+/// | E0202 | `EvalError::UninitializedVariable`    |
+/// | E0301 | `EvalError::NotProcedure`             |
+/// | E0302 | `EvalError::ArityMismatch`            |
+/// | E0303 | `EvalError::Primitive`                |
+/// | E0304 | `EvalError::ValuesMismatch`           |
+/// | E0305 | `EvalError::NotRecord`                |
+/// | E0401 | `EvalError::Raised`                   |
+/// | E0402 | `EvalError::HandlerReturned`          |
+///
+/// Parse errors keep their own codes, and errors in libraries the code of the inner error.
+impl ToDiagnostic for EvalError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, span, label) = match self {
+            EvalError::Parse(error) => return error.to_diagnostic(),
+            EvalError::InLibrary { name, path, error } => {
+                let diagnostic = error.to_diagnostic().with_note(format!("in library `{name}`"));
+                return match path {
+                    Some(path) => diagnostic.with_note(format!("in `{path}`")),
+                    None => diagnostic,
+                };
+            }
+            EvalError::UnsupportedForm(_, span) => ("E0101", span, "unsupported"),
+            EvalError::UnboundVariable(_, span) => ("E0201", span, "unbound"),
+            EvalError::UninitializedVariable(_, span) => ("E0202", span, "uninitialized"),
+            EvalError::NotProcedure(span) => ("E0301", span, "not a procedure"),
+            EvalError::ArityMismatch { span, .. } => ("E0302", span, "wrong number of arguments"),
+            EvalError::Primitive { span, .. } => ("E0303", span, "raised here"),
+            EvalError::ValuesMismatch { span, .. } => ("E0304", span, "wrong number of values"),
+            EvalError::NotRecord { span, .. } => ("E0305", span, "wrong record type"),
+            EvalError::Raised(_, span) => ("E0401", span, "raised here"),
+            EvalError::HandlerReturned(span) => ("E0402", span, "raised here"),
+        };
+
+        let diagnostic = Diagnostic::error(code, self.to_string()).with_primary_label(*span, Some(label));
+        match self {
+            EvalError::UninitializedVariable(..) => {
+                diagnostic.with_help("variables bound by `letrec` and internal definitions can't be used before their initialization")
+            }
+            EvalError::HandlerReturned(_) => diagnostic.with_help("handlers may only return from `raise-continuable`"),
+            _ => diagnostic,
+        }
+    }
+}
@@ -0,0 +1,593 @@
+use std::{collections::HashMap, io::Write, path::PathBuf};
+
+use pluine_common::{Datum, Expander, Form, ParseError};
+
+use crate::{
+    compile::{Compiler, Globals},
+    *,
+};
+
+/// Evaluator of Pluine programs, keeping the global variables defined from one program to the next
+pub struct Interpreter {
+    pub(crate) globals: Globals,
+    /// Macros and libraries defined from one program to the next
+    expander: Expander,
+    /// Written to by `display`, `write` and `newline`
+    pub(crate) output: Box<dyn Write>,
+    /// Greatest number of frames the continuation held so far
+    #[cfg(test)]
+    pub(crate) max_depth: usize,
+}
+
+impl Interpreter {
+    /// Interpreter writing to the standard output
+    pub fn new() -> Self {
+        Interpreter::with_output(std::io::stdout())
+    }
+
+    /// Interpreter writing to `output`
+    pub fn with_output(output: impl Write + 'static) -> Self {
+        let mut globals = HashMap::new();
+        primitive::define_all(&mut globals);
+        let mut expander = Expander::new();
+        for (name, exports) in library::LIBRARIES {
+            expander.add_builtin_library(name, exports);
+        }
+
+        Interpreter {
+            globals,
+            expander,
+            output: Box::new(output),
+            #[cfg(test)]
+            max_depth: 0,
+        }
+    }
+
+    /// Appends a directory to search for library files, library `(foo bar)` being read from
+    /// `foo/bar.sld` under the first directory having it.
+    pub fn add_library_path(&mut self, path: impl Into<PathBuf>) {
+        self.expander.add_library_path(path);
+    }
+
+    /// Reads and evaluates every form of `src` in order, returning the value of the last one.
+    ///
+    /// Each form has its macro uses expanded before being evaluated, so that it can use the macros
+    /// defined by the previous ones. Libraries imported by a form are evaluated before it, the
+    /// first time only. The value is unspecified if there are no forms, or if the last one is a
+    /// definition or declaration.
+    pub fn eval_str(&mut self, src: &str) -> Result<Value, EvalError> {
+        let mut value = Value::Unspecified;
+        for datum in Datum::parse_str_all(src)? {
+            let form = self.expander.expand(&datum);
+            // libraries loaded before an error are defined all the same
+            for library in self.expander.take_loaded() {
+                for form in &library.forms {
+                    self.eval(form).map_err(|error| EvalError::InLibrary {
+                        name: library.name.clone(),
+                        path: library.path.as_ref().map(|path| path.display().to_string().into()),
+                        error: Box::new(error),
+                    })?;
+                }
+            }
+            value = self.eval(&form.map_err(ParseError::from)?)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Evaluates a top-level form, definitions binding global variables.
+    pub fn eval(&mut self, form: &Form) -> Result<Value, EvalError> {
+        let node = Compiler::new(&mut self.globals).form(form)?;
+        self.execute(node, form.span())
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use pluine_lex::diagnostic::ToDiagnostic;
+
+    use super::*;
+
+    /// Written value of the last form, or the error message
+    pub(crate) fn eval(src: &str) -> String {
+        match Interpreter::with_output(std::io::sink()).eval_str(src) {
+            Ok(value) => value.written().to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    /// Output written by the program
+    pub(crate) fn output(src: &str) -> String {
+        #[derive(Clone, Default)]
+        struct Output(Rc<RefCell<Vec<u8>>>);
+
+        impl Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.borrow_mut().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Output::default();
+        Interpreter::with_output(output.clone()).eval_str(src).unwrap();
+        let bytes = output.0.borrow().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn error(src: &str) -> EvalError {
+        Interpreter::with_output(std::io::sink()).eval_str(src).unwrap_err()
+    }
+
+    #[test]
+    fn self_evaluating_and_quoted() {
+        assert_eq!("42", eval("42"));
+        assert_eq!("\"a\"", eval("\"a\""));
+        assert_eq!("(a (b . c) #(1))", eval("'(a (b . c) #(1))"));
+        assert_eq!("#u8(1 2)", eval("#u8(1 2)"));
+    }
+
+    #[test]
+    fn vertical_identifiers() {
+        assert_eq!("\"a«b\"", eval("(symbol->string '|a«b|)"));
+    }
+
+    #[cfg(feature = "unicode_identifiers")]
+    #[test]
+    fn unicode_identifiers() {
+        assert_eq!("3", eval("(define (λ 漢字) (+ 漢字 1)) (λ 2)"));
+    }
+
+    #[test]
+    fn definitions_and_assignments() {
+        assert_eq!("3", eval("(define x 1) (define y 2) (+ x y)"));
+        assert_eq!("5", eval("(define x 1) (set! x 5) x"));
+        assert_eq!("2", eval("(begin (define x 1) (define y (+ x 1))) y"));
+        assert_eq!("#<procedure square>", eval("(define (square x) (* x x)) square"));
+        assert_eq!("(1 2 (3 4))", eval("(define-values (x y . z) (values 1 2 3 4)) (list x y z)"));
+    }
+
+    #[test]
+    fn conditionals() {
+        assert_eq!("yes", eval("(if 0 'yes 'no)"));
+        assert_eq!("no", eval("(if #f 'yes 'no)"));
+        assert_eq!("#<unspecified>", eval("(if #f #f)"));
+    }
+
+    #[test]
+    fn derived_conditionals() {
+        assert_eq!("b", eval("(cond (#f 'a) ((= 1 1) 'b) (else 'c))"));
+        assert_eq!("c", eval("(cond (#f 'a) (else 'c))"));
+        assert_eq!("2", eval("(cond ((assv 'b '((a 1) (b 2))) => cadr) (else #f))"));
+        assert_eq!("3", eval("(cond (#f) (3))"));
+        assert_eq!("#<unspecified>", eval("(cond (#f 1))"));
+
+        assert_eq!("composite", eval("(case (* 2 3) ((2 3 5 7) 'prime) ((1 4 6 8 9) 'composite))"));
+        assert_eq!(
+            "c",
+            eval("(case (car '(c d)) ((a e i o u) 'vowel) ((w y) 'semivowel) (else => (lambda (x) x)))")
+        );
+        assert_eq!("4", eval("(case 3 ((3) => (lambda (x) (+ x 1))) (else 0))"));
+
+        assert_eq!("#t", eval("(and)"));
+        assert_eq!("(f g)", eval("(and 1 2 '(f g))"));
+        assert_eq!("#f", eval("(and 1 #f (car 1))"));
+        assert_eq!("#f", eval("(or)"));
+        assert_eq!("1", eval("(or #f 1 (car 1))"));
+
+        assert_eq!("2", eval("(when (= 1 1) 1 2)"));
+        assert_eq!("#<unspecified>", eval("(unless (= 1 1) 1 2)"));
+    }
+
+    #[test]
+    fn proper_tail_calls() {
+        /// Greatest continuation depth of a loop of 1000 iterations, each through `form`
+        fn max_depth(form: &str) -> usize {
+            let mut interpreter = Interpreter::with_output(std::io::sink());
+            let src = format!("(define (loop n) (if (= n 0) 0 {form})) (loop 1000)");
+            interpreter.eval_str(&src).unwrap();
+            interpreter.max_depth
+        }
+
+        let forms = [
+            "(if #t (loop (- n 1)) #f)",
+            "(if #f #f (loop (- n 1)))",
+            "(cond (#f #f) (else (loop (- n 1))))",
+            "(cond ((- n 1) => loop))",
+            "(case n ((-1) #f) (else (loop (- n 1))))",
+            "(case (- n 1) ((-1) #f) (else => loop))",
+            "(and #t (loop (- n 1)))",
+            "(or #f (loop (- n 1)))",
+            "(when #t (loop (- n 1)))",
+            "(unless #f (loop (- n 1)))",
+            "(let ((m (- n 1))) (loop m))",
+            "(let* ((m n) (m (- m 1))) (loop m))",
+            "(letrec ((m (- n 1))) (loop m))",
+            "(begin #f (loop (- n 1)))",
+            "(apply loop (list (- n 1)))",
+        ];
+        for form in forms {
+            let depth = max_depth(form);
+            assert!(depth < 10, "`{form}` grew the continuation to {depth} frames");
+        }
+
+        assert!(max_depth("(+ 0 (loop (- n 1)))") > 1000);
+    }
+
+    #[test]
+    fn procedures() {
+        assert_eq!("6", eval("((lambda (x y) (* x y)) 2 3)"));
+        assert_eq!("(1 (2 3))", eval("((lambda (x . rest) (list x rest)) 1 2 3)"));
+        assert_eq!("()", eval("((lambda args args))"));
+        assert_eq!(
+            "120",
+            eval("(define (factorial n) (if (= n 0) 1 (* n (factorial (- n 1))))) (factorial 5)")
+        );
+    }
+
+    #[test]
+    fn lexical_scoping() {
+        let src = "
+            (define (make-counter)
+              (let ((count 0))
+                (lambda () (set! count (+ count 1)) count)))
+            (define a (make-counter))
+            (define b (make-counter))
+            (a) (a) (b)
+            (list (a) (b))";
+        assert_eq!("(3 2)", eval(src));
+
+        // the body of `f` refers to the global `x`, not to the one of the caller
+        assert_eq!("1", eval("(define x 1) (define (f) x) (let ((x 2)) (f))"));
+        assert_eq!("2", eval("(define x 1) (let ((x 2)) (define (f) x) (f))"));
+    }
+
+    #[test]
+    fn internal_definitions() {
+        let src = "
+            (define (f n)
+              (define (even? n) (if (= n 0) #t (odd? (- n 1))))
+              (define (odd? n) (if (= n 0) #f (even? (- n 1))))
+              (even? n))
+            (f 10)";
+        assert_eq!("#t", eval(src));
+        assert_eq!("3", eval("(define (f x) (define x 3) x) (f 1)"));
+        assert_eq!("(2 1)", eval("(define (f) (define-values (a b) (values 1 2)) (list b a)) (f)"));
+    }
+
+    #[test]
+    fn let_family() {
+        assert_eq!("3", eval("(let ((x 1) (y 2)) (+ x y))"));
+        assert_eq!("1", eval("(let ((x 1)) (let ((x 2) (y x)) y))"));
+        assert_eq!("3", eval("(let* ((x 1) (y (+ x 1))) (+ x y))"));
+        assert_eq!("(2 1)", eval("(let* () (define x 1) (let* ((x 2) (y x)) (list x 1)))"));
+        assert_eq!("#t", eval("(letrec ((even? (lambda (n) (if (= n 0) #t (odd? (- n 1))))) (odd? (lambda (n) (if (= n 0) #f (even? (- n 1)))))) (even? 100))"));
+        assert_eq!("2", eval("(letrec* ((x 1) (y (+ x 1))) y)"));
+        assert_eq!(
+            "(3 2 1)",
+            eval("(let loop ((n 3) (list '())) (if (= n 0) (reverse list) (loop (- n 1) (cons n list))))")
+        );
+
+        // the inits of `let-values` are evaluated outside of the scope of the formals
+        assert_eq!(
+            "(2 1 (4))",
+            eval("(let-values (((a b) (values 1 2)) ((c . d) (values 3 4))) (list b a d))")
+        );
+        assert_eq!(
+            "(2 1)",
+            eval("(let ((a 1) (b 2)) (let-values (((a b) (values b a)) ((c) a)) (list a c)))")
+        );
+        assert_eq!("(1 3)", eval("(let*-values (((a b) (values 1 2)) ((c) (+ a b))) (list a c))"));
+        assert_eq!("0", eval("(let*-values () 0)"));
+    }
+
+    #[test]
+    fn named_let_init_scope() {
+        // the inits are evaluated outside of the scope of the loop variable
+        assert_eq!("1", eval("(define loop 1) (let loop ((n loop)) n)"));
+    }
+
+    #[test]
+    fn iteration() {
+        assert_eq!(
+            "#(0 1 2 3 4)",
+            eval("(do ((vec (make-vector 5)) (i 0 (+ i 1))) ((= i 5) vec) (vector-set! vec i i))")
+        );
+        assert_eq!(
+            "25",
+            eval("(let ((x '(1 3 5 7 9))) (do ((x x (cdr x)) (sum 0 (+ sum (car x)))) ((null? x) sum)))")
+        );
+        assert_eq!("#<unspecified>", eval("(do ((i 0 (+ i 1))) ((= i 3)))"));
+        assert_eq!("done", eval("(do ((i 0 (+ i 1))) ((= i 200000) 'done))"));
+
+        // each iteration binds fresh variables, and the inits are evaluated outside of their scope
+        assert_eq!("(2 1 0)", eval("(do ((i 0 (+ i 1)) (thunks '() (cons (lambda () i) thunks))) ((= i 3) (list ((car thunks)) ((cadr thunks)) ((car (cddr thunks))))))"));
+        assert_eq!("1", eval("(define i 1) (do ((i i (+ i 1)) (first i)) (#t first))"));
+    }
+
+    #[test]
+    fn case_lambda() {
+        let src = "
+            (define range
+              (case-lambda
+                ((e) (range 0 e))
+                ((b e) (do ((r '() (cons e r)) (e (- e 1) (- e 1))) ((< e b) r)))))
+            (list (range 3) (range 3 5))";
+        assert_eq!("((0 1 2) (3 4))", eval(src));
+        assert_eq!("(1 (2 3))", eval("((case-lambda ((a) a) ((a . rest) (list a rest))) 1 2 3)"));
+        assert_eq!("#<procedure f>", eval("(define f (case-lambda ((a) a))) f"));
+        assert_eq!(
+            "wrong number of arguments, expected 1 to 3 but got 2",
+            eval("((case-lambda ((a) a) ((a b c) a)) 1 2)")
+        );
+    }
+
+    #[test]
+    fn quasiquotation() {
+        assert_eq!("(list 3 4)", eval("`(list ,(+ 1 2) 4)"));
+        assert_eq!("(list a (quote a))", eval("(let ((name 'a)) `(list ,name ',name))"));
+        assert_eq!("(a 3 4 5 6 b)", eval("`(a ,(+ 1 2) ,@(list 4 (abs -5) 6) b)"));
+        assert_eq!("((foo 7) . cons)", eval("`((foo ,(- 10 3)) ,@(cdr '(c)) . ,(car '(cons)))"));
+        assert_eq!("#(10 5 2 4 3 8)", eval("`#(10 5 ,(sqrt 4) ,@(list (sqrt 16) (sqrt 9)) 8)"));
+        assert_eq!("(1 2 . 3)", eval("`(1 ,@'(2) . 3)"));
+        assert_eq!("(1 2)", eval("(let ((x '(1 2))) `(,@x))"));
+
+        // unquotations belong to the innermost quasiquotation
+        assert_eq!(
+            "(a (quasiquote (b (unquote (+ 1 2)) (unquote (foo 4 d)) e)) f)",
+            eval("`(a `(b ,(+ 1 2) ,(foo ,(+ 1 3) d) e) f)")
+        );
+        assert_eq!(
+            "(a (quasiquote (b (unquote x) (unquote (quote y)) d)) e)",
+            eval("(let ((name1 'x) (name2 'y)) `(a `(b ,,name1 ,',name2 d) e))")
+        );
+
+        // lists are built by the primitives whatever the program binds their names to
+        assert_eq!("(1 2)", eval("(let ((list vector) (append 0)) `(1 ,@(cons 2 '())))"));
+        assert_eq!("append: argument 2 must be a proper list", eval("`(1 ,@2 3)"));
+    }
+
+    #[test]
+    fn deep_recursion() {
+        let src = "(define (count n) (if (= n 0) 0 (+ 1 (count (- n 1))))) (count 100000)";
+        assert_eq!("100000", eval(src));
+    }
+
+    #[test]
+    fn deeply_nested_expressions() {
+        let src = format!("{}0{}", "(+ 1 ".repeat(10_000), ")".repeat(10_000));
+        assert_eq!("10000", eval(&src));
+
+        let src = format!("{}x{}", "(let* ((x 1)) ((lambda (y) ".repeat(1_000), ") x))".repeat(1_000));
+        assert_eq!("1", eval(&src));
+    }
+
+    #[test]
+    fn runtime_errors() {
+        assert_eq!("unbound variable `y`", eval("(define x 1) y"));
+        assert_eq!("unbound variable `y`", eval("(set! y 1)"));
+        assert_eq!("variable `y` used before its initialization", eval("(letrec ((x y) (y 1)) x)"));
+        assert_eq!("attempt to call a non-procedure", eval("(1 2)"));
+        assert_eq!("wrong number of arguments, expected 1 but got 2", eval("((lambda (x) x) 1 2)"));
+        assert_eq!(
+            "wrong number of arguments, expected at least 1 but got 0",
+            eval("((lambda (x . y) x))")
+        );
+        assert_eq!(
+            "wrong number of values, expected 2 but got 1",
+            eval("(let-values (((a b) (values 1))) a)")
+        );
+        assert_eq!(
+            "wrong number of values, expected at least 1 but got 0",
+            eval("(define-values (a . b) (values))")
+        );
+    }
+
+    #[test]
+    fn error_spans() {
+        let EvalError::UnboundVariable(_, span) = error("(+ 1\n  y)") else {
+            panic!("expected an unbound variable");
+        };
+        assert_eq!((7, 8), (span.start(), span.end()));
+
+        let diagnostic = error("(car 1)").to_diagnostic();
+        assert_eq!("E0303", diagnostic.code);
+        assert_eq!("car: argument 1 must be a pair", diagnostic.message);
+
+        assert!(matches!(error("(+ 1"), EvalError::Parse(_)));
+    }
+
+    #[test]
+    fn globals_persist() {
+        let mut interpreter = Interpreter::with_output(std::io::sink());
+        interpreter.eval_str("(define (add x) (+ x y)) (define y 1)").unwrap();
+        assert_eq!("3", interpreter.eval_str("(set! y 2) (add 1)").unwrap().written().to_string());
+
+        interpreter
+            .eval_str("(define-syntax inc! (syntax-rules () ((_ x) (set! x (+ x 1)))))")
+            .unwrap();
+        assert_eq!("3", interpreter.eval_str("(inc! y) y").unwrap().written().to_string());
+    }
+
+    #[test]
+    fn macros() {
+        let my_or = "
+            (define-syntax my-or
+              (syntax-rules ()
+                ((_) #f)
+                ((_ e) e)
+                ((_ e r ...) (let ((t e)) (if t t (my-or r ...))))))";
+        assert_eq!("3", eval(&format!("{my_or} (my-or #f (+ 1 2) (car 1))")));
+        assert_eq!("#f", eval(&format!("{my_or} (my-or)")));
+
+        let src = "
+            (define-syntax while
+              (syntax-rules ()
+                ((_ test body ...) (let loop () (when test body ... (loop))))))
+            (define i 0)
+            (while (< i 5) (set! i (+ i 1)))
+            i";
+        assert_eq!("5", eval(src));
+
+        // nested ellipses, vectors and dotted patterns
+        assert_eq!(
+            "(1 2 3)",
+            eval("(define-syntax flat (syntax-rules () ((_ (a ...) ...) '(a ... ...)))) (flat (1 2) (3) ())")
+        );
+        assert_eq!(
+            "((2 3 1) (5 4))",
+            eval("(define-syntax m (syntax-rules () ((_ (a b ...) ...) '((b ... a) ...)))) (m (1 2 3) (4 5))")
+        );
+        assert_eq!(
+            "(1 2 3)",
+            eval("(define-syntax m (syntax-rules () ((_ #(a ...)) (list a ...)))) (m #(1 2 3))")
+        );
+        assert_eq!(
+            "(3 1 2)",
+            eval("(define-syntax m (syntax-rules () ((_ a ... . b) '(b a ...)))) (m 1 2 . 3)")
+        );
+
+        // literals, underscore and custom ellipsis
+        assert_eq!(
+            "(1 2)",
+            eval("(define-syntax m (syntax-rules (=>) ((_ a => b) (list a b)))) (m 1 => 2)")
+        );
+        assert_eq!("2", eval("(define-syntax m (syntax-rules () ((_ _ b) b))) (m 1 2)"));
+        assert_eq!(
+            "(1 2 3)",
+            eval("(define-syntax m (syntax-rules ::: () ((_ a :::) (list a :::)))) (m 1 2 3)")
+        );
+    }
+
+    #[test]
+    fn macro_scopes() {
+        let src = "
+            (define (f)
+              (define-syntax twice (syntax-rules () ((_ e) (begin e e))))
+              (define n 0)
+              (twice (set! n (+ n 1)))
+              n)
+            (f)";
+        assert_eq!("2", eval(src));
+
+        // macros expanding to definitions, within bodies or at top level
+        let def = "(define-syntax def (syntax-rules () ((_ name value) (define name value))))";
+        assert_eq!("3", eval(&format!("{def} (def x 3) x")));
+        assert_eq!("4", eval(&format!("{def} (define (g) (def y 4) y) (g)")));
+
+        assert_eq!("7", eval("(let-syntax ((foo (syntax-rules () ((_ x) (* x 7))))) (foo 1))"));
+        let src = "
+            (letrec-syntax ((ev? (syntax-rules () ((_) #t) ((_ x . r) (od? . r))))
+                            (od? (syntax-rules () ((_) #f) ((_ x . r) (ev? . r)))))
+              (ev? 1 2 3 4))";
+        assert_eq!("#t", eval(src));
+
+        // macro defining macros, the inner ellipses being escaped
+        let src = "
+            (define-syntax be-like-begin
+              (syntax-rules ()
+                ((_ name) (define-syntax name (syntax-rules () ((name expr (... ...)) (begin expr (... ...))))))))
+            (be-like-begin sequence)
+            (sequence 1 2 3 4)";
+        assert_eq!("4", eval(src));
+
+        // variables shadow macros of the same name
+        assert_eq!("5", eval("(define-syntax ten (syntax-rules () ((_) 10))) (define ten 5) ten"));
+        assert_eq!("1", eval("(define-syntax ten (syntax-rules () ((_) 10))) ((lambda (ten) ten) 1)"));
+    }
+
+    #[test]
+    fn hygiene() {
+        // inserted bindings don't capture the variables of the macro use
+        let src = "
+            (define-syntax swap! (syntax-rules () ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))
+            (define tmp 1)
+            (define other 2)
+            (swap! tmp other)
+            (list tmp other)";
+        assert_eq!("(2 1)", eval(src));
+
+        let src = "
+            (define-syntax repeat
+              (syntax-rules () ((_ n body) (let loop ((i 0)) (when (< i n) body (loop (+ i 1)))))))
+            (define i 'outer)
+            (define seen '())
+            (repeat 2 (set! seen (cons i seen)))
+            seen";
+        assert_eq!("(outer outer)", eval(src));
+        let src = "
+            (define-syntax times
+              (syntax-rules () ((_ n body) (do ((i 0 (+ i 1))) ((= i n)) body))))
+            (define i 'outer)
+            (define seen '())
+            (times 2 (set! seen (cons i seen)))
+            seen";
+        assert_eq!("(outer outer)", eval(src));
+        let src = "(define-syntax m (syntax-rules () ((_ e) (let ((t 5)) `(,t ,e . ,t))))) (let ((t 1)) (m t))";
+        assert_eq!("(5 1 . 5)", eval(src));
+
+        // inserted references don't refer to the bindings of the macro use
+        let src = "(let ((x 'outer)) (let-syntax ((m (syntax-rules () ((m) x)))) (let ((x 'inner)) (m))))";
+        assert_eq!("outer", eval(src));
+        assert_eq!(
+            "(1 2)",
+            eval("(define-syntax m (syntax-rules () ((_) (list 1 2)))) (let ((list vector)) (m))")
+        );
+        assert_eq!(
+            "10",
+            eval("(define x 10) (define-syntax get (syntax-rules () ((_) x))) ((lambda (x) (get)) 1)")
+        );
+
+        // keywords bound as variables
+        let src = "
+            (define-syntax my-or
+              (syntax-rules ()
+                ((_) #f)
+                ((_ e) e)
+                ((_ e1 e2 ...) (let ((temp e1)) (if temp temp (my-or e2 ...))))))
+            (let ((x #f) (y 7) (temp 8) (let odd?) (if even?))
+              (my-or x (let temp) (if y) y))";
+        assert_eq!("7", eval(src));
+        assert_eq!(
+            "2",
+            eval("(define-syntax m (syntax-rules () ((_ c a b) (cond (c a) (else b))))) (let ((else #f)) (m #f 1 2))")
+        );
+        assert_eq!(
+            "no",
+            eval("(define-syntax m (syntax-rules (=>) ((_ =>) 'yes) ((_ x) 'no))) (let ((=> 1)) (m =>))")
+        );
+    }
+
+    #[test]
+    fn macro_errors() {
+        assert_eq!(
+            "no syntax rule matches the macro use",
+            eval("(define-syntax m (syntax-rules () ((_ a) a))) (m)")
+        );
+        assert_eq!(
+            "syntactic keyword used out of place",
+            eval("(define-syntax m (syntax-rules () ((_) 1))) m")
+        );
+
+        // diagnostics point at the macro use, or at the part of it a pattern variable matched
+        let src = "(define-syntax m (syntax-rules () ((_ a) (+ a (car 1)))))\n(m 2)";
+        let EvalError::Primitive { span, .. } = error(src) else {
+            panic!("expected a primitive error");
+        };
+        assert_eq!((58, 63), (span.start(), span.end()));
+        let EvalError::UnboundVariable(_, span) = error(&src.replace("(m 2)", "(m y)")) else {
+            panic!("expected an unbound variable");
+        };
+        assert_eq!((61, 62), (span.start(), span.end()));
+    }
+}
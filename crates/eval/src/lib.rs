@@ -0,0 +1,41 @@
+//! Pluine evaluator.
+//!
+//! Programs are read and lowered to the AST of `pluine-common`, which the [`Interpreter`]
+//! compiles, resolving variables to their binding, before running it on a machine whose
+//! continuation is kept on the heap.
+
+mod interpreter;
+pub use interpreter::Interpreter;
+
+mod value;
+pub use value::{Pair, Value};
+
+mod procedure;
+pub use procedure::{Arity, Procedure};
+
+mod error;
+pub use error::EvalError;
+
+mod primitive;
+pub use primitive::PrimitiveError;
+
+mod condition;
+pub use condition::{ErrorKind, ErrorObject};
+
+mod port;
+pub use port::InputPort;
+
+mod promise;
+pub use promise::Promise;
+
+mod record;
+pub use record::{Record, RecordType};
+
+mod library;
+
+mod compile;
+
+mod machine;
+
+mod write;
+pub use write::Written;
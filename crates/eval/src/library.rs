@@ -0,0 +1,428 @@
+//! Standard libraries of R7RS 5.6.1 built into the interpreter, listing the forms and procedures
+//! implemented so far.
+
+/// Name and exported identifiers of each library
+pub(crate) static LIBRARIES: &[(&[&str], &[&str])] = &[
+    (
+        &["scheme", "base"],
+        &[
+            // syntax
+            "quote",
+            "quasiquote",
+            "unquote",
+            "unquote-splicing",
+            "lambda",
+            "if",
+            "set!",
+            "include",
+            "include-ci",
+            "cond",
+            "case",
+            "and",
+            "or",
+            "when",
+            "unless",
+            "let",
+            "let*",
+            "letrec",
+            "letrec*",
+            "let-values",
+            "let*-values",
+            "begin",
+            "do",
+            "parameterize",
+            "guard",
+            "let-syntax",
+            "letrec-syntax",
+            "syntax-rules",
+            "define",
+            "define-values",
+            "define-record-type",
+            "define-syntax",
+            "else",
+            "=>",
+            "...",
+            "_",
+            // control
+            "apply",
+            "call-with-current-continuation",
+            "call/cc",
+            "dynamic-wind",
+            "values",
+            "call-with-values",
+            "make-parameter",
+            "procedure?",
+            // exceptions
+            "raise",
+            "raise-continuable",
+            "with-exception-handler",
+            "error",
+            "error-object?",
+            "error-object-message",
+            "error-object-irritants",
+            "read-error?",
+            "file-error?",
+            // input
+            "input-port?",
+            "open-input-string",
+            "eof-object",
+            "eof-object?",
+            // output
+            "newline",
+            // equivalence and booleans
+            "eq?",
+            "eqv?",
+            "equal?",
+            "not",
+            "boolean?",
+            "boolean=?",
+            // pairs and lists
+            "pair?",
+            "null?",
+            "list?",
+            "cons",
+            "car",
+            "cdr",
+            "caar",
+            "cadr",
+            "cdar",
+            "cddr",
+            "set-car!",
+            "set-cdr!",
+            "list",
+            "length",
+            "append",
+            "reverse",
+            "list-tail",
+            "list-ref",
+            "list-copy",
+            "memq",
+            "memv",
+            "member",
+            "assq",
+            "assv",
+            "assoc",
+            // numbers
+            "number?",
+            "complex?",
+            "real?",
+            "rational?",
+            "integer?",
+            "exact-integer?",
+            "exact?",
+            "inexact?",
+            "=",
+            "<",
+            ">",
+            "<=",
+            ">=",
+            "zero?",
+            "positive?",
+            "negative?",
+            "odd?",
+            "even?",
+            "max",
+            "min",
+            "+",
+            "*",
+            "-",
+            "/",
+            "abs",
+            "quotient",
+            "remainder",
+            "modulo",
+            "floor",
+            "ceiling",
+            "truncate",
+            "round",
+            "sqrt",
+            "expt",
+            "exact",
+            "inexact",
+            "number->string",
+            "string->number",
+            // symbols, characters and strings
+            "symbol?",
+            "symbol->string",
+            "string->symbol",
+            "char?",
+            "char->integer",
+            "integer->char",
+            "char=?",
+            "string?",
+            "string-length",
+            "string-ref",
+            "substring",
+            "string-append",
+            "string-copy",
+            "string=?",
+            "string->list",
+            "list->string",
+            // vectors
+            "vector?",
+            "make-vector",
+            "vector",
+            "vector-length",
+            "vector-ref",
+            "vector-set!",
+            "vector->list",
+            "list->vector",
+        ],
+    ),
+    (&["scheme", "case-lambda"], &["case-lambda"]),
+    (&["scheme", "file"], &["open-input-file"]),
+    (&["scheme", "lazy"], &["delay", "delay-force", "force", "make-promise", "promise?"]),
+    (&["scheme", "read"], &["read"]),
+    (&["scheme", "write"], &["display", "write"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use pluine_common::{ParseError, SyntaxError};
+    use pluine_lex::diagnostic::ToDiagnostic;
+
+    use super::*;
+    use crate::{interpreter::tests::eval, primitive::MODULES, *};
+
+    /// Interpreter searching a new directory holding `files`, given by path and contents
+    fn with_files(test: &str, files: &[(&str, &str)]) -> Interpreter {
+        let directory = std::env::temp_dir().join(format!("pluine-{}-{test}", std::process::id()));
+        for (path, contents) in files {
+            let path = directory.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        let mut interpreter = Interpreter::with_output(std::io::sink());
+        interpreter.add_library_path(directory);
+        interpreter
+    }
+
+    /// Written value of the last form, or the error message
+    fn run(interpreter: &mut Interpreter, src: &str) -> String {
+        match interpreter.eval_str(src) {
+            Ok(value) => value.written().to_string(),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn builtin_libraries() {
+        for (name, exports) in LIBRARIES {
+            let mut interpreter = Interpreter::with_output(std::io::sink());
+            interpreter.eval_str(&format!("(import (prefix ({}) p:))", name.join(" "))).unwrap();
+            for export in *exports {
+                // keywords can't be referenced, variables must be bound
+                match interpreter.eval_str(&format!("p:{export}")) {
+                    Ok(_) | Err(EvalError::Parse(ParseError::Syntax(SyntaxError::MisplacedKeyword(_)))) => {}
+                    Err(error) => panic!("`{export}` of `{name:?}`: {error}"),
+                }
+            }
+        }
+
+        for primitive in MODULES.into_iter().flatten() {
+            let exported = LIBRARIES.iter().any(|(_, exports)| exports.contains(&primitive.name));
+            assert!(exported, "`{}` is not exported by any library", primitive.name);
+        }
+
+        assert_eq!("3", eval("(import (scheme base) (only (scheme write) display)) (+ 1 2)"));
+        assert_eq!("library `(scheme foo)` not found", eval("(import (scheme foo))"));
+    }
+
+    #[test]
+    fn import_sets() {
+        let mut interpreter = with_files(
+            "import-sets",
+            &[(
+                "util/math.sld",
+                "(define-library (util math)
+                   (export square (rename cube third-power) pi)
+                   (import (scheme base))
+                   (begin
+                     (define pi 3)
+                     (define (square x) (* x x))
+                     (define (cube x) (* x (square x)))))",
+            )],
+        );
+
+        let src = "(import (prefix (only (util math) square third-power) m:)) (list (m:square 3) (m:third-power 2))";
+        assert_eq!("(9 8)", run(&mut interpreter, src));
+        assert_eq!("unbound variable `m:pi`", run(&mut interpreter, "m:pi"));
+        assert_eq!(
+            "`m:square` is imported and can't be assigned",
+            run(&mut interpreter, "(set! m:square 0)")
+        );
+        assert_eq!("unbound variable `cube`", run(&mut interpreter, "(import (util math)) (cube 2)"));
+        assert_eq!(
+            "(3 4)",
+            run(
+                &mut interpreter,
+                "(import (rename (except (util math) third-power) (pi tau))) (list tau (square 2))"
+            )
+        );
+        assert_eq!(
+            "`cube` is not imported by the inner import set",
+            run(&mut interpreter, "(import (only (util math) cube))")
+        );
+
+        // the variables of the library are its own, whatever the program defines
+        assert_eq!("16", run(&mut interpreter, "(define (square x) 0) (define pi 0) (m:square 4)"));
+        assert_eq!("#<procedure (util math) square>", run(&mut interpreter, "m:square"));
+    }
+
+    #[test]
+    fn exported_macros() {
+        let mut interpreter = with_files(
+            "exported-macros",
+            &[(
+                "counter.sld",
+                "(define-library (counter)
+                   (export count! current)
+                   (import (scheme base))
+                   (begin
+                     (define total 0)
+                     (define step 1)
+                     (define (current) total)
+                     (define-syntax count!
+                       (syntax-rules ()
+                         ((_) (set! total (+ total step)))
+                         ((_ n) (set! total (+ total n)))))))",
+            )],
+        );
+
+        // references inserted by the macro are to the library, not to the program
+        let src = "(import (counter))
+                   (define total 100)
+                   (define step 10)
+                   (count!)
+                   (let ((total 1000) (+ -)) (count! total))
+                   (list (current) total)";
+        assert_eq!("(1001 100)", run(&mut interpreter, src));
+    }
+
+    #[test]
+    fn declarations() {
+        let mut interpreter = with_files(
+            "declarations",
+            &[
+                (
+                    "shapes/area.sld",
+                    "(define-library (shapes area)
+                       (include-library-declarations \"exports.scm\")
+                       (import (scheme base))
+                       (cond-expand
+                         ((and r7rs (not foo) (library (scheme base))) (begin (define feature 'r7rs)))
+                         (else (begin (define feature 'other))))
+                       (cond-expand
+                         ((or foo (library (no such))) (begin (define missing #t)))
+                         (else))
+                       (include \"square.scm\")
+                       (include-ci \"circle.scm\"))",
+                ),
+                ("shapes/exports.scm", "(export square-area circle-area feature Pi)"),
+                ("shapes/square.scm", "(define (square-area side) (* side side))"),
+                ("shapes/circle.scm", "(DEFINE |Pi| 3) (DEFINE (CIRCLE-AREA R) (* |Pi| R R))"),
+            ],
+        );
+
+        // identifiers between vertical lines are not folded by `include-ci`
+        let src = "(import (shapes area)) (list (square-area 2) (circle-area 1) feature Pi)";
+        assert_eq!("(4 3 r7rs 3)", run(&mut interpreter, src));
+    }
+
+    #[test]
+    fn program_includes() {
+        let directory = std::env::temp_dir().join(format!("pluine-{}-program-includes", std::process::id()));
+        let mut interpreter = with_files(
+            "program-includes",
+            &[
+                ("double.scm", "(define (double x) (+ x x))"),
+                ("TRIPLE.scm", "(DEFINE (TRIPLE X) (* 3 X))"),
+            ],
+        );
+
+        let double = directory.join("double.scm");
+        let triple = directory.join("TRIPLE.scm");
+        let src = format!(
+            "(include {:?}) (let () (include-ci {:?}) (list (double 2) (triple 2)))",
+            double.display().to_string(),
+            triple.display().to_string(),
+        );
+        assert_eq!("(4 6)", run(&mut interpreter, &src));
+    }
+
+    #[test]
+    fn loading() {
+        let mut interpreter = with_files(
+            "loading",
+            &[
+                (
+                    "a.sld",
+                    "(define-library (a) (export x) (import (scheme base) (b)) (begin (define x (bump!))))",
+                ),
+                (
+                    "b.sld",
+                    "(define-library (b) (export bump!) (import (scheme base)) (begin (define n 0) (define (bump!) (set! n (+ n 1)) n)))",
+                ),
+                ("c.sld", "(define-library (c) (import (scheme base)) (begin (display 'c)))"),
+            ],
+        );
+
+        // bodies are evaluated once, dependencies first
+        assert_eq!("1", run(&mut interpreter, "(import (a)) x"));
+        assert_eq!("2", run(&mut interpreter, "(import (b)) (bump!)"));
+        // only imported identifiers are bound within a library
+        assert_eq!(
+            "unbound variable `(c) display` (in library `(c)`)",
+            run(&mut interpreter, "(import (c))")
+        );
+
+        // libraries can also be defined by the program
+        let src = "(define-library (d) (export z) (import (scheme base) (a)) (begin (define z (* x 10))))
+                   (import (d))
+                   z";
+        assert_eq!("10", run(&mut interpreter, src));
+    }
+
+    #[test]
+    fn errors() {
+        let cycle = "(define-library (c) (export y) (import (scheme base) (d)) (begin (define y 1)))";
+        let mut interpreter = with_files(
+            "errors",
+            &[
+                ("c.sld", cycle),
+                ("d.sld", "(define-library (d) (export z) (import (c)))"),
+                ("e.sld", "(define-library (e) (export w) (import (scheme base)))"),
+                ("f.sld", "(define-library (f) (import (scheme base)) (begin (car 1)))"),
+                ("g.sld", "(define-library (g) (include \"missing.scm\"))"),
+            ],
+        );
+
+        let error = interpreter.eval_str("(import (c))").unwrap_err();
+        let message = error.to_string();
+        assert!(message.starts_with("library `(c)` imports itself"), "{message}");
+        let diagnostic = error.to_diagnostic();
+        assert_eq!("C0212", diagnostic.code);
+        // innermost file first
+        assert!(diagnostic.notes[0].ends_with("d.sld`") && diagnostic.notes[1].ends_with("c.sld`"));
+        // the cycle is reported where it closes, in `d.sld`
+        let EvalError::Parse(ParseError::Syntax(SyntaxError::InFile { error, .. })) = error else {
+            panic!("expected an error in a file")
+        };
+        let ParseError::Syntax(SyntaxError::InFile { error, .. }) = *error else {
+            panic!("expected an error in a file")
+        };
+        let ParseError::Syntax(SyntaxError::ImportCycle(_, span)) = *error else {
+            panic!("expected an import cycle")
+        };
+        let src = "(define-library (d) (export z) (import (c)))";
+        assert_eq!(src.rfind("(c)").unwrap(), span.start());
+
+        assert!(run(&mut interpreter, "(import (e))").starts_with("exported identifier `w` is neither defined nor imported"));
+        assert!(run(&mut interpreter, "(import (f))").starts_with("car: argument 1 must be a pair (in library `(f)`)"));
+        assert!(run(&mut interpreter, "(import (g))").starts_with("can't read"));
+        assert_eq!("library `(h)` not found", run(&mut interpreter, "(import (h))"));
+    }
+}
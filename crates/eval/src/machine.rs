@@ -0,0 +1,837 @@
+//! Machine running compiled [`Node`]s.
+//!
+//! Rust does not guarantee tail call optimization, so the machine never recurses: it loops over
+//! a control, either a node to evaluate or a value to return, and a continuation of heap
+//! allocated frames telling what to do with the returned value. Calls in tail position push no
+//! frame, which makes tail calls proper as R7RS 3.5 requires, and the depth of non-tail
+//! recursion is only bounded by memory.
+//!
+//! Capturing the continuation for `call/cc` merely shares its frames, a frame being copied when
+//! returned to while shared, so that continuations may be returned to any number of times.
+
+use std::{cell::RefCell, ops::ControlFlow, rc::Rc};
+
+use pluine_lex::{diagnostic::ToDiagnostic, span::Span};
+
+use crate::{
+    compile::{Arrow, Call, Case, Clause, DefineValues, Global, Guard, If, Lambda, Local, Node, Scope, Variable},
+    primitive::Function,
+    procedure::{CaseClosure, Closure, Parameter, ProcedureKind},
+    promise::State,
+    *,
+};
+
+type Result<T> = std::result::Result<T, EvalError>;
+
+/// State of the machine besides the control, captured by `call/cc`
+#[derive(Clone, Default)]
+pub(crate) struct Machine {
+    continuation: Option<Rc<Continuation>>,
+    /// Innermost `dynamic-wind` the control is in
+    winders: Option<Rc<Winder>>,
+    /// Innermost exception handler
+    handlers: Option<Rc<Handlers>>,
+    /// Innermost binding of a parameter object by `parameterize`
+    parameters: Option<Rc<Parameterization>>,
+}
+
+/// Thunks of a `dynamic-wind`, along with the enclosing one
+pub(crate) struct Winder {
+    before: Value,
+    after: Value,
+    parent: Option<Rc<Winder>>,
+    /// Number of enclosing `dynamic-wind`s
+    depth: usize,
+    /// Points to the call to `dynamic-wind`, locating errors of the `before` and `after` thunks
+    span: Span,
+}
+
+/// Exception handler, along with the enclosing ones
+pub(crate) struct Handlers {
+    handler: Handler,
+    parent: Option<Rc<Handlers>>,
+}
+
+/// Value of a parameter object within a `parameterize`, along with the enclosing bindings
+pub(crate) struct Parameterization {
+    parameter: Rc<Parameter>,
+    value: Value,
+    parent: Option<Rc<Parameterization>>,
+}
+
+enum Handler {
+    /// Installed by `with-exception-handler`, called with the raised object
+    Procedure(Value),
+    /// Escapes to the continuation of a `guard`, then calls its clauses
+    Guard { target: Machine, clauses: Value },
+}
+
+/// Slots of a frame, `None` until initialized, along with the enclosing frame
+pub(crate) struct Environment {
+    slots: RefCell<Vec<Option<Value>>>,
+    parent: Option<Rc<Environment>>,
+}
+
+impl Environment {
+    fn extend(parent: &Option<Rc<Environment>>, slots: Vec<Option<Value>>) -> Option<Rc<Environment>> {
+        Some(Rc::new(Environment { slots: RefCell::new(slots), parent: parent.clone() }))
+    }
+
+    /// Frame `depth` levels up from `environment`
+    fn frame(environment: &Option<Rc<Environment>>, depth: usize) -> &Environment {
+        let mut frame = environment.as_deref().expect("locals are resolved within a frame");
+        for _ in 0..depth {
+            frame = frame.parent.as_deref().expect("locals are resolved within a frame");
+        }
+
+        frame
+    }
+}
+
+pub(crate) enum Control {
+    Eval(Node, Option<Rc<Environment>>),
+    Return(Value),
+    /// Calls a procedure in tail position
+    Apply(Value, Vec<Value>, Span),
+    /// Calls the current exception handler, see R7RS 6.11
+    Raise {
+        object: Value,
+        continuable: bool,
+        span: Span,
+    },
+}
+
+/// What to do with a value once returned
+#[derive(Clone)]
+enum Frame {
+    If(Rc<If>),
+    /// Index of the next node to evaluate
+    Sequence(Rc<[Node]>, usize),
+    /// Index of the next node to evaluate if the value is true
+    And(Rc<[Node]>, usize),
+    /// Index of the next node to evaluate if the value is false
+    Or(Rc<[Node]>, usize),
+    Arrow(Rc<Arrow>),
+    Case(Rc<Case>),
+    /// Calls the returned recipient with the value of a `cond` test or the key of a `case`
+    Recipient(Value, Span),
+    /// Calls a thunk once the winders are set, discarding the returned value
+    Thunk {
+        thunk: Value,
+        winders: Option<Rc<Winder>>,
+        span: Span,
+    },
+    /// Calls the `after` thunk of a `dynamic-wind`, then returns the value of its thunk
+    Unwind(Rc<Winder>),
+    /// Returns a value in place of the one returned
+    Return(Value),
+    /// Sets the winders, handlers and parameters of the continuation escaped to
+    Rewound {
+        winders: Option<Rc<Winder>>,
+        handlers: Option<Rc<Handlers>>,
+        parameters: Option<Rc<Parameterization>>,
+    },
+    /// Calls the consumer of `call-with-values` with the values returned
+    Consumer(Value, Span),
+    /// Calls a procedure, discarding the value returned
+    Apply {
+        procedure: Value,
+        arguments: Vec<Value>,
+        span: Span,
+    },
+    /// Restores the handlers once a handler or the thunk of `with-exception-handler` returns
+    Handlers(Option<Rc<Handlers>>),
+    /// Reports the return of the handler of a `raise`
+    NonContinuable(Span),
+    /// Raises an object with `raise-continuable`, discarding the value returned
+    Raise(Value, Span),
+    /// Stores the value of the thunk of a promise, or forces the promise returned by the thunk of a
+    /// `delay-force`
+    Force(Rc<Promise>, Span),
+    /// Makes a parameter object of the converted initial value
+    MakeParameter(Value),
+    /// Stores the converted value of the binding at `index`, converting the following ones
+    Convert {
+        bindings: Vec<(Rc<Parameter>, Value)>,
+        index: usize,
+        thunk: Value,
+        span: Span,
+    },
+    /// Restores the parameters once the body of a `parameterize` returns
+    Parameters(Option<Rc<Parameterization>>),
+    SetLocal(Local),
+    SetGlobal(Rc<Global>, Span),
+    DefineGlobal(Rc<Global>),
+    DefineValues(Rc<DefineValues>),
+    Operator(Rc<Call>),
+    Operand {
+        call: Rc<Call>,
+        procedure: Value,
+        /// Operands evaluated so far
+        arguments: Vec<Value>,
+    },
+    Scope {
+        scope: Rc<Scope>,
+        /// Inits evaluated so far
+        values: Vec<Value>,
+    },
+}
+
+/// Linked list of frames, each evaluated in its own environment
+#[derive(Clone)]
+struct Continuation {
+    frame: Frame,
+    environment: Option<Rc<Environment>>,
+    next: Option<Rc<Continuation>>,
+    /// Number of frames, this one included
+    #[cfg(test)]
+    depth: usize,
+}
+
+/// Frames are unlinked iteratively, as the default recursive drop would overflow the stack on
+/// deep recursions.
+impl Drop for Continuation {
+    fn drop(&mut self) {
+        let mut next = self.next.take();
+
+        while let Some(continuation) = next {
+            match Rc::try_unwrap(continuation) {
+                Ok(mut continuation) => next = continuation.next.take(),
+                // still referenced elsewhere
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+fn push(continuation: &mut Option<Rc<Continuation>>, frame: Frame, environment: Option<Rc<Environment>>) {
+    let next = continuation.take();
+    #[cfg(test)]
+    let depth = next.as_ref().map_or(0, |next| next.depth) + 1;
+    *continuation = Some(Rc::new(Continuation {
+        frame,
+        environment,
+        next,
+        #[cfg(test)]
+        depth,
+    }));
+}
+
+impl Machine {
+    /// Number of frames of the continuation
+    #[cfg(test)]
+    fn depth(&self) -> usize {
+        self.continuation.as_ref().map_or(0, |continuation| continuation.depth)
+    }
+
+    /// Procedure escaping to the current continuation, see R7RS 6.10
+    pub(crate) fn capture(&self) -> Value {
+        Value::Procedure(Procedure(ProcedureKind::Continuation(Rc::new(self.clone()))))
+    }
+
+    /// Calls `thunk` within a `dynamic-wind`, calling `before` first.
+    pub(crate) fn wind(&mut self, before: Value, thunk: Value, after: Value, span: Span) -> Control {
+        let depth = self.winders.as_ref().map_or(0, |winder| winder.depth + 1);
+        let winder = Rc::new(Winder {
+            before: before.clone(),
+            after,
+            parent: self.winders.clone(),
+            depth,
+            span,
+        });
+
+        push(&mut self.continuation, Frame::Unwind(winder.clone()), None);
+        push(&mut self.continuation, Frame::Thunk { thunk, winders: Some(winder), span }, None);
+        Control::Apply(before, Vec::new(), span)
+    }
+
+    /// Calls `thunk` with `handler` installed, see R7RS `with-exception-handler`.
+    pub(crate) fn with_handler(&mut self, handler: Value, thunk: Value, span: Span) -> Control {
+        push(&mut self.continuation, Frame::Handlers(self.handlers.clone()), None);
+        self.handlers = Some(Rc::new(Handlers {
+            handler: Handler::Procedure(handler),
+            parent: self.handlers.take(),
+        }));
+        Control::Apply(thunk, Vec::new(), span)
+    }
+
+    /// Value of a promise, calling its thunk unless already done. The promise is forced again in
+    /// place of the one returned by the thunk of a `delay-force`, which takes no frame, see R7RS
+    /// 4.2.5.
+    pub(crate) fn force(&mut self, promise: Rc<Promise>, span: Span) -> Control {
+        match promise.state() {
+            State::Done(value) => Control::Return(value),
+            State::Pending { thunk, .. } => {
+                push(&mut self.continuation, Frame::Force(promise, span), None);
+                Control::Apply(thunk, Vec::new(), span)
+            }
+        }
+    }
+
+    /// Calls `consumer` with the values returned by `producer`, see R7RS `call-with-values`.
+    pub(crate) fn call_with_values(&mut self, producer: Value, consumer: Value, span: Span) -> Control {
+        push(&mut self.continuation, Frame::Consumer(consumer, span), None);
+        Control::Apply(producer, Vec::new(), span)
+    }
+
+    /// Makes a parameter object whose initial value is `value` converted by `converter`.
+    pub(crate) fn convert_parameter(&mut self, value: Value, converter: Value, span: Span) -> Control {
+        push(&mut self.continuation, Frame::MakeParameter(converter.clone()), None);
+        Control::Apply(converter, vec![value], span)
+    }
+
+    /// Calls `thunk` with each parameter bound to its value, the values from `index` on being
+    /// converted first.
+    pub(crate) fn parameterize(&mut self, bindings: Vec<(Rc<Parameter>, Value)>, index: usize, thunk: Value, span: Span) -> Control {
+        let next = bindings[index..].iter().position(|(parameter, _)| parameter.converter.is_some());
+        if let Some(index) = next.map(|position| index + position) {
+            let (parameter, value) = &bindings[index];
+            let (converter, value) = (parameter.converter.clone().expect("found above"), value.clone());
+            push(&mut self.continuation, Frame::Convert { bindings, index, thunk, span }, None);
+            return Control::Apply(converter, vec![value], span);
+        }
+
+        push(&mut self.continuation, Frame::Parameters(self.parameters.clone()), None);
+        for (parameter, value) in bindings {
+            self.parameters = Some(Rc::new(Parameterization { parameter, value, parent: self.parameters.take() }));
+        }
+        Control::Apply(thunk, Vec::new(), span)
+    }
+
+    /// Value of `parameter` in the current dynamic environment
+    fn parameter(&self, parameter: &Rc<Parameter>) -> Value {
+        let mut parameterization = self.parameters.as_deref();
+        while let Some(binding) = parameterization {
+            if Rc::ptr_eq(&binding.parameter, parameter) {
+                return binding.value.clone();
+            }
+            parameterization = binding.parent.as_deref();
+        }
+
+        parameter.value.clone()
+    }
+
+    /// Calls the current handler with `object`, in the dynamic environment of the raise but for
+    /// the handler being the enclosing one. Once the handler returns, its value is returned by
+    /// `raise-continuable` whereas a `raise` fails.
+    fn raise(&mut self, object: Value, continuable: bool, span: Span) -> Result<Control> {
+        let Some(handlers) = self.handlers.clone() else {
+            let description = match &object {
+                Value::Error(error) => error.to_string(),
+                _ => object.written().to_string(),
+            };
+            return Err(EvalError::Raised(description.into(), span));
+        };
+
+        let frame = match continuable {
+            true => Frame::Handlers(Some(handlers.clone())),
+            false => Frame::NonContinuable(span),
+        };
+        push(&mut self.continuation, frame, None);
+        self.handlers = handlers.parent.clone();
+
+        Ok(match &handlers.handler {
+            Handler::Procedure(handler) => Control::Apply(handler.clone(), vec![object], span),
+            Handler::Guard { target, clauses } => {
+                // the clauses re-raise the object within the handler if none matches
+                let arguments = vec![object, self.capture()];
+                self.escape(target, Frame::Apply { procedure: clauses.clone(), arguments, span })
+            }
+        })
+    }
+
+    /// Replaces the state of the machine by `target`, running `last` in its continuation once
+    /// the `after` thunks of the `dynamic-wind`s being exited, then the `before` thunks of those
+    /// being entered are called.
+    fn escape(&mut self, target: &Machine, last: Frame) -> Control {
+        let mut exited = Vec::new();
+        let mut entered = Vec::new();
+        let (mut from, mut to) = (self.winders.clone(), target.winders.clone());
+        // walks up from the deepest side until reaching the innermost common winder
+        loop {
+            match (&from, &to) {
+                (Some(left), Some(right)) if Rc::ptr_eq(left, right) => break,
+                (None, None) => break,
+                (Some(left), Some(right)) if left.depth < right.depth => {
+                    let right = right.clone();
+                    to = right.parent.clone();
+                    entered.push(right);
+                }
+                (None, Some(right)) => {
+                    let right = right.clone();
+                    to = right.parent.clone();
+                    entered.push(right);
+                }
+                (Some(left), _) => {
+                    let left = left.clone();
+                    from = left.parent.clone();
+                    exited.push(left);
+                }
+            }
+        }
+
+        // frames are pushed from the last one to run
+        self.continuation = target.continuation.clone();
+        push(&mut self.continuation, last, None);
+        let frame = Frame::Rewound {
+            winders: target.winders.clone(),
+            handlers: target.handlers.clone(),
+            parameters: target.parameters.clone(),
+        };
+        push(&mut self.continuation, frame, None);
+        for winder in entered {
+            let frame = Frame::Thunk {
+                thunk: winder.before.clone(),
+                winders: winder.parent.clone(),
+                span: winder.span,
+            };
+            push(&mut self.continuation, frame, None);
+        }
+        for winder in exited.into_iter().rev() {
+            let frame = Frame::Thunk {
+                thunk: winder.after.clone(),
+                winders: winder.parent.clone(),
+                span: winder.span,
+            };
+            push(&mut self.continuation, frame, None);
+        }
+
+        Control::Return(Value::Unspecified)
+    }
+}
+
+impl Interpreter {
+    /// Runs `node` at top level, returning its value. Errors are raised at `span`, the span of the
+    /// top-level form, if their diagnostic doesn't locate them.
+    pub(crate) fn execute(&mut self, node: Node, span: Span) -> Result<Value> {
+        let mut control = Control::Eval(node, None);
+        let mut machine = Machine::default();
+
+        loop {
+            #[cfg(test)]
+            {
+                self.max_depth = self.max_depth.max(machine.depth());
+            }
+            control = match self.step(control, &mut machine) {
+                Ok(ControlFlow::Continue(control)) => control,
+                Ok(ControlFlow::Break(value)) => return Ok(value),
+                // raised as an error object if the program installed a handler
+                Err(error) => match machine.handlers.is_some() {
+                    true => Control::Raise {
+                        object: Value::Error(Rc::new(ErrorObject::from_error(ErrorKind::Error, &error))),
+                        continuable: false,
+                        span: error.to_diagnostic().labels.first().map_or(span, |label| label.span),
+                    },
+                    false => return Err(error),
+                },
+            };
+        }
+    }
+
+    /// Evaluates a node or returns a value to the innermost frame, breaking with the value
+    /// returned once the continuation is empty.
+    fn step(&mut self, control: Control, machine: &mut Machine) -> Result<ControlFlow<Value, Control>> {
+        let value = match control {
+            Control::Return(value) => value,
+            Control::Apply(procedure, arguments, span) => {
+                return Ok(ControlFlow::Continue(self.apply(procedure, arguments, span, machine)?))
+            }
+            Control::Raise { object, continuable, span } => return Ok(ControlFlow::Continue(machine.raise(object, continuable, span)?)),
+            Control::Eval(node, environment) => match &node {
+                Node::Constant(value) => value.clone(),
+                Node::Local(local) => {
+                    let value = Environment::frame(&environment, local.depth).slots.borrow()[local.index].clone();
+                    value.ok_or_else(|| EvalError::UninitializedVariable(local.name.as_ref().into(), local.span))?
+                }
+                Node::Global(global, span) => {
+                    let value = global.value.borrow().clone();
+                    value.ok_or_else(|| EvalError::UnboundVariable(global.name.as_ref().into(), *span))?
+                }
+                Node::SetLocal(local, value) => {
+                    push(&mut machine.continuation, Frame::SetLocal(local.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval((**value).clone(), environment)));
+                }
+                Node::SetGlobal(global, value, span) => {
+                    push(
+                        &mut machine.continuation,
+                        Frame::SetGlobal(global.clone(), *span),
+                        environment.clone(),
+                    );
+                    return Ok(ControlFlow::Continue(Control::Eval((**value).clone(), environment)));
+                }
+                Node::DefineGlobal(global, value) => {
+                    push(&mut machine.continuation, Frame::DefineGlobal(global.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval((**value).clone(), environment)));
+                }
+                Node::DefineValues(definition) => {
+                    let value = definition.value.clone();
+                    push(
+                        &mut machine.continuation,
+                        Frame::DefineValues(definition.clone()),
+                        environment.clone(),
+                    );
+                    return Ok(ControlFlow::Continue(Control::Eval(value, environment)));
+                }
+                Node::If(if_node) => {
+                    let test = if_node.test.clone();
+                    push(&mut machine.continuation, Frame::If(if_node.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval(test, environment)));
+                }
+                Node::Lambda(lambda) => {
+                    let closure = Closure { lambda: lambda.clone(), environment };
+                    Value::Procedure(Procedure(ProcedureKind::Closure(Rc::new(closure))))
+                }
+                Node::CaseLambda(case_lambda) => {
+                    let closure = CaseClosure { case_lambda: case_lambda.clone(), environment };
+                    Value::Procedure(Procedure(ProcedureKind::CaseClosure(Rc::new(closure))))
+                }
+                Node::Delay(lambda, chained) => {
+                    let thunk = Value::Procedure(Procedure(ProcedureKind::Closure(Rc::new(Closure {
+                        lambda: lambda.clone(),
+                        environment,
+                    }))));
+                    Value::Promise(Rc::new(Promise::new(State::Pending { thunk, chained: *chained })))
+                }
+                Node::Sequence(nodes) => {
+                    return Ok(ControlFlow::Continue(sequence(
+                        nodes.clone(),
+                        0,
+                        environment,
+                        &mut machine.continuation,
+                    )));
+                }
+                Node::Call(call) => {
+                    let operator = call.operator.clone();
+                    push(&mut machine.continuation, Frame::Operator(call.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval(operator, environment)));
+                }
+                Node::Scope(scope) => {
+                    return Ok(ControlFlow::Continue(scope_init(
+                        scope.clone(),
+                        Vec::new(),
+                        environment,
+                        &mut machine.continuation,
+                    )?));
+                }
+                Node::And(nodes) => {
+                    return Ok(ControlFlow::Continue(junction(
+                        nodes.clone(),
+                        0,
+                        Frame::And,
+                        environment,
+                        &mut machine.continuation,
+                    )));
+                }
+                Node::Or(nodes) => {
+                    return Ok(ControlFlow::Continue(junction(
+                        nodes.clone(),
+                        0,
+                        Frame::Or,
+                        environment,
+                        &mut machine.continuation,
+                    )));
+                }
+                Node::Arrow(arrow) => {
+                    let test = arrow.test.clone();
+                    push(&mut machine.continuation, Frame::Arrow(arrow.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval(test, environment)));
+                }
+                Node::Case(case) => {
+                    let key = case.key.clone();
+                    push(&mut machine.continuation, Frame::Case(case.clone()), environment.clone());
+                    return Ok(ControlFlow::Continue(Control::Eval(key, environment)));
+                }
+                Node::Guard(guard) => {
+                    let Guard { body, clauses } = &**guard;
+                    let clauses = Value::Procedure(Procedure(ProcedureKind::Closure(Rc::new(Closure {
+                        lambda: clauses.clone(),
+                        environment: environment.clone(),
+                    }))));
+                    let handler = Handler::Guard { target: machine.clone(), clauses };
+
+                    push(&mut machine.continuation, Frame::Handlers(machine.handlers.clone()), None);
+                    machine.handlers = Some(Rc::new(Handlers { handler, parent: machine.handlers.take() }));
+                    return Ok(ControlFlow::Continue(Control::Eval(body.clone(), environment)));
+                }
+                Node::Reraise(span) => {
+                    let slots = Environment::frame(&environment, 0).slots.borrow();
+                    let [Some(object), Some(Value::Procedure(Procedure(ProcedureKind::Continuation(handler))))] = &slots[..] else {
+                        unreachable!("guard clauses are called with the object and the continuation of the handler");
+                    };
+                    let control = machine.escape(handler, Frame::Raise(object.clone(), *span));
+                    return Ok(ControlFlow::Continue(control));
+                }
+            },
+        };
+
+        // returns the value to the innermost frame
+        let Some(top) = machine.continuation.take() else {
+            return Ok(ControlFlow::Break(value));
+        };
+        let mut top = Rc::unwrap_or_clone(top);
+        machine.continuation = top.next.take();
+        let environment = top.environment.take();
+
+        let control = match &mut top.frame {
+            Frame::If(if_node) => match value.is_true() {
+                true => Control::Eval(if_node.consequent.clone(), environment),
+                false => Control::Eval(if_node.alternate.clone(), environment),
+            },
+            Frame::Sequence(nodes, index) => sequence(nodes.clone(), *index, environment, &mut machine.continuation),
+            Frame::And(nodes, index) => match value.is_true() {
+                true => junction(nodes.clone(), *index, Frame::And, environment, &mut machine.continuation),
+                false => Control::Return(value),
+            },
+            Frame::Or(nodes, index) => match value.is_true() {
+                true => Control::Return(value),
+                false => junction(nodes.clone(), *index, Frame::Or, environment, &mut machine.continuation),
+            },
+            Frame::Arrow(arrow) => match value.is_true() {
+                true => {
+                    push(&mut machine.continuation, Frame::Recipient(value, arrow.span), environment.clone());
+                    Control::Eval(arrow.recipient.clone(), environment)
+                }
+                false => Control::Eval(arrow.alternate.clone(), environment),
+            },
+            Frame::Case(case) => {
+                let clause = case
+                    .clauses
+                    .iter()
+                    .find(|(data, _)| data.iter().any(|datum| datum.eqv(&value)))
+                    .map_or(&case.else_clause, |(_, clause)| clause);
+
+                match clause {
+                    Clause::Sequence(node) => Control::Eval(node.clone(), environment),
+                    Clause::Arrow(recipient) => {
+                        push(&mut machine.continuation, Frame::Recipient(value, case.span), environment.clone());
+                        Control::Eval(recipient.clone(), environment)
+                    }
+                }
+            }
+            Frame::Recipient(argument, span) => Control::Apply(value, vec![argument.clone()], *span),
+            Frame::Thunk { thunk, winders, span } => {
+                machine.winders = winders.clone();
+                Control::Apply(thunk.clone(), Vec::new(), *span)
+            }
+            Frame::Unwind(winder) => {
+                machine.winders = winder.parent.clone();
+                push(&mut machine.continuation, Frame::Return(value), None);
+                Control::Apply(winder.after.clone(), Vec::new(), winder.span)
+            }
+            Frame::Return(value) => Control::Return(value.clone()),
+            Frame::Rewound { winders, handlers, parameters } => {
+                machine.winders = winders.clone();
+                machine.handlers = handlers.clone();
+                machine.parameters = parameters.clone();
+                Control::Return(value)
+            }
+            Frame::Consumer(consumer, span) => Control::Apply(consumer.clone(), value.into_values(), *span),
+            Frame::Apply { procedure, arguments, span } => Control::Apply(procedure.clone(), std::mem::take(arguments), *span),
+            Frame::Handlers(handlers) => {
+                machine.handlers = handlers.clone();
+                Control::Return(value)
+            }
+            Frame::NonContinuable(span) => return Err(EvalError::HandlerReturned(*span)),
+            Frame::Raise(object, span) => Control::Raise { object: object.clone(), continuable: true, span: *span },
+            Frame::Force(promise, span) => match (promise.state(), value) {
+                // forced again by its own thunk
+                (State::Done(value), _) => Control::Return(value),
+                (State::Pending { chained: true, .. }, Value::Promise(next)) => {
+                    promise.chain(&next);
+                    machine.force(promise.clone(), *span)
+                }
+                (State::Pending { .. }, value) => {
+                    promise.resolve(value.clone());
+                    Control::Return(value)
+                }
+            },
+            Frame::MakeParameter(converter) => {
+                let parameter = Parameter { value, converter: Some(converter.clone()) };
+                Control::Return(Value::Procedure(Procedure(ProcedureKind::Parameter(Rc::new(parameter)))))
+            }
+            Frame::Convert { bindings, index, thunk, span } => {
+                let mut bindings = std::mem::take(bindings);
+                bindings[*index].1 = value;
+                machine.parameterize(bindings, *index + 1, thunk.clone(), *span)
+            }
+            Frame::Parameters(parameters) => {
+                machine.parameters = parameters.clone();
+                Control::Return(value)
+            }
+            Frame::SetLocal(local) => {
+                Environment::frame(&environment, local.depth).slots.borrow_mut()[local.index] = Some(value);
+                Control::Return(Value::Unspecified)
+            }
+            Frame::SetGlobal(global, span) => {
+                let mut global_value = global.value.borrow_mut();
+                if global_value.is_none() {
+                    return Err(EvalError::UnboundVariable(global.name.as_ref().into(), *span));
+                }
+                *global_value = Some(value);
+                Control::Return(Value::Unspecified)
+            }
+            Frame::DefineGlobal(global) => {
+                *global.value.borrow_mut() = Some(value);
+                Control::Return(Value::Unspecified)
+            }
+            Frame::DefineValues(definition) => {
+                let values = spread(value, definition.arity, definition.span)?;
+                for (variable, value) in definition.variables.iter().zip(values) {
+                    match variable {
+                        Variable::Local(local) => {
+                            Environment::frame(&environment, local.depth).slots.borrow_mut()[local.index] = Some(value);
+                        }
+                        Variable::Global(global) => *global.value.borrow_mut() = Some(value),
+                    }
+                }
+                Control::Return(Value::Unspecified)
+            }
+            Frame::Operator(call) => self.next_operand(call.clone(), value, Vec::new(), environment, machine)?,
+            Frame::Operand { call, procedure, arguments } => {
+                let mut arguments = std::mem::take(arguments);
+                arguments.push(value);
+                self.next_operand(call.clone(), procedure.clone(), arguments, environment, machine)?
+            }
+            Frame::Scope { scope, values } => {
+                let mut values = std::mem::take(values);
+                values.push(value);
+                scope_init(scope.clone(), values, environment, &mut machine.continuation)?
+            }
+        };
+
+        Ok(ControlFlow::Continue(control))
+    }
+
+    /// Evaluates the next operand of `call`, applying the procedure once all are evaluated.
+    fn next_operand(
+        &mut self,
+        call: Rc<Call>,
+        procedure: Value,
+        arguments: Vec<Value>,
+        environment: Option<Rc<Environment>>,
+        machine: &mut Machine,
+    ) -> Result<Control> {
+        let Some(operand) = call.operands.get(arguments.len()).cloned() else {
+            return self.apply(procedure, arguments, call.span, machine);
+        };
+
+        push(
+            &mut machine.continuation,
+            Frame::Operand { call, procedure, arguments },
+            environment.clone(),
+        );
+        Ok(Control::Eval(operand, environment))
+    }
+
+    /// Calls `procedure`, the body of closures being evaluated in the current continuation, which
+    /// continuations replace.
+    fn apply(&mut self, procedure: Value, arguments: Vec<Value>, span: Span, machine: &mut Machine) -> Result<Control> {
+        let Value::Procedure(procedure) = procedure else {
+            return Err(EvalError::NotProcedure(span));
+        };
+
+        let arity = procedure.arity();
+        if !arity.accepts(arguments.len()) {
+            return Err(EvalError::ArityMismatch { expected: arity, found: arguments.len(), span });
+        }
+
+        match procedure.0 {
+            ProcedureKind::Primitive(primitive) => {
+                let error = |error| EvalError::Primitive { name: primitive.name, error, span };
+                match primitive.function {
+                    Function::Value(function) => Ok(Control::Return(function(self, &arguments).map_err(error)?)),
+                    Function::Control(function) => function(machine, arguments, span).map_err(error),
+                }
+            }
+            ProcedureKind::Closure(closure) => Ok(enter(&closure.lambda, &closure.environment, arguments)),
+            ProcedureKind::CaseClosure(closure) => {
+                let clauses = &closure.case_lambda.clauses;
+                let lambda = clauses.iter().find(|lambda| lambda.arity.accepts(arguments.len()));
+                let lambda = lambda.ok_or(EvalError::ArityMismatch { expected: arity, found: arguments.len(), span })?;
+                Ok(enter(lambda, &closure.environment, arguments))
+            }
+            ProcedureKind::Continuation(target) => Ok(machine.escape(&target, Frame::Return(Value::values(arguments)))),
+            ProcedureKind::Parameter(parameter) => Ok(Control::Return(machine.parameter(&parameter))),
+            ProcedureKind::Record(procedure) => Ok(Control::Return(procedure.call(arguments, span)?)),
+        }
+    }
+}
+
+/// Evaluates the body of `lambda` in a new frame of `environment` holding the arguments.
+fn enter(lambda: &Lambda, environment: &Option<Rc<Environment>>, arguments: Vec<Value>) -> Control {
+    let mut slots = Vec::with_capacity(lambda.frame_size);
+    slots.extend(parameters(arguments, lambda.arity).map(Some));
+    slots.resize(lambda.frame_size, None);
+
+    Control::Eval(lambda.body.clone(), Environment::extend(environment, slots))
+}
+
+/// Values of the parameters of formals taking `arguments`, those past the required ones being
+/// packed in a list if the formals have a rest parameter
+fn parameters(arguments: Vec<Value>, arity: Arity) -> impl Iterator<Item = Value> {
+    let mut arguments = arguments.into_iter();
+    let required: Vec<_> = arguments.by_ref().take(arity.min).collect();
+    let rest = arity.max.is_none().then(|| Value::list(arguments));
+    required.into_iter().chain(rest)
+}
+
+/// Values of the formals bound to the values returned, as by `let-values`
+fn spread(value: Value, arity: Arity, span: Span) -> Result<impl Iterator<Item = Value>> {
+    let values = value.into_values();
+    if !arity.accepts(values.len()) {
+        return Err(EvalError::ValuesMismatch { expected: arity, found: values.len(), span });
+    }
+
+    Ok(parameters(values, arity))
+}
+
+/// Evaluates the node at `index`, the last one in tail position.
+fn sequence(nodes: Rc<[Node]>, index: usize, environment: Option<Rc<Environment>>, continuation: &mut Option<Rc<Continuation>>) -> Control {
+    let node = nodes[index].clone();
+    if index + 1 < nodes.len() {
+        push(continuation, Frame::Sequence(nodes, index + 1), environment.clone());
+    }
+
+    Control::Eval(node, environment)
+}
+
+/// Evaluates the node at `index` of an `and` or `or`, the last one in tail position.
+fn junction(
+    nodes: Rc<[Node]>,
+    index: usize,
+    frame: fn(Rc<[Node]>, usize) -> Frame,
+    environment: Option<Rc<Environment>>,
+    continuation: &mut Option<Rc<Continuation>>,
+) -> Control {
+    let node = nodes[index].clone();
+    if index + 1 < nodes.len() {
+        push(continuation, frame(nodes, index + 1), environment.clone());
+    }
+
+    Control::Eval(node, environment)
+}
+
+/// Evaluates the next init of `scope`, entering its frame once all are evaluated.
+fn scope_init(
+    scope: Rc<Scope>,
+    values: Vec<Value>,
+    environment: Option<Rc<Environment>>,
+    continuation: &mut Option<Rc<Continuation>>,
+) -> Result<Control> {
+    if let Some(init) = scope.inits.get(values.len()).cloned() {
+        push(continuation, Frame::Scope { scope, values }, environment.clone());
+        return Ok(Control::Eval(init, environment));
+    }
+
+    let mut slots = Vec::with_capacity(scope.frame_size);
+    match scope.formals.is_empty() {
+        true => slots.extend(values.into_iter().map(Some)),
+        false => {
+            for (value, (arity, span)) in values.into_iter().zip(&scope.formals) {
+                slots.extend(spread(value, *arity, *span)?.map(Some));
+            }
+        }
+    }
+    slots.resize(scope.frame_size, None);
+    Ok(Control::Eval(scope.body.clone(), Environment::extend(&environment, slots)))
+}
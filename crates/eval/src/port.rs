@@ -0,0 +1,48 @@
+//! Input ports of R7RS 6.13
+
+use std::{cell::Cell, rc::Rc};
+
+use pluine_common::ParseError;
+use pluine_lex::Lexer;
+use pluine_parser::{DatumGraph, Reader};
+
+use crate::Value;
+
+/// Textual input port reading from a string, such as the contents of a file
+///
+/// The lexer state is kept between reads, each read resuming where the previous datum ended
+/// rather than lexing the source again.
+pub struct InputPort {
+    src: Rc<str>,
+    /// Byte offset of the next character to read
+    position: Cell<usize>,
+    /// Set by a previously read `#!fold-case` directive
+    fold_case: Cell<bool>,
+}
+
+impl InputPort {
+    pub(crate) fn new(src: impl Into<Rc<str>>) -> Self {
+        InputPort { src: src.into(), position: Cell::new(0), fold_case: Cell::new(false) }
+    }
+
+    /// Text the port reads from, which the spans of its read errors point into
+    pub(crate) fn src(&self) -> &Rc<str> {
+        &self.src
+    }
+
+    /// Next datum, `None` once only atmosphere is left. Errors leave the port where it was.
+    pub(crate) fn read(&self) -> Result<Option<Value>, ParseError> {
+        let lexer = Lexer::new_at(&self.src, self.position.get()).with_fold_case(self.fold_case.get());
+        let mut reader = Reader::from_lexer(lexer);
+
+        let Some(datum) = reader.next().transpose()? else {
+            return Ok(None);
+        };
+        let value = Value::from_graph(&DatumGraph::new(&self.src, datum))?;
+
+        self.position.set(reader.lexer().offset());
+        self.fold_case.set(reader.lexer().folds_case());
+
+        Ok(Some(value))
+    }
+}
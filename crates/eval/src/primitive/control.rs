@@ -0,0 +1,166 @@
+//! Control features of R7RS 6.10
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::control("apply", Arity::at_least(2), apply),
+    Primitive::control("call-with-current-continuation", Arity::exactly(1), call_cc),
+    Primitive::control("call/cc", Arity::exactly(1), call_cc),
+    Primitive::control("dynamic-wind", Arity::exactly(3), dynamic_wind),
+    Primitive::new("values", Arity::at_least(0), |_, arguments| Ok(Value::values(arguments.to_vec()))),
+    Primitive::control("call-with-values", Arity::exactly(2), call_with_values),
+];
+
+/// `(apply <procedure> <argument>* <list>)`, the list elements being appended to the arguments
+fn apply(_: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let spread = list(&arguments, arguments.len() - 1)?;
+    arguments.pop();
+
+    let procedure = arguments.remove(0);
+    arguments.extend(spread);
+    Ok(Control::Apply(procedure, arguments, span))
+}
+
+/// Calls the procedure with the current continuation, which may be called any number of times,
+/// even once `call/cc` returned.
+fn call_cc(machine: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let procedure = arguments.pop().expect("one argument");
+    Ok(Control::Apply(procedure, vec![machine.capture()], span))
+}
+
+/// `(dynamic-wind <before> <thunk> <after>)`, `before` being called whenever the extent of the call
+/// to `thunk` is entered, and `after` whenever it is exited, including by continuations.
+fn dynamic_wind(machine: &mut Machine, arguments: Vec<Value>, span: Span) -> Result<Control> {
+    for index in 0..arguments.len() {
+        procedure(&arguments, index)?;
+    }
+
+    let [before, thunk, after] = <[Value; 3]>::try_from(arguments).unwrap_or_else(|_| unreachable!("three arguments"));
+    Ok(machine.wind(before, thunk, after, span))
+}
+
+/// `(call-with-values <producer> <consumer>)`, the consumer being called in tail position with the
+/// values returned by the producer
+fn call_with_values(machine: &mut Machine, arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let [producer, consumer] = <[Value; 2]>::try_from(arguments).unwrap_or_else(|_| unreachable!("two arguments"));
+    Ok(machine.call_with_values(producer, consumer, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::{eval, output};
+
+    #[test]
+    fn apply() {
+        assert_eq!("10", eval("(apply + 1 2 '(3 4))"));
+        assert_eq!("()", eval("(apply list '())"));
+        assert_eq!("apply: argument 3 must be a proper list", eval("(apply + 1 2)"));
+        assert_eq!("attempt to call a non-procedure", eval("(apply 1 '())"));
+    }
+
+    #[test]
+    fn multiple_values() {
+        assert_eq!("1", eval("(values 1)"));
+        assert_eq!("1 2", eval("(values 1 2)"));
+        assert_eq!("5", eval("(call-with-values (lambda () (values 4 5)) (lambda (a b) b))"));
+        assert_eq!("-1", eval("(call-with-values * -)"));
+        assert_eq!("()", eval("(call-with-values values list)"));
+        assert_eq!("(1)", eval("(call-with-values (lambda () 1) list)"));
+        assert_eq!(
+            "wrong number of arguments, expected 2 but got 3",
+            eval("(call-with-values (lambda () (values 1 2 3)) cons)")
+        );
+    }
+
+    #[test]
+    fn escaping_continuations() {
+        assert_eq!("3", eval("(+ 1 (call/cc (lambda (k) (+ 10 (k 2)))))"));
+        assert_eq!("2", eval("(+ 1 (call-with-current-continuation (lambda (k) 1)))"));
+        assert_eq!("#<continuation>", eval("(call/cc (lambda (k) k))"));
+        assert_eq!("()", eval("(call-with-values (lambda () (call/cc (lambda (k) (k)))) list)"));
+        assert_eq!("(1 2)", eval("(call-with-values (lambda () (call/cc (lambda (k) (k 1 2)))) list)"));
+
+        let src = "
+            (define (find-first predicate list)
+              (call/cc
+                (lambda (return)
+                  (let loop ((list list))
+                    (if (pair? list) (begin (if (predicate (car list)) (return (car list))) (loop (cdr list)))))
+                  #f)))
+            (list (find-first negative? '(1 -2 3 -4)) (find-first negative? '(1 2)))";
+        assert_eq!("(-2 #f)", eval(src));
+    }
+
+    #[test]
+    fn reentrant_continuations() {
+        // each call to `k` returns once more from `call/cc`, the `let` binding a new `n`
+        let src = "
+            (let ((k #f) (results '()))
+              (let ((n (call/cc (lambda (c) (set! k c) 0))))
+                (set! results (cons n results))
+                (if (< n 3) (k (+ n 1)) (reverse results))))";
+        assert_eq!("(0 1 2 3)", eval(src));
+
+        // operands evaluated before the capture are kept on each return
+        let src = "
+            (define k #f)
+            (define count 0)
+            (let ((result (list 'a (call/cc (lambda (c) (set! k c) 0)))))
+              (set! count (+ count 1))
+              (if (< count 3) (k count) result))";
+        assert_eq!("(a 2)", eval(src));
+    }
+
+    #[test]
+    fn generators() {
+        let src = "
+            (define (make-generator list)
+              (define return #f)
+              (define (resume ignored)
+                (let loop ((list list))
+                  (when (pair? list)
+                    (call/cc (lambda (next) (set! resume next) (return (car list))))
+                    (loop (cdr list))))
+                (return 'done))
+              (lambda () (call/cc (lambda (caller) (set! return caller) (resume #f)))))
+            (define next (make-generator '(1 2 3)))
+            (let* ((a (next)) (b (next)) (c (next)) (d (next)))
+              (list a b c d))";
+        assert_eq!("(1 2 3 done)", eval(src));
+    }
+
+    #[test]
+    fn dynamic_wind() {
+        assert_eq!(
+            "(connect talk1 disconnect connect talk2 disconnect)",
+            eval(
+                "(let ((path '()) (c #f))
+                   (let ((add (lambda (s) (set! path (cons s path)))))
+                     (dynamic-wind
+                       (lambda () (add 'connect))
+                       (lambda () (add (call/cc (lambda (c0) (set! c c0) 'talk1))))
+                       (lambda () (add 'disconnect)))
+                     (if (< (length path) 4)
+                         (c 'talk2)
+                         (reverse path))))"
+            )
+        );
+
+        assert_eq!("before during after 1", output(
+            "(display (dynamic-wind (lambda () (display \"before \")) (lambda () (display \"during \") 1) (lambda () (display \"after \"))))"
+        ));
+
+        // escaping out of nested extents runs the `after` thunks from the innermost one
+        let src = "
+            (call/cc
+              (lambda (k)
+                (dynamic-wind
+                  (lambda () (display 1))
+                  (lambda () (dynamic-wind (lambda () (display 2)) (lambda () (k 'out)) (lambda () (display 3))))
+                  (lambda () (display 4)))))";
+        assert_eq!("1234", output(src));
+        assert_eq!("out", eval(src));
+
+        assert_eq!("dynamic-wind: argument 2 must be a procedure", eval("(dynamic-wind list 1 list)"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Promises and parameter objects of R7RS 4.2.5 and 4.2.6
+
+use super::*;
+use crate::{
+    procedure::{Parameter, ProcedureKind},
+    promise::State,
+};
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::control("force", Arity::exactly(1), force),
+    Primitive::new("make-promise", Arity::exactly(1), |_, arguments| {
+        Ok(match &arguments[0] {
+            Value::Promise(_) => arguments[0].clone(),
+            value => Value::Promise(Rc::new(Promise::new(State::Done(value.clone())))),
+        })
+    }),
+    Primitive::new("promise?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Promise(_)).into())
+    }),
+    Primitive::control("make-parameter", Arity::between(1, 2), make_parameter),
+];
+
+/// Called by `parameterize` expressions with each parameter followed by its value, then the thunk
+/// of the body, it isn't bound to a global variable.
+pub(crate) static PARAMETERIZE: Primitive = Primitive::control("parameterize", Arity::at_least(1), parameterize);
+
+/// Value of a promise, computing it if not done yet, other values being returned as is
+fn force(machine: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    Ok(match arguments.pop().expect("one argument") {
+        Value::Promise(promise) => machine.force(promise, span),
+        value => Control::Return(value),
+    })
+}
+
+/// `(make-parameter <value> <converter>?)`, the converter being called on the initial value too
+fn make_parameter(machine: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    if arguments.len() == 2 {
+        procedure(&arguments, 1)?;
+    }
+
+    let converter = (arguments.len() == 2).then(|| arguments.pop().expect("two arguments"));
+    let value = arguments.pop().expect("one or two arguments");
+    Ok(match converter {
+        Some(converter) => machine.convert_parameter(value, converter, span),
+        None => Control::Return(Value::Procedure(Procedure(ProcedureKind::Parameter(Rc::new(Parameter {
+            value,
+            converter: None,
+        }))))),
+    })
+}
+
+fn parameterize(machine: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let thunk = arguments.pop().expect("the thunk of the body");
+
+    let mut bindings = Vec::with_capacity(arguments.len() / 2);
+    let mut arguments = arguments.into_iter().enumerate();
+    while let (Some((index, parameter)), Some((_, value))) = (arguments.next(), arguments.next()) {
+        let Value::Procedure(Procedure(ProcedureKind::Parameter(parameter))) = parameter else {
+            return Err(PrimitiveError::WrongType { expected: "a parameter object", position: index + 1 });
+        };
+        bindings.push((parameter, value));
+    }
+
+    Ok(machine.parameterize(bindings, 0, thunk, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::{eval, output};
+
+    #[test]
+    fn promises() {
+        assert_eq!("3", eval("(force (delay (+ 1 2)))"));
+        assert_eq!("(3 3)", eval("(let ((p (delay (+ 1 2)))) (list (force p) (force p)))"));
+        assert_eq!("1", output("(define p (delay (begin (display 1) 'done))) (force p) (force p)"));
+        assert_eq!("5", eval("(force 5)"));
+        assert_eq!(
+            "(#t #t #f)",
+            eval("(list (promise? (delay 1)) (promise? (make-promise 1)) (promise? 1))")
+        );
+        assert_eq!("7", eval("(force (make-promise 7))"));
+        assert_eq!("#t", eval("(let ((p (delay 1))) (eq? p (make-promise p)))"));
+        assert_eq!("#<promise>", eval("(delay 1)"));
+
+        // R7RS 4.2.5, the promise being forced again by its own expression
+        let src = "
+            (define count 0)
+            (define x 5)
+            (define p
+              (delay (begin (set! count (+ count 1))
+                            (if (> count x) count (force p)))))
+            (list (force p) (begin (set! x 10) (force p)))";
+        assert_eq!("(6 6)", eval(src));
+    }
+
+    #[test]
+    fn iterative_forcing() {
+        // would exhaust the memory if each `delay-force` kept a frame
+        let src = "
+            (define (loop n) (delay-force (if (= n 0) (delay 'done) (loop (- n 1)))))
+            (force (loop 100000))";
+        assert_eq!("done", eval(src));
+
+        let src = "
+            (define (stream-tail stream n)
+              (delay-force (if (= n 0) stream (stream-tail (cdr (force stream)) (- n 1)))))
+            (define (from n) (delay (cons n (from (+ n 1)))))
+            (car (force (stream-tail (from 0) 1000)))";
+        assert_eq!("1000", eval(src));
+    }
+
+    #[test]
+    fn parameters() {
+        let src = "
+            (define radix (make-parameter 10 (lambda (x) (if (and (exact-integer? x) (<= 2 x 16)) x (error \"invalid radix\")))))
+            (define (f) (radix))
+            (list (f) (parameterize ((radix 2)) (f)) (f))";
+        assert_eq!("(10 2 10)", eval(src));
+        assert_eq!("uncaught exception: invalid radix", eval(&src.replace("(radix 2)", "(radix 0)")));
+        assert_eq!("2", eval("(define p (make-parameter 1 (lambda (x) (* x 2)))) (p)"));
+        assert_eq!(
+            "6",
+            eval("(define p (make-parameter 1 (lambda (x) (* x 2)))) (parameterize ((p 3)) (p))")
+        );
+        assert_eq!("#<parameter>", eval("(make-parameter 1)"));
+        assert_eq!(
+            "parameterize: argument 1 must be a parameter object",
+            eval("(parameterize ((car 1)) 1)")
+        );
+        assert_eq!("make-parameter: argument 2 must be a procedure", eval("(make-parameter 1 2)"));
+
+        // escaping restores the values of the parameters, entering again rebinds them
+        let src = "
+            (define p (make-parameter 'outer))
+            (let ((k #f) (seen '()))
+              (parameterize ((p 'inner))
+                (call/cc (lambda (c) (set! k c)))
+                (set! seen (cons (p) seen)))
+              (set! seen (cons (p) seen))
+              (if (< (length seen) 4) (k #f) (reverse seen)))";
+        assert_eq!("(inner outer inner outer)", eval(src));
+        assert_eq!(
+            "outer",
+            eval("(define p (make-parameter 'outer)) (guard (e (#t (p))) (parameterize ((p 'inner)) (raise 1)))")
+        );
+    }
+}
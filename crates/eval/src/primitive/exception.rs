@@ -0,0 +1,173 @@
+//! Exceptions of R7RS 6.11
+
+use super::*;
+use crate::condition::{ErrorKind, ErrorObject};
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::control("raise", Arity::exactly(1), |_, mut arguments, span| {
+        Ok(Control::Raise { object: arguments.remove(0), continuable: false, span })
+    }),
+    Primitive::control("raise-continuable", Arity::exactly(1), |_, mut arguments, span| {
+        Ok(Control::Raise { object: arguments.remove(0), continuable: true, span })
+    }),
+    Primitive::control("with-exception-handler", Arity::exactly(2), with_exception_handler),
+    Primitive::control("error", Arity::at_least(1), error),
+    Primitive::new("error-object?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Error(_)).into())
+    }),
+    Primitive::new("error-object-message", Arity::exactly(1), |_, arguments| {
+        Ok(Value::string(error_object(arguments, 0)?.message()))
+    }),
+    Primitive::new("error-object-irritants", Arity::exactly(1), |_, arguments| {
+        Ok(Value::list(error_object(arguments, 0)?.irritants().iter().cloned()))
+    }),
+    Primitive::new("read-error?", Arity::exactly(1), |_, arguments| {
+        Ok(is_error(&arguments[0], ErrorKind::Read))
+    }),
+    Primitive::new("file-error?", Arity::exactly(1), |_, arguments| {
+        Ok(is_error(&arguments[0], ErrorKind::File))
+    }),
+];
+
+/// `(with-exception-handler <handler> <thunk>)`
+fn with_exception_handler(machine: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    procedure(&arguments, 0)?;
+    procedure(&arguments, 1)?;
+
+    let thunk = arguments.pop().expect("two arguments");
+    let handler = arguments.pop().expect("two arguments");
+    Ok(machine.with_handler(handler, thunk, span))
+}
+
+/// `(error <message> <irritant>*)`, raising a new error object
+fn error(_: &mut Machine, mut arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let message = string(&arguments, 0)?.borrow().clone();
+    let irritants = arguments.split_off(1);
+
+    let object = ErrorObject::new(ErrorKind::Error, message, irritants, None);
+    Ok(Control::Raise { object: Value::Error(Rc::new(object)), continuable: false, span })
+}
+
+fn error_object(arguments: &[Value], index: usize) -> Result<&Rc<ErrorObject>> {
+    argument(arguments, index, "an error object", |value| match value {
+        Value::Error(error) => Some(error),
+        _ => None,
+    })
+}
+
+fn is_error(value: &Value, kind: ErrorKind) -> Value {
+    matches!(value, Value::Error(error) if error.kind() == kind).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::{eval, output};
+
+    #[test]
+    fn handlers() {
+        assert_eq!(
+            "42",
+            eval("(with-exception-handler (lambda (e) 42) (lambda () (+ (raise-continuable 'oops) 0)))")
+        );
+        assert_eq!(
+            "(handled . oops)",
+            eval("(with-exception-handler (lambda (e) (cons 'handled e)) (lambda () (raise-continuable 'oops)))")
+        );
+
+        // handlers are called with the enclosing handler installed
+        let src = "
+            (with-exception-handler
+              (lambda (e) (list 'outer e))
+              (lambda ()
+                (with-exception-handler
+                  (lambda (e) (raise-continuable (list 'inner e)))
+                  (lambda () (raise-continuable 'oops)))))";
+        assert_eq!("(outer (inner oops))", eval(src));
+
+        // escaping from the handler, as `guard` does
+        let src = "
+            (call/cc
+              (lambda (k)
+                (with-exception-handler (lambda (e) (k (list 'caught e))) (lambda () (raise 'boom)))))";
+        assert_eq!("(caught boom)", eval(src));
+    }
+
+    #[test]
+    fn non_continuable() {
+        assert_eq!(
+            "exception handler returned from a non-continuable `raise`",
+            eval("(with-exception-handler (lambda (e) 0) (lambda () (raise 'oops)))")
+        );
+        assert_eq!("uncaught exception: oops", eval("(raise 'oops)"));
+        assert_eq!("uncaught exception: \"a\"", eval("(raise \"a\")"));
+    }
+
+    #[test]
+    fn error_objects() {
+        let src = "
+            (guard (e ((error-object? e) (list (error-object-message e) (error-object-irritants e))))
+              (error \"bad thing:\" 1 'two))";
+        assert_eq!("(\"bad thing:\" (1 two))", eval(src));
+        assert_eq!("uncaught exception: bad thing: 1 \"two\"", eval("(error \"bad thing:\" 1 \"two\")"));
+        assert_eq!("#f", eval("(guard (e (#t (error-object? e))) (raise 'oops))"));
+        assert_eq!(
+            "(#f #f)",
+            eval("(guard (e (#t (list (read-error? e) (file-error? e)))) (error \"a\"))")
+        );
+        assert_eq!("error: argument 1 must be a string", eval("(error 'oops)"));
+    }
+
+    #[test]
+    fn runtime_errors_are_raised() {
+        let src = "(guard (e ((error-object? e) (error-object-message e))) (car 1))";
+        assert_eq!("\"car: argument 1 must be a pair\"", eval(src));
+        let src = "(guard (e (#t (error-object-message e))) (undefined-variable))";
+        assert_eq!("\"unbound variable `undefined-variable`\"", eval(src));
+        assert_eq!("uncaught exception: unbound variable `x`", eval("(guard (e ((string? e) e)) x)"));
+    }
+
+    #[test]
+    fn guard() {
+        assert_eq!(
+            "42",
+            eval("(guard (condition ((assq 'a condition) => cdr) ((assq 'b condition))) (raise (list (cons 'a 42))))")
+        );
+        assert_eq!(
+            "(b . 23)",
+            eval("(guard (condition ((assq 'a condition) => cdr) ((assq 'b condition))) (raise (list (cons 'b 23))))")
+        );
+        assert_eq!("other", eval("(guard (e ((symbol? e) 'symbol) (else 'other)) (raise 1))"));
+        assert_eq!("3", eval("(guard (e (#t 'caught)) (define x 1) (+ x 2))"));
+
+        // re-raised with `raise-continuable` in the dynamic environment of the handler
+        let src = "
+            (with-exception-handler
+              (lambda (e) (* e 2))
+              (lambda () (+ 1 (guard (e ((symbol? e) 'symbol)) (raise-continuable 20)))))";
+        assert_eq!("41", eval(src));
+        assert_eq!("uncaught exception: 1", eval("(guard (e ((symbol? e) 'symbol)) (raise 1))"));
+    }
+
+    #[test]
+    fn guard_unwinds() {
+        let src = "
+            (guard (e (#t (display \"caught \") e))
+              (dynamic-wind
+                (lambda () (display \"in \"))
+                (lambda () (raise 'oops))
+                (lambda () (display \"out \"))))";
+        assert_eq!("in out caught ", output(src));
+
+        // re-raising enters the extent of the raise again
+        let src = "
+            (with-exception-handler
+              (lambda (e) 0)
+              (lambda ()
+                (guard (e (#f #f))
+                  (dynamic-wind
+                    (lambda () (display \"in \"))
+                    (lambda () (raise-continuable 'oops))
+                    (lambda () (display \"out \"))))))";
+        assert_eq!("in out in out ", output(src));
+    }
+}
@@ -0,0 +1,128 @@
+//! Input of R7RS 6.13.2, from ports reading strings or files
+
+use super::*;
+use crate::condition::{ErrorKind, ErrorObject};
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("input-port?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::InputPort(_)).into())
+    }),
+    Primitive::new("open-input-string", Arity::exactly(1), |_, arguments| {
+        Ok(Value::InputPort(Rc::new(InputPort::new(string(arguments, 0)?.borrow().as_str()))))
+    }),
+    Primitive::control("open-input-file", Arity::exactly(1), open_input_file),
+    Primitive::control("read", Arity::exactly(1), read),
+    Primitive::new("eof-object", Arity::exactly(0), |_, _| Ok(Value::Eof)),
+    Primitive::new("eof-object?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Eof).into())
+    }),
+];
+
+/// Port reading the whole file, raising a file error if it can't be read
+fn open_input_file(_: &mut Machine, arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let path = string(&arguments, 0)?.borrow().clone();
+
+    Ok(match std::fs::read_to_string(&path) {
+        Ok(src) => Control::Return(Value::InputPort(Rc::new(InputPort::new(src)))),
+        Err(error) => {
+            let message = format!("could not open file: {error}");
+            let object = ErrorObject::new(ErrorKind::File, message, vec![Value::string(&path)], None);
+            Control::Raise { object: Value::Error(Rc::new(object)), continuable: false, span }
+        }
+    })
+}
+
+/// Next datum of the port, raising a read error located within the input if malformed
+fn read(_: &mut Machine, arguments: Vec<Value>, span: Span) -> Result<Control> {
+    let port = argument(&arguments, 0, "an input port", |value| match value {
+        Value::InputPort(port) => Some(port),
+        _ => None,
+    })?;
+
+    Ok(match port.read() {
+        Ok(Some(value)) => Control::Return(value),
+        Ok(None) => Control::Return(Value::Eof),
+        Err(error) => {
+            let object = ErrorObject::from_error(ErrorKind::Read, &error).in_input(port.src().clone());
+            Control::Raise { object: Value::Error(Rc::new(object)), continuable: false, span }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{interpreter::tests::eval, *};
+
+    #[test]
+    fn read() {
+        let src = "
+            (define port (open-input-string \"(a . b) #(1 \\\"c\\\") ; comment\"))
+            (let* ((first (read port)) (second (read port)) (third (read port)))
+              (list first second (eof-object? third) (input-port? port)))";
+        assert_eq!("((a . b) #(1 \"c\") #t #t)", eval(src));
+        assert_eq!("#<eof>", eval("(eof-object)"));
+
+        // data are read lazily, up to the end of the datum
+        assert_eq!("1", eval("(read (open-input-string \"1 #z\"))"));
+
+        let src = "
+            (define port (open-input-string \"#!fold-case ABC DEF\"))
+            (let* ((first (read port)) (second (read port))) (list first second))";
+        assert_eq!("(abc def)", eval(src));
+    }
+
+    #[test]
+    fn shared_data() {
+        let src = "(let ((x (read (open-input-string \"(#0=(1 2) #0# #1=\\\"s\\\" #1#)\")))) (list (eq? (car x) (cadr x)) (eq? (list-ref x 2) (list-ref x 3))))";
+        assert_eq!("(#t #t)", eval(src));
+
+        // each label doubling the size of the datum it would be copied to
+        let labels = (1..64)
+            .map(|label| format!("#{label}=(#{}# #{}#)", label - 1, label - 1))
+            .collect::<String>();
+        let src = format!("(let ((x (read (open-input-string \"(#0=(a) {labels})\")))) (eq? (car (list-ref x 63)) (list-ref x 62)))");
+        assert_eq!("#t", eval(&src));
+    }
+
+    #[test]
+    fn circular_data() {
+        let src = "(let ((x (read (open-input-string \"#0=(a b . #0#)\")))) (list (car x) (eq? x (cddr x))))";
+        assert_eq!("(a #t)", eval(src));
+        let src = "(let ((v (read (open-input-string \"#0=#(1 #0#)\")))) (eq? v (vector-ref v 1)))";
+        assert_eq!("#t", eval(src));
+    }
+
+    #[test]
+    fn read_errors() {
+        let src = "
+            (define port (open-input-string \"1 )\"))
+            (read port)
+            (guard (e ((read-error? e) (error-object-message e))) (read port))";
+        assert_eq!("\"unexpected ')', no list or vector to close\"", eval(src));
+        assert_eq!(
+            "uncaught exception: end of file reached, no closing ')' found",
+            eval("(read (open-input-string \"(1\"))")
+        );
+
+        // the error object carries the span within the input, along with the input itself
+        let mut interpreter = Interpreter::with_output(std::io::sink());
+        let src = "(guard (e (#t e)) (read (open-input-string \"(a #\\\\nope)\")))";
+        let Value::Error(error) = interpreter.eval_str(src).unwrap() else {
+            panic!("expected an error object");
+        };
+        assert_eq!(ErrorKind::Read, error.kind());
+        assert_eq!(Some((3, 9)), error.span().map(|span| (span.start(), span.end())));
+        assert_eq!(Some("(a #\\nope)"), error.input());
+
+        let Value::Error(error) = interpreter.eval_str("(guard (e (#t e)) (car 1))").unwrap() else {
+            panic!("expected an error object");
+        };
+        assert_eq!(None, error.input());
+    }
+
+    #[test]
+    fn file_errors() {
+        let src = "(guard (e ((file-error? e) (error-object-irritants e))) (open-input-file \"/nonexistent/file.scm\"))";
+        assert_eq!("(\"/nonexistent/file.scm\")", eval(src));
+    }
+}
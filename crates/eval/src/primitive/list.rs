@@ -0,0 +1,161 @@
+//! Pairs and lists of R7RS 6.4
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("pair?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Pair(_)).into())
+    }),
+    Primitive::new("null?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Null).into())
+    }),
+    Primitive::new("list?", Arity::exactly(1), |_, arguments| {
+        Ok(arguments[0].list_elements().is_some().into())
+    }),
+    Primitive::new("cons", Arity::exactly(2), |_, arguments| {
+        Ok(Value::cons(arguments[0].clone(), arguments[1].clone()))
+    }),
+    Primitive::new("car", Arity::exactly(1), |_, arguments| Ok(pair(arguments, 0)?.car())),
+    Primitive::new("cdr", Arity::exactly(1), |_, arguments| Ok(pair(arguments, 0)?.cdr())),
+    Primitive::new("caar", Arity::exactly(1), |_, arguments| path(arguments, &[Pair::car, Pair::car])),
+    Primitive::new("cadr", Arity::exactly(1), |_, arguments| path(arguments, &[Pair::cdr, Pair::car])),
+    Primitive::new("cdar", Arity::exactly(1), |_, arguments| path(arguments, &[Pair::car, Pair::cdr])),
+    Primitive::new("cddr", Arity::exactly(1), |_, arguments| path(arguments, &[Pair::cdr, Pair::cdr])),
+    Primitive::new("set-car!", Arity::exactly(2), |_, arguments| {
+        pair(arguments, 0)?.set_car(arguments[1].clone());
+        Ok(Value::Unspecified)
+    }),
+    Primitive::new("set-cdr!", Arity::exactly(2), |_, arguments| {
+        pair(arguments, 0)?.set_cdr(arguments[1].clone());
+        Ok(Value::Unspecified)
+    }),
+    Primitive::new("list", Arity::at_least(0), |_, arguments| {
+        Ok(Value::list(arguments.iter().cloned()))
+    }),
+    Primitive::new("length", Arity::exactly(1), |_, arguments| {
+        Ok(Value::from(list(arguments, 0)?.len() as i64))
+    }),
+    Primitive::new("append", Arity::at_least(0), append),
+    Primitive::new("reverse", Arity::exactly(1), |_, arguments| {
+        Ok(Value::list(list(arguments, 0)?.into_iter().rev()))
+    }),
+    Primitive::new("list-tail", Arity::exactly(2), |_, arguments| list_tail(arguments)),
+    Primitive::new("list-ref", Arity::exactly(2), |_, arguments| match list_tail(arguments)? {
+        Value::Pair(pair) => Ok(pair.car()),
+        _ => Err(PrimitiveError::IndexOutOfRange),
+    }),
+    Primitive::new("list-copy", Arity::exactly(1), |_, arguments| Ok(list_copy(&arguments[0]))),
+    Primitive::new("memq", Arity::exactly(2), |_, arguments| member(arguments, Value::eqv)),
+    Primitive::new("memv", Arity::exactly(2), |_, arguments| member(arguments, Value::eqv)),
+    Primitive::new("member", Arity::exactly(2), |_, arguments| member(arguments, Value::equal)),
+    Primitive::new("assq", Arity::exactly(2), |_, arguments| association(arguments, Value::eqv)),
+    Primitive::new("assv", Arity::exactly(2), |_, arguments| association(arguments, Value::eqv)),
+    Primitive::new("assoc", Arity::exactly(2), |_, arguments| association(arguments, Value::equal)),
+];
+
+/// Applies the accessors in order, such as `cdr` then `car` for `cadr`.
+fn path(arguments: &[Value], accessors: &[fn(&Pair) -> Value]) -> Result<Value> {
+    let mut value = arguments[0].clone();
+    for accessor in accessors {
+        let Value::Pair(pair) = &value else {
+            return Err(PrimitiveError::WrongType { expected: "a pair", position: 1 });
+        };
+        value = accessor(pair);
+    }
+
+    Ok(value)
+}
+
+fn append(_: &mut Interpreter, arguments: &[Value]) -> Result<Value> {
+    let Some((last, lists)) = arguments.split_last() else {
+        return Ok(Value::Null);
+    };
+
+    let mut elements = Vec::new();
+    for index in 0..lists.len() {
+        elements.extend(list(arguments, index)?);
+    }
+
+    // the last list is shared, and may be any value
+    Ok(Value::list_with_tail(elements, last.clone()))
+}
+
+fn list_tail(arguments: &[Value]) -> Result<Value> {
+    let mut list = arguments[0].clone();
+    for _ in 0..index(arguments, 1)? {
+        list = match list {
+            Value::Pair(pair) => pair.cdr(),
+            _ => return Err(PrimitiveError::IndexOutOfRange),
+        };
+    }
+
+    Ok(list)
+}
+
+/// Copies the pairs of a possibly improper list, other values being returned as is.
+fn list_copy(list: &Value) -> Value {
+    let mut elements = Vec::new();
+    let mut tail = list.clone();
+    while let Value::Pair(pair) = tail {
+        elements.push(pair.car());
+        tail = pair.cdr();
+    }
+
+    Value::list_with_tail(elements, tail)
+}
+
+/// First sublist whose car is equivalent to the first argument, `#f` if none
+fn member(arguments: &[Value], equivalent: fn(&Value, &Value) -> bool) -> Result<Value> {
+    let mut list = arguments[1].clone();
+    loop {
+        list = match list {
+            Value::Pair(ref pair) if equivalent(&arguments[0], &pair.car()) => return Ok(list),
+            Value::Pair(pair) => pair.cdr(),
+            _ => return Ok(Value::Boolean(false)),
+        };
+    }
+}
+
+/// First pair of the association list whose car is equivalent to the first argument, `#f` if none
+fn association(arguments: &[Value], equivalent: fn(&Value, &Value) -> bool) -> Result<Value> {
+    for entry in list(arguments, 1)? {
+        let Value::Pair(pair) = &entry else {
+            return Err(PrimitiveError::WrongType { expected: "an association list", position: 2 });
+        };
+        if equivalent(&arguments[0], &pair.car()) {
+            return Ok(entry);
+        }
+    }
+
+    Ok(Value::Boolean(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn construction() {
+        assert_eq!("(1 2 . 3)", eval("(cons 1 (cons 2 3))"));
+        assert_eq!("(1 2 3 4)", eval("(append '(1) '(2 3) '(4))"));
+        assert_eq!("(1 . 2)", eval("(append '(1) 2)"));
+        assert_eq!("(3 2 1)", eval("(reverse (list 1 2 3))"));
+    }
+
+    #[test]
+    fn access() {
+        assert_eq!("2", eval("(cadr '(1 2 3))"));
+        assert_eq!("3", eval("(list-ref '(1 2 3) 2)"));
+        assert_eq!("3", eval("(length '(1 2 3))"));
+        assert_eq!("(b c)", eval("(memq 'b '(a b c))"));
+        assert_eq!("((2) 3)", eval("(assoc '(2) '(((1) 2) ((2) 3)))"));
+        assert_eq!("(1 9)", eval("(let ((list (list 1 2))) (set-car! (cdr list) 9) list)"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("car: argument 1 must be a pair", eval("(car '())"));
+        assert_eq!("length: argument 1 must be a proper list", eval("(length '(1 . 2))"));
+        assert_eq!("list-ref: index out of range", eval("(list-ref '(1) 1)"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Procedures built into the interpreter, bound as global variables
+
+mod control;
+mod derived;
+mod exception;
+mod input;
+mod list;
+mod numeric;
+mod output;
+mod predicate;
+mod string;
+mod vector;
+
+use std::{cell::RefCell, rc::Rc};
+
+pub(crate) use derived::PARAMETERIZE;
+use pluine_lex::span::Span;
+use pluine_number::{Number, NumberError, Real};
+use thiserror::Error;
+
+use crate::{
+    compile::Globals,
+    machine::{Control, Machine},
+    value::Pair,
+    *,
+};
+
+type Result<T> = std::result::Result<T, PrimitiveError>;
+
+/// Built-in procedure, its arguments being counted by the machine before the call
+pub(crate) struct Primitive {
+    pub(crate) name: &'static str,
+    pub(crate) arity: Arity,
+    pub(crate) function: Function,
+}
+
+pub(crate) enum Function {
+    /// Returns a value to the continuation
+    Value(fn(&mut Interpreter, &[Value]) -> Result<Value>),
+    /// Returns what the machine does next, such as calling a procedure in tail position as with
+    /// `apply`, the machine being given for control features such as `call/cc`
+    Control(fn(&mut Machine, Vec<Value>, Span) -> Result<Control>),
+}
+
+impl Primitive {
+    const fn new(name: &'static str, arity: Arity, function: fn(&mut Interpreter, &[Value]) -> Result<Value>) -> Self {
+        Primitive { name, arity, function: Function::Value(function) }
+    }
+
+    const fn control(name: &'static str, arity: Arity, function: fn(&mut Machine, Vec<Value>, Span) -> Result<Control>) -> Self {
+        Primitive { name, arity, function: Function::Control(function) }
+    }
+}
+
+/// Error raised by a primitive procedure, located at the call by the machine
+#[derive(Debug, PartialEq, Error)]
+pub enum PrimitiveError {
+    /// Argument of the wrong type
+    #[error("argument {position} must be {expected}")]
+    WrongType {
+        /// Expected type, along with its article
+        expected: &'static str,
+        /// One-based argument position
+        position: usize,
+    },
+    /// Index past the end of a string, vector or list
+    #[error("index out of range")]
+    IndexOutOfRange,
+    /// See [`NumberError`]
+    #[error(transparent)]
+    Number(#[from] NumberError),
+    /// Writing to the output failed
+    #[error("output error: {0}")]
+    Output(std::io::ErrorKind),
+}
+
+pub(crate) static MODULES: [&[Primitive]; 10] = [
+    control::PRIMITIVES,
+    derived::PRIMITIVES,
+    exception::PRIMITIVES,
+    input::PRIMITIVES,
+    list::PRIMITIVES,
+    numeric::PRIMITIVES,
+    output::PRIMITIVES,
+    predicate::PRIMITIVES,
+    string::PRIMITIVES,
+    vector::PRIMITIVES,
+];
+
+/// Binds every primitive in `globals`.
+pub(crate) fn define_all(globals: &mut Globals) {
+    for primitive in MODULES.into_iter().flatten() {
+        let global = compile::Global {
+            name: primitive.name.into(),
+            value: RefCell::new(Some(Value::Procedure(primitive.into()))),
+        };
+        globals.insert(primitive.name.into(), Rc::new(global));
+    }
+}
+
+/// Primitive named `name`, called by derived expressions whatever the program binds the name to
+pub(crate) fn builtin(name: &str) -> Value {
+    let primitive = MODULES
+        .into_iter()
+        .flatten()
+        .find(|primitive| primitive.name == name)
+        .expect("derived expressions call existing primitives");
+    Value::Procedure(primitive.into())
+}
+
+/// Argument at `index`, converted by `convert` or reported as not being `expected`.
+fn argument<'a, T>(
+    arguments: &'a [Value],
+    index: usize,
+    expected: &'static str,
+    convert: impl FnOnce(&'a Value) -> Option<T>,
+) -> Result<T> {
+    convert(&arguments[index]).ok_or(PrimitiveError::WrongType { expected, position: index + 1 })
+}
+
+fn number(arguments: &[Value], index: usize) -> Result<&Number> {
+    argument(arguments, index, "a number", |value| match value {
+        Value::Number(number) => Some(number),
+        _ => None,
+    })
+}
+
+fn real(arguments: &[Value], index: usize) -> Result<&Real> {
+    argument(arguments, index, "a real number", |value| match value {
+        Value::Number(Number::Real(real)) => Some(real),
+        _ => None,
+    })
+}
+
+/// Exact non-negative integer fitting in a `usize`, such as an index or a length
+fn index(arguments: &[Value], index: usize) -> Result<usize> {
+    argument(arguments, index, "an exact non-negative integer", |value| match value {
+        Value::Number(Number::Real(Real::Integer(integer))) => usize::try_from(integer).ok(),
+        _ => None,
+    })
+}
+
+fn pair(arguments: &[Value], index: usize) -> Result<&Rc<Pair>> {
+    argument(arguments, index, "a pair", |value| match value {
+        Value::Pair(pair) => Some(pair),
+        _ => None,
+    })
+}
+
+fn list(arguments: &[Value], index: usize) -> Result<Vec<Value>> {
+    argument(arguments, index, "a proper list", Value::list_elements)
+}
+
+fn string(arguments: &[Value], index: usize) -> Result<&Rc<RefCell<String>>> {
+    argument(arguments, index, "a string", |value| match value {
+        Value::String(string) => Some(string),
+        _ => None,
+    })
+}
+
+fn character(arguments: &[Value], index: usize) -> Result<char> {
+    argument(arguments, index, "a character", |value| match value {
+        Value::Character(character) => Some(*character),
+        _ => None,
+    })
+}
+
+fn procedure(arguments: &[Value], index: usize) -> Result<&Procedure> {
+    argument(arguments, index, "a procedure", |value| match value {
+        Value::Procedure(procedure) => Some(procedure),
+        _ => None,
+    })
+}
+
+fn vector(arguments: &[Value], index: usize) -> Result<&Rc<RefCell<Vec<Value>>>> {
+    argument(arguments, index, "a vector", |value| match value {
+        Value::Vector(vector) => Some(vector),
+        _ => None,
+    })
+}
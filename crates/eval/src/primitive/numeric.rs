@@ -0,0 +1,229 @@
+//! Numerical operations of R7RS 6.2.6
+
+use std::cmp::Ordering;
+
+use pluine_number::{Number, Real};
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("number?", Arity::exactly(1), |_, arguments| Ok(is_number(&arguments[0], |_| true))),
+    Primitive::new("complex?", Arity::exactly(1), |_, arguments| Ok(is_number(&arguments[0], |_| true))),
+    Primitive::new("real?", Arity::exactly(1), |_, arguments| {
+        Ok(is_number(&arguments[0], Number::is_real))
+    }),
+    Primitive::new("rational?", Arity::exactly(1), |_, arguments| {
+        Ok(is_number(&arguments[0], Number::is_rational))
+    }),
+    Primitive::new("integer?", Arity::exactly(1), |_, arguments| {
+        Ok(is_number(&arguments[0], Number::is_integer))
+    }),
+    Primitive::new("exact-integer?", Arity::exactly(1), |_, arguments| {
+        Ok(is_number(&arguments[0], Number::is_exact_integer))
+    }),
+    Primitive::new("exact?", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.is_exact().into())
+    }),
+    Primitive::new("inexact?", Arity::exactly(1), |_, arguments| {
+        Ok((!number(arguments, 0)?.is_exact()).into())
+    }),
+    Primitive::new("=", Arity::at_least(1), equal),
+    Primitive::new("<", Arity::at_least(1), |_, arguments| {
+        compare(arguments, |left, right| left < right)
+    }),
+    Primitive::new(">", Arity::at_least(1), |_, arguments| {
+        compare(arguments, |left, right| left > right)
+    }),
+    Primitive::new("<=", Arity::at_least(1), |_, arguments| {
+        compare(arguments, |left, right| left <= right)
+    }),
+    Primitive::new(">=", Arity::at_least(1), |_, arguments| {
+        compare(arguments, |left, right| left >= right)
+    }),
+    Primitive::new("zero?", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.is_zero().into())
+    }),
+    Primitive::new("positive?", Arity::exactly(1), |_, arguments| {
+        Ok((real(arguments, 0)?.signum() == Some(Ordering::Greater)).into())
+    }),
+    Primitive::new("negative?", Arity::exactly(1), |_, arguments| {
+        Ok((real(arguments, 0)?.signum() == Some(Ordering::Less)).into())
+    }),
+    Primitive::new("odd?", Arity::exactly(1), |_, arguments| {
+        Ok((!is_even(number(arguments, 0)?)?).into())
+    }),
+    Primitive::new(
+        "even?",
+        Arity::exactly(1),
+        |_, arguments| Ok(is_even(number(arguments, 0)?)?.into()),
+    ),
+    Primitive::new("max", Arity::at_least(1), |_, arguments| extremum(arguments, Real::max)),
+    Primitive::new("min", Arity::at_least(1), |_, arguments| extremum(arguments, Real::min)),
+    Primitive::new("+", Arity::at_least(0), |_, arguments| {
+        fold(arguments, Number::from(0), |left, right| left + right)
+    }),
+    Primitive::new("*", Arity::at_least(0), |_, arguments| {
+        fold(arguments, Number::from(1), |left, right| left * right)
+    }),
+    Primitive::new("-", Arity::at_least(1), |_, arguments| match arguments.len() {
+        // negated rather than subtracted from zero, keeping the sign of inexact zeros
+        1 => Ok((-number(arguments, 0)?).into()),
+        _ => inverse_fold(arguments, Number::from(0), |left, right| Ok(left - right)),
+    }),
+    Primitive::new("/", Arity::at_least(1), |_, arguments| {
+        inverse_fold(arguments, Number::from(1), Number::divide)
+    }),
+    Primitive::new("abs", Arity::exactly(1), |_, arguments| {
+        Ok(Number::Real(real(arguments, 0)?.abs()).into())
+    }),
+    Primitive::new("quotient", Arity::exactly(2), |_, arguments| {
+        Ok(number(arguments, 0)?.truncate_divide(number(arguments, 1)?)?.0.into())
+    }),
+    Primitive::new("remainder", Arity::exactly(2), |_, arguments| {
+        Ok(number(arguments, 0)?.truncate_divide(number(arguments, 1)?)?.1.into())
+    }),
+    Primitive::new("modulo", Arity::exactly(2), |_, arguments| {
+        Ok(number(arguments, 0)?.floor_divide(number(arguments, 1)?)?.1.into())
+    }),
+    Primitive::new("floor", Arity::exactly(1), |_, arguments| Ok(number(arguments, 0)?.floor()?.into())),
+    Primitive::new("ceiling", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.ceiling()?.into())
+    }),
+    Primitive::new("truncate", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.truncate()?.into())
+    }),
+    Primitive::new("round", Arity::exactly(1), |_, arguments| Ok(number(arguments, 0)?.round()?.into())),
+    Primitive::new("sqrt", Arity::exactly(1), |_, arguments| Ok(number(arguments, 0)?.sqrt().into())),
+    Primitive::new("expt", Arity::exactly(2), |_, arguments| {
+        Ok(number(arguments, 0)?.expt(number(arguments, 1)?)?.into())
+    }),
+    Primitive::new("exact", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.to_exact()?.into())
+    }),
+    Primitive::new("inexact", Arity::exactly(1), |_, arguments| {
+        Ok(number(arguments, 0)?.to_inexact().into())
+    }),
+    Primitive::new("number->string", Arity::between(1, 2), number_to_string),
+    Primitive::new("string->number", Arity::between(1, 2), string_to_number),
+];
+
+fn is_number(value: &Value, predicate: fn(&Number) -> bool) -> Value {
+    matches!(value, Value::Number(number) if predicate(number)).into()
+}
+
+fn is_even(number: &Number) -> Result<bool> {
+    Ok(number.truncate_divide(&Number::from(2))?.1.is_zero())
+}
+
+fn equal(_: &mut Interpreter, arguments: &[Value]) -> Result<Value> {
+    let numbers = (0..arguments.len())
+        .map(|index| number(arguments, index))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(numbers.windows(2).all(|pair| pair[0] == pair[1]).into())
+}
+
+/// Whether `holds` for every consecutive reals
+fn compare(arguments: &[Value], holds: fn(&Real, &Real) -> bool) -> Result<Value> {
+    let reals = (0..arguments.len())
+        .map(|index| real(arguments, index))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(reals.windows(2).all(|pair| holds(pair[0], pair[1])).into())
+}
+
+fn extremum(arguments: &[Value], select: fn(&Real, &Real) -> Real) -> Result<Value> {
+    let mut extremum = real(arguments, 0)?.clone();
+    for index in 1..arguments.len() {
+        extremum = select(&extremum, real(arguments, index)?);
+    }
+
+    Ok(Number::Real(extremum).into())
+}
+
+fn fold(arguments: &[Value], initial: Number, operation: fn(&Number, &Number) -> Number) -> Result<Value> {
+    let mut result = initial;
+    for index in 0..arguments.len() {
+        result = operation(&result, number(arguments, index)?);
+    }
+
+    Ok(result.into())
+}
+
+/// `(- z)` and `(/ z)` apply `operation` to the identity, the other arguments being folded into
+/// the first.
+fn inverse_fold(
+    arguments: &[Value],
+    identity: Number,
+    operation: impl Fn(&Number, &Number) -> std::result::Result<Number, NumberError>,
+) -> Result<Value> {
+    if arguments.len() == 1 {
+        return Ok(operation(&identity, number(arguments, 0)?)?.into());
+    }
+
+    let mut result = number(arguments, 0)?.clone();
+    for index in 1..arguments.len() {
+        result = operation(&result, number(arguments, index)?)?;
+    }
+
+    Ok(result.into())
+}
+
+fn radix(arguments: &[Value], index: usize) -> Result<u32> {
+    match arguments.get(index) {
+        None => Ok(10),
+        Some(_) => argument(arguments, index, "a radix of 2, 8, 10 or 16", |value| match value {
+            Value::Number(Number::Real(Real::Integer(integer))) => {
+                u32::try_from(integer).ok().filter(|radix| matches!(radix, 2 | 8 | 10 | 16))
+            }
+            _ => None,
+        }),
+    }
+}
+
+fn number_to_string(_: &mut Interpreter, arguments: &[Value]) -> Result<Value> {
+    let string = number(arguments, 0)?.to_string_radix(radix(arguments, 1)?)?;
+    Ok(Value::string(&string))
+}
+
+fn string_to_number(_: &mut Interpreter, arguments: &[Value]) -> Result<Value> {
+    let number = Number::parse(&string(arguments, 0)?.borrow(), radix(arguments, 1)?);
+    Ok(number.map_or(Value::Boolean(false), Value::Number))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!("6", eval("(+ 1 2 3)"));
+        assert_eq!("0", eval("(+)"));
+        assert_eq!("-1", eval("(- 1)"));
+        assert_eq!("-0.0", eval("(- 0.0)"));
+        assert_eq!("0.0", eval("(- 0.0 0.0)"));
+        assert_eq!("1/2", eval("(/ 2)"));
+        assert_eq!("1.5", eval("(/ 6 2 2.0)"));
+        assert_eq!("-1", eval("(modulo 5 -3)"));
+        assert_eq!("2", eval("(remainder 5 -3)"));
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!("#t", eval("(< 1 2 3)"));
+        assert_eq!("#f", eval("(< 1 3 2)"));
+        assert_eq!("#t", eval("(= 1 1.0 2/2)"));
+        assert_eq!("2.0", eval("(max 1 2.0)"));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!("\"ff\"", eval("(number->string 255 16)"));
+        assert_eq!("255", eval("(string->number \"#xff\")"));
+        assert_eq!("#f", eval("(string->number \"1a\")"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("/: division by exact zero", eval("(/ 1 0)"));
+        assert_eq!("<: argument 2 must be a real number", eval("(< 1 'a)"));
+    }
+}
@@ -0,0 +1,41 @@
+//! Output of R7RS 6.13.3, to the output of the interpreter
+
+use std::io::Write;
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("display", Arity::exactly(1), |interpreter, arguments| {
+        output(interpreter, format_args!("{}", arguments[0]))
+    }),
+    Primitive::new("write", Arity::exactly(1), |interpreter, arguments| {
+        output(interpreter, format_args!("{}", arguments[0].written()))
+    }),
+    Primitive::new("newline", Arity::exactly(0), |interpreter, _| {
+        output(interpreter, format_args!("\n"))
+    }),
+];
+
+fn output(interpreter: &mut Interpreter, arguments: std::fmt::Arguments) -> Result<Value> {
+    interpreter
+        .output
+        .write_fmt(arguments)
+        .map_err(|error| PrimitiveError::Output(error.kind()))?;
+    Ok(Value::Unspecified)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::output;
+
+    #[test]
+    fn display_and_write() {
+        assert_eq!(
+            "(a \"b\" #\\c)\nb c",
+            output(r#"(write '(a "b" #\c)) (newline) (display "b") (display #\space) (display 'c)"#)
+        );
+
+        let src = r#"(define x (list "a" 2)) (set-cdr! (cdr x) x) (write x) (display x)"#;
+        assert_eq!(r#"#0=("a" 2 . #0#)#0=(a 2 . #0#)"#, output(src));
+    }
+}
@@ -0,0 +1,60 @@
+//! Equivalence predicates of R7RS 6.1, along with the type predicates of booleans and procedures
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("eq?", Arity::exactly(2), |_, arguments| Ok(arguments[0].eqv(&arguments[1]).into())),
+    Primitive::new("eqv?", Arity::exactly(2), |_, arguments| Ok(arguments[0].eqv(&arguments[1]).into())),
+    Primitive::new("equal?", Arity::exactly(2), |_, arguments| {
+        Ok(arguments[0].equal(&arguments[1]).into())
+    }),
+    Primitive::new("not", Arity::exactly(1), |_, arguments| Ok((!arguments[0].is_true()).into())),
+    Primitive::new("boolean?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Boolean(_)).into())
+    }),
+    Primitive::new("boolean=?", Arity::at_least(2), boolean_equal),
+    Primitive::new("procedure?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Procedure(_)).into())
+    }),
+];
+
+fn boolean_equal(_: &mut Interpreter, arguments: &[Value]) -> Result<Value> {
+    let booleans = (0..arguments.len())
+        .map(|index| {
+            argument(arguments, index, "a boolean", |value| match value {
+                Value::Boolean(boolean) => Some(*boolean),
+                _ => None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(booleans.windows(2).all(|pair| pair[0] == pair[1]).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn equivalence() {
+        assert_eq!("#t", eval("(eq? 'a 'a)"));
+        assert_eq!("#f", eval("(eqv? \"a\" \"a\")"));
+        assert_eq!("#t", eval("(equal? '(1 #(2 \"a\")) (list 1 (vector 2 \"a\")))"));
+        assert_eq!("#t", eval("(let ((p (lambda (x) x))) (eqv? p p))"));
+        assert_eq!("#f", eval("(eqv? 2 2.0)"));
+
+        // compared and dropped without recursing on either side of the pairs
+        let src = "
+            (define (nest n) (let loop ((i 0) (x '())) (if (= i n) x (loop (+ i 1) (cons x '())))))
+            (equal? (nest 100000) (nest 100000))";
+        assert_eq!("#t", eval(src));
+    }
+
+    #[test]
+    fn booleans() {
+        assert_eq!("#f", eval("(not 0)"));
+        assert_eq!("#t", eval("(not #f)"));
+        assert_eq!("#t", eval("(boolean=? #f #f #f)"));
+        assert_eq!("boolean=?: argument 2 must be a boolean", eval("(boolean=? #f 0)"));
+    }
+}
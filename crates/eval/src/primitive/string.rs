@@ -0,0 +1,108 @@
+//! Symbols, characters and strings of R7RS 6.5 to 6.7
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("symbol?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Symbol(_)).into())
+    }),
+    Primitive::new("symbol->string", Arity::exactly(1), |_, arguments| {
+        let name = argument(arguments, 0, "a symbol", |value| match value {
+            Value::Symbol(name) => Some(name),
+            _ => None,
+        })?;
+        Ok(Value::string(name))
+    }),
+    Primitive::new("string->symbol", Arity::exactly(1), |_, arguments| {
+        Ok(Value::symbol(&string(arguments, 0)?.borrow()))
+    }),
+    Primitive::new("char?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Character(_)).into())
+    }),
+    Primitive::new("char->integer", Arity::exactly(1), |_, arguments| {
+        Ok(Value::from(i64::from(u32::from(character(arguments, 0)?))))
+    }),
+    Primitive::new("integer->char", Arity::exactly(1), |_, arguments| {
+        let scalar = argument(arguments, 0, "a Unicode scalar value", |value| match value {
+            Value::Number(Number::Real(Real::Integer(integer))) => u32::try_from(integer).ok().and_then(char::from_u32),
+            _ => None,
+        })?;
+        Ok(Value::Character(scalar))
+    }),
+    Primitive::new("char=?", Arity::at_least(1), |_, arguments| {
+        let characters = (0..arguments.len())
+            .map(|index| character(arguments, index))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(characters.windows(2).all(|pair| pair[0] == pair[1]).into())
+    }),
+    Primitive::new("string?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::String(_)).into())
+    }),
+    Primitive::new("string-length", Arity::exactly(1), |_, arguments| {
+        Ok(Value::from(string(arguments, 0)?.borrow().chars().count() as i64))
+    }),
+    Primitive::new("string-ref", Arity::exactly(2), |_, arguments| {
+        let character = string(arguments, 0)?.borrow().chars().nth(index(arguments, 1)?);
+        character.map(Value::Character).ok_or(PrimitiveError::IndexOutOfRange)
+    }),
+    Primitive::new("substring", Arity::exactly(3), |_, arguments| {
+        let (start, end) = (index(arguments, 1)?, index(arguments, 2)?);
+        let string = string(arguments, 0)?.borrow();
+        if start > end || end > string.chars().count() {
+            return Err(PrimitiveError::IndexOutOfRange);
+        }
+        Ok(Value::string(&string.chars().skip(start).take(end - start).collect::<String>()))
+    }),
+    Primitive::new("string-append", Arity::at_least(0), |_, arguments| {
+        let mut appended = String::new();
+        for index in 0..arguments.len() {
+            appended.push_str(&string(arguments, index)?.borrow());
+        }
+        Ok(Value::string(&appended))
+    }),
+    Primitive::new("string-copy", Arity::exactly(1), |_, arguments| {
+        Ok(Value::string(&string(arguments, 0)?.borrow()))
+    }),
+    Primitive::new("string=?", Arity::at_least(1), |_, arguments| {
+        let strings = (0..arguments.len())
+            .map(|index| string(arguments, index))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(strings.windows(2).all(|pair| *pair[0].borrow() == *pair[1].borrow()).into())
+    }),
+    Primitive::new("string->list", Arity::exactly(1), |_, arguments| {
+        Ok(Value::list(string(arguments, 0)?.borrow().chars().map(Value::Character)))
+    }),
+    Primitive::new("list->string", Arity::exactly(1), |_, arguments| {
+        let characters = list(arguments, 0)?
+            .iter()
+            .map(|element| match element {
+                Value::Character(character) => Ok(*character),
+                _ => Err(PrimitiveError::WrongType { expected: "a list of characters", position: 1 }),
+            })
+            .collect::<Result<String>>()?;
+        Ok(Value::string(&characters))
+    }),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn symbols_and_characters() {
+        assert_eq!("\"abc\"", eval("(symbol->string 'abc)"));
+        assert_eq!("#t", eval("(eq? 'abc (string->symbol \"abc\"))"));
+        assert_eq!("955", eval("(char->integer #\\λ)"));
+        assert_eq!("#\\a", eval("(integer->char 97)"));
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!("3", eval("(string-length \"aλb\")"));
+        assert_eq!("#\\λ", eval("(string-ref \"aλb\" 1)"));
+        assert_eq!("\"λb\"", eval("(substring \"aλb\" 1 3)"));
+        assert_eq!("\"ab\"", eval("(string-append \"a\" \"\" \"b\")"));
+        assert_eq!("\"ab\"", eval("(list->string (string->list \"ab\"))"));
+        assert_eq!("substring: index out of range", eval("(substring \"a\" 0 2)"));
+    }
+}
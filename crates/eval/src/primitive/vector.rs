@@ -0,0 +1,45 @@
+//! Vectors of R7RS 6.8
+
+use super::*;
+
+pub(super) static PRIMITIVES: &[Primitive] = &[
+    Primitive::new("vector?", Arity::exactly(1), |_, arguments| {
+        Ok(matches!(arguments[0], Value::Vector(_)).into())
+    }),
+    Primitive::new("make-vector", Arity::between(1, 2), |_, arguments| {
+        let fill = arguments.get(1).cloned().unwrap_or(Value::Unspecified);
+        Ok(Value::vector(vec![fill; index(arguments, 0)?]))
+    }),
+    Primitive::new("vector", Arity::at_least(0), |_, arguments| Ok(Value::vector(arguments.to_vec()))),
+    Primitive::new("vector-length", Arity::exactly(1), |_, arguments| {
+        Ok(Value::from(vector(arguments, 0)?.borrow().len() as i64))
+    }),
+    Primitive::new("vector-ref", Arity::exactly(2), |_, arguments| {
+        let vector = vector(arguments, 0)?.borrow();
+        vector.get(index(arguments, 1)?).cloned().ok_or(PrimitiveError::IndexOutOfRange)
+    }),
+    Primitive::new("vector-set!", Arity::exactly(3), |_, arguments| {
+        let mut vector = vector(arguments, 0)?.borrow_mut();
+        *vector.get_mut(index(arguments, 1)?).ok_or(PrimitiveError::IndexOutOfRange)? = arguments[2].clone();
+        Ok(Value::Unspecified)
+    }),
+    Primitive::new("vector->list", Arity::exactly(1), |_, arguments| {
+        Ok(Value::list(vector(arguments, 0)?.borrow().iter().cloned()))
+    }),
+    Primitive::new("list->vector", Arity::exactly(1), |_, arguments| {
+        Ok(Value::vector(list(arguments, 0)?))
+    }),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn vectors() {
+        assert_eq!("#(0 0)", eval("(make-vector 2 0)"));
+        assert_eq!("#(1 x)", eval("(let ((v (vector 1 2))) (vector-set! v 1 'x) v)"));
+        assert_eq!("(1 2)", eval("(vector->list (list->vector '(1 2)))"));
+        assert_eq!("vector-ref: index out of range", eval("(vector-ref #(1) 1)"));
+    }
+}
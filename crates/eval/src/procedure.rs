@@ -0,0 +1,150 @@
+use std::{
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+use crate::{
+    compile::{CaseLambda, Lambda},
+    machine::{Environment, Machine},
+    primitive::Primitive,
+    record::RecordProcedure,
+    Value,
+};
+
+/// Callable value, either built into the interpreter, created by a `lambda` or `case-lambda`
+/// expression, captured by `call/cc`, made by `make-parameter` or defined by `define-record-type`
+#[derive(Clone)]
+pub struct Procedure(pub(crate) ProcedureKind);
+
+#[derive(Clone)]
+pub(crate) enum ProcedureKind {
+    Primitive(&'static Primitive),
+    Closure(Rc<Closure>),
+    CaseClosure(Rc<CaseClosure>),
+    /// State of the machine to restore when called
+    Continuation(Rc<Machine>),
+    Parameter(Rc<Parameter>),
+    Record(Rc<RecordProcedure>),
+}
+
+/// Procedure created by a `lambda` expression, along with the environment it was evaluated in
+pub(crate) struct Closure {
+    pub(crate) lambda: Rc<Lambda>,
+    pub(crate) environment: Option<Rc<Environment>>,
+}
+
+/// Procedure created by a `case-lambda` expression, its clauses sharing the environment
+pub(crate) struct CaseClosure {
+    pub(crate) case_lambda: Rc<CaseLambda>,
+    pub(crate) environment: Option<Rc<Environment>>,
+}
+
+/// Parameter object of R7RS 4.2.6, returning its value when called without arguments
+pub(crate) struct Parameter {
+    /// Value outside of any `parameterize`, already converted
+    pub(crate) value: Value,
+    /// Procedure converting the values given by `parameterize`, if any
+    pub(crate) converter: Option<Value>,
+}
+
+impl Procedure {
+    /// Name of primitives, record procedures and procedures bound by `define`, if any
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            ProcedureKind::Primitive(primitive) => Some(primitive.name),
+            ProcedureKind::Closure(closure) => closure.lambda.name.as_deref(),
+            ProcedureKind::CaseClosure(closure) => closure.case_lambda.name.as_deref(),
+            ProcedureKind::Record(procedure) => Some(&procedure.name),
+            ProcedureKind::Continuation(_) | ProcedureKind::Parameter(_) => None,
+        }
+    }
+
+    /// See [`Arity`]
+    pub fn arity(&self) -> Arity {
+        match &self.0 {
+            ProcedureKind::Primitive(primitive) => primitive.arity,
+            ProcedureKind::Closure(closure) => closure.lambda.arity,
+            ProcedureKind::CaseClosure(closure) => closure.case_lambda.arity,
+            // the values returned
+            ProcedureKind::Continuation(_) => Arity::at_least(0),
+            ProcedureKind::Parameter(_) => Arity::exactly(0),
+            ProcedureKind::Record(procedure) => procedure.arity(),
+        }
+    }
+
+    /// Whether both are the same procedure, as by `eqv?`
+    pub(crate) fn ptr_eq(&self, other: &Procedure) -> bool {
+        match (&self.0, &other.0) {
+            (ProcedureKind::Primitive(left), ProcedureKind::Primitive(right)) => std::ptr::eq(*left, *right),
+            (ProcedureKind::Closure(left), ProcedureKind::Closure(right)) => Rc::ptr_eq(left, right),
+            (ProcedureKind::CaseClosure(left), ProcedureKind::CaseClosure(right)) => Rc::ptr_eq(left, right),
+            (ProcedureKind::Continuation(left), ProcedureKind::Continuation(right)) => Rc::ptr_eq(left, right),
+            (ProcedureKind::Parameter(left), ProcedureKind::Parameter(right)) => Rc::ptr_eq(left, right),
+            (ProcedureKind::Record(left), ProcedureKind::Record(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl From<&'static Primitive> for Procedure {
+    fn from(primitive: &'static Primitive) -> Self {
+        Procedure(ProcedureKind::Primitive(primitive))
+    }
+}
+
+/// Number of arguments accepted by a procedure
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Arity {
+    /// Required arguments
+    pub min: usize,
+    /// Maximum number of arguments, `None` if the procedure takes any number of additional
+    /// arguments
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    /// Exactly `count` arguments
+    pub const fn exactly(count: usize) -> Self {
+        Arity { min: count, max: Some(count) }
+    }
+
+    /// `min` arguments or more
+    pub const fn at_least(min: usize) -> Self {
+        Arity { min, max: None }
+    }
+
+    /// Between `min` and `max` arguments, both inclusive
+    pub const fn between(min: usize, max: usize) -> Self {
+        Arity { min, max: Some(max) }
+    }
+
+    /// Whether `count` arguments are accepted
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(formatter, "{max}"),
+            Some(max) => write!(formatter, "{} to {max}", self.min),
+            None => write!(formatter, "at least {}", self.min),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arity() {
+        assert!(Arity::exactly(2).accepts(2));
+        assert!(!Arity::exactly(2).accepts(3));
+        assert!(Arity::at_least(1).accepts(10));
+        assert!(!Arity::between(1, 2).accepts(0));
+        assert_eq!("1 to 2", Arity::between(1, 2).to_string());
+        assert_eq!("at least 0", Arity::at_least(0).to_string());
+    }
+}
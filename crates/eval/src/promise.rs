@@ -0,0 +1,43 @@
+//! Promises of R7RS 4.2.5
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::*;
+
+/// Value of `delay`, `delay-force` and `make-promise`, computed at most once by `force`
+///
+/// Forcing a promise made by `delay-force` makes it share the state of the promise its expression
+/// returned, so that forcing a chain of them, as with lazy streams, runs in constant space.
+pub struct Promise(RefCell<Rc<RefCell<State>>>);
+
+#[derive(Clone)]
+pub(crate) enum State {
+    Done(Value),
+    /// Thunk evaluating the delayed expression, a promise to force in turn if `chained`
+    Pending {
+        thunk: Value,
+        chained: bool,
+    },
+}
+
+impl Promise {
+    pub(crate) fn new(state: State) -> Self {
+        Promise(RefCell::new(Rc::new(RefCell::new(state))))
+    }
+
+    pub(crate) fn state(&self) -> State {
+        self.0.borrow().borrow().clone()
+    }
+
+    pub(crate) fn resolve(&self, value: Value) {
+        *self.0.borrow().borrow_mut() = State::Done(value);
+    }
+
+    /// Takes over the state of `other`, which then shares the state of `self`, see
+    /// `promise-update!` in R7RS 7.3.
+    pub(crate) fn chain(&self, other: &Promise) {
+        let state = other.state();
+        *self.0.borrow().borrow_mut() = state;
+        *other.0.borrow_mut() = self.0.borrow().clone();
+    }
+}
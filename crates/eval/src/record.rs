@@ -0,0 +1,148 @@
+//! Record types of R7RS 5.5
+
+use std::{cell::RefCell, rc::Rc};
+
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// Type defined by `define-record-type`, distinct from every other type
+pub struct RecordType {
+    pub(crate) name: Rc<str>,
+    pub(crate) fields: Vec<Rc<str>>,
+}
+
+/// Instance of a record type, made by its constructor
+pub struct Record {
+    pub(crate) record_type: Rc<RecordType>,
+    /// One value per field of the type, unspecified unless initialized by the constructor
+    pub(crate) fields: RefCell<Vec<Value>>,
+}
+
+/// Procedure defined along with a record type
+pub(crate) struct RecordProcedure {
+    pub(crate) name: Rc<str>,
+    pub(crate) record_type: Rc<RecordType>,
+    pub(crate) kind: RecordProcedureKind,
+}
+
+pub(crate) enum RecordProcedureKind {
+    /// Index of the field initialized by each argument
+    Constructor(Vec<usize>),
+    Predicate,
+    /// Index of the field
+    Accessor(usize),
+    /// Index of the field
+    Modifier(usize),
+}
+
+impl RecordType {
+    /// Name the type is defined with, such as `<point>`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Record {
+    /// See [`RecordType`]
+    pub fn record_type(&self) -> &RecordType {
+        &self.record_type
+    }
+}
+
+impl RecordProcedure {
+    pub(crate) fn arity(&self) -> Arity {
+        match &self.kind {
+            RecordProcedureKind::Constructor(fields) => Arity::exactly(fields.len()),
+            RecordProcedureKind::Predicate | RecordProcedureKind::Accessor(_) => Arity::exactly(1),
+            RecordProcedureKind::Modifier(_) => Arity::exactly(2),
+        }
+    }
+
+    /// Calls the procedure with arguments it accepts, as checked by the machine.
+    pub(crate) fn call(&self, mut arguments: Vec<Value>, span: Span) -> Result<Value, EvalError> {
+        let record = match (&self.kind, &arguments[..]) {
+            (RecordProcedureKind::Constructor(indexes), _) => {
+                let mut fields = vec![Value::Unspecified; self.record_type.fields.len()];
+                for (index, argument) in indexes.iter().zip(arguments) {
+                    fields[*index] = argument;
+                }
+                let record = Record { record_type: self.record_type.clone(), fields: RefCell::new(fields) };
+                return Ok(Value::Record(Rc::new(record)));
+            }
+            (RecordProcedureKind::Predicate, [value]) => {
+                let is_instance = matches!(value, Value::Record(record) if Rc::ptr_eq(&record.record_type, &self.record_type));
+                return Ok(is_instance.into());
+            }
+            (_, [Value::Record(record), ..]) if Rc::ptr_eq(&record.record_type, &self.record_type) => record.clone(),
+            _ => {
+                return Err(EvalError::NotRecord {
+                    procedure: self.name.as_ref().into(),
+                    record_type: self.record_type.name.as_ref().into(),
+                    span,
+                })
+            }
+        };
+
+        Ok(match self.kind {
+            RecordProcedureKind::Accessor(index) => record.fields.borrow()[index].clone(),
+            RecordProcedureKind::Modifier(index) => {
+                record.fields.borrow_mut()[index] = arguments.pop().expect("two arguments");
+                Value::Unspecified
+            }
+            RecordProcedureKind::Constructor(_) | RecordProcedureKind::Predicate => unreachable!("returned above"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::tests::eval;
+
+    #[test]
+    fn records() {
+        let definition = "
+            (define-record-type <pare>
+              (kons x y)
+              pare?
+              (x kar set-kar!)
+              (y kdr))";
+        assert_eq!(
+            "(#t #f 2 3)",
+            eval(&format!(
+                "{definition} (define p (kons 1 2)) (set-kar! p 3) (list (pare? p) (pare? 1) (kdr (kons 1 2)) (kar p))"
+            ))
+        );
+        assert_eq!("#<record <pare>>", eval(&format!("{definition} (kons 1 2)")));
+        assert_eq!("#<record-type <pare>>", eval(&format!("{definition} <pare>")));
+        assert_eq!("#<procedure kar>", eval(&format!("{definition} kar")));
+        assert_eq!(
+            "kar: argument 1 must be a record of type `<pare>`",
+            eval(&format!("{definition} (kar '(1 . 2))"))
+        );
+        assert_eq!(
+            "wrong number of arguments, expected 2 but got 1",
+            eval(&format!("{definition} (kons 1)"))
+        );
+
+        // each definition makes a distinct type, fields left out of the constructor are unspecified
+        let src = "
+            (define-record-type point (make-point y) point? (x point-x) (y point-y))
+            (define p (make-point 2))
+            (define-record-type point (make-point x y) point? (x point-x) (y point-y))
+            (list (point? p) (point-x (make-point 1 2)) (eqv? p p))";
+        assert_eq!("(#f 1 #t)", eval(src));
+        assert_eq!(
+            "#<unspecified>",
+            eval("(define-record-type point (make-point y) point? (x point-x) (y point-y)) (point-x (make-point 2))")
+        );
+
+        // internal definitions
+        let src = "
+            (define (f)
+              (define-record-type node #f node? (value node-value))
+              (node? 1))
+            (f)";
+        assert_eq!("#f", eval(src));
+    }
+}
@@ -0,0 +1,395 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use pluine_common::{Datum, DatumKind, ParseError};
+use pluine_lex::span::Spanned;
+use pluine_number::Number;
+use pluine_parser::{DatumGraph, Node, SimpleDatum};
+
+use crate::*;
+
+/// Scheme value, see R7RS 3.2
+///
+/// Strings, pairs, vectors, bytevectors and records are mutable objects compared by identity with
+/// `eqv?`, cloning a value cloning the reference to them.
+#[derive(Clone)]
+pub enum Value {
+    /// Value of expressions R7RS leaves unspecified, such as `(if #f #f)`
+    Unspecified,
+    /// `#t` or `#f`
+    Boolean(bool),
+    /// See [`Number`]
+    Number(Number),
+    /// Unicode scalar value
+    Character(char),
+    /// Mutable string
+    String(Rc<RefCell<String>>),
+    /// Symbols of the same name being `eqv?`
+    Symbol(Rc<str>),
+    /// Empty list `()`
+    Null,
+    /// See [`Pair`]
+    Pair(Rc<Pair>),
+    /// Mutable vector
+    Vector(Rc<RefCell<Vec<Value>>>),
+    /// Mutable bytevector
+    ByteVector(Rc<RefCell<Vec<u8>>>),
+    /// See [`Procedure`]
+    Procedure(Procedure),
+    /// See [`ErrorObject`]
+    Error(Rc<ErrorObject>),
+    /// See [`InputPort`]
+    InputPort(Rc<InputPort>),
+    /// See [`Promise`]
+    Promise(Rc<Promise>),
+    /// See [`Record`]
+    Record(Rc<Record>),
+    /// See [`RecordType`]
+    RecordType(Rc<RecordType>),
+    /// Returned by `read` at the end of the input
+    Eof,
+    /// Zero or several values returned by `values`, a single value being returned as itself
+    Values(Rc<[Value]>),
+}
+
+/// Mutable pair, the building block of lists
+pub struct Pair {
+    car: RefCell<Value>,
+    cdr: RefCell<Value>,
+}
+
+impl Pair {
+    /// R7RS `car`
+    pub fn car(&self) -> Value {
+        self.car.borrow().clone()
+    }
+
+    /// R7RS `cdr`
+    pub fn cdr(&self) -> Value {
+        self.cdr.borrow().clone()
+    }
+
+    /// R7RS `set-car!`
+    pub fn set_car(&self, value: Value) {
+        *self.car.borrow_mut() = value;
+    }
+
+    /// R7RS `set-cdr!`
+    pub fn set_cdr(&self, value: Value) {
+        *self.cdr.borrow_mut() = value;
+    }
+}
+
+/// Pairs are unlinked iteratively, as the default recursive drop would overflow the stack on long
+/// lists, or on lists nested deeply through their `car`.
+impl Drop for Pair {
+    fn drop(&mut self) {
+        // pairs still referenced elsewhere are left alone
+        fn unlink(value: &mut Value, pending: &mut Vec<Value>) {
+            if matches!(value, Value::Pair(pair) if Rc::strong_count(pair) == 1) {
+                pending.push(std::mem::replace(value, Value::Null));
+            }
+        }
+
+        let mut pending = Vec::new();
+        unlink(self.car.get_mut(), &mut pending);
+        unlink(self.cdr.get_mut(), &mut pending);
+
+        while let Some(Value::Pair(pair)) = pending.pop() {
+            if let Ok(mut pair) = Rc::try_unwrap(pair) {
+                unlink(pair.car.get_mut(), &mut pending);
+                unlink(pair.cdr.get_mut(), &mut pending);
+            }
+        }
+    }
+}
+
+impl Value {
+    /// R7RS `cons`
+    pub fn cons(car: Value, cdr: Value) -> Value {
+        Value::Pair(Rc::new(Pair { car: RefCell::new(car), cdr: RefCell::new(cdr) }))
+    }
+
+    /// Proper list of `elements`
+    pub fn list(elements: impl IntoIterator<Item = Value>) -> Value {
+        Value::list_with_tail(elements, Value::Null)
+    }
+
+    /// List of `elements` ending with `tail`, improper unless `tail` is a list.
+    pub fn list_with_tail(elements: impl IntoIterator<Item = Value>, tail: Value) -> Value {
+        let elements = elements.into_iter().collect::<Vec<_>>();
+        elements.into_iter().rev().fold(tail, |list, element| Value::cons(element, list))
+    }
+
+    /// New mutable string
+    pub fn string(string: &str) -> Value {
+        Value::String(Rc::new(RefCell::new(string.to_string())))
+    }
+
+    /// Symbol named `name`
+    pub fn symbol(name: &str) -> Value {
+        Value::Symbol(name.into())
+    }
+
+    /// New mutable vector
+    pub fn vector(elements: Vec<Value>) -> Value {
+        Value::Vector(Rc::new(RefCell::new(elements)))
+    }
+
+    /// Values returned together, as by R7RS `values`
+    pub fn values(mut values: Vec<Value>) -> Value {
+        match values.len() {
+            1 => values.pop().expect("one value"),
+            _ => Value::Values(values.into()),
+        }
+    }
+
+    /// Values returned together, a single one unless returned by `values`
+    pub(crate) fn into_values(self) -> Vec<Value> {
+        match self {
+            Value::Values(values) => values.to_vec(),
+            value => vec![value],
+        }
+    }
+
+    /// Every value but `#f` counts as true in conditionals.
+    pub fn is_true(&self) -> bool {
+        !matches!(self, Value::Boolean(false))
+    }
+
+    /// Elements of a proper list, `None` for improper and circular lists.
+    pub fn list_elements(&self) -> Option<Vec<Value>> {
+        let mut elements = Vec::new();
+        let mut list = self.clone();
+        // advanced every other element, meeting `list` only if the list is circular
+        let mut slow = self.clone();
+
+        loop {
+            match list {
+                Value::Null => return Some(elements),
+                Value::Pair(pair) => {
+                    elements.push(pair.car());
+                    list = pair.cdr();
+                }
+                _ => return None,
+            }
+
+            if elements.len() % 2 == 0 {
+                let Value::Pair(pair) = slow else {
+                    unreachable!("slow trails behind list")
+                };
+                slow = pair.cdr();
+                if list.eqv(&slow) && matches!(list, Value::Pair(_)) {
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// R7RS `eqv?`, numbers being equivalent when of the same exactness and numerically equal.
+    pub fn eqv(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Unspecified, Value::Unspecified) | (Value::Null, Value::Null) | (Value::Eof, Value::Eof) => true,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Number(left), Value::Number(right)) => number_eqv(left, right),
+            (Value::Character(left), Value::Character(right)) => left == right,
+            (Value::String(left), Value::String(right)) => Rc::ptr_eq(left, right),
+            (Value::Symbol(left), Value::Symbol(right)) => left == right,
+            (Value::Pair(left), Value::Pair(right)) => Rc::ptr_eq(left, right),
+            (Value::Vector(left), Value::Vector(right)) => Rc::ptr_eq(left, right),
+            (Value::ByteVector(left), Value::ByteVector(right)) => Rc::ptr_eq(left, right),
+            (Value::Procedure(left), Value::Procedure(right)) => left.ptr_eq(right),
+            (Value::Error(left), Value::Error(right)) => Rc::ptr_eq(left, right),
+            (Value::InputPort(left), Value::InputPort(right)) => Rc::ptr_eq(left, right),
+            (Value::Promise(left), Value::Promise(right)) => Rc::ptr_eq(left, right),
+            (Value::Record(left), Value::Record(right)) => Rc::ptr_eq(left, right),
+            (Value::RecordType(left), Value::RecordType(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+
+    /// R7RS `equal?`, comparing the contents of pairs, strings, vectors and bytevectors.
+    pub fn equal(&self, other: &Value) -> bool {
+        let mut pending = vec![(self.clone(), other.clone())];
+
+        while let Some((left, right)) = pending.pop() {
+            let equal = match (&left, &right) {
+                (Value::Pair(left), Value::Pair(right)) => {
+                    pending.push((left.cdr(), right.cdr()));
+                    pending.push((left.car(), right.car()));
+                    true
+                }
+                (Value::String(left), Value::String(right)) => *left.borrow() == *right.borrow(),
+                (Value::Vector(left), Value::Vector(right)) => {
+                    let (left, right) = (left.borrow(), right.borrow());
+                    pending.extend(left.iter().cloned().zip(right.iter().cloned()).rev());
+                    left.len() == right.len()
+                }
+                (Value::ByteVector(left), Value::ByteVector(right)) => *left.borrow() == *right.borrow(),
+                _ => left.eqv(&right),
+            };
+
+            if !equal {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Value of a quoted or self-evaluating datum
+    pub fn from_datum(datum: &Datum) -> Value {
+        match &datum.kind {
+            DatumKind::Boolean(boolean) => Value::Boolean(*boolean),
+            DatumKind::Number(number) => Value::Number(number.clone()),
+            DatumKind::Character(character) => Value::Character(*character),
+            DatumKind::String(string) => Value::string(string),
+            DatumKind::Symbol(name) => Value::symbol(name),
+            DatumKind::List { elements, tail } => {
+                let tail = tail.as_deref().map_or(Value::Null, Value::from_datum);
+                Value::list_with_tail(elements.iter().map(Value::from_datum), tail)
+            }
+            DatumKind::Vector(elements) => Value::vector(elements.iter().map(Value::from_datum).collect()),
+            DatumKind::ByteVector(bytes) => Value::ByteVector(Rc::new(RefCell::new(bytes.clone()))),
+        }
+    }
+
+    /// Value of a datum given to `read`, with the structures its labels share or make circular.
+    ///
+    /// Each node is allocated once and only then filled in with its elements, so that the
+    /// references to a label are `eq?` to the labeled datum, even from within it.
+    pub(crate) fn from_graph(graph: &DatumGraph) -> Result<Value, ParseError> {
+        let mut values = HashMap::new();
+        let mut compounds = Vec::new();
+        let mut pending = vec![graph.root()];
+
+        while let Some(id) = pending.pop() {
+            if values.contains_key(&id) {
+                continue;
+            }
+
+            let value = match graph.node(id) {
+                Node::Simple(simple_datum) => simple_value(simple_datum)?,
+                Node::List { elements, .. } if elements.is_empty() => Value::Null,
+                Node::List { elements, .. } => Value::list(elements.iter().map(|_| Value::Unspecified)),
+                Node::Vector { .. } => Value::vector(Vec::new()),
+            };
+            if matches!(value, Value::Pair(_) | Value::Vector(_)) {
+                compounds.push(id);
+            }
+            values.insert(id, value);
+            pending.extend(graph.children(id));
+        }
+
+        for id in compounds {
+            match (graph.node(id), &values[&id]) {
+                (Node::List { elements, tail }, Value::Pair(first)) => {
+                    let mut pair = first.clone();
+                    for (index, element) in elements.iter().enumerate() {
+                        pair.set_car(values[element].clone());
+                        if index + 1 < elements.len() {
+                            let Value::Pair(next) = pair.cdr() else {
+                                unreachable!("allocated with one pair per element")
+                            };
+                            pair = next;
+                        }
+                    }
+                    pair.set_cdr(tail.map_or(Value::Null, |tail| values[&tail].clone()));
+                }
+                (Node::Vector { elements }, Value::Vector(vector)) => {
+                    *vector.borrow_mut() = elements.iter().map(|element| values[element].clone()).collect();
+                }
+                _ => unreachable!("only lists and vectors are filled in"),
+            }
+        }
+
+        Ok(values.remove(&graph.root()).expect("root visited first"))
+    }
+}
+
+fn simple_value(simple_datum: &SimpleDatum) -> Result<Value, ParseError> {
+    Ok(match simple_datum {
+        SimpleDatum::Boolean(boolean) => Value::Boolean(boolean.value()),
+        SimpleDatum::Number(number) => {
+            Value::Number(Number::try_from(number).map_err(|error| ParseError::InvalidNumber(error, number.span()))?)
+        }
+        SimpleDatum::Character(character) => Value::Character(character.value()),
+        SimpleDatum::String(string) => Value::string(&string.value()),
+        SimpleDatum::Symbol(symbol) => Value::symbol(symbol.name()),
+        SimpleDatum::ByteVector(byte_vector) => Value::ByteVector(Rc::new(RefCell::new(byte_vector.to_vec()))),
+    })
+}
+
+impl From<bool> for Value {
+    fn from(boolean: bool) -> Self {
+        Value::Boolean(boolean)
+    }
+}
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(integer: i64) -> Self {
+        Value::Number(integer.into())
+    }
+}
+
+fn number_eqv(left: &Number, right: &Number) -> bool {
+    match (left.is_exact(), right.is_exact()) {
+        (true, true) => left == right,
+        // distinguishes `0.0` from `-0.0`, and NaN from itself
+        (false, false) => {
+            let bits = |number: &Number| [number.real_part().to_f64().to_bits(), number.imaginary_part().to_f64().to_bits()];
+            bits(left) == bits(right)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(elements: &[i64]) -> Value {
+        Value::list(elements.iter().map(|element| Value::from(*element)))
+    }
+
+    #[test]
+    fn equivalence() {
+        assert!(Value::from(2).eqv(&Value::from(2)));
+        assert!(!Value::from(2).eqv(&Value::Number(2.0.into())));
+        assert!(Value::symbol("a").eqv(&Value::symbol("a")));
+        assert!(!Value::string("a").eqv(&Value::string("a")));
+        assert!(!list(&[1]).eqv(&list(&[1])));
+    }
+
+    #[test]
+    fn structural_equality() {
+        assert!(list(&[1, 2, 3]).equal(&list(&[1, 2, 3])));
+        assert!(!list(&[1, 2, 3]).equal(&list(&[1, 2])));
+        assert!(Value::vector(vec![list(&[1]), Value::string("a")]).equal(&Value::vector(vec![list(&[1]), Value::string("a")])));
+        assert!(!Value::vector(vec![Value::from(1)]).equal(&Value::vector(vec![Value::from(2)])));
+    }
+
+    #[test]
+    fn list_elements() {
+        assert_eq!(Some(3), list(&[1, 2, 3]).list_elements().map(|elements| elements.len()));
+        assert!(Value::list_with_tail([Value::from(1)], Value::from(2)).list_elements().is_none());
+
+        let last = Value::cons(Value::from(2), Value::Null);
+        let circular = Value::cons(Value::from(1), last.clone());
+        let Value::Pair(last) = last else { unreachable!() };
+        last.set_cdr(circular.clone());
+        assert!(circular.list_elements().is_none());
+        // breaks the cycle so that the list is freed
+        last.set_cdr(Value::Null);
+    }
+
+    #[test]
+    fn long_list_drop() {
+        drop(Value::list((0..1_000_000).map(Value::from)));
+    }
+}
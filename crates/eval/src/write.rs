@@ -0,0 +1,347 @@
+//! External representation of values, as by R7RS `display` and `write`
+//!
+//! Pairs and vectors are written with an explicit stack rather than recursion, and those
+//! contained in themselves are labeled, as in `#0=(a . #0#)`, so that writing circular structures
+//! terminates. Structures shared without cycles are written in full each time.
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt::{self, Debug, Display, Write},
+    rc::Rc,
+};
+
+use pluine_number::Number;
+
+use crate::{procedure::ProcedureKind, *};
+
+/// Representation of a value as written by R7RS `write`, see [`Value::written`]
+pub struct Written<'a>(&'a Value);
+
+impl Value {
+    /// Representation readable back by `read`, strings and characters being escaped.
+    pub fn written(&self) -> Written<'_> {
+        Written(self)
+    }
+}
+
+/// Representation written by R7RS `display`
+impl Display for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(self, false, formatter)
+    }
+}
+
+impl Display for Written<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(self.0, true, formatter)
+    }
+}
+
+/// Representation written by R7RS `write`
+impl Debug for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_value(self, true, formatter)
+    }
+}
+
+/// Element of the representation left to write
+enum Item {
+    Value(Value),
+    /// Rest of a list after its first element
+    Tail(Value),
+    Str(&'static str),
+}
+
+fn write_value(value: &Value, escape: bool, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let cyclic = cyclic(value);
+    let mut labels = HashMap::new();
+    let mut pending = vec![Item::Value(value.clone())];
+
+    while let Some(item) = pending.pop() {
+        let value = match item {
+            Item::Value(value) => value,
+            Item::Tail(Value::Null) => {
+                formatter.write_char(')')?;
+                continue;
+            }
+            // labeled pairs are written after a dot, their label preceding them
+            Item::Tail(Value::Pair(pair)) if !cyclic.contains(&Rc::as_ptr(&pair).cast()) => {
+                formatter.write_char(' ')?;
+                pending.push(Item::Tail(pair.cdr()));
+                pending.push(Item::Value(pair.car()));
+                continue;
+            }
+            Item::Tail(tail) => {
+                formatter.write_str(" . ")?;
+                pending.push(Item::Str(")"));
+                tail
+            }
+            Item::Str(str) => {
+                formatter.write_str(str)?;
+                continue;
+            }
+        };
+
+        if let Some(address) = address(&value).filter(|address| cyclic.contains(address)) {
+            let label = labels.len();
+            match labels.entry(address) {
+                Entry::Occupied(entry) => {
+                    write!(formatter, "#{}#", entry.get())?;
+                    continue;
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(label);
+                    write!(formatter, "#{label}=")?;
+                }
+            }
+        }
+
+        match value {
+            Value::Pair(pair) => {
+                formatter.write_char('(')?;
+                pending.push(Item::Tail(pair.cdr()));
+                pending.push(Item::Value(pair.car()));
+            }
+            Value::Vector(elements) => {
+                formatter.write_str("#(")?;
+                pending.push(Item::Str(")"));
+                for (index, element) in elements.borrow().iter().enumerate().rev() {
+                    pending.push(Item::Value(element.clone()));
+                    if index > 0 {
+                        pending.push(Item::Str(" "));
+                    }
+                }
+            }
+            // written one after another, as a REPL would
+            Value::Values(values) => {
+                for (index, value) in values.iter().enumerate().rev() {
+                    pending.push(Item::Value(value.clone()));
+                    if index > 0 {
+                        pending.push(Item::Str(" "));
+                    }
+                }
+            }
+            atom => write_atom(&atom, escape, formatter)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Address of a pair or vector, identifying it
+fn address(value: &Value) -> Option<*const ()> {
+    match value {
+        Value::Pair(pair) => Some(Rc::as_ptr(pair).cast()),
+        Value::Vector(elements) => Some(Rc::as_ptr(elements).cast()),
+        _ => None,
+    }
+}
+
+/// Addresses of the pairs and vectors reachable from `value` that a depth first traversal reaches
+/// again from within themselves, every cycle going through at least one of them
+fn cyclic(value: &Value) -> HashSet<*const ()> {
+    enum Visit {
+        Enter(Value),
+        Exit(*const ()),
+    }
+
+    let mut cyclic = HashSet::new();
+    let mut path = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![Visit::Enter(value.clone())];
+
+    while let Some(visit) = pending.pop() {
+        let value = match visit {
+            Visit::Enter(value) => value,
+            Visit::Exit(address) => {
+                path.remove(&address);
+                continue;
+            }
+        };
+        if let Value::Values(values) = &value {
+            pending.extend(values.iter().rev().cloned().map(Visit::Enter));
+            continue;
+        }
+        let Some(address) = address(&value) else {
+            continue;
+        };
+        if path.contains(&address) {
+            cyclic.insert(address);
+            continue;
+        }
+        if !visited.insert(address) {
+            continue;
+        }
+
+        path.insert(address);
+        pending.push(Visit::Exit(address));
+        match value {
+            Value::Pair(pair) => {
+                pending.push(Visit::Enter(pair.cdr()));
+                pending.push(Visit::Enter(pair.car()));
+            }
+            Value::Vector(elements) => pending.extend(elements.borrow().iter().rev().cloned().map(Visit::Enter)),
+            _ => unreachable!("only pairs and vectors have an address"),
+        }
+    }
+
+    cyclic
+}
+
+/// Writes a value that is neither a pair, a vector nor multiple values.
+fn write_atom(value: &Value, escape: bool, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match value {
+        Value::Unspecified => formatter.write_str("#<unspecified>"),
+        Value::Boolean(true) => formatter.write_str("#t"),
+        Value::Boolean(false) => formatter.write_str("#f"),
+        Value::Number(number) => Display::fmt(number, formatter),
+        Value::Character(character) if escape => write_character(*character, formatter),
+        Value::Character(character) => formatter.write_char(*character),
+        Value::String(string) if escape => write_string(&string.borrow(), formatter),
+        Value::String(string) => formatter.write_str(&string.borrow()),
+        Value::Symbol(name) if escape && needs_vertical_lines(name) => {
+            formatter.write_char('|')?;
+            for char in name.chars() {
+                match char {
+                    '|' | '\\' => write!(formatter, "\\{char}")?,
+                    _ => formatter.write_char(char)?,
+                }
+            }
+            formatter.write_char('|')
+        }
+        Value::Symbol(name) => formatter.write_str(name),
+        Value::Null => formatter.write_str("()"),
+        Value::Pair(_) | Value::Vector(_) | Value::Values(_) => unreachable!("written by write_value"),
+        Value::ByteVector(bytes) => {
+            formatter.write_str("#u8(")?;
+            for (index, byte) in bytes.borrow().iter().enumerate() {
+                if index > 0 {
+                    formatter.write_char(' ')?;
+                }
+                write!(formatter, "{byte}")?;
+            }
+            formatter.write_char(')')
+        }
+        Value::Procedure(Procedure(ProcedureKind::Continuation(_))) => formatter.write_str("#<continuation>"),
+        Value::Procedure(Procedure(ProcedureKind::Parameter(_))) => formatter.write_str("#<parameter>"),
+        Value::Procedure(procedure) => match procedure.name() {
+            Some(name) => write!(formatter, "#<procedure {name}>"),
+            None => formatter.write_str("#<procedure>"),
+        },
+        Value::Error(error) => {
+            formatter.write_str("#<error ")?;
+            write_string(error.message(), formatter)?;
+            formatter.write_char('>')
+        }
+        Value::InputPort(_) => formatter.write_str("#<input-port>"),
+        Value::Promise(_) => formatter.write_str("#<promise>"),
+        Value::Record(record) => write!(formatter, "#<record {}>", record.record_type.name),
+        Value::RecordType(record_type) => write!(formatter, "#<record-type {}>", record_type.name),
+        Value::Eof => formatter.write_str("#<eof>"),
+    }
+}
+
+fn write_character(character: char, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match character {
+        '\u{7}' => "alarm",
+        '\u{8}' => "backspace",
+        '\u{7F}' => "delete",
+        '\u{1B}' => "escape",
+        '\n' => "newline",
+        '\0' => "null",
+        '\r' => "return",
+        ' ' => "space",
+        '\t' => "tab",
+        character if character.is_control() => return write!(formatter, "#\\x{:X}", character as u32),
+        character => return write!(formatter, "#\\{character}"),
+    };
+
+    write!(formatter, "#\\{name}")
+}
+
+fn write_string(string: &str, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+    formatter.write_char('"')?;
+    for char in string.chars() {
+        match char {
+            '"' => formatter.write_str("\\\"")?,
+            '\\' => formatter.write_str("\\\\")?,
+            '\u{7}' => formatter.write_str("\\a")?,
+            '\u{8}' => formatter.write_str("\\b")?,
+            '\t' => formatter.write_str("\\t")?,
+            '\n' => formatter.write_str("\\n")?,
+            '\r' => formatter.write_str("\\r")?,
+            char if char.is_control() => write!(formatter, "\\x{:X};", char as u32)?,
+            char => formatter.write_char(char)?,
+        }
+    }
+    formatter.write_char('"')
+}
+
+/// Whether the symbol would not be read back as itself without vertical lines
+fn needs_vertical_lines(name: &str) -> bool {
+    name.is_empty()
+        || name == "."
+        || Number::parse(name, 10).is_some()
+        || name.starts_with('#')
+        || name
+            .chars()
+            .any(|char| char.is_whitespace() || char.is_control() || "()\"';`|,".contains(char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_write() {
+        let value = Value::list([Value::string("a\"b"), Value::Character(' '), Value::symbol("c d"), Value::from(1)]);
+        assert_eq!("(a\"b   c d 1)", value.to_string());
+        assert_eq!(r#"("a\"b" #\space |c d| 1)"#, value.written().to_string());
+    }
+
+    #[test]
+    fn compound_values() {
+        let value = Value::list_with_tail([Value::vector(vec![Value::Null, Value::Boolean(true)])], Value::from(2));
+        assert_eq!("(#(() #t) . 2)", value.to_string());
+        assert_eq!("#u8(0 255)", Value::ByteVector(std::rc::Rc::new(vec![0, 255].into())).to_string());
+    }
+
+    #[test]
+    fn deeply_nested_values() {
+        let value = (0..100_000).fold(Value::Null, |value, _| Value::list([value]));
+        let written = value.to_string();
+        assert_eq!(200_002, written.len());
+        assert!(written.starts_with("((") && written.ends_with("))"));
+    }
+
+    #[test]
+    fn circular_values() {
+        let Value::Pair(last) = Value::list([Value::from(3)]) else {
+            unreachable!()
+        };
+        let list = Value::list_with_tail([Value::from(1), Value::from(2)], Value::Pair(last.clone()));
+        last.set_cdr(list.clone());
+        assert_eq!("#0=(1 2 3 . #0#)", list.to_string());
+
+        // labeled in the middle of a list, the label following a dot
+        let Value::Pair(first) = list.clone() else { unreachable!() };
+        last.set_cdr(first.cdr());
+        assert_eq!("(1 . #0=(2 3 . #0#))", list.written().to_string());
+
+        let vector = Value::vector(vec![Value::string("a")]);
+        let Value::Vector(elements) = &vector else { unreachable!() };
+        elements.borrow_mut().push(Value::list([vector.clone(), vector.clone()]));
+        assert_eq!(r#"#0=#("a" (#0# #0#))"#, vector.written().to_string());
+
+        // shared without cycles, written in full
+        let shared = Value::list([Value::from(1)]);
+        assert_eq!("((1) (1))", Value::list([shared.clone(), shared]).to_string());
+    }
+
+    #[test]
+    fn symbols_read_back() {
+        assert_eq!("|1|", Value::symbol("1").written().to_string());
+        assert_eq!("||", Value::symbol("").written().to_string());
+        assert_eq!("a->b", Value::symbol("a->b").written().to_string());
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+/// How the lines of a form are indented, relative to its opening parenthesis.
+///
+/// Forms without a rule are indented as procedure calls: continuation lines are aligned with
+/// the first argument if it shares the operator's line, and with the operator otherwise.
+///
+/// ```scheme
+/// (vector-map f
+///             xs)
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum IndentRule {
+    /// The first `n` arguments are distinguished and indented twice, the remaining body is
+    /// indented once.
+    ///
+    /// ```scheme
+    /// (do ((i 0 (+ i 1)))
+    ///     ((= i 10))
+    ///   (display i))
+    /// ```
+    Body(usize),
+    /// [`Self::Body`] with one distinguished argument, or two for a named `let`, when the first
+    /// argument is an identifier.
+    ///
+    /// ```scheme
+    /// (let loop ((i 0))
+    ///   (loop (+ i 1)))
+    /// ```
+    Let,
+}
+
+impl IndentRule {
+    /// Number of distinguished arguments, `named` being whether the first argument is an
+    /// identifier.
+    pub(crate) fn distinguished(self, named: bool) -> usize {
+        match self {
+            IndentRule::Body(distinguished) => distinguished,
+            IndentRule::Let => 1 + named as usize,
+        }
+    }
+}
+
+/// Formatter options, the default rules cover the R7RS-small syntax and derived expressions.
+///
+/// ```
+/// # use pluine_fmt::{FormatConfig, IndentRule};
+/// let config = FormatConfig::default().with_rule("with-output-file", IndentRule::Body(1));
+///
+/// let formatted = pluine_fmt::format("(with-output-file \"out\"\n(write x))", &config).unwrap();
+/// assert_eq!("(with-output-file \"out\"\n  (write x))\n", formatted);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct FormatConfig {
+    /// Number of spaces a body is indented with, distinguished arguments use twice as many
+    pub indent_width: usize,
+    rules: BTreeMap<String, IndentRule>,
+}
+
+impl FormatConfig {
+    /// Configuration without any [`IndentRule`]s, every form is indented as a procedure call.
+    pub fn empty() -> Self {
+        Self { indent_width: 2, rules: BTreeMap::new() }
+    }
+
+    /// Adds or replaces the rule of forms whose operator is `name`
+    pub fn with_rule(mut self, name: impl Into<String>, rule: IndentRule) -> Self {
+        self.rules.insert(name.into(), rule);
+        self
+    }
+
+    /// Sets [`Self::indent_width`]
+    pub fn with_indent_width(mut self, indent_width: usize) -> Self {
+        self.indent_width = indent_width;
+        self
+    }
+
+    /// Rule of forms whose operator is `name`, matched case sensitively.
+    pub fn rule(&self, name: &str) -> Option<IndentRule> {
+        self.rules.get(name).copied()
+    }
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        const BODY_RULES: &[(&str, usize)] = &[
+            ("begin", 0),
+            ("case", 1),
+            ("case-lambda", 0),
+            ("define", 1),
+            ("define-library", 1),
+            ("define-record-type", 1),
+            ("define-syntax", 1),
+            ("define-values", 1),
+            ("delay", 0),
+            ("delay-force", 0),
+            ("do", 2),
+            ("guard", 1),
+            ("lambda", 1),
+            ("let*", 1),
+            ("let*-values", 1),
+            ("let-syntax", 1),
+            ("let-values", 1),
+            ("letrec", 1),
+            ("letrec*", 1),
+            ("letrec-syntax", 1),
+            ("parameterize", 1),
+            ("syntax-rules", 1),
+            ("unless", 1),
+            ("when", 1),
+        ];
+
+        BODY_RULES
+            .iter()
+            .fold(Self::empty(), |config, (name, distinguished)| {
+                config.with_rule(*name, IndentRule::Body(*distinguished))
+            })
+            .with_rule("let", IndentRule::Let)
+    }
+}
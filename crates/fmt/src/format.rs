@@ -0,0 +1,443 @@
+use pluine_lex::{
+    cst::{Cst, CstToken, SyntaxKind},
+    diagnostic::{Diagnostic, ToDiagnostic},
+    span::Span,
+    TokenizeError,
+};
+use thiserror::Error;
+
+use crate::*;
+
+/// Reasons for refusing to format a source string.
+#[derive(Debug, PartialEq, Error)]
+pub enum FormatError {
+    /// Source contains tokens the lexer could not scan, formatting them could change their meaning
+    #[error("source contains invalid tokens")]
+    Tokenize(Vec<TokenizeError>),
+    /// Span points to the opening parenthesis
+    #[error("unclosed list")]
+    UnclosedList(Span),
+    /// Span points to the closing parenthesis
+    #[error("unexpected closing parenthesis")]
+    UnexpectedCloseParenthesis(Span),
+}
+
+impl FormatError {
+    /// One diagnostic per error, lexer errors keep their own codes.
+    ///
+    /// | Code  | Error                                     |
+    /// |-------|-------------------------------------------|
+    /// | F0101 | `FormatError::UnclosedList`               |
+    /// | F0102 | `FormatError::UnexpectedCloseParenthesis` |
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            FormatError::Tokenize(errors) => errors.iter().map(ToDiagnostic::to_diagnostic).collect(),
+            FormatError::UnclosedList(span) => {
+                vec![Diagnostic::error("F0101", self.to_string()).with_primary_label(*span, Some("list opened here"))]
+            }
+            FormatError::UnexpectedCloseParenthesis(span) => {
+                vec![Diagnostic::error("F0102", self.to_string()).with_primary_label(*span, Some("no matching '('"))]
+            }
+        }
+    }
+}
+
+/// Formats a source string, see the [crate documentation](crate) for the applied style.
+pub fn format(src: &str, config: &FormatConfig) -> Result<String, FormatError> {
+    let (cst, errors) = Cst::parse(src);
+
+    if !errors.is_empty() {
+        return Err(FormatError::Tokenize(errors));
+    }
+
+    let mut formatter = Formatter {
+        config,
+        output: String::with_capacity(src.len()),
+        column: 0,
+        frames: Vec::new(),
+        pending_line_endings: 0,
+        previous: None,
+    };
+
+    for token in cst.tokens() {
+        formatter.push(token)?;
+    }
+
+    if let Some(frame) = formatter.frames.first() {
+        return Err(FormatError::UnclosedList(frame.open_span));
+    }
+
+    if !formatter.output.is_empty() {
+        formatter.output.push('\n');
+    }
+
+    Ok(formatter.output)
+}
+
+/// Whether `src` is left unchanged by [`format()`].
+pub fn check(src: &str, config: &FormatConfig) -> Result<bool, FormatError> {
+    format(src, config).map(|formatted| formatted == src)
+}
+
+/// Open list the formatter is currently within.
+#[derive(Debug)]
+struct Frame<'src> {
+    open_span: Span,
+    /// Output column of the opening parenthesis
+    open_column: usize,
+    /// Width of the opening token, `(`, `#(` or `#u8(`
+    open_width: usize,
+    vector: bool,
+    /// Number of started datums, a prefixed datum such as `'x` counts once
+    element_count: usize,
+    /// First element, if an identifier
+    operator: Option<&'src str>,
+    /// Whether the second element is an identifier, used by [`IndentRule::Let`]
+    named: bool,
+    /// Output column of the second element, if on the same line as the first
+    first_argument_column: Option<usize>,
+    /// Quote or unquote awaiting its datum
+    prefix_pending: bool,
+}
+
+impl Frame<'_> {
+    /// Column of a line starting with the element at `index`.
+    fn indentation(&self, index: usize, config: &FormatConfig) -> usize {
+        let element_column = self.open_column + self.open_width;
+
+        if self.vector || index == 0 {
+            return element_column;
+        }
+
+        let Some(operator) = self.operator else {
+            return element_column;
+        };
+
+        match config.rule(operator) {
+            Some(rule) => match index <= rule.distinguished(self.named) {
+                true => self.open_column + 2 * config.indent_width,
+                false => self.open_column + config.indent_width,
+            },
+            None => self.first_argument_column.unwrap_or(element_column),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Formatter<'cfg, 'src> {
+    config: &'cfg FormatConfig,
+    output: String,
+    /// Output column, counted in chars
+    column: usize,
+    /// Innermost list last, explicit stack instead of recursion
+    frames: Vec<Frame<'src>>,
+    /// Line endings in the whitespace since the previous token
+    pending_line_endings: usize,
+    /// Kind and text of the previous non-whitespace token
+    previous: Option<(SyntaxKind, &'src str)>,
+}
+
+impl<'src> Formatter<'_, 'src> {
+    /// At most one blank line is kept between two lines
+    const MAX_LINE_BREAKS: usize = 2;
+
+    fn push(&mut self, token: &CstToken<'src>) -> Result<(), FormatError> {
+        let kind = token.kind();
+
+        if kind == SyntaxKind::Whitespace {
+            self.pending_line_endings += line_ending_count(token.text());
+            return Ok(());
+        }
+
+        if kind == SyntaxKind::CloseParenthesis && self.frames.is_empty() {
+            return Err(FormatError::UnexpectedCloseParenthesis(token.span()));
+        }
+
+        let starts_element =
+            !kind.is_atmosphere() && kind != SyntaxKind::CloseParenthesis && self.frames.last().is_none_or(|frame| !frame.prefix_pending);
+        let index = self.frames.last().map_or(0, |frame| frame.element_count);
+
+        let line_break = self.write_separator(kind, index);
+        let column = self.column;
+        self.write_token(token.text());
+
+        if let Some(frame) = self.frames.last_mut() {
+            if starts_element {
+                match index {
+                    0 => frame.operator = (kind == SyntaxKind::Identifier).then_some(token.text()),
+                    1 => {
+                        frame.named = kind == SyntaxKind::Identifier;
+                        frame.first_argument_column = (!line_break).then_some(column);
+                    }
+                    _ => {}
+                }
+                frame.element_count += 1;
+            }
+
+            if !kind.is_atmosphere() {
+                frame.prefix_pending = is_prefix(kind);
+            }
+        }
+
+        match kind {
+            SyntaxKind::OpenParenthesis | SyntaxKind::OpenVector | SyntaxKind::OpenBytevector => self.frames.push(Frame {
+                open_span: token.span(),
+                open_column: column,
+                open_width: token.text().chars().count(),
+                vector: kind != SyntaxKind::OpenParenthesis,
+                element_count: 0,
+                operator: None,
+                named: false,
+                first_argument_column: None,
+                prefix_pending: false,
+            }),
+            SyntaxKind::CloseParenthesis => {
+                self.frames.pop();
+            }
+            _ => {}
+        }
+
+        self.previous = Some((kind, token.text()));
+        self.pending_line_endings = 0;
+
+        Ok(())
+    }
+
+    /// Writes the line breaks and indentation, or the space, preceding a token. Returns whether a
+    /// line was broken.
+    fn write_separator(&mut self, kind: SyntaxKind, index: usize) -> bool {
+        let Some((previous_kind, previous_text)) = self.previous else {
+            // leading blank lines are dropped
+            return false;
+        };
+
+        // semicolon comments extend to the end of the line
+        let after_line_comment = previous_kind == SyntaxKind::Comment && previous_text.starts_with(';');
+
+        let line_breaks = match kind {
+            // closing parentheses are never placed on a line of their own
+            SyntaxKind::CloseParenthesis if !after_line_comment => return false,
+            SyntaxKind::CloseParenthesis => 1,
+            _ if is_prefix(previous_kind) || is_opening(previous_kind) => return false,
+            _ if after_line_comment => self.pending_line_endings.max(1),
+            _ if self.pending_line_endings == 0 => {
+                self.output.push(' ');
+                self.column += 1;
+                return false;
+            }
+            _ => self.pending_line_endings,
+        };
+
+        let indentation = self.frames.last().map_or(0, |frame| frame.indentation(index, self.config));
+
+        self.output
+            .extend(std::iter::repeat_n('\n', line_breaks.min(Self::MAX_LINE_BREAKS)));
+        self.output.extend(std::iter::repeat_n(' ', indentation));
+        self.column = indentation;
+
+        true
+    }
+
+    /// Tokens are written verbatim, strings and nested comments may span several lines.
+    fn write_token(&mut self, text: &str) {
+        self.output.push_str(text);
+
+        match text.rfind(['\n', '\r']) {
+            Some(line_ending) => self.column = text[line_ending + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+}
+
+/// Quotes, unquotes, datum comments and label definitions are joined with the datum they apply to.
+fn is_prefix(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Quote
+            | SyntaxKind::Quasiquote
+            | SyntaxKind::Unquote
+            | SyntaxKind::UnquoteSplicing
+            | SyntaxKind::DatumComment
+            | SyntaxKind::LabelDefinition
+    )
+}
+
+fn is_opening(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::OpenParenthesis | SyntaxKind::OpenVector | SyntaxKind::OpenBytevector
+    )
+}
+
+/// `\r\n` counts as a single line ending
+fn line_ending_count(text: &str) -> usize {
+    text.matches('\n').count() + text.matches('\r').count() - text.matches("\r\n").count()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn assert_formatted(expected: &str, src: &str) {
+        assert_eq!(Ok(expected.to_string()), format(src, &FormatConfig::default()));
+    }
+
+    fn non_whitespace_tokens(src: &str) -> Vec<String> {
+        Cst::parse(src)
+            .0
+            .tokens()
+            .filter(|token| token.kind() != SyntaxKind::Whitespace)
+            .map(|token| token.text().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert_formatted("", "");
+        assert_formatted("", " \n\n\t");
+    }
+
+    #[test]
+    fn spacing() {
+        assert_formatted("(a b '(c) ,@d)\n", "(  a   b\t' ( c )  ,@ d  )  ");
+        assert_formatted("#(1 2) #;(x)\n", "#( 1 2 ) #; (x)");
+        assert_formatted("#u8(1\n    2)\n", "#u8( 1\n2 )");
+        assert_formatted("#0=(a . #0#)\n", "#0= ( a . #0# )");
+    }
+
+    #[test]
+    fn closing_parentheses_are_joined() {
+        assert_formatted("(a\n (b))\n", "(a\n(b)\n)\n");
+    }
+
+    #[test]
+    fn closing_parenthesis_after_line_comment() {
+        assert_formatted("(a ; comment\n )\n", "(a ; comment\n)");
+    }
+
+    #[test]
+    fn blank_lines_are_collapsed() {
+        assert_formatted("(a)\n\n(b)\n", "\n\n(a)\n\n\n\n(b)\n\n");
+    }
+
+    #[test]
+    fn procedure_call_alignment() {
+        assert_formatted("(f a\n   b\n   c)\n", "(f a\nb\n  c)");
+        assert_formatted("(f\n a\n b)\n", "(f\na\nb)");
+        assert_formatted("((f a)\n b)\n", "((f a)\nb)");
+    }
+
+    #[test]
+    fn body_indentation() {
+        assert_formatted("(lambda (x)\n  x)\n", "(lambda (x)\nx)");
+        assert_formatted("(lambda\n    (x)\n  x)\n", "(lambda\n(x)\nx)");
+        assert_formatted("(begin\n  (a)\n  (b))\n", "(begin\n(a)\n(b))");
+    }
+
+    #[test]
+    fn named_let() {
+        assert_formatted("(let loop\n    ((i 0))\n  (loop i))\n", "(let loop\n((i 0))\n(loop i))");
+        assert_formatted("(let ((i 0))\n  i)\n", "(let ((i 0))\ni)");
+    }
+
+    #[test]
+    fn custom_rules() {
+        let config = FormatConfig::empty()
+            .with_indent_width(4)
+            .with_rule("my-define", IndentRule::Body(1));
+
+        assert_eq!(Ok("(my-define x\n    1)\n".to_string()), format("(my-define x\n1)", &config));
+        assert_eq!(Ok("(define x\n        1)\n".to_string()), format("(define x\n1)", &config));
+    }
+
+    #[test]
+    fn comments_are_kept_in_place() {
+        assert_formatted(
+            "(define (f)\n  ;; body\n  #| nested\n   comment |# (g) ; g\n  (h))\n",
+            "(define (f)\n;; body\n#| nested\n   comment |# (g) ; g\n(h))",
+        );
+    }
+
+    #[test]
+    fn multi_line_tokens() {
+        assert_formatted("(f \"a\nb\" c\n   d)\n", "(f \"a\nb\" c\nd)");
+    }
+
+    #[test]
+    fn line_endings_are_normalized() {
+        assert_formatted("(a\n b)\n", "(a\r\nb)\r\n");
+    }
+
+    #[test]
+    fn check_mode() {
+        let config = FormatConfig::default();
+
+        assert_eq!(Ok(true), check("(a b)\n", &config));
+        assert_eq!(Ok(false), check("(a  b)\n", &config));
+        assert_eq!(Ok(false), check("(a b)", &config));
+    }
+
+    #[test]
+    fn errors() {
+        let config = FormatConfig::default();
+
+        let Err(FormatError::UnclosedList(span)) = format("(a (b)", &config) else {
+            panic!("expected an unclosed list error");
+        };
+        assert_eq!((0, 1), (span.start(), span.end()));
+
+        let Err(FormatError::UnexpectedCloseParenthesis(span)) = format("(a))", &config) else {
+            panic!("expected an unexpected closing parenthesis error");
+        };
+        assert_eq!((3, 4), (span.start(), span.end()));
+
+        let error = format("(#z)", &config).unwrap_err();
+        assert!(matches!(error, FormatError::Tokenize(_)));
+        assert_eq!("L0801", error.diagnostics()[0].code);
+    }
+
+    fn fragments() -> impl Strategy<Value = String> {
+        let fragment = prop_oneof![
+            Just("("),
+            Just(")"),
+            Just("#("),
+            Just("#u8("),
+            Just("'"),
+            Just(",@"),
+            Just("#;"),
+            Just(" "),
+            Just("\n"),
+            Just("\n\n\n"),
+            Just("; comment\n"),
+            Just("#| a\nb |#"),
+            Just("\"a\n  b\""),
+            Just("define"),
+            Just("let"),
+            Just("f"),
+            Just("42"),
+            Just("."),
+            Just("#0="),
+            Just("#0#"),
+        ];
+
+        prop::collection::vec(fragment, 0..40).prop_map(|fragments| fragments.join(" "))
+    }
+
+    proptest! {
+        #[test]
+        fn idempotent(src in fragments()) {
+            if let Ok(formatted) = format(&src, &FormatConfig::default()) {
+                prop_assert_eq!(Ok(formatted.clone()), format(&formatted, &FormatConfig::default()));
+            }
+        }
+
+        #[test]
+        fn tokens_are_preserved(src in fragments()) {
+            if let Ok(formatted) = format(&src, &FormatConfig::default()) {
+                prop_assert_eq!(non_whitespace_tokens(&src), non_whitespace_tokens(&formatted));
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Pluine Formatter
+//!
+//! Opinionated Scheme code formatter built on the lossless [`pluine_lex::cst`]. Line breaks are
+//! left to the author, the formatter only normalizes what surrounds them:
+//!
+//! - Lines are re-indented according to the [`IndentRule`] of their enclosing form.
+//! - Tokens on the same line are separated by a single space. No space is placed after opening
+//!   parentheses, quotes, unquotes, datum comments and datum label definitions.
+//! - Closing parentheses are moved to the end of the previous line, unless it ends with a semicolon
+//!   comment.
+//! - Consecutive blank lines are collapsed into one, leading blank lines and trailing whitespace
+//!   are removed and the output ends with a single `\n`.
+//!
+//! Comments, strings and other tokens are kept verbatim and in place.
+//!
+//! ```
+//! # use pluine_fmt::FormatConfig;
+//! let src = "(define (f x)   ; doubles x\n(* x\n2)\n)";
+//!
+//! let formatted = pluine_fmt::format(src, &FormatConfig::default()).unwrap();
+//! assert_eq!("(define (f x) ; doubles x\n  (* x\n     2))\n", formatted);
+//! ```
+//!
+//! Sources containing invalid tokens or unbalanced parentheses are rejected with a
+//! [`FormatError`].
+
+mod config;
+pub use config::{FormatConfig, IndentRule};
+
+mod format;
+pub use format::{check, format, FormatError};
@@ -0,0 +1,159 @@
+//! `pluine-fmt` command line interface
+//!
+//! ```text
+//! pluine-fmt [--check] [--indent-width <N>] [--rule <NAME>=<N>]... [FILE]...
+//! ```
+//!
+//! Files are formatted in place, standard input is formatted to standard output when no file is
+//! given. With `--check`, nothing is written and unformatted files are listed instead. Each
+//! `--rule` adds an [`IndentRule::Body`] with `N` distinguished arguments for forms named `NAME`.
+//!
+//! Exits with `1` if a file could not be formatted, or was not formatted in check mode.
+//! `-h` and `--help` print the usage to standard output and exit with `0`.
+
+use std::{
+    io::{IsTerminal, Read},
+    process::ExitCode,
+};
+
+use pluine_fmt::{FormatConfig, FormatError, IndentRule};
+use pluine_lex::{diagnostic::Renderer, line_index::LineIndex};
+
+const USAGE: &str = "usage: pluine-fmt [--check] [--indent-width <N>] [--rule <NAME>=<N>]... [FILE]...";
+
+struct Options {
+    /// Print the usage and exit
+    help: bool,
+    check: bool,
+    config: FormatConfig,
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if options.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let mut success = true;
+
+    if options.files.is_empty() {
+        let mut src = String::new();
+        if let Err(error) = std::io::stdin().read_to_string(&mut src) {
+            eprintln!("error: failed to read standard input: {error}");
+            return ExitCode::FAILURE;
+        }
+
+        success = match pluine_fmt::format(&src, &options.config) {
+            Ok(formatted) if options.check => report_unformatted("<stdin>", formatted == src),
+            Ok(formatted) => {
+                print!("{formatted}");
+                true
+            }
+            Err(error) => report_error("<stdin>", &src, &error),
+        };
+    }
+
+    for file in &options.files {
+        success &= format_file(file, &options);
+    }
+
+    match success {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn format_file(file: &str, options: &Options) -> bool {
+    let src = match std::fs::read_to_string(file) {
+        Ok(src) => src,
+        Err(error) => {
+            eprintln!("error: failed to read {file}: {error}");
+            return false;
+        }
+    };
+
+    let formatted = match pluine_fmt::format(&src, &options.config) {
+        Ok(formatted) => formatted,
+        Err(error) => return report_error(file, &src, &error),
+    };
+
+    if options.check {
+        return report_unformatted(file, formatted == src);
+    }
+
+    if formatted != src {
+        if let Err(error) = std::fs::write(file, formatted) {
+            eprintln!("error: failed to write {file}: {error}");
+            return false;
+        }
+    }
+
+    true
+}
+
+fn report_unformatted(file: &str, formatted: bool) -> bool {
+    if !formatted {
+        println!("{file} is not formatted");
+    }
+
+    formatted
+}
+
+fn report_error(file: &str, src: &str, error: &FormatError) -> bool {
+    let renderer = match std::io::stderr().is_terminal() {
+        true => Renderer::ansi(),
+        false => Renderer::plain(),
+    };
+    let line_index = LineIndex::new(src);
+
+    for diagnostic in error.diagnostics() {
+        eprint!("{}", renderer.render(&diagnostic, file, &line_index));
+    }
+
+    false
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        help: false,
+        check: false,
+        config: FormatConfig::default(),
+        files: Vec::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => options.check = true,
+            "--indent-width" => {
+                let width = args.next().ok_or("missing value for '--indent-width'")?;
+                let width = width.parse().map_err(|_| format!("invalid indent width '{width}'"))?;
+                options.config = options.config.with_indent_width(width);
+            }
+            "--rule" => {
+                let rule = args.next().ok_or("missing value for '--rule'")?;
+                let (name, distinguished) = rule
+                    .split_once('=')
+                    .and_then(|(name, distinguished)| Some((name, distinguished.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid rule '{rule}', expected '<NAME>=<N>'"))?;
+                options.config = options.config.with_rule(name, IndentRule::Body(distinguished));
+            }
+            "-h" | "--help" => {
+                options.help = true;
+                return Ok(options);
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ => options.files.push(arg),
+        }
+    }
+
+    Ok(options)
+}
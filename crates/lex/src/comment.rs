@@ -1,17 +1,23 @@
 use alloc::vec::Vec;
 
+use thiserror::Error;
+
 use crate::*;
 
+/// Comment of any of the three R7RS kinds
 #[derive(Debug, PartialEq, Spanned)]
 pub enum Comment<'src> {
     /// EBNF-ish: `<all characters up to a line ending>`
     ///
     /// String does not include the leading semi-colon, nor the line ending.
     Semicolon(SemicolonComment<'src>),
+    /// See [`NestedComment`]
     Nested(NestedComment<'src>),
+    /// See [`SectionComment`]
     Section(SectionComment),
 }
 
+/// EBNF: `; <all characters up to a line ending>`
 #[derive(Debug, PartialEq, Spanned)]
 pub struct SemicolonComment<'src> {
     pub(crate) inner: &'src str,
@@ -19,20 +25,36 @@ pub struct SemicolonComment<'src> {
     pub(crate) span: Span,
 }
 
-/// EBNF: `#| <NestedCommentText> <NestedComment>* <NestedCommentText> |#`
+/// EBNF: `#| <NestedCommentText> <NestedCommentContinuation>* |#`
 #[derive(Debug, PartialEq, Spanned)]
 pub struct NestedComment<'src> {
-    leading_text: NestedCommentText<'src>,
-    nested_comment: Vec<NestedComment<'src>>,
-    trailing_text: NestedCommentText<'src>,
+    pub(crate) leading_text: NestedCommentText<'src>,
+    pub(crate) nested_comment: Vec<NestedCommentContinuation<'src>>,
     #[span]
-    span: Span,
+    pub(crate) span: Span,
+}
+
+/// A nested comment along with the text following it, up until the next nested comment or
+/// the closing `|#` of the enclosing comment.
+///
+/// EBNF: `<NestedComment> <NestedCommentText>`
+#[derive(Debug, PartialEq)]
+pub struct NestedCommentContinuation<'src> {
+    pub(crate) nested_comment: NestedComment<'src>,
+    pub(crate) trailing_text: NestedCommentText<'src>,
 }
 
 /// EBNF-ish: `<all characters except CommentOpen and CommentClose>`
 /// (may be empty)
 #[derive(Debug, PartialEq)]
-pub struct NestedCommentText<'src>(&'src str);
+pub struct NestedCommentText<'src>(pub(crate) &'src str);
+
+#[derive(Debug, PartialEq, Error, Spanned)]
+pub enum NestedCommentScanError {
+    /// Inner span points to the innermost unclosed `#|`
+    #[error("end of file reached, no closing '|#' found for nested comment")]
+    EndOfFile(Span),
+}
 
 /// EBNF: `#; <Atmosphere>* <Datum>`
 ///
@@ -42,4 +64,4 @@ pub struct NestedCommentText<'src>(&'src str);
 /// only the `#;` is registered. The rest is instead placed in separate token
 /// stream elements. Span points to only the `#;` comment token.
 #[derive(Debug, PartialEq, Spanned)]
-pub struct SectionComment(Span);
+pub struct SectionComment(pub(crate) Span);
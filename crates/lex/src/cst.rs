@@ -0,0 +1,466 @@
+//! Lossless concrete syntax tree.
+//!
+//! Unlike lexer tokens, which drop whitespace and leave parentheses unmatched, a [`Cst`]
+//! retains every byte of the source string and groups parenthesized tokens into lists. Printing
+//! a `Cst` is guaranteed to reproduce its source, invalid tokens and unbalanced parentheses
+//! included:
+//!
+//! ```
+//! # use pluine_lex::cst::Cst;
+//! let src = "(define x ; comment\n  #|nested|# 1)";
+//! let (cst, errors) = Cst::parse(src);
+//!
+//! assert!(errors.is_empty());
+//! assert_eq!(src, cst.to_string());
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::*;
+
+/// Token classification exposed by the [`Cst`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SyntaxKind {
+    /// Spaces, tabs and line endings
+    Whitespace,
+    /// Semicolon or nested comment
+    Comment,
+    /// `#;`, commenting out the following datum
+    DatumComment,
+    /// `#!fold-case` or `#!no-fold-case`
+    Directive,
+    /// `(`
+    OpenParenthesis,
+    /// `#(`
+    OpenVector,
+    /// `#u8(`
+    OpenBytevector,
+    /// `)`
+    CloseParenthesis,
+    /// `.`
+    Dot,
+    /// `'`
+    Quote,
+    /// `` ` ``
+    Quasiquote,
+    /// `,`
+    Unquote,
+    /// `,@`
+    UnquoteSplicing,
+    /// Simple, vertical or peculiar identifier
+    Identifier,
+    /// `#t`, `#true`, `#f` or `#false`
+    Boolean,
+    /// Number literal, prefix included
+    Number,
+    /// `#\` character literal
+    Character,
+    /// `"` delimited string literal
+    String,
+    /// `#n=`
+    LabelDefinition,
+    /// `#n#`
+    LabelReference,
+    /// Source region skipped after a lexer error
+    Unknown,
+}
+
+impl SyntaxKind {
+    /// Either a comment, a datum comment, a directive or whitespace.
+    pub fn is_atmosphere(&self) -> bool {
+        matches!(self, Self::Whitespace | Self::Comment | Self::DatumComment | Self::Directive)
+    }
+
+    fn of(token: &TokenAll) -> Self {
+        match token {
+            TokenAll::InterToken(Atmosphere::Comment(Comment::Section(_))) => Self::DatumComment,
+            TokenAll::InterToken(Atmosphere::Comment(_)) => Self::Comment,
+            TokenAll::InterToken(Atmosphere::Directive(_)) => Self::Directive,
+            TokenAll::Token(Token::Identifier(_)) => Self::Identifier,
+            TokenAll::Token(Token::Boolean(_)) => Self::Boolean,
+            TokenAll::Token(Token::Number(_)) => Self::Number,
+            TokenAll::Token(Token::Character(_)) => Self::Character,
+            TokenAll::Token(Token::String(_)) => Self::String,
+            TokenAll::Token(Token::Label(label)) => match label.variant {
+                DatumLabelVariant::Definition => Self::LabelDefinition,
+                DatumLabelVariant::Reference => Self::LabelReference,
+            },
+            TokenAll::Token(Token::Other(token_char)) => match token_char.inner {
+                TokenCharVariant::OpenParenthesis => Self::OpenParenthesis,
+                TokenCharVariant::CloseParenthesis => Self::CloseParenthesis,
+                TokenCharVariant::PoundOpenParenthesis => Self::OpenVector,
+                TokenCharVariant::PoundU8OpenParenthesis => Self::OpenBytevector,
+                TokenCharVariant::Dot => Self::Dot,
+                TokenCharVariant::Apostophe => Self::Quote,
+                TokenCharVariant::GraveAccent => Self::Quasiquote,
+                TokenCharVariant::Comma => Self::Unquote,
+                TokenCharVariant::CommaAt => Self::UnquoteSplicing,
+            },
+            TokenAll::Unknown(_) => Self::Unknown,
+        }
+    }
+}
+
+/// Leaf of the [`Cst`], holding the exact source text it was scanned from.
+#[derive(Debug, PartialEq)]
+pub struct CstToken<'src> {
+    pub(crate) kind: SyntaxKind,
+    pub(crate) text: &'src str,
+    pub(crate) span: Span,
+}
+
+impl<'src> CstToken<'src> {
+    /// See [`SyntaxKind`]
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Source text, unaffected by case folding and escapes
+    pub fn text(&self) -> &'src str {
+        self.text
+    }
+
+    /// Source region of [`Self::text`]
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// Tokens enclosed by `(` or `#(`, and a matching `)`.
+#[derive(Debug, PartialEq)]
+pub struct CstList<'src> {
+    pub(crate) open: CstToken<'src>,
+    pub(crate) elements: Vec<CstElement<'src>>,
+    pub(crate) close: Option<CstToken<'src>>,
+}
+
+impl<'src> CstList<'src> {
+    /// Either [`SyntaxKind::OpenParenthesis`], [`SyntaxKind::OpenVector`] or
+    /// [`SyntaxKind::OpenBytevector`]
+    pub fn open(&self) -> &CstToken<'src> {
+        &self.open
+    }
+
+    /// Elements between the parentheses, whitespace included
+    pub fn elements(&self) -> &[CstElement<'src>] {
+        &self.elements
+    }
+
+    /// `None` if the end of file was reached before a matching `)`
+    pub fn close(&self) -> Option<&CstToken<'src>> {
+        self.close.as_ref()
+    }
+}
+
+/// Node of the [`Cst`]
+#[derive(Debug, PartialEq)]
+pub enum CstElement<'src> {
+    /// See [`CstToken`]
+    Token(CstToken<'src>),
+    /// See [`CstList`]
+    List(CstList<'src>),
+}
+
+/// Lossless concrete syntax tree, see the [module documentation](self).
+#[derive(Debug, PartialEq)]
+pub struct Cst<'src> {
+    pub(crate) elements: Vec<CstElement<'src>>,
+}
+
+impl<'src> Cst<'src> {
+    /// Builds a `Cst` from tokens scanned by [`Lexer::tokenize_all_recovering`].
+    ///
+    /// Invalid tokens become [`SyntaxKind::Unknown`] tokens, their errors are returned alongside
+    /// the tree. Closing parentheses without a matching opening parenthesis are kept as top-level
+    /// tokens.
+    pub fn parse(src: &'src str) -> (Self, Vec<TokenizeError>) {
+        let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+        // Parent frames of the list currently being built, avoids recursion.
+        let mut open_lists: Vec<(CstToken<'src>, Vec<CstElement<'src>>)> = Vec::new();
+        let mut elements = Vec::new();
+
+        // End of the previous token, anything in between two tokens is whitespace.
+        let mut previous_end = 0;
+
+        for token in tokens {
+            let span = token.span();
+
+            if previous_end < span.start() {
+                elements.push(CstElement::Token(Self::whitespace(src, previous_end, span.start())));
+            }
+            previous_end = span.end();
+
+            let kind = SyntaxKind::of(&token);
+            let cst_token = CstToken { kind, text: &src[span.start()..span.end()], span };
+
+            match kind {
+                SyntaxKind::OpenParenthesis | SyntaxKind::OpenVector | SyntaxKind::OpenBytevector => {
+                    open_lists.push((cst_token, core::mem::take(&mut elements)));
+                }
+                SyntaxKind::CloseParenthesis => match open_lists.pop() {
+                    Some((open, parent_elements)) => {
+                        let list_elements = core::mem::replace(&mut elements, parent_elements);
+                        elements.push(CstElement::List(CstList { open, elements: list_elements, close: Some(cst_token) }));
+                    }
+                    None => elements.push(CstElement::Token(cst_token)),
+                },
+                _ => elements.push(CstElement::Token(cst_token)),
+            }
+        }
+
+        if previous_end < src.len() {
+            elements.push(CstElement::Token(Self::whitespace(src, previous_end, src.len())));
+        }
+
+        while let Some((open, parent_elements)) = open_lists.pop() {
+            let list_elements = core::mem::replace(&mut elements, parent_elements);
+            elements.push(CstElement::List(CstList { open, elements: list_elements, close: None }));
+        }
+
+        (Self { elements }, errors)
+    }
+
+    /// Top-level elements, whitespace included
+    pub fn elements(&self) -> &[CstElement<'src>] {
+        &self.elements
+    }
+
+    /// All tokens in source order, depth first.
+    pub fn tokens(&self) -> impl Iterator<Item = &CstToken<'src>> {
+        CstTokens { stack: alloc::vec![(self.elements.iter(), None)] }
+    }
+
+    fn whitespace(src: &'src str, start: usize, end: usize) -> CstToken<'src> {
+        CstToken {
+            kind: SyntaxKind::Whitespace,
+            text: &src[start..end],
+            span: Span::new(src, start, end),
+        }
+    }
+}
+
+/// Prints the exact source string the `Cst` was parsed from.
+impl Display for Cst<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.tokens().try_for_each(|token| f.write_str(token.text))
+    }
+}
+
+/// Depth first token iterator, keeping a stack of list element iterators and their closing
+/// tokens.
+struct CstTokens<'cst, 'src> {
+    stack: Vec<(core::slice::Iter<'cst, CstElement<'src>>, Option<&'cst CstToken<'src>>)>,
+}
+
+impl<'cst, 'src> Iterator for CstTokens<'cst, 'src> {
+    type Item = &'cst CstToken<'src>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (elements, close) = self.stack.last_mut()?;
+
+            match elements.next() {
+                Some(CstElement::Token(token)) => return Some(token),
+                Some(CstElement::List(list)) => {
+                    self.stack.push((list.elements.iter(), list.close.as_ref()));
+                    return Some(&list.open);
+                }
+                None => {
+                    let close = close.take();
+                    self.stack.pop();
+
+                    if close.is_some() {
+                        return close;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+    };
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn kinds(cst: &Cst) -> Vec<SyntaxKind> {
+        cst.tokens().map(CstToken::kind).collect()
+    }
+
+    #[test]
+    fn nested_lists() {
+        let src = "(a #(b) ())";
+        let (cst, errors) = Cst::parse(src);
+        assert!(errors.is_empty());
+
+        let [CstElement::List(list)] = cst.elements() else {
+            panic!("expected a single list, got {:?}", cst.elements());
+        };
+
+        assert_eq!(SyntaxKind::OpenParenthesis, list.open().kind());
+        assert!(list.close().is_some());
+        assert_eq!(5, list.elements().len());
+        assert!(matches!(&list.elements()[2], CstElement::List(vector) if vector.open().kind() == SyntaxKind::OpenVector));
+    }
+
+    #[test]
+    fn whitespace_and_comments_retained() {
+        let src = "  #!fold-case\n#;  ; x\r\n#| a |#\tfoo";
+        let (cst, _) = Cst::parse(src);
+
+        use SyntaxKind::*;
+        assert_eq!(
+            alloc::vec![
+                Whitespace,
+                Directive,
+                Whitespace,
+                DatumComment,
+                Whitespace,
+                Comment,
+                Whitespace,
+                Comment,
+                Whitespace,
+                Identifier
+            ],
+            kinds(&cst)
+        );
+        assert_eq!(src, cst.to_string());
+    }
+
+    #[test]
+    fn datum_labels() {
+        let (cst, _) = Cst::parse("#0=(#0#)");
+
+        let expected = vec![
+            SyntaxKind::LabelDefinition,
+            SyntaxKind::OpenParenthesis,
+            SyntaxKind::LabelReference,
+            SyntaxKind::CloseParenthesis,
+        ];
+        assert_eq!(expected, kinds(&cst));
+    }
+
+    #[test]
+    fn token_text_is_not_folded() {
+        let src = "#!fold-case ABC";
+        let (cst, _) = Cst::parse(src);
+
+        assert_eq!(Some("ABC"), cst.tokens().last().map(CstToken::text));
+    }
+
+    #[test]
+    fn unclosed_list() {
+        let src = "(a (b";
+        let (cst, errors) = Cst::parse(src);
+        assert!(errors.is_empty());
+
+        let [CstElement::List(list)] = cst.elements() else {
+            panic!("expected a single list, got {:?}", cst.elements());
+        };
+        assert_eq!(None, list.close());
+        assert_eq!(src, cst.to_string());
+    }
+
+    #[test]
+    fn unmatched_close() {
+        let src = "a) b";
+        let (cst, _) = Cst::parse(src);
+
+        assert_eq!(4, cst.elements().len());
+        assert_eq!(src, cst.to_string());
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let src = "(#z \"\\q\" |a";
+        let (cst, errors) = Cst::parse(src);
+
+        assert_eq!(3, errors.len());
+        assert_eq!(src, cst.to_string());
+    }
+
+    /// Whitespace is the only source text not covered by any token
+    fn assert_whitespace_gaps(cst: &Cst) {
+        for token in cst.tokens().filter(|token| token.kind() == SyntaxKind::Whitespace) {
+            assert!(
+                token.text().chars().all(|char| matches!(char, ' ' | '\t' | '\r' | '\n')),
+                "{:?}",
+                token.text()
+            );
+        }
+    }
+
+    /// Source made up of valid and invalid token fragments
+    fn fragment_source() -> impl Strategy<Value = String> {
+        let fragment = prop_oneof![
+            Just("("),
+            Just(")"),
+            Just("#("),
+            Just("'"),
+            Just("`"),
+            Just(","),
+            Just(",@"),
+            Just("."),
+            Just(" "),
+            Just("\t"),
+            Just("\n"),
+            Just("\r\n"),
+            Just("\r"),
+            Just("; comment\n"),
+            Just("#| nested #| comment |# |#"),
+            Just("#;"),
+            Just("#!fold-case"),
+            Just("#!no-fold-case"),
+            Just("abc"),
+            Just("λ"),
+            Just("|a b\\|c|"),
+            Just("+"),
+            Just("-1.5e10"),
+            Just("#x-FF"),
+            Just("1/2"),
+            Just("+inf.0"),
+            Just("1+2i"),
+            Just("#t"),
+            Just("#false"),
+            Just("#\\a"),
+            Just("#\\space"),
+            Just("#\\x3bb"),
+            Just("\"str\\n\\x41;\""),
+            Just("\"\\\n  continued\""),
+            Just("#z"),
+            Just("\"\\q\""),
+            Just("#|"),
+            Just("\""),
+            Just("|"),
+            Just("#"),
+            Just("\\"),
+            Just("#\\"),
+        ];
+
+        proptest::collection::vec(fragment, 0..32).prop_map(|fragments| fragments.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_arbitrary(src in any::<String>()) {
+            let (cst, _) = Cst::parse(&src);
+            prop_assert_eq!(&src, &cst.to_string());
+            assert_whitespace_gaps(&cst);
+        }
+
+        #[test]
+        fn round_trip_fragments(src in fragment_source()) {
+            let (cst, _) = Cst::parse(&src);
+            prop_assert_eq!(&src, &cst.to_string());
+            assert_whitespace_gaps(&cst);
+        }
+    }
+}
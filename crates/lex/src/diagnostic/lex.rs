@@ -0,0 +1,185 @@
+use alloc::string::ToString;
+
+use crate::diagnostic::*;
+
+/// Error codes:
+///
+/// | Code  | Error                                             |
+/// |-------|---------------------------------------------------|
+/// | L0101 | `StringLiteralScanError::EndOfFile`               |
+/// | L0102 | `StringLiteralScanError::UnknownEscape`           |
+/// | L0103 | `StringLiteralScanError::UnknownWhitespace`       |
+/// | L0201 | `InlineCodePointScanError::OutOfBounds`           |
+/// | L0202 | `InlineCodePointScanError::InvalidCodePoint`      |
+/// | L0203 | `InlineCodePointScanError::InvalidHexDigit`       |
+/// | L0204 | `InlineCodePointScanError::InvalidSequenceChar`   |
+/// | L0205 | `InlineCodePointScanError::MissingDigit`          |
+/// | L0206 | `InlineCodePointScanError::EndOfFile`             |
+/// | L0301 | `IdentifierScanError::InvalidInitial`             |
+/// | L0302 | `IdentifierScanError::InvalidSubsequent`          |
+/// | L0304 | `IdentifierScanError::IncompletePeculiar`         |
+/// | L0305 | `IdentifierScanError::EndOfFile`                  |
+/// | L0306 | `IdentifierScanError::UnknownEscape`              |
+/// | L0401 | `CharacterLiteralScanError::UnknownName`          |
+/// | L0402 | `CharacterLiteralScanError::EndOfFile`            |
+/// | L0501 | `NumberLiteralScanError::UnknownPrefix`           |
+/// | L0502 | `NumberLiteralScanError::DuplicateRadix`          |
+/// | L0503 | `NumberLiteralScanError::DuplicateExactness`      |
+/// | L0504 | `NumberLiteralScanError::UnexpectedCharacter`     |
+/// | L0505 | `NumberLiteralScanError::UnexpectedEnd`           |
+/// | L0601 | `NestedCommentScanError::EndOfFile`               |
+/// | L0701 | `DirectiveScanError::Unknown`                     |
+/// | L0801 | `TokenizeError::UnknownHashSyntax`                |
+/// | L0802 | `TokenizeError::DatumLabelOutOfBounds`            |
+///
+/// Inline hex escape errors keep their `L02XX` code regardless of the token they are part of.
+impl ToDiagnostic for TokenizeError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            TokenizeError::String(error) => error.to_diagnostic(),
+            TokenizeError::Identifier(error) => error.to_diagnostic(),
+            TokenizeError::Character(error) => error.to_diagnostic(),
+            TokenizeError::Number(error) => error.to_diagnostic(),
+            TokenizeError::NestedComment(error) => error.to_diagnostic(),
+            TokenizeError::Directive(error) => error.to_diagnostic(),
+            TokenizeError::UnknownHashSyntax(span) => {
+                spanned_error("L0801", self, *span, None).with_help("booleans are written as '#t', '#true', '#f' or '#false'")
+            }
+            TokenizeError::DatumLabelOutOfBounds(span) => spanned_error("L0802", self, *span, None),
+        }
+    }
+}
+
+impl ToDiagnostic for StringLiteralScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            StringLiteralScanError::InlineHex(error) => error.to_diagnostic(),
+            StringLiteralScanError::EndOfFile(span) => end_of_file_error("L0101", self, *span, "unterminated string"),
+            StringLiteralScanError::UnknownEscape(span) => {
+                spanned_error("L0102", self, *span, Some("unknown escape")).with_help("a literal backslash is written as '\\\\'")
+            }
+            StringLiteralScanError::UnknownWhitespace(span) => spanned_error("L0103", self, *span, Some("expected a line ending")),
+        }
+    }
+}
+
+impl ToDiagnostic for InlineCodePointScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self {
+            InlineCodePointScanError::OutOfBounds(_) => "L0201",
+            InlineCodePointScanError::InvalidCodePoint(_) => "L0202",
+            InlineCodePointScanError::InvalidHexDigit(_) => "L0203",
+            InlineCodePointScanError::InvalidSequenceChar(_) => "L0204",
+            InlineCodePointScanError::MissingDigit(_) => "L0205",
+            InlineCodePointScanError::EndOfFile(_) => "L0206",
+        };
+
+        let diagnostic = spanned_error(code, self, self.span(), None);
+
+        match self {
+            InlineCodePointScanError::InvalidCodePoint(_) => {
+                diagnostic.with_note("surrogate code points (D800 to DFFF) and values above 10FFFF are not unicode scalar values")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl ToDiagnostic for IdentifierScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            IdentifierScanError::InlineHex(error) => error.to_diagnostic(),
+            IdentifierScanError::InvalidInitial(span) => spanned_error("L0301", self, *span, None)
+                .with_help("other ASCII characters can be used within '|' delimited identifiers, such as '|{a}|'"),
+            IdentifierScanError::InvalidSubsequent(span) => spanned_error("L0302", self, *span, None),
+            IdentifierScanError::IncompletePeculiar(span) => spanned_error("L0304", self, *span, None),
+            IdentifierScanError::EndOfFile(span) => end_of_file_error("L0305", self, *span, "unterminated identifier"),
+            IdentifierScanError::UnknownEscape(span) => spanned_error("L0306", self, *span, Some("unknown escape")),
+        }
+    }
+}
+
+impl ToDiagnostic for CharacterLiteralScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            CharacterLiteralScanError::CodePoint(error) => error.to_diagnostic(),
+            CharacterLiteralScanError::UnknownName(span) => spanned_error("L0401", self, *span, None)
+                .with_note("character names are case sensitive unless a '#!fold-case' directive is in effect"),
+            CharacterLiteralScanError::EndOfFile(span) => spanned_error("L0402", self, *span, None),
+        }
+    }
+}
+
+impl ToDiagnostic for NumberLiteralScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self {
+            NumberLiteralScanError::UnknownPrefix(_) => "L0501",
+            NumberLiteralScanError::DuplicateRadix(_) => "L0502",
+            NumberLiteralScanError::DuplicateExactness(_) => "L0503",
+            NumberLiteralScanError::UnexpectedCharacter(_) => "L0504",
+            NumberLiteralScanError::UnexpectedEnd(_) => "L0505",
+        };
+
+        spanned_error(code, self, self.span(), None)
+    }
+}
+
+impl ToDiagnostic for NestedCommentScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            NestedCommentScanError::EndOfFile(span) => spanned_error("L0601", self, *span, Some("unclosed comment"))
+                .with_note("nested comments must be balanced, each '#|' requires a matching '|#'"),
+        }
+    }
+}
+
+impl ToDiagnostic for DirectiveScanError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            DirectiveScanError::Unknown(span) => spanned_error("L0701", self, *span, None),
+        }
+    }
+}
+
+/// Error diagnostic with the error message and a primary label
+fn spanned_error(code: &'static str, error: &impl core::error::Error, span: Span, label: Option<&str>) -> Diagnostic {
+    Diagnostic::error(code, error.to_string()).with_primary_label(span, label)
+}
+
+/// End of file spans cover the remaining source, only the opening ASCII delimiter is labeled.
+fn end_of_file_error(code: &'static str, error: &impl core::error::Error, span: Span, label: &str) -> Diagnostic {
+    spanned_error(code, error, span.truncate(1), Some(label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(src: &str) -> &'static str {
+        Lexer::new(src).tokenize_all().unwrap_err().to_diagnostic().code
+    }
+
+    #[test]
+    fn codes() {
+        assert_eq!("L0101", code("\"abc"));
+        assert_eq!("L0102", code("\"\\q\""));
+        assert_eq!("L0201", code("\"\\xFFFFFFFFF;\""));
+        assert_eq!("L0203", code("|\\xg;|"));
+        assert_eq!("L0301", code("{a"));
+        assert_eq!("L0305", code("|abc"));
+        assert_eq!("L0401", code("#\\foo"));
+        assert_eq!("L0502", code("#x#x1"));
+        assert_eq!("L0601", code("#| abc"));
+        assert_eq!("L0701", code("#!foo"));
+        assert_eq!("L0801", code("#z"));
+        assert_eq!("L0802", code("#99999999999999999999="));
+    }
+
+    #[test]
+    fn end_of_file_labels_opening_delimiter() {
+        let src = "\"abc\ndef";
+        let diagnostic = Lexer::new(src).tokenize_all().unwrap_err().to_diagnostic();
+
+        assert_eq!(Span::new(src, 0, 1), diagnostic.labels[0].span);
+    }
+}
@@ -0,0 +1,140 @@
+//! Error reporting through annotated source snippets.
+//!
+//! Errors are first converted to a [`Diagnostic`] using [`ToDiagnostic`], which can then be
+//! rendered with a [`Renderer`]:
+//!
+//! ```
+//! # use pluine_lex::{diagnostic::{Renderer, ToDiagnostic}, line_index::LineIndex, Lexer};
+//! let src = "(list\n  #z)";
+//! let error = Lexer::new(src).tokenize_all().unwrap_err();
+//!
+//! let rendered =
+//!     Renderer::plain().render(&error.to_diagnostic(), "main.scm", &LineIndex::new(src));
+//! let expected = "\
+//! error[L0801]: unknown syntax following '#'
+//!  --> main.scm:2:3
+//!   |
+//! 2 |   #z)
+//!   |   ^^
+//!   |
+//!   = help: booleans are written as '#t', '#true', '#f' or '#false'
+//! ";
+//! assert_eq!(expected, rendered);
+//! ```
+//!
+//! Error codes are stable, they are never reused nor reassigned once released. Lexer errors use
+//! the `L` prefix, see the [`ToDiagnostic`] implementation for [`TokenizeError`] for the full
+//! list.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::*;
+
+mod lex;
+
+mod render;
+pub use render::Renderer;
+
+/// Implemented by errors which can be reported as a [`Diagnostic`].
+pub trait ToDiagnostic {
+    /// Converts `self` into a diagnostic, usually with a primary label at the error span.
+    fn to_diagnostic(&self) -> Diagnostic;
+}
+
+/// Severity level of a [`Diagnostic`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    /// Source code could not be processed
+    Error,
+    /// Source code was processed, but is likely incorrect
+    Warning,
+}
+
+/// Whether a [`Label`] marks the cause of a diagnostic or merely provides context.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LabelStyle {
+    /// Underlined with `^`
+    Primary,
+    /// Underlined with `-`
+    Secondary,
+}
+
+/// Annotated source code region.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Label {
+    /// Annotated region, must originate from the source the diagnostic is rendered with
+    pub span: Span,
+    /// Printed after the underline, on the last line of the span
+    pub message: Option<String>,
+    /// See [`LabelStyle`]
+    pub style: LabelStyle,
+}
+
+/// Error or warning report, constructed using the builder methods.
+///
+/// ```
+/// # use pluine_lex::diagnostic::{Diagnostic, Severity};
+/// let diagnostic = Diagnostic::new(Severity::Warning, "W0001", "unused variable")
+///     .with_note("variables starting with '_' are not reported");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    /// See [`Severity`]
+    pub severity: Severity,
+    /// Stable error code, such as `L0101`
+    pub code: &'static str,
+    /// Single line summary of the diagnostic
+    pub message: String,
+    /// Primary labels are listed before secondary ones when rendered
+    pub labels: Vec<Label>,
+    /// Additional context, rendered after the source snippet
+    pub notes: Vec<String>,
+    /// Suggestions for fixing the reported issue, rendered after the notes
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Diagnostic without any labels, notes or help.
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    /// Shorthand for [`Self::new`] with [`Severity::Error`]
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    /// Adds a [`LabelStyle::Primary`] label
+    pub fn with_primary_label(self, span: Span, message: Option<&str>) -> Self {
+        self.with_label(span, message, LabelStyle::Primary)
+    }
+
+    /// Adds a [`LabelStyle::Secondary`] label
+    pub fn with_secondary_label(self, span: Span, message: Option<&str>) -> Self {
+        self.with_label(span, message, LabelStyle::Secondary)
+    }
+
+    /// Adds a note
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Adds a help message
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    fn with_label(mut self, span: Span, message: Option<&str>, style: LabelStyle) -> Self {
+        self.labels.push(Label { span, message: message.map(Into::into), style });
+        self
+    }
+}
@@ -0,0 +1,447 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::{diagnostic::*, line_index::*};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Renders a [`Diagnostic`] as an annotated source snippet, in the style of `rustc`:
+///
+/// ```text
+/// error[L0801]: unknown syntax following '#'
+///  --> main.scm:2:3
+///   |
+/// 2 |   #z
+///   |   ^^
+///   |
+///   = help: booleans are written as '#t', '#true', '#f' or '#false'
+/// ```
+///
+/// Line and column numbers are one-based, columns being counted in characters. Labels spanning
+/// multiple lines only show their first and last line.
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer {
+    ansi_colors: bool,
+}
+
+impl Renderer {
+    /// Renderer without any ANSI escape codes
+    pub fn plain() -> Self {
+        Self { ansi_colors: false }
+    }
+
+    /// Renderer coloring severities, labels and the line number gutter with ANSI escape codes
+    pub fn ansi() -> Self {
+        Self { ansi_colors: true }
+    }
+
+    /// `line_index` must be built from the source the diagnostic spans point into.
+    pub fn render(&self, diagnostic: &Diagnostic, file_name: &str, line_index: &LineIndex) -> String {
+        let mut output = String::new();
+
+        // Writing to a `String` never fails
+        self.write_diagnostic(&mut output, diagnostic, file_name, line_index)
+            .expect("infallible String write");
+
+        output
+    }
+
+    fn write_diagnostic(&self, output: &mut String, diagnostic: &Diagnostic, file_name: &str, line_index: &LineIndex) -> core::fmt::Result {
+        let (severity_name, severity_color) = match diagnostic.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+        };
+
+        writeln!(
+            output,
+            "{}{}",
+            self.paint(severity_color, format_args!("{severity_name}[{}]", diagnostic.code)),
+            self.paint(BOLD, format_args!(": {}", diagnostic.message))
+        )?;
+
+        let mut labels = diagnostic.labels.iter().collect::<Vec<_>>();
+        // stable sort, keeping insertion order within each style
+        labels.sort_by_key(|label| label.style != LabelStyle::Primary);
+
+        let label_lines = labels.iter().map(|label| LabelLines::new(label, line_index)).collect::<Vec<_>>();
+
+        let mut displayed_lines = label_lines
+            .iter()
+            .flat_map(|label_lines| [label_lines.first_line, label_lines.last_line])
+            .collect::<Vec<_>>();
+        displayed_lines.sort_unstable();
+        displayed_lines.dedup();
+
+        // one-based line number digits
+        let gutter_width = displayed_lines.last().map_or(1, |last_line| (last_line + 1).ilog10() as usize + 1);
+        let gutter_padding = Padding(gutter_width);
+
+        if let Some(first_label) = labels.first() {
+            let start = line_index.line_column(first_label.span.start(), ColumnUnit::Char);
+            writeln!(
+                output,
+                "{gutter_padding}{} {file_name}:{}:{}",
+                self.paint(BLUE, "-->"),
+                start.line + 1,
+                start.column + 1
+            )?;
+        }
+
+        if !displayed_lines.is_empty() {
+            writeln!(output, "{gutter_padding} {}", self.paint(BLUE, "|"))?;
+        }
+
+        let mut previous_line = None;
+
+        for line in displayed_lines.iter().copied() {
+            if previous_line.is_some_and(|previous_line| previous_line + 1 < line) {
+                writeln!(output, "{}", self.paint(BLUE, "..."))?;
+            }
+            previous_line = Some(line);
+
+            let line_str = line_index.line_str(line).unwrap_or_default();
+            let line_number = line + 1;
+            writeln!(
+                output,
+                "{} {}",
+                self.paint(BLUE, format_args!("{line_number:>gutter_width$} |")),
+                line_str
+            )?;
+
+            for (label, label_lines) in labels.iter().zip(&label_lines) {
+                if line != label_lines.first_line && line != label_lines.last_line {
+                    continue;
+                }
+
+                self.write_underline(output, gutter_padding, line_str, label, label_lines, line)?;
+            }
+        }
+
+        if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+            writeln!(output, "{gutter_padding} {}", self.paint(BLUE, "|"))?;
+        }
+
+        for note in &diagnostic.notes {
+            writeln!(
+                output,
+                "{gutter_padding} {} {}: {note}",
+                self.paint(BLUE, "="),
+                self.paint(BOLD, "note")
+            )?;
+        }
+
+        for help in &diagnostic.help {
+            writeln!(
+                output,
+                "{gutter_padding} {} {}: {help}",
+                self.paint(BLUE, "="),
+                self.paint(BOLD, "help")
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_underline(
+        &self,
+        output: &mut String,
+        gutter_padding: Padding,
+        line_str: &str,
+        label: &Label,
+        label_lines: &LabelLines,
+        line: usize,
+    ) -> core::fmt::Result {
+        let line_char_count = line_str.chars().count();
+
+        let start_column = if line == label_lines.first_line {
+            label_lines.start_column
+        } else {
+            0
+        };
+        let end_column = if line == label_lines.last_line {
+            label_lines.end_column
+        } else {
+            line_char_count
+        };
+
+        // Clamped as spans may include line endings. Zero width spans, such as a span containing
+        // only a line ending, are underlined with a single marker.
+        let start_column = start_column.min(line_char_count);
+        let underline_width = end_column.min(line_char_count).saturating_sub(start_column).max(1);
+
+        // Tabs are kept in order to align with the source line
+        let indentation = line_str
+            .chars()
+            .take(start_column)
+            .map(|char| if char == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let (marker, color) = match label.style {
+            LabelStyle::Primary => ('^', RED),
+            LabelStyle::Secondary => ('-', BLUE),
+        };
+
+        let underline = core::iter::repeat_n(marker, underline_width).collect::<String>();
+
+        write!(
+            output,
+            "{gutter_padding} {} {indentation}{}",
+            self.paint(BLUE, "|"),
+            self.paint(color, &underline)
+        )?;
+
+        match &label.message {
+            Some(message) if line == label_lines.last_line => writeln!(output, " {}", self.paint(color, message)),
+            _ => writeln!(output),
+        }
+    }
+
+    fn paint<T: core::fmt::Display>(&self, color: &'static str, text: T) -> Painted<T> {
+        Painted { color: self.ansi_colors.then_some(color), text }
+    }
+}
+
+/// Line and column positions of a label span
+struct LabelLines {
+    first_line: usize,
+    /// Line containing the last character, end offset being exclusive
+    last_line: usize,
+    start_column: usize,
+    /// Column on `last_line`
+    end_column: usize,
+}
+
+impl LabelLines {
+    fn new(label: &Label, line_index: &LineIndex) -> Self {
+        let start = line_index.line_column(label.span.start(), ColumnUnit::Char);
+        let end = line_index.line_column(label.span.end(), ColumnUnit::Char);
+
+        // `Span` is never empty
+        let last_line = line_index.line(label.span.end() - 1);
+
+        // End offset is at the start of the next line if the span ends with a line ending
+        let end_column = if end.line == last_line { end.column } else { usize::MAX };
+
+        Self {
+            first_line: start.line,
+            last_line,
+            start_column: start.column,
+            end_column,
+        }
+    }
+}
+
+/// Text optionally wrapped in an ANSI color code
+struct Painted<T> {
+    color: Option<&'static str>,
+    text: T,
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.color {
+            Some(color) => write!(f, "{color}{}{RESET}", self.text),
+            None => write!(f, "{}", self.text),
+        }
+    }
+}
+
+/// Whitespace matching the line number gutter width
+#[derive(Clone, Copy)]
+struct Padding(usize);
+
+impl core::fmt::Display for Padding {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:width$}", "", width = self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(src: &str, diagnostic: &Diagnostic) -> String {
+        Renderer::plain().render(diagnostic, "test.scm", &LineIndex::new(src))
+    }
+
+    fn span(src: &str, start: usize, end: usize) -> Span {
+        Span::new(src, start, end)
+    }
+
+    #[test]
+    fn primary_label() {
+        let src = "(a\n  #z)";
+        let diagnostic = Diagnostic::error("L0801", "unknown syntax following '#'").with_primary_label(span(src, 5, 7), Some("here"));
+
+        let expected = "\
+error[L0801]: unknown syntax following '#'
+ --> test.scm:2:3
+  |
+2 |   #z)
+  |   ^^ here
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn secondary_label_and_gap() {
+        let src = "(define (f)\n  1\n  2\n  (g))";
+        let diagnostic = Diagnostic::error("E0001", "message")
+            .with_secondary_label(span(src, 8, 11), Some("context"))
+            .with_primary_label(span(src, 22, 25), None);
+
+        let expected = "\
+error[E0001]: message
+ --> test.scm:4:3
+  |
+1 | (define (f)
+  |         --- context
+...
+4 |   (g))
+  |   ^^^
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn multiple_labels_on_one_line() {
+        let src = "(a b c)";
+        let diagnostic = Diagnostic::error("E0001", "message")
+            .with_primary_label(span(src, 3, 4), Some("first"))
+            .with_secondary_label(span(src, 1, 2), Some("second"));
+
+        let expected = "\
+error[E0001]: message
+ --> test.scm:1:4
+  |
+1 | (a b c)
+  |    ^ first
+  |  - second
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn multiline_label() {
+        let src = "\"abc\ndef\" x";
+        let diagnostic = Diagnostic::error("E0001", "message").with_primary_label(span(src, 0, 9), Some("string"));
+
+        let expected = "\
+error[E0001]: message
+ --> test.scm:1:1
+  |
+1 | \"abc
+  | ^^^^
+2 | def\" x
+  | ^^^^ string
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn line_ending_label() {
+        let src = "a\r\nb";
+        let diagnostic = Diagnostic::error("E0001", "message").with_primary_label(span(src, 1, 3), None);
+
+        let expected = "\
+error[E0001]: message
+ --> test.scm:1:2
+  |
+1 | a
+  |  ^
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn multi_byte_and_tab_alignment() {
+        let src = "\tλλ x";
+        let diagnostic = Diagnostic::error("E0001", "message").with_primary_label(span(src, 6, 7), None);
+
+        let expected = "\
+error[E0001]: message
+ --> test.scm:1:5
+  |
+1 | \tλλ x
+  | \t   ^
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn gutter_width() {
+        let src = "\n\n\n\n\n\n\n\n\nabc";
+        let diagnostic = Diagnostic::error("E0001", "message").with_primary_label(span(src, 9, 12), None);
+
+        let expected = "\
+error[E0001]: message
+  --> test.scm:10:1
+   |
+10 | abc
+   | ^^^
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn notes_and_help() {
+        let src = "x";
+        let diagnostic = Diagnostic::new(Severity::Warning, "W0001", "message")
+            .with_primary_label(span(src, 0, 1), None)
+            .with_note("a note")
+            .with_help("some help");
+
+        let expected = "\
+warning[W0001]: message
+ --> test.scm:1:1
+  |
+1 | x
+  | ^
+  |
+  = note: a note
+  = help: some help
+";
+        assert_eq!(expected, render(src, &diagnostic));
+    }
+
+    #[test]
+    fn without_labels() {
+        let diagnostic = Diagnostic::error("E0001", "message").with_note("a note");
+
+        let expected = "\
+error[E0001]: message
+  |
+  = note: a note
+";
+        assert_eq!(expected, render("", &diagnostic));
+    }
+
+    #[test]
+    fn ansi_colors() {
+        let src = "x";
+        let diagnostic = Diagnostic::error("E0001", "message").with_primary_label(span(src, 0, 1), Some("label"));
+        let rendered = Renderer::ansi().render(&diagnostic, "test.scm", &LineIndex::new(src));
+
+        assert!(rendered.starts_with("\x1b[1;31merror[E0001]\x1b[0m\x1b[1m: message\x1b[0m\n"));
+        assert!(rendered.contains("\x1b[1;31m^\x1b[0m \x1b[1;31mlabel\x1b[0m"));
+    }
+
+    #[test]
+    fn tokenize_error() {
+        let src = "(display \"abc)\n";
+        let error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected = "\
+error[L0101]: end of file reached, no closing '\"' found
+ --> test.scm:1:10
+  |
+1 | (display \"abc)
+  |          ^ unterminated string
+";
+        assert_eq!(expected, render(src, &error.to_diagnostic()));
+    }
+}
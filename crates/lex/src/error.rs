@@ -3,10 +3,34 @@ use thiserror::Error;
 use crate::*;
 
 /// Error returned by [`Lexer::tokenize_all`]
+///
+/// See [`ToDiagnostic`](crate::diagnostic::ToDiagnostic) for reporting.
 // NOTE: thiserror currently not being used because it requires inner errors
 // to be `dyn Error + 'static`. No `'src` lifetime allowed that is.
-#[derive(Debug, PartialEq, Error)]
+#[derive(Debug, PartialEq, Error, Spanned)]
 pub enum TokenizeError {
+    /// String literal
     #[error("failed to tokenize string")]
     String(#[from] StringLiteralScanError),
+    /// Identifier, vertical identifiers included
+    #[error("failed to tokenize identifier")]
+    Identifier(#[from] IdentifierScanError),
+    /// Character literal
+    #[error("failed to tokenize character")]
+    Character(#[from] CharacterLiteralScanError),
+    /// Number literal
+    #[error("failed to tokenize number")]
+    Number(#[from] NumberLiteralScanError),
+    /// Nested comment
+    #[error("failed to tokenize nested comment")]
+    NestedComment(#[from] NestedCommentScanError),
+    /// `#!` directive
+    #[error("failed to tokenize directive")]
+    Directive(#[from] DirectiveScanError),
+    /// Inner span points to the `#` and any subsequent characters up until the next delimiter
+    #[error("unknown syntax following '#'")]
+    UnknownHashSyntax(Span),
+    /// Inner span points to the entire datum label
+    #[error("datum label is too large, labels must fit in an u64")]
+    DatumLabelOutOfBounds(Span),
 }
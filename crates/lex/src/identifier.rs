@@ -1,81 +1,201 @@
 mod core {
+    use alloc::{borrow::Cow, string::String};
+
     use crate::*;
 
     /// Known in some contexts as "Symbol".
     #[derive(Debug, PartialEq, Spanned)]
     pub enum Identifier<'src> {
+        /// `abc`
         Simple(SimpleIdentifier<'src>),
+        /// `|a b c|`
         Vertical(VerticalIdentifier<'src>),
+        /// `+`, `-`, `...` or `->abc`
         Peculiar(PeculiarIdentifier<'src>),
     }
+
+    impl<'src> Identifier<'src> {
+        /// Symbol name, case folded if a `#!fold-case` directive was in effect. Vertical
+        /// identifiers have their escapes resolved and exclude the enclosing `|`s.
+        pub fn name(&self) -> Cow<'src, str> {
+            match self {
+                Identifier::Simple(simple) => simple.inner.clone(),
+                Identifier::Peculiar(peculiar) => peculiar.inner.clone(),
+                Identifier::Vertical(vertical) => match vertical.inner.as_slice() {
+                    [] => Cow::Borrowed(""),
+                    [SymbolElement::Str(str)] => Cow::Borrowed(str),
+                    elements => {
+                        let mut name = String::new();
+
+                        for element in elements {
+                            match element {
+                                SymbolElement::MnemonicEscape(mnemonic_escape) => name.push(mnemonic_escape.char()),
+                                SymbolElement::InlineCodePoint(inline_code_point) => name.push(inline_code_point.inner()),
+                                SymbolElement::VerticalLineEscape => name.push('|'),
+                                SymbolElement::Str(str) => name.push_str(str),
+                            }
+                        }
+
+                        Cow::Owned(name)
+                    }
+                },
+            }
+        }
+    }
 }
-pub(crate) use core::Identifier;
+pub use core::Identifier;
 
 mod simple {
+    use alloc::borrow::Cow;
+
     use crate::*;
 
-    // TODO: if not(unicode_identifiers):
-    // ascii_initial =  |char| .is_ascii_alphabetic() || is_ascii_non_letter
-    // TODO: if (unicode_identifiers):
-    // char = ascii_initial || (!.is_ascii() && in unicode category)
-    //
-    // Unicode category: Ll, Lt, Lm, Lo, Nd, Nl, No, Pd, Pc, Po, Sc, Sm, Sk, So, or Co categories or is
-    // U+200C or U+200D
-    //
-    //  ASCII Non letter: `! | $ | % | & | * | / | : | < | = | > | ? | @ | ^ | _ | ~`
+    /// EBNF: `<Letter> | <SpecialInitial>`
+    ///
+    /// ASCII Non letter: `! | $ | % | & | * | / | : | < | = | > | ? | @ | ^ | _ | ~`
+    ///
+    /// With `unicode_identifiers`, non-ASCII characters are also valid if they are in one of the
+    /// Lu, Ll, Lt, Lm, Lo, Mn, Nl, No, Pd, Pc, Po, Sc, Sm, Sk, So, or Co general categories, or
+    /// are U+200C or U+200D.
     #[derive(Debug, PartialEq)]
-    pub struct SimpleInitial(char);
-
-    // TODO: if not(unicode_identifiers):
-    // ascii_subsequent =  |char| .is_ascii_alphanumberic() || is_ascii_non_letter
-    // TODO: if (unicode_identifiers):
-    // char = ascii_initial || (!.is_ascii() && in unicode category)
-    //
-    // Unicode category: Lu, Ll, Lt, Lm, Lo, Mn, Mc, Me, Nd, Nl, No, Pd, Pc, Po, Sc, Sm, Sk, So, or
-    // Co, or is U+200C or U+200DG
-    //
-    // ASCII Non Letter: `! | $ | % | & | * | / | : | < | = | > | ? | @ | ^ | _ | ~ | @ | + | - | .`
-    //
-    // Digit: 0..9
+    pub struct SimpleInitial;
+
+    impl SimpleInitial {
+        pub(crate) fn is_valid(char: char) -> bool {
+            char.is_ascii_alphabetic() || Self::is_special_initial(char) || super::unicode::is_initial(char)
+        }
+
+        fn is_special_initial(char: char) -> bool {
+            matches!(
+                char,
+                '!' | '$' | '%' | '&' | '*' | '/' | ':' | '<' | '=' | '>' | '?' | '@' | '^' | '_' | '~'
+            )
+        }
+    }
+
+    /// EBNF: `<SimpleInitial> | <Digit> | <SpecialSubsequent>`
+    ///
+    /// Special subsequent: `+ | - | . | @`
+    ///
+    /// With `unicode_identifiers`, non-ASCII characters of the Nd, Mc and Me general categories
+    /// are valid in addition to those valid as a [`SimpleInitial`].
     #[derive(Debug, PartialEq)]
-    pub struct SimpleSubsequent(char);
+    pub struct SimpleSubsequent;
+
+    impl SimpleSubsequent {
+        pub(crate) fn is_valid(char: char) -> bool {
+            SimpleInitial::is_valid(char)
+                || char.is_ascii_digit()
+                || matches!(char, '+' | '-' | '.' | '@')
+                || super::unicode::is_subsequent(char)
+        }
+    }
 
     /// EBNF: `<SimpleInitial> <SimpleSubsequent>*`
     #[derive(Debug, PartialEq, Spanned)]
     pub struct SimpleIdentifier<'src> {
-        inner: &'src str,
+        /// Owned only if case folded by a `#!fold-case` directive
+        pub(crate) inner: Cow<'src, str>,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 }
 pub(crate) use simple::{SimpleIdentifier, SimpleInitial, SimpleSubsequent};
 
+/// Unicode general category checks for the `unicode_identifiers` feature.
+///
+/// Only ever true for non-ASCII characters, an ASCII character not permitted by the ASCII
+/// grammar stays forbidden even if its general category is allowed. `\` (Po) being one example.
+/// Without the feature, all non-ASCII characters are rejected.
+mod unicode {
+    #[cfg(feature = "unicode_identifiers")]
+    use unicode_general_category::{get_general_category, GeneralCategory};
+
+    /// U+200C ZERO WIDTH NON-JOINER and U+200D ZERO WIDTH JOINER, both in the otherwise
+    /// disallowed Cf category.
+    #[cfg(feature = "unicode_identifiers")]
+    const ZERO_WIDTH_JOINERS: [char; 2] = ['\u{200C}', '\u{200D}'];
+
+    #[cfg(feature = "unicode_identifiers")]
+    pub(super) fn is_initial(char: char) -> bool {
+        use GeneralCategory::*;
+
+        !char.is_ascii()
+            && (ZERO_WIDTH_JOINERS.contains(&char)
+                || matches!(
+                    get_general_category(char),
+                    UppercaseLetter
+                        | LowercaseLetter
+                        | TitlecaseLetter
+                        | ModifierLetter
+                        | OtherLetter
+                        | NonspacingMark
+                        | LetterNumber
+                        | OtherNumber
+                        | DashPunctuation
+                        | ConnectorPunctuation
+                        | OtherPunctuation
+                        | CurrencySymbol
+                        | MathSymbol
+                        | ModifierSymbol
+                        | OtherSymbol
+                        | PrivateUse
+                ))
+    }
+
+    /// Categories valid only as subsequent characters, R7RS forbids identifiers from starting
+    /// with them.
+    #[cfg(feature = "unicode_identifiers")]
+    pub(super) fn is_subsequent(char: char) -> bool {
+        use GeneralCategory::*;
+
+        !char.is_ascii() && matches!(get_general_category(char), DecimalNumber | SpacingMark | EnclosingMark)
+    }
+
+    #[cfg(not(feature = "unicode_identifiers"))]
+    pub(super) fn is_initial(_char: char) -> bool {
+        false
+    }
+
+    #[cfg(not(feature = "unicode_identifiers"))]
+    pub(super) fn is_subsequent(_char: char) -> bool {
+        false
+    }
+}
+
 mod vertical {
     use alloc::vec::Vec;
 
     use crate::*;
 
+    /// EBNF: `| <SymbolElement>* |`
+    ///
+    /// Never case folded, not even if a `#!fold-case` directive is in effect.
     #[derive(Debug, PartialEq, Spanned)]
     pub struct VerticalIdentifier<'src> {
-        inner: Vec<SymbolElement<'src>>,
+        pub(crate) inner: Vec<SymbolElement<'src>>,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 
-    /// EBNF: `<inline hex escape>` | `<mnemonic escape>` | `<any character except '|' or '\'>`
-    // TODO: any other character must still be valid ascii, or part a allowed unicode group, `SimpleSubsequent`
+    /// EBNF: `<inline hex escape>` | `<mnemonic escape>` | `\|` | `<SymbolElementCharacter>`
     #[derive(Debug, PartialEq)]
     pub enum SymbolElement<'src> {
         MnemonicEscape(MnemonicEscape),
         InlineCodePoint(InlineCodePoint),
+        /// EBNF: `\|`
+        VerticalLineEscape,
+        /// Any character other than `|` and `\`, whatever the `unicode_identifiers` feature
+        ///
+        /// Stored as one continuous string slice, see [`StringElement::Chars`].
         Str(&'src str),
     }
-
-    pub struct SymbolElementCharacter(char);
 }
-pub(crate) use vertical::VerticalIdentifier;
+pub(crate) use vertical::{SymbolElement, VerticalIdentifier};
 
 mod peculiar {
+    use alloc::borrow::Cow;
+
     use crate::*;
 
     /// Invalid exceptions: +i and -i and ifnan
@@ -84,19 +204,58 @@ mod peculiar {
     /// EBNF `[<Sign>] . <DotSubsequent> <Subsequent>*`
     #[derive(Debug, PartialEq, Spanned)]
     pub struct PeculiarIdentifier<'src> {
-        /// Span can't be reused trivially for str
-        /// as it includes surrounding quotes
-        inner: &'src str,
+        /// Owned only if case folded by a `#!fold-case` directive
+        pub(crate) inner: Cow<'src, str>,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 
     /// EBNF: `<SimpleInitial> | <Sign> | @`
     #[derive(Debug, PartialEq)]
-    pub struct SignSubsequent(char);
+    pub struct SignSubsequent;
+
+    impl SignSubsequent {
+        pub(crate) fn is_valid(char: char) -> bool {
+            SimpleInitial::is_valid(char) || matches!(char, '+' | '-' | '@')
+        }
+    }
 
     /// EBNF: <SignSubsequent> | .
     #[derive(Debug, PartialEq)]
-    pub struct DotSubsequent(char);
+    pub struct DotSubsequent;
+
+    impl DotSubsequent {
+        pub(crate) fn is_valid(char: char) -> bool {
+            SignSubsequent::is_valid(char) || char == '.'
+        }
+    }
+}
+pub(crate) use peculiar::{DotSubsequent, PeculiarIdentifier, SignSubsequent};
+
+mod error {
+    use thiserror::Error;
+
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Error, Spanned)]
+    pub enum IdentifierScanError {
+        /// Inner span points to the invalid character
+        #[error("invalid initial identifier character, expected a letter or one of '!$%&*/:<=>?@^_~'")]
+        InvalidInitial(Span),
+        /// Inner span points to the invalid character
+        #[error("invalid identifier character, expected a letter, digit or one of '!$%&*/:<=>?@^_~+-.'")]
+        InvalidSubsequent(Span),
+        /// Inner span points to the entire identifier
+        #[error("incomplete peculiar identifier, '+.' and '-.' must be followed by another character")]
+        IncompletePeculiar(Span),
+        /// Inner span points to the entire vertical identifier
+        #[error("end of file reached, no closing '|' found")]
+        EndOfFile(Span),
+        /// Inner span points to the backslash and the escaped character
+        #[error("unknown escape character, expected one of '|', 'x', 'X', 'a', 'b', 't', 'n' or 'r'")]
+        UnknownEscape(Span),
+        #[error("invalid inline code point (inline hex escape)")]
+        InlineHex(#[from] InlineCodePointScanError),
+    }
 }
-pub(crate) use peculiar::PeculiarIdentifier;
+pub(crate) use error::IdentifierScanError;
@@ -0,0 +1,33 @@
+use crate::*;
+
+/// Marks a datum for later references, allowing shared and cyclic structures to be read.
+///
+/// EBNF: `# <DecimalDigit>+ = | # <DecimalDigit>+ #`
+#[derive(Debug, PartialEq, Spanned)]
+pub struct DatumLabel {
+    pub(crate) label: u64,
+    pub(crate) variant: DatumLabelVariant,
+    #[span]
+    pub(crate) span: Span,
+}
+
+impl DatumLabel {
+    /// Decimal label number, leading zeros are insignificant
+    pub fn label(&self) -> u64 {
+        self.label
+    }
+
+    /// Whether the label is defined or referenced
+    pub fn variant(&self) -> DatumLabelVariant {
+        self.variant
+    }
+}
+
+/// See [`DatumLabel`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DatumLabelVariant {
+    /// EBNF: `# <DecimalDigit>+ =`, labels the datum that follows
+    Definition,
+    /// EBNF: `# <DecimalDigit>+ #`, stands for the previously labeled datum
+    Reference,
+}
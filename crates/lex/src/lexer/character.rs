@@ -0,0 +1,222 @@
+use crate::*;
+
+impl Lexer<'_> {
+    /// `#\` scanned
+    ///
+    /// `start_index` points to the `#`
+    ///
+    /// EBNF: `#\<any char> | #\<CharacterNameVariant> | #\x<HexadecimalDigit>+`
+    ///
+    /// A character literal must be followed by a delimiter, but the first character may itself be
+    /// a delimiter. `#\(` is thus the character `(`, while `#\space` is a named character.
+    pub(super) fn scan_character(&mut self, start_index: usize) -> Result<(), CharacterLiteralScanError> {
+        let Some((char_index, char)) = self.scanner.next() else {
+            let span = self.scanner.span_to_end_of_file(start_index);
+            return Err(CharacterLiteralScanError::EndOfFile(span));
+        };
+
+        let end_index = self.scanner.scan_until_delimiter();
+        let span = self.scanner.span(start_index, end_index);
+
+        let literal_str = &self.scanner.src()[char_index..end_index];
+
+        let character = if literal_str.len() == char.len_utf8() {
+            CharacterLiteral::Simple(CharacterSimple { inner: char, span })
+        } else if let Some(code_point) = Self::scan_character_code_point(literal_str, span)? {
+            CharacterLiteral::CodePoint(CharacterCodePoint { inner: code_point, span })
+        } else {
+            let name = self.maybe_fold_case(literal_str);
+
+            let Some(name_variant) = CharacterNameVariant::from_name(&name) else {
+                return Err(CharacterLiteralScanError::UnknownName(span));
+            };
+
+            CharacterLiteral::Name(CharacterName { inner: name_variant, span })
+        };
+
+        self.push_token(Token::Character(character));
+
+        Ok(())
+    }
+
+    /// Returns `None` if `literal_str` isn't an `x` followed by hexadecimal digits only, in
+    /// which case it might still be a character name.
+    fn scan_character_code_point(literal_str: &str, span: Span) -> Result<Option<char>, InlineCodePointScanError> {
+        let Some(hex_str) = literal_str.strip_prefix(['x', 'X']) else {
+            return Ok(None);
+        };
+
+        if !hex_str.chars().all(|char| char.is_ascii_hexdigit()) {
+            return Ok(None);
+        }
+
+        let code_point = hex_str.chars().try_fold(0u32, |code_point, char| {
+            let digit = char.to_digit(HexadecimalDigit::RADIX).expect("validated hex digit");
+            code_point
+                .checked_mul(HexadecimalDigit::RADIX)
+                .map(|shifted_code_point| shifted_code_point + digit)
+        });
+
+        let Some(code_point) = code_point else {
+            return Err(InlineCodePointScanError::OutOfBounds(span));
+        };
+
+        InlineCodePoint::new(span, code_point).map(|inline_code_point| Some(inline_code_point.inner()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_character(src: &str) -> CharacterLiteral {
+        match Lexer::new(src).tokenize_all().unwrap().remove(0) {
+            TokenAll::Token(Token::Character(character)) => character,
+            other => panic!("expected character token, got {:?}", other),
+        }
+    }
+
+    fn scan_error(src: &str) -> CharacterLiteralScanError {
+        match Lexer::new(src).tokenize_all().unwrap_err() {
+            TokenizeError::Character(error) => error,
+            other => panic!("expected character error, got {:?}", other),
+        }
+    }
+
+    fn expected_simple(src: &str, char: char) {
+        let expected = CharacterLiteral::Simple(CharacterSimple { inner: char, span: Span::new(src, 0, src.len()) });
+        assert_eq!(expected, scan_character(src));
+    }
+
+    fn expected_code_point(src: &str, char: char) {
+        let expected = CharacterLiteral::CodePoint(CharacterCodePoint { inner: char, span: Span::new(src, 0, src.len()) });
+        assert_eq!(expected, scan_character(src));
+    }
+
+    fn expected_name(src: &str, variant: CharacterNameVariant) {
+        let expected = CharacterLiteral::Name(CharacterName { inner: variant, span: Span::new(src, 0, src.len()) });
+        assert_eq!(expected, scan_character(src));
+    }
+
+    #[test]
+    fn simple() {
+        expected_simple("#\\a", 'a');
+        expected_simple("#\\λ", 'λ');
+        expected_simple("#\\ ", ' ');
+        expected_simple("#\\A", 'A');
+    }
+
+    #[test]
+    fn x_alone_is_simple() {
+        expected_simple("#\\x", 'x');
+        expected_simple("#\\X", 'X');
+    }
+
+    #[test]
+    fn delimiter_as_character() {
+        expected_simple("#\\(", '(');
+        expected_simple("#\\)", ')');
+        expected_simple("#\\;", ';');
+        expected_simple("#\\|", '|');
+        expected_simple("#\\\"", '"');
+
+        // trailing delimiter is scanned as a separate token
+        let src = "#\\((";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        assert_eq!(2, tokens.len());
+    }
+
+    #[test]
+    fn code_point() {
+        expected_code_point("#\\x3bb", 'λ');
+        expected_code_point("#\\x41", 'A');
+        expected_code_point("#\\X1F4AF", '💯');
+        expected_code_point("#\\x0", '\0');
+    }
+
+    #[test]
+    fn names() {
+        expected_name("#\\alarm", CharacterNameVariant::Alarm);
+        expected_name("#\\backspace", CharacterNameVariant::Backspace);
+        expected_name("#\\delete", CharacterNameVariant::Delete);
+        expected_name("#\\escape", CharacterNameVariant::Escape);
+        expected_name("#\\newline", CharacterNameVariant::Newline);
+        expected_name("#\\null", CharacterNameVariant::Null);
+        expected_name("#\\return", CharacterNameVariant::Return);
+        expected_name("#\\space", CharacterNameVariant::Space);
+        expected_name("#\\tab", CharacterNameVariant::Tab);
+    }
+
+    #[test]
+    fn delimited_name() {
+        let src = "(#\\space)";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let expected = TokenAll::Token(Token::Character(CharacterLiteral::Name(CharacterName {
+            inner: CharacterNameVariant::Space,
+            span: Span::new(src, 1, 8),
+        })));
+        assert_eq!(expected, tokens[1]);
+        assert_eq!(3, tokens.len());
+    }
+
+    #[test]
+    fn value() {
+        assert_eq!('a', scan_character("#\\a").value());
+        assert_eq!('λ', scan_character("#\\x3bb").value());
+        assert_eq!(' ', scan_character("#\\space").value());
+        assert_eq!('\u{7F}', scan_character("#\\delete").value());
+    }
+
+    #[test]
+    fn names_are_case_sensitive() {
+        let src = "#\\SPACE";
+        assert_eq!(CharacterLiteralScanError::UnknownName(Span::new(src, 0, 7)), scan_error(src));
+    }
+
+    #[test]
+    fn names_folded_by_directive() {
+        let src = "#!fold-case #\\SPACE #\\A";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let expected_name = TokenAll::Token(Token::Character(CharacterLiteral::Name(CharacterName {
+            inner: CharacterNameVariant::Space,
+            span: Span::new(src, 12, 19),
+        })));
+        assert_eq!(expected_name, tokens[1]);
+
+        // single characters are not names, and thus not folded
+        let expected_simple = TokenAll::Token(Token::Character(CharacterLiteral::Simple(CharacterSimple {
+            inner: 'A',
+            span: Span::new(src, 20, 23),
+        })));
+        assert_eq!(expected_simple, tokens[2]);
+    }
+
+    #[test]
+    fn unknown_name_error() {
+        let src = "#\\foo";
+        assert_eq!(CharacterLiteralScanError::UnknownName(Span::new(src, 0, 5)), scan_error(src));
+
+        // not all hexadecimal digits
+        let src = "#\\x4g";
+        assert_eq!(CharacterLiteralScanError::UnknownName(Span::new(src, 0, 5)), scan_error(src));
+    }
+
+    #[test]
+    fn invalid_code_point_error() {
+        let src = "#\\xD800";
+        let expected = CharacterLiteralScanError::CodePoint(InlineCodePointScanError::InvalidCodePoint(Span::new(src, 0, 7)));
+        assert_eq!(expected, scan_error(src));
+
+        let src = "#\\xFFFFFFFFF";
+        let expected = CharacterLiteralScanError::CodePoint(InlineCodePointScanError::OutOfBounds(Span::new(src, 0, 12)));
+        assert_eq!(expected, scan_error(src));
+    }
+
+    #[test]
+    fn end_of_file_error() {
+        let src = "#\\";
+        assert_eq!(CharacterLiteralScanError::EndOfFile(Span::new(src, 0, 2)), scan_error(src));
+    }
+}
@@ -0,0 +1,235 @@
+use alloc::vec::Vec;
+
+use crate::*;
+
+impl<'src> Lexer<'src> {
+    /// `;` scanned
+    pub(super) fn scan_semicolon_comment(&mut self, start_index: usize) {
+        let (end_index, comment_str) = self.scanner.scan_until_line_ending();
+
+        let span = self.scanner.span(start_index, end_index);
+
+        let semicolon_comment = SemicolonComment { inner: comment_str, span };
+        let comment_token = TokenAll::InterToken(Atmosphere::Comment(Comment::Semicolon(semicolon_comment)));
+
+        self.push_token_all(comment_token);
+    }
+
+    /// `#|` scanned
+    ///
+    /// `start_index` points to the `#`.
+    pub(super) fn scan_nested_comment(&mut self, start_index: usize) -> Result<(), NestedCommentScanError> {
+        let src = self.scanner.src();
+
+        // Explicit stack rather than recursion, see `tokenize_all`.
+        // Last element is the innermost comment currently being scanned.
+        let mut open_comments = alloc::vec![PartialNestedComment::new(start_index)];
+
+        loop {
+            let Some((char_index, char)) = self.scanner.next() else {
+                let innermost = open_comments.last().expect("at least one open comment");
+                let span = self.scanner.span(innermost.start_index, innermost.start_index + 2);
+                return Err(NestedCommentScanError::EndOfFile(span));
+            };
+
+            match char {
+                '#' if self.scanner.next_if_eq('|').is_some() => {
+                    let current = open_comments.last_mut().expect("at least one open comment");
+                    current.end_text(NestedCommentText(&src[current.text_start_index..char_index]));
+
+                    open_comments.push(PartialNestedComment::new(char_index));
+                }
+                '|' if self.scanner.next_if_eq('#').is_some() => {
+                    let mut current = open_comments.pop().expect("at least one open comment");
+                    current.end_text(NestedCommentText(&src[current.text_start_index..char_index]));
+
+                    // + 2 to include `|#` in span
+                    let end_index = char_index + 2;
+                    let span = self.scanner.span(current.start_index, end_index);
+                    let nested_comment = current.finalize(span);
+
+                    match open_comments.last_mut() {
+                        Some(parent) => {
+                            parent.pending_nested_comment = Some(nested_comment);
+                            parent.text_start_index = end_index;
+                        }
+                        None => {
+                            let comment_token = TokenAll::InterToken(Atmosphere::Comment(Comment::Nested(nested_comment)));
+                            self.push_token_all(comment_token);
+
+                            return Ok(());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Nested comment whose closing `|#` has yet to be scanned.
+struct PartialNestedComment<'src> {
+    /// Index of the opening `#|`
+    start_index: usize,
+    /// Start of the text currently being scanned
+    text_start_index: usize,
+    leading_text: Option<NestedCommentText<'src>>,
+    continuations: Vec<NestedCommentContinuation<'src>>,
+    /// Closed nested comment waiting for its trailing text
+    pending_nested_comment: Option<NestedComment<'src>>,
+}
+
+impl<'src> PartialNestedComment<'src> {
+    fn new(start_index: usize) -> Self {
+        Self {
+            start_index,
+            // skip `#|`
+            text_start_index: start_index + 2,
+            leading_text: None,
+            continuations: Vec::new(),
+            pending_nested_comment: None,
+        }
+    }
+
+    /// Text ended by either an opening `#|` or a closing `|#`.
+    fn end_text(&mut self, text: NestedCommentText<'src>) {
+        match self.pending_nested_comment.take() {
+            Some(nested_comment) => self
+                .continuations
+                .push(NestedCommentContinuation { nested_comment, trailing_text: text }),
+            None => {
+                debug_assert!(self.leading_text.is_none());
+                self.leading_text = Some(text);
+            }
+        }
+    }
+
+    fn finalize(self, span: Span) -> NestedComment<'src> {
+        NestedComment {
+            leading_text: self.leading_text.expect("text ended before finalizing"),
+            nested_comment: self.continuations,
+            span,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_nested_comment(src: &str) -> NestedComment<'_> {
+        match Lexer::new(src).tokenize_all().unwrap().remove(0) {
+            TokenAll::InterToken(Atmosphere::Comment(Comment::Nested(nested_comment))) => nested_comment,
+            other => panic!("expected nested comment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn flat() {
+        let src = "#| abc |#";
+
+        let expected = NestedComment {
+            leading_text: NestedCommentText(" abc "),
+            nested_comment: Vec::new(),
+            span: Span::new(src, 0, 9),
+        };
+        assert_eq!(expected, scan_nested_comment(src));
+    }
+
+    #[test]
+    fn empty() {
+        let src = "#||#";
+
+        let expected = NestedComment {
+            leading_text: NestedCommentText(""),
+            nested_comment: Vec::new(),
+            span: Span::new(src, 0, 4),
+        };
+        assert_eq!(expected, scan_nested_comment(src));
+    }
+
+    #[test]
+    fn nested() {
+        let src = "#| a #| b |# c #| d #|e|# |# f |#";
+
+        let expected = NestedComment {
+            leading_text: NestedCommentText(" a "),
+            nested_comment: alloc::vec![
+                NestedCommentContinuation {
+                    nested_comment: NestedComment {
+                        leading_text: NestedCommentText(" b "),
+                        nested_comment: Vec::new(),
+                        span: Span::new(src, 5, 12),
+                    },
+                    trailing_text: NestedCommentText(" c "),
+                },
+                NestedCommentContinuation {
+                    nested_comment: NestedComment {
+                        leading_text: NestedCommentText(" d "),
+                        nested_comment: alloc::vec![NestedCommentContinuation {
+                            nested_comment: NestedComment {
+                                leading_text: NestedCommentText("e"),
+                                nested_comment: Vec::new(),
+                                span: Span::new(src, 20, 25),
+                            },
+                            trailing_text: NestedCommentText(" "),
+                        }],
+                        span: Span::new(src, 15, 28),
+                    },
+                    trailing_text: NestedCommentText(" f "),
+                },
+            ],
+            span: Span::new(src, 0, 33),
+        };
+        assert_eq!(expected, scan_nested_comment(src));
+    }
+
+    #[test]
+    fn code_is_commented_out() {
+        let src = "#| (define x \"|\") ; |# y";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        assert_eq!(2, tokens.len());
+        assert!(matches!(tokens[1], TokenAll::Token(Token::Identifier(_))));
+    }
+
+    #[test]
+    fn lone_vertical_line_and_hash() {
+        let src = "#| | # |#";
+        let expected = NestedComment {
+            leading_text: NestedCommentText(" | # "),
+            nested_comment: Vec::new(),
+            span: Span::new(src, 0, 9),
+        };
+        assert_eq!(expected, scan_nested_comment(src));
+    }
+
+    #[test]
+    fn section_comment() {
+        let src = "#; (a b) c";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        // Only `#;` is registered, commented out datum is left to the parser
+        let expected = TokenAll::InterToken(Atmosphere::Comment(Comment::Section(SectionComment(Span::new(src, 0, 2)))));
+        assert_eq!(expected, tokens[0]);
+        assert_eq!(6, tokens.len());
+    }
+
+    #[test]
+    fn end_of_file_error() {
+        let src = "#| abc";
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(
+            TokenizeError::NestedComment(NestedCommentScanError::EndOfFile(Span::new(src, 0, 2))),
+            actual_error
+        );
+
+        // points at innermost unclosed opener
+        let src = "#| a #| b |# c #| d";
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(
+            TokenizeError::NestedComment(NestedCommentScanError::EndOfFile(Span::new(src, 15, 17))),
+            actual_error
+        );
+    }
+}
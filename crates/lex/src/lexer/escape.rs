@@ -0,0 +1,169 @@
+use crate::*;
+
+impl Lexer<'_> {
+    /// `\x` or `\X` have already been scanned
+    ///
+    /// `start_index` points to the `\` in `\x<HexDigit>+`
+    pub(super) fn scan_inline_hex(&mut self, start_index: usize) -> Result<InlineCodePoint, InlineCodePointScanError> {
+        // Invalid characters are left unconsumed, letting error recovery resume at a possible
+        // closing `"` or `|`.
+        let Some((char_index, char)) = self.scanner.peek() else {
+            let span = self.scanner.span_to_end_of_file(start_index);
+            return Err(InlineCodePointScanError::EndOfFile(span));
+        };
+
+        if char == InlineCodePoint::TERIMINATOR {
+            self.scanner.next();
+            let span = self.scanner.span(start_index, char_index + 1);
+            return Err(InlineCodePointScanError::MissingDigit(span));
+        }
+
+        let Some(hex_value) = char.to_digit(HexadecimalDigit::RADIX) else {
+            let span = self.scanner.span_char(char_index);
+            return Err(InlineCodePointScanError::InvalidHexDigit(span));
+        };
+
+        self.scanner.next();
+        let mut current_code_point = hex_value;
+
+        loop {
+            let Some((next_char_index, next_char)) = self.scanner.peek() else {
+                let span = self.scanner.span_to_end_of_file(start_index);
+                return Err(InlineCodePointScanError::EndOfFile(span));
+            };
+
+            if next_char == InlineCodePoint::TERIMINATOR {
+                self.scanner.next();
+                let span = self.scanner.span(start_index, next_char_index + 1);
+                return InlineCodePoint::new(span, current_code_point);
+            }
+
+            let Some(next_code_point) = next_char.to_digit(HexadecimalDigit::RADIX) else {
+                let span = self.scanner.span_char(next_char_index);
+                return Err(InlineCodePointScanError::InvalidSequenceChar(span));
+            };
+
+            self.scanner.next();
+
+            let Some(shifted_prev_code_point) = current_code_point.checked_mul(HexadecimalDigit::RADIX) else {
+                let span = self.scanner.span(start_index, next_char_index + 1);
+                return Err(InlineCodePointScanError::OutOfBounds(span));
+            };
+
+            // Within bounds guaranteed by `checked_mul`.
+            current_code_point = shifted_prev_code_point + next_code_point;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii() {
+        assert_valid('💯', "1F4AF");
+    }
+
+    #[test]
+    fn emoji() {
+        assert_valid('a', "61");
+    }
+
+    #[test]
+    fn leading_zeroes() {
+        assert_valid('a', "0061");
+    }
+
+    #[test]
+    fn upper_and_lower_case() {
+        // (tests both first and subsequent chars)
+        assert_valid('אּ', "fb30");
+        assert_valid('אּ', "FB30");
+    }
+
+    #[test]
+    fn out_of_bounds_error() {
+        // Error span up until the character creating the out of bounds
+        assert_err!(InlineCodePointScanError::OutOfBounds, 0, 11, "FFFFFFFFF");
+    }
+
+    #[test]
+    fn invalid_hexadecimal_digit_error() {
+        // first character
+        assert_err!(InlineCodePointScanError::InvalidHexDigit, 2, 3, "x");
+    }
+
+    #[test]
+    fn invalid_sequence_character_error() {
+        // subsequent character
+        assert_err!(InlineCodePointScanError::InvalidSequenceChar, 3, 4, "1x");
+    }
+
+    #[test]
+    fn invalid_codepoint_error() {
+        assert_err!(InlineCodePointScanError::InvalidCodePoint, "D800");
+    }
+
+    #[test]
+    fn at_least_one_digit_error() {
+        // ; added in assert_err
+        assert_err!(InlineCodePointScanError::MissingDigit, "");
+    }
+
+    #[test]
+    fn end_of_file_error() {
+        // at first
+        assert_eof("");
+        // after first
+        assert_eof("00");
+
+        fn assert_eof(src: &str) {
+            // omit semicolon
+            let src = alloc::format!("\\x{}", src);
+
+            let mut lexer = Lexer::new(&src);
+            lexer.scanner.next();
+            lexer.scanner.next();
+
+            let start = 0;
+
+            let err = lexer.scan_inline_hex(start).unwrap_err();
+
+            let expected_span = Span::new(&src, start, src.len());
+            let expected_error = InlineCodePointScanError::EndOfFile(expected_span);
+            assert_eq!(expected_error, err);
+        }
+    }
+
+    fn assert_valid(expected_char: char, hex_str: &str) {
+        let hex_str = alloc::format!("\\x{};", hex_str);
+        let mut lexer = Lexer::new(&hex_str);
+        lexer.scanner.next();
+        lexer.scanner.next();
+
+        let actual_char = lexer.scan_inline_hex(0).expect("invalid inline hex character").inner();
+        assert_eq!(expected_char, actual_char);
+    }
+
+    macro_rules! assert_err {
+        ($err_type:tt::$err_variant:tt, $hex_str:literal) => {
+            // Add 3 because of the inserted "\x" and ";"
+            assert_err!($err_type::$err_variant, 0, $hex_str.len() + 3, $hex_str)
+        };
+        ($err_type:tt::$err_variant:tt, $start:literal, $end:expr, $hex_str:literal) => {
+            let hex_str = alloc::format!("\\x{};", $hex_str);
+
+            let expected_error = $err_type::$err_variant($crate::Span::new(&hex_str, $start, $end));
+
+            let mut lexer = Lexer::new(&hex_str);
+            lexer.scanner.next();
+            lexer.scanner.next();
+
+            let actual_scan_error = lexer.scan_inline_hex(0).expect_err("expected invalid hex string");
+
+            assert_eq!(expected_error, actual_scan_error);
+        };
+    }
+    use assert_err;
+}
@@ -0,0 +1,463 @@
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+use crate::*;
+
+impl<'src> Lexer<'src> {
+    /// Validates an already delimited lexeme spanning from `start` to `end`.
+    ///
+    /// Lexeme is assumed to not be a number or a lone `.`, both of which are matched before
+    /// identifiers.
+    pub(super) fn scan_identifier(&self, start: usize, end: usize) -> Result<Identifier<'src>, IdentifierScanError> {
+        let lexeme = &self.scanner.src()[start..end];
+        let span = self.scanner.span(start, end);
+
+        let mut chars = lexeme.char_indices().map(|(index, char)| (start + index, char));
+        let inner = self.maybe_fold_case(lexeme);
+
+        // Lexeme is never empty
+        let (_, initial) = chars.next().expect("non-empty identifier lexeme");
+
+        match initial {
+            '+' | '-' => {
+                let Some((second_index, second)) = chars.next() else {
+                    return Ok(Identifier::Peculiar(PeculiarIdentifier { inner, span }));
+                };
+
+                if second == '.' {
+                    let Some((dot_subsequent_index, dot_subsequent)) = chars.next() else {
+                        return Err(IdentifierScanError::IncompletePeculiar(span));
+                    };
+
+                    if !DotSubsequent::is_valid(dot_subsequent) {
+                        return Err(IdentifierScanError::InvalidSubsequent(
+                            self.char_span(dot_subsequent_index, dot_subsequent),
+                        ));
+                    }
+                } else if !SignSubsequent::is_valid(second) {
+                    return Err(IdentifierScanError::InvalidSubsequent(self.char_span(second_index, second)));
+                }
+
+                self.validate_subsequent(chars)?;
+
+                Ok(Identifier::Peculiar(PeculiarIdentifier { inner, span }))
+            }
+            '.' => {
+                let Some((dot_subsequent_index, dot_subsequent)) = chars.next() else {
+                    // Lone dots are scanned as `TokenCharVariant::Dot`
+                    unreachable!("lone dot matched as identifier");
+                };
+
+                if !DotSubsequent::is_valid(dot_subsequent) {
+                    return Err(IdentifierScanError::InvalidSubsequent(
+                        self.char_span(dot_subsequent_index, dot_subsequent),
+                    ));
+                }
+
+                self.validate_subsequent(chars)?;
+
+                Ok(Identifier::Peculiar(PeculiarIdentifier { inner, span }))
+            }
+            _ => {
+                if !SimpleInitial::is_valid(initial) {
+                    return Err(IdentifierScanError::InvalidInitial(self.char_span(start, initial)));
+                }
+
+                self.validate_subsequent(chars)?;
+
+                Ok(Identifier::Simple(SimpleIdentifier { inner, span }))
+            }
+        }
+    }
+
+    /// Case folds `str` if a `#!fold-case` directive is in effect. Borrowed if folding leaves
+    /// `str` unchanged.
+    ///
+    /// Folding is done character by character as with R7RS `char-foldcase`, this approximates
+    /// `string-foldcase` for all but a few context dependent characters, such as the final sigma.
+    pub(super) fn maybe_fold_case(&self, str: &'src str) -> Cow<'src, str> {
+        if !self.fold_case || !str.chars().any(|char| char.to_lowercase().ne([char])) {
+            return Cow::Borrowed(str);
+        }
+
+        Cow::Owned(str.chars().flat_map(char::to_lowercase).collect::<String>())
+    }
+
+    fn validate_subsequent(&self, mut chars: impl Iterator<Item = (usize, char)>) -> Result<(), IdentifierScanError> {
+        match chars.find(|(_, char)| !SimpleSubsequent::is_valid(*char)) {
+            Some((index, char)) => Err(IdentifierScanError::InvalidSubsequent(self.char_span(index, char))),
+            None => Ok(()),
+        }
+    }
+
+    /// Span covering a single, possibly multi-byte, character
+    fn char_span(&self, index: usize, char: char) -> Span {
+        self.scanner.span(index, index + char.len_utf8())
+    }
+
+    /// `|` scanned
+    pub(super) fn scan_vertical_identifier(&mut self, start_index: usize) -> Result<(), IdentifierScanError> {
+        let mut symbol_elements = Vec::new();
+        // keeping track of start index for `SymbolElement::Str`
+        let mut str_state: Option<usize> = None;
+
+        let src = self.scanner.src();
+        let end_str = |symbol_elements: &mut Vec<SymbolElement<'src>>, str_state: &mut Option<usize>, end: usize| {
+            if let Some(str_start) = str_state.take() {
+                symbol_elements.push(SymbolElement::Str(&src[str_start..end]));
+            }
+        };
+
+        loop {
+            let Some((char_index, char)) = self.scanner.next() else {
+                let eof_span = self.scanner.span_to_end_of_file(start_index);
+                return Err(IdentifierScanError::EndOfFile(eof_span));
+            };
+
+            match char {
+                '|' => {
+                    end_str(&mut symbol_elements, &mut str_state, char_index);
+
+                    let identifier = VerticalIdentifier {
+                        inner: symbol_elements,
+                        // + 1 to include `|` in span
+                        span: self.scanner.span(start_index, char_index + 1),
+                    };
+
+                    self.push_token(Token::Identifier(Identifier::Vertical(identifier)));
+
+                    return Ok(());
+                }
+                '\\' => {
+                    end_str(&mut symbol_elements, &mut str_state, char_index);
+
+                    let Some((char_nested_index, char_nested)) = self.scanner.next() else {
+                        let eof_span = self.scanner.span_to_end_of_file(start_index);
+                        return Err(IdentifierScanError::EndOfFile(eof_span));
+                    };
+
+                    let symbol_element = match char_nested {
+                        '|' => SymbolElement::VerticalLineEscape,
+                        'x' | 'X' => SymbolElement::InlineCodePoint(self.scan_inline_hex(char_index)?),
+                        'a' => SymbolElement::MnemonicEscape(MnemonicEscape::Alarm),
+                        'b' => SymbolElement::MnemonicEscape(MnemonicEscape::Backspace),
+                        't' => SymbolElement::MnemonicEscape(MnemonicEscape::Tab),
+                        'n' => SymbolElement::MnemonicEscape(MnemonicEscape::Newline),
+                        'r' => SymbolElement::MnemonicEscape(MnemonicEscape::Return),
+                        _ => {
+                            // include the unknown escape character
+                            let span = self.scanner.span(char_index, char_nested_index + char_nested.len_utf8());
+                            return Err(IdentifierScanError::UnknownEscape(span));
+                        }
+                    };
+
+                    symbol_elements.push(symbol_element);
+                }
+                _ => {
+                    str_state.get_or_insert(char_index);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_identifier(src: &str, identifier: Identifier) {
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        assert_eq!(alloc::vec![TokenAll::Token(Token::Identifier(identifier))], tokens);
+    }
+
+    fn expected_error(src: &str, error: IdentifierScanError) {
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(TokenizeError::Identifier(error), actual_error);
+    }
+
+    fn simple(src: &str) -> Identifier<'_> {
+        Identifier::Simple(SimpleIdentifier { inner: src.into(), span: Span::new(src, 0, src.len()) })
+    }
+
+    fn peculiar(src: &str) -> Identifier<'_> {
+        Identifier::Peculiar(PeculiarIdentifier { inner: src.into(), span: Span::new(src, 0, src.len()) })
+    }
+
+    #[test]
+    fn simple_identifiers() {
+        for src in [
+            "a",
+            "abc",
+            "list->vector",
+            "<=?",
+            "a34kTMNs",
+            "the-word-recursion-has-many-meanings",
+            "!$%&*/:<=>?@^_~",
+        ] {
+            expected_identifier(src, simple(src));
+        }
+    }
+
+    #[test]
+    fn simple_identifier_subsequent_characters() {
+        for src in ["a1", "a+", "a-", "a.", "a@", "a.b.c"] {
+            expected_identifier(src, simple(src));
+        }
+    }
+
+    #[test]
+    fn peculiar_identifiers() {
+        for src in ["+", "-", "...", "..", "+a", "-@", "->x", "+.a", "-..", ".a", "+-"] {
+            expected_identifier(src, peculiar(src));
+        }
+    }
+
+    #[test]
+    fn identifier_delimited() {
+        let src = "abc)";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let expected = TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+            inner: "abc".into(),
+            span: Span::new(src, 0, 3),
+        })));
+        assert_eq!(&expected, &tokens[0]);
+    }
+
+    #[test]
+    fn invalid_initial_error() {
+        let src = "{a";
+        expected_error(src, IdentifierScanError::InvalidInitial(Span::new(src, 0, 1)));
+    }
+
+    #[test]
+    fn invalid_subsequent_error() {
+        let src = "ab#c";
+        expected_error(src, IdentifierScanError::InvalidSubsequent(Span::new(src, 2, 3)));
+
+        // multi-byte characters are included in their entirety
+        let src = "a«";
+        expected_error(src, IdentifierScanError::InvalidSubsequent(Span::new(src, 1, 3)));
+
+        // sign subsequent
+        let src = "+#a";
+        expected_error(src, IdentifierScanError::InvalidSubsequent(Span::new(src, 1, 2)));
+
+        // dot subsequent
+        let src = ".#a";
+        expected_error(src, IdentifierScanError::InvalidSubsequent(Span::new(src, 1, 2)));
+    }
+
+    #[test]
+    fn incomplete_peculiar_error() {
+        let src = "+.";
+        expected_error(src, IdentifierScanError::IncompletePeculiar(Span::new(src, 0, 2)));
+    }
+
+    mod vertical {
+        use super::*;
+
+        fn expected_vertical<'src>(src: &'src str, elements: impl IntoIterator<Item = SymbolElement<'src>>) {
+            expected_identifier(
+                src,
+                Identifier::Vertical(VerticalIdentifier {
+                    inner: elements.into_iter().collect(),
+                    span: Span::new(src, 0, src.len()),
+                }),
+            );
+        }
+
+        #[test]
+        fn empty() {
+            expected_vertical("||", []);
+        }
+
+        #[test]
+        fn any_ascii_character() {
+            expected_vertical("|hello world #[x] (\"x\")|", [SymbolElement::Str("hello world #[x] (\"x\")")]);
+        }
+
+        #[test]
+        fn inline_hex_not_restricted() {
+            let src = "|\\x3BB;|";
+            expected_vertical(src, [SymbolElement::InlineCodePoint(InlineCodePoint('λ', Span::new(src, 1, 7)))]);
+        }
+
+        #[test]
+        fn escapes() {
+            let src = "|a\\|b\\x41;\\t|";
+            expected_vertical(
+                src,
+                [
+                    SymbolElement::Str("a"),
+                    SymbolElement::VerticalLineEscape,
+                    SymbolElement::Str("b"),
+                    SymbolElement::InlineCodePoint(InlineCodePoint('A', Span::new(src, 5, 10))),
+                    SymbolElement::MnemonicEscape(MnemonicEscape::Tab),
+                ],
+            );
+        }
+
+        #[test]
+        fn name() {
+            let name = |src| match Lexer::new(src).tokenize_all().unwrap().pop() {
+                Some(TokenAll::Token(Token::Identifier(identifier))) => identifier.name(),
+                other => panic!("expected identifier token, got {:?}", other),
+            };
+
+            assert!(matches!(name("||"), Cow::Borrowed("")));
+            assert!(matches!(name("|a b|"), Cow::Borrowed("a b")));
+            assert_eq!("a|bA\t", name("|a\\|b\\x41;\\t|"));
+        }
+
+        #[test]
+        fn unknown_escape_error() {
+            let src = "|\\y|";
+            expected_error(src, IdentifierScanError::UnknownEscape(Span::new(src, 1, 3)));
+        }
+
+        #[test]
+        fn non_ascii_elements() {
+            // any character but `|` and `\`, whether in an identifier category or not
+            let src = "|λ 漢字 a«b|";
+            let expected = Identifier::Vertical(VerticalIdentifier {
+                inner: alloc::vec![SymbolElement::Str("λ 漢字 a«b")],
+                span: Span::new(src, 0, src.len()),
+            });
+            expected_identifier(src, expected);
+        }
+
+        #[test]
+        fn end_of_file_error() {
+            let src = "|abc";
+            expected_error(src, IdentifierScanError::EndOfFile(Span::new(src, 0, 4)));
+
+            let src = "|abc\\";
+            expected_error(src, IdentifierScanError::EndOfFile(Span::new(src, 0, 5)));
+        }
+    }
+
+    #[cfg(not(feature = "unicode_identifiers"))]
+    mod ascii_only {
+        use super::*;
+
+        #[test]
+        fn non_ascii_initial_error() {
+            let src = "λ";
+            expected_error(src, IdentifierScanError::InvalidInitial(Span::new(src, 0, 2)));
+        }
+
+        #[test]
+        fn non_ascii_subsequent_error() {
+            let src = "aλ";
+            expected_error(src, IdentifierScanError::InvalidSubsequent(Span::new(src, 1, 3)));
+        }
+    }
+
+    #[cfg(feature = "unicode_identifiers")]
+    mod unicode {
+        use super::*;
+
+        /// One character per allowed general category
+        const INITIAL_CATEGORIES: [(&str, char); 16] = [
+            ("Lu", 'Λ'),
+            ("Ll", 'λ'),
+            ("Lt", 'ǅ'),
+            ("Lm", 'ʰ'),
+            ("Lo", '漢'),
+            ("Mn", '\u{0301}'),
+            ("Nl", 'Ⅻ'),
+            ("No", '²'),
+            ("Pd", '‐'),
+            ("Pc", '‿'),
+            ("Po", '·'),
+            ("Sc", '€'),
+            ("Sm", '∀'),
+            ("Sk", '˜'),
+            ("So", '©'),
+            ("Co", '\u{E000}'),
+        ];
+
+        const SUBSEQUENT_ONLY_CATEGORIES: [(&str, char); 3] = [("Nd", '٣'), ("Mc", '\u{0903}'), ("Me", '\u{20DD}')];
+
+        const ZERO_WIDTH_JOINERS: [char; 2] = ['\u{200C}', '\u{200D}'];
+
+        fn lexes_as_simple(src: &str) -> bool {
+            Lexer::new(src)
+                .tokenize_all()
+                .is_ok_and(|tokens| tokens == alloc::vec![TokenAll::Token(Token::Identifier(simple(src)))])
+        }
+
+        #[test]
+        fn initial_categories() {
+            for (category, char) in INITIAL_CATEGORIES.into_iter().chain(ZERO_WIDTH_JOINERS.map(|char| ("Cf", char))) {
+                let src = alloc::format!("{char}a");
+                assert!(lexes_as_simple(&src), "{category} ({char:?}) as initial");
+            }
+        }
+
+        #[test]
+        fn subsequent_categories() {
+            let categories = INITIAL_CATEGORIES
+                .into_iter()
+                .chain(SUBSEQUENT_ONLY_CATEGORIES)
+                .chain(ZERO_WIDTH_JOINERS.map(|char| ("Cf", char)));
+
+            for (category, char) in categories {
+                let src = alloc::format!("a{char}");
+                assert!(lexes_as_simple(&src), "{category} ({char:?}) as subsequent");
+            }
+        }
+
+        #[test]
+        fn subsequent_only_categories_as_initial_error() {
+            for (_, char) in SUBSEQUENT_ONLY_CATEGORIES {
+                let src = alloc::format!("{char}a");
+                expected_error(&src, IdentifierScanError::InvalidInitial(Span::new(&src, 0, char.len_utf8())));
+            }
+        }
+
+        #[test]
+        fn disallowed_categories_error() {
+            // Pi, Pf, Ps, Pe, Cf (other than U+200C and U+200D)
+            for char in ['«', '»', '「', '」', '\u{200B}'] {
+                let src = alloc::format!("a{char}");
+                expected_error(
+                    &src,
+                    IdentifierScanError::InvalidSubsequent(Span::new(&src, 1, 1 + char.len_utf8())),
+                );
+            }
+        }
+
+        #[test]
+        fn ascii_forbidden_stays_forbidden() {
+            // '\\', '#', ',' and '\'' are in Po, '`' in Sk
+            for char in ['\\', '#', ',', '\'', '`'] {
+                let src = alloc::format!("a{char}b");
+                expected_error(&src, IdentifierScanError::InvalidSubsequent(Span::new(&src, 1, 2)));
+            }
+        }
+
+        #[test]
+        fn greek_and_cjk() {
+            for src in ["λ", "λ-calculus", "Σύνολο", "漢字", "変数->リスト", "αβγ2"] {
+                expected_identifier(src, simple(src));
+            }
+
+            for src in ["+λ", "-漢字", ".λ"] {
+                expected_identifier(src, peculiar(src));
+            }
+        }
+
+        #[test]
+        fn fold_case() {
+            let src = "#!fold-case ΛΑΜΒΔΑ";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            let expected = TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+                inner: "λαμβδα".into(),
+                span: Span::new(src, 12, src.len()),
+            })));
+            assert_eq!(expected, tokens[1]);
+        }
+    }
+}
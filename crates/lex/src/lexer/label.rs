@@ -0,0 +1,82 @@
+use crate::*;
+
+impl Lexer<'_> {
+    /// `#` scanned, followed by a decimal digit
+    pub(super) fn scan_datum_label(&mut self, start_index: usize) -> Result<(), TokenizeError> {
+        // `None` once overflowed, the label is still scanned until its end
+        let mut label = Some(0u64);
+
+        let (end_index, variant) = loop {
+            match self.scanner.peek() {
+                Some((_, digit @ '0'..='9')) => {
+                    self.scanner.next();
+                    label = label.and_then(|label| label.checked_mul(10)?.checked_add(u64::from(digit as u8 - b'0')));
+                }
+                Some((index, '=')) => break (index + 1, DatumLabelVariant::Definition),
+                Some((index, '#')) => break (index + 1, DatumLabelVariant::Reference),
+                _ => {
+                    let end_index = self.scanner.scan_until_delimiter();
+                    return Err(TokenizeError::UnknownHashSyntax(self.scanner.span(start_index, end_index)));
+                }
+            }
+        };
+
+        self.scanner.next();
+        let span = self.scanner.span(start_index, end_index);
+
+        let Some(label) = label else {
+            return Err(TokenizeError::DatumLabelOutOfBounds(span));
+        };
+
+        self.push_token(Token::Label(DatumLabel { label, variant, span }));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected_label(src: &str, label: u64, variant: DatumLabelVariant) {
+        let expected = TokenAll::Token(Token::Label(DatumLabel { label, variant, span: Span::new(src, 0, src.len()) }));
+        assert_eq!(alloc::vec![expected], Lexer::new(src).tokenize_all().unwrap());
+    }
+
+    #[test]
+    fn definition() {
+        expected_label("#0=", 0, DatumLabelVariant::Definition);
+        expected_label("#0042=", 42, DatumLabelVariant::Definition);
+    }
+
+    #[test]
+    fn reference() {
+        expected_label("#1#", 1, DatumLabelVariant::Reference);
+    }
+
+    #[test]
+    fn followed_by_datum() {
+        let tokens = Lexer::new("#0='a").tokenize_all().unwrap();
+
+        assert_eq!(3, tokens.len());
+        assert_eq!(Span::new("#0='a", 0, 3), tokens[0].span());
+    }
+
+    #[test]
+    fn unterminated_error() {
+        let src = "#12 a";
+        let error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 3)), error);
+
+        let src = "#1x#";
+        let error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 4)), error);
+    }
+
+    #[test]
+    fn out_of_bounds_error() {
+        let src = "#18446744073709551616#";
+        let error = Lexer::new(src).tokenize_all().unwrap_err();
+        assert_eq!(TokenizeError::DatumLabelOutOfBounds(Span::new(src, 0, src.len())), error);
+    }
+}
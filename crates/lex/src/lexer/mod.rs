@@ -0,0 +1,695 @@
+use alloc::vec::Vec;
+
+use crate::*;
+
+mod character;
+mod comment;
+mod escape;
+mod identifier;
+mod label;
+mod number;
+mod string;
+
+/// Entrypoint for using `pluine_lex`.
+/// ```
+/// # use pluine_lex::Lexer;
+/// let src = "\"abc\"";
+/// let tokens = Lexer::new(src)
+///     .tokenize_all()
+///     .expect("valid source code string");
+/// ```
+///
+/// Tokens may also be pulled lazily, `Lexer` being an iterator over token results:
+/// ```
+/// # use pluine_lex::Lexer;
+/// let mut lexer = Lexer::new("(a b)");
+/// assert!(lexer.next_token().is_some_and(|token| token.is_ok()));
+/// assert_eq!(3, lexer.count());
+/// ```
+pub struct Lexer<'src> {
+    scanner: Scanner<'src>,
+    /// Set by the scan methods, at most one token is produced per scanned token class.
+    pending_token: Option<TokenAll<'src>>,
+    /// Toggled by `#!fold-case` and `#!no-fold-case` directives
+    fold_case: bool,
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Result<TokenAll<'src>, TokenizeError>;
+
+    /// See [`Lexer::next_token`]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+impl<'src> Lexer<'src> {
+    /// Construct a new `Lexer`.
+    pub fn new(src: &'src str) -> Self {
+        Self { scanner: Scanner::new(src), pending_token: None, fold_case: false }
+    }
+
+    /// Construct a `Lexer` resuming at byte index `offset`, as returned by [`Self::offset`].
+    ///
+    /// Spans remain relative to the start of `src`.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is not on a UTF-8 sequence boundary.
+    pub fn new_at(src: &'src str, offset: usize) -> Self {
+        Self {
+            scanner: Scanner::new_at(src, offset),
+            pending_token: None,
+            fold_case: false,
+        }
+    }
+
+    /// Folds the case of identifiers and character names as if the source started with
+    /// `#!fold-case`, or stops doing so as if it started with `#!no-fold-case`.
+    pub fn with_fold_case(mut self, fold_case: bool) -> Self {
+        self.fold_case = fold_case;
+        self
+    }
+
+    /// Byte index of the next character to scan, the length of the source once exhausted.
+    pub fn offset(&self) -> usize {
+        self.scanner.offset()
+    }
+
+    /// Whether case is being folded, see [`Self::with_fold_case`].
+    pub fn folds_case(&self) -> bool {
+        self.fold_case
+    }
+
+    /// A result is returned because tokens are validated to some degree. No
+    /// error recovery is applied. Meaning, no tokenization is performed on the remaining source
+    /// string once an invalid token is encountered. See [`Self::tokenize_all_recovering`] for a
+    /// variant which keeps going.
+    pub fn tokenize_all(self) -> Result<Vec<TokenAll<'src>>, TokenizeError> {
+        self.collect()
+    }
+
+    /// Tokenizes the entire source string, collecting every [`TokenizeError`] rather than
+    /// stopping at the first one.
+    ///
+    /// Each invalid token is replaced by a [`TokenAll::Unknown`] token. Lexing is then resumed at
+    /// the next delimiter, or past the closing `"` or `|` for strings and vertical identifiers.
+    ///
+    /// ```
+    /// # use pluine_lex::Lexer;
+    /// let (tokens, errors) = Lexer::new("(foo #z {bar)").tokenize_all_recovering();
+    /// assert_eq!(5, tokens.len());
+    /// assert_eq!(2, errors.len());
+    /// ```
+    pub fn tokenize_all_recovering(mut self) -> (Vec<TokenAll<'src>>, Vec<TokenizeError>) {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+
+        while let Some(result) = self.scan_next() {
+            match result {
+                Ok(token) => tokens.push(token),
+                Err((error, unknown_token)) => {
+                    tokens.push(TokenAll::Unknown(unknown_token));
+                    errors.push(error);
+                }
+            }
+        }
+
+        (tokens, errors)
+    }
+
+    /// Scans the next token, atmosphere included, `None` once the source string is exhausted.
+    ///
+    /// The lexer resynchronizes after an error as described in [`Self::tokenize_all_recovering`],
+    /// so scanning may continue past an `Err`.
+    pub fn next_token(&mut self) -> Option<Result<TokenAll<'src>, TokenizeError>> {
+        self.scan_next().map(|result| result.map_err(|(error, _)| error))
+    }
+
+    /// Skipped region is returned alongside the error.
+    fn scan_next(&mut self) -> Option<Result<TokenAll<'src>, (TokenizeError, UnknownToken<'src>)>> {
+        loop {
+            let (start_index, char) = self.scanner.next()?;
+
+            if let Err(error) = self.scan_token(start_index, char) {
+                let end_index = self.resynchronize(char);
+                let unknown_token = UnknownToken {
+                    inner: &self.scanner.src()[start_index..end_index],
+                    span: self.scanner.span(start_index, end_index),
+                };
+
+                return Some(Err((error, unknown_token)));
+            }
+
+            // `None` if only whitespace was scanned
+            if let Some(token) = self.pending_token.take() {
+                return Some(Ok(token));
+            }
+        }
+    }
+
+    /// Dispatches on the first character of a token class.
+    //
+    // NOTE: Avoid using recursion here. Tail call optimization can't be guaranteed by the rust
+    // compiler, and the `tailcall` crate does not perform well for mutual recursion. Makes it also
+    // hard to reason about potential origins of UTF-8 sequence boundary errors.
+    fn scan_token(&mut self, start_index: usize, char: char) -> Result<(), TokenizeError> {
+        match char {
+            // Atmosphere Whitespace
+            ' ' | '\t' | '\r' | '\n' => {}
+            ';' => {
+                self.scan_semicolon_comment(start_index);
+            }
+            '"' => {
+                self.scan_string(start_index)?;
+            }
+            '(' => self.push_token_char(start_index, start_index + 1, TokenCharVariant::OpenParenthesis),
+            ')' => self.push_token_char(start_index, start_index + 1, TokenCharVariant::CloseParenthesis),
+            '\'' => self.push_token_char(start_index, start_index + 1, TokenCharVariant::Apostophe),
+            '`' => self.push_token_char(start_index, start_index + 1, TokenCharVariant::GraveAccent),
+            ',' => match self.scanner.next_if_eq('@') {
+                Some(at_index) => self.push_token_char(start_index, at_index + 1, TokenCharVariant::CommaAt),
+                None => self.push_token_char(start_index, start_index + 1, TokenCharVariant::Comma),
+            },
+            '#' => {
+                self.scan_hash(start_index)?;
+            }
+            '|' => {
+                self.scan_vertical_identifier(start_index)?;
+            }
+            _ => {
+                self.scan_atom(start_index)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Forwards the scanner past an invalid token starting with `start_char`, returns the index
+    /// from which lexing is resumed.
+    ///
+    /// Strings and vertical identifiers may contain delimiters, these are instead skipped past
+    /// their closing `"` or `|`, unless the scanner already reached EOF.
+    fn resynchronize(&mut self, start_char: char) -> usize {
+        if !matches!(start_char, '"' | '|') {
+            return self.scanner.scan_until_delimiter();
+        }
+
+        while let Some(char) = self.scanner.next_char() {
+            match char {
+                '\\' => {
+                    self.scanner.next();
+                }
+                _ if char == start_char => break,
+                _ => {}
+            }
+        }
+
+        self.scanner.offset()
+    }
+
+    fn push_token_all(&mut self, token: TokenAll<'src>) {
+        debug_assert!(self.pending_token.is_none(), "pending token not yet returned");
+        self.pending_token = Some(token);
+    }
+
+    fn push_token(&mut self, token: Token<'src>) {
+        self.push_token_all(TokenAll::Token(token));
+    }
+
+    fn push_token_char(&mut self, start_index: usize, end_index: usize, variant: TokenCharVariant) {
+        let span = self.scanner.span(start_index, end_index);
+        self.push_token(Token::Other(TokenChar { inner: variant, span }));
+    }
+
+    /// `#` scanned
+    fn scan_hash(&mut self, start_index: usize) -> Result<(), TokenizeError> {
+        if let Some(parenthesis_index) = self.scanner.next_if_eq('(') {
+            self.push_token_char(start_index, parenthesis_index + 1, TokenCharVariant::PoundOpenParenthesis);
+            return Ok(());
+        }
+
+        // `(` being a delimiter, `#u8(` can not be scanned along with other hash prefixed lexemes
+        if self.scanner.src()[self.scanner.offset()..].starts_with("u8(") {
+            self.scanner.nth(2);
+            self.push_token_char(start_index, start_index + 4, TokenCharVariant::PoundU8OpenParenthesis);
+            return Ok(());
+        }
+
+        if self.scanner.next_if_eq('\\').is_some() {
+            return Ok(self.scan_character(start_index)?);
+        }
+
+        if self.scanner.next_if_eq('|').is_some() {
+            return Ok(self.scan_nested_comment(start_index)?);
+        }
+
+        if let Some(semicolon_index) = self.scanner.next_if_eq(';') {
+            let section_comment = SectionComment(self.scanner.span(start_index, semicolon_index + 1));
+            self.push_token_all(TokenAll::InterToken(Atmosphere::Comment(Comment::Section(section_comment))));
+            return Ok(());
+        }
+
+        if self.scanner.next_if_eq('!').is_some() {
+            return Ok(self.scan_directive(start_index)?);
+        }
+
+        if self.scanner.peek().is_some_and(|(_, char)| char.is_ascii_digit()) {
+            return self.scan_datum_label(start_index);
+        }
+
+        let end_index = self.scanner.scan_until_delimiter();
+
+        if self.scanner.src()[start_index + 1..end_index]
+            .starts_with(|char| RadixMarker::from_char(char).is_some() || Exactness::from_char(char).is_some())
+        {
+            let number = self.scan_number(start_index, end_index)?;
+            self.push_token(Token::Number(number));
+            return Ok(());
+        }
+
+        // `#` can be directly followed by a delimiter
+        let span = self.scanner.span(start_index, end_index.max(start_index + 1));
+
+        let lexeme = &self.scanner.src()[start_index..end_index];
+        let boolean = ["#t", "#true"].iter().any(|candidate| candidate.eq_ignore_ascii_case(lexeme));
+
+        if boolean || ["#f", "#false"].iter().any(|candidate| candidate.eq_ignore_ascii_case(lexeme)) {
+            self.push_token(Token::Boolean(Boolean { inner: boolean, span }));
+            return Ok(());
+        }
+
+        Err(TokenizeError::UnknownHashSyntax(span))
+    }
+
+    /// `#!` scanned
+    fn scan_directive(&mut self, start_index: usize) -> Result<(), DirectiveScanError> {
+        let end_index = self.scanner.scan_until_delimiter();
+        let span = self.scanner.span(start_index, end_index);
+
+        // skip `#!`
+        let name = &self.scanner.src()[start_index + 2..end_index];
+        let Some(directive_variant) = DirectiveVariant::from_name(name) else {
+            return Err(DirectiveScanError::Unknown(span));
+        };
+
+        self.fold_case = directive_variant == DirectiveVariant::FoldCase;
+
+        let directive = Directive { inner: directive_variant, span };
+        self.push_token_all(TokenAll::InterToken(Atmosphere::Directive(directive)));
+
+        Ok(())
+    }
+
+    /// Any character not starting another token class scanned, which makes it either a number,
+    /// an identifier, or a lone `.`
+    fn scan_atom(&mut self, start_index: usize) -> Result<(), TokenizeError> {
+        let end_index = self.scanner.scan_until_delimiter();
+
+        let lexeme = &self.scanner.src()[start_index..end_index];
+
+        if lexeme == "." {
+            self.push_token_char(start_index, end_index, TokenCharVariant::Dot);
+            return Ok(());
+        }
+
+        match self.scan_number(start_index, end_index) {
+            Ok(number) => {
+                self.push_token(Token::Number(number));
+                return Ok(());
+            }
+            Err(number_error) if Self::is_numeric_lexeme(lexeme) => return Err(number_error.into()),
+            // not a number, try identifier instead
+            Err(_) => {}
+        }
+
+        let identifier = self.scan_identifier(start_index, end_index)?;
+        self.push_token(Token::Identifier(identifier));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+
+    use super::*;
+
+    #[test]
+    fn skip_atmosphere_whitespace() {
+        let src = " \t\n\r";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        assert!(tokens.is_empty())
+    }
+
+    #[test]
+    fn semicolon_comment() {
+        let src = " ;\t\n ";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = TokenAll::InterToken(Atmosphere::Comment(Comment::Semicolon(SemicolonComment {
+            inner: "\t",
+            span: Span::new(src, 1, 3),
+        })));
+
+        assert_eq!(&expected, comment);
+    }
+
+    mod token_char {
+        use super::*;
+
+        fn expected_token_char(src: &str, start: usize, end: usize, variant: TokenCharVariant) -> TokenAll<'_> {
+            TokenAll::Token(Token::Other(TokenChar { inner: variant, span: Span::new(src, start, end) }))
+        }
+
+        #[test]
+        fn all_variants() {
+            let src = "( ) #( . ' ` , ,@";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            let expected = alloc::vec![
+                expected_token_char(src, 0, 1, TokenCharVariant::OpenParenthesis),
+                expected_token_char(src, 2, 3, TokenCharVariant::CloseParenthesis),
+                expected_token_char(src, 4, 6, TokenCharVariant::PoundOpenParenthesis),
+                expected_token_char(src, 7, 8, TokenCharVariant::Dot),
+                expected_token_char(src, 9, 10, TokenCharVariant::Apostophe),
+                expected_token_char(src, 11, 12, TokenCharVariant::GraveAccent),
+                expected_token_char(src, 13, 14, TokenCharVariant::Comma),
+                expected_token_char(src, 15, 17, TokenCharVariant::CommaAt),
+            ];
+            assert_eq!(expected, tokens);
+        }
+
+        #[test]
+        fn no_delimiter_required() {
+            let src = "'(a)";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            assert_eq!(expected_token_char(src, 0, 1, TokenCharVariant::Apostophe), tokens[0]);
+            assert_eq!(expected_token_char(src, 1, 2, TokenCharVariant::OpenParenthesis), tokens[1]);
+            assert_eq!(expected_token_char(src, 3, 4, TokenCharVariant::CloseParenthesis), tokens[3]);
+        }
+
+        #[test]
+        fn bytevector_open() {
+            let src = "#u8(1)";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+            assert_eq!(expected_token_char(src, 0, 4, TokenCharVariant::PoundU8OpenParenthesis), tokens[0]);
+
+            let src = "#u8 ()";
+            let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+            assert_eq!(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 3)), actual_error);
+        }
+    }
+
+    mod boolean {
+        use super::*;
+
+        #[test]
+        fn all_representations() {
+            for (src, expected) in [
+                ("#t", true),
+                ("#true", true),
+                ("#f", false),
+                ("#false", false),
+                ("#TRUE", true),
+                ("#F", false),
+                ("#tRuE", true),
+            ] {
+                let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+                let expected_token = TokenAll::Token(Token::Boolean(Boolean { inner: expected, span: Span::new(src, 0, src.len()) }));
+                assert_eq!(alloc::vec![expected_token], tokens);
+            }
+        }
+
+        #[test]
+        fn unknown_hash_syntax_error() {
+            let src = "#tru";
+            let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+            assert_eq!(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 4)), actual_error);
+
+            let src = "# a";
+            let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+            assert_eq!(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 1)), actual_error);
+        }
+    }
+
+    mod directive {
+        use super::*;
+
+        fn identifier_inner<'a, 'src>(token: &'a TokenAll<'src>) -> &'a Cow<'src, str> {
+            match token {
+                TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier { inner, .. }))) => inner,
+                TokenAll::Token(Token::Identifier(Identifier::Peculiar(PeculiarIdentifier { inner, .. }))) => inner,
+                other => panic!("expected simple or peculiar identifier, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn directives() {
+            let src = "#!fold-case #!NO-FOLD-CASE";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            let expected = alloc::vec![
+                TokenAll::InterToken(Atmosphere::Directive(Directive {
+                    inner: DirectiveVariant::FoldCase,
+                    span: Span::new(src, 0, 11)
+                })),
+                TokenAll::InterToken(Atmosphere::Directive(Directive {
+                    inner: DirectiveVariant::NoFoldCase,
+                    span: Span::new(src, 12, 26)
+                })),
+            ];
+            assert_eq!(expected, tokens);
+        }
+
+        #[test]
+        fn fold_case_toggling() {
+            let src = "ABC #!fold-case ABC ->X #!no-fold-case ABC";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            assert_eq!(&Cow::Borrowed("ABC"), identifier_inner(&tokens[0]));
+            assert_eq!(&Cow::<str>::Owned("abc".into()), identifier_inner(&tokens[2]));
+            assert_eq!("->x", identifier_inner(&tokens[3]));
+            assert_eq!(&Cow::Borrowed("ABC"), identifier_inner(&tokens[5]));
+        }
+
+        #[test]
+        fn unchanged_identifiers_stay_borrowed() {
+            let src = "#!fold-case abc";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            assert!(matches!(identifier_inner(&tokens[1]), Cow::Borrowed("abc")));
+        }
+
+        #[test]
+        fn vertical_identifiers_not_folded() {
+            let src = "#!fold-case |ABC|";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+            let expected = TokenAll::Token(Token::Identifier(Identifier::Vertical(VerticalIdentifier {
+                inner: alloc::vec![SymbolElement::Str("ABC")],
+                span: Span::new(src, 12, 17),
+            })));
+            assert_eq!(expected, tokens[1]);
+        }
+
+        #[test]
+        fn unknown_directive_error() {
+            let src = "#!fold";
+            let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+            assert_eq!(
+                TokenizeError::Directive(DirectiveScanError::Unknown(Span::new(src, 0, 6))),
+                actual_error
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_token_classes() {
+        let src = "(define (f x) (list x #t \"s\" #\\a 1 'y))";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        assert_eq!(17, tokens.len());
+    }
+
+    mod recovery {
+        use super::*;
+
+        fn unknown<'src>(src: &'src str, start: usize, end: usize) -> TokenAll<'src> {
+            TokenAll::Unknown(UnknownToken { inner: &src[start..end], span: Span::new(src, start, end) })
+        }
+
+        fn identifier<'src>(src: &'src str, start: usize, end: usize) -> TokenAll<'src> {
+            TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+                inner: src[start..end].into(),
+                span: Span::new(src, start, end),
+            })))
+        }
+
+        #[test]
+        fn valid_source_has_no_errors() {
+            let src = "(define (f x) (list x #t \"s\" #\\a 1 'y))";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            assert_eq!(Lexer::new(src).tokenize_all().unwrap(), tokens);
+            assert!(errors.is_empty());
+        }
+
+        #[test]
+        fn resumes_at_delimiter() {
+            let src = "(foo #z bar)";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            assert_eq!(alloc::vec![TokenizeError::UnknownHashSyntax(Span::new(src, 5, 7))], errors);
+            assert_eq!(identifier(src, 1, 4), tokens[1]);
+            assert_eq!(unknown(src, 5, 7), tokens[2]);
+            assert_eq!(identifier(src, 8, 11), tokens[3]);
+            assert_eq!(5, tokens.len());
+        }
+
+        #[test]
+        fn collects_all_errors() {
+            let src = "#z {a #\\foo ok";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_errors = alloc::vec![
+                TokenizeError::UnknownHashSyntax(Span::new(src, 0, 2)),
+                TokenizeError::Identifier(IdentifierScanError::InvalidInitial(Span::new(src, 3, 4))),
+                TokenizeError::Character(CharacterLiteralScanError::UnknownName(Span::new(src, 6, 11))),
+            ];
+            assert_eq!(expected_errors, errors);
+
+            let expected_tokens = alloc::vec![unknown(src, 0, 2), unknown(src, 3, 5), unknown(src, 6, 11), identifier(src, 12, 14)];
+            assert_eq!(expected_tokens, tokens);
+        }
+
+        #[test]
+        fn string_resumes_past_closing_quote() {
+            let src = "\"a b\\q c\\\" d\" x";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_error = StringLiteralScanError::UnknownEscape(Span::new(src, 4, 6));
+            assert_eq!(alloc::vec![TokenizeError::String(expected_error)], errors);
+            assert_eq!(alloc::vec![unknown(src, 0, 13), identifier(src, 14, 15)], tokens);
+        }
+
+        #[test]
+        fn string_closing_quote_not_consumed_by_escape_error() {
+            // invalid hex digit
+            let src = "\"\\x\" x";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_error = StringLiteralScanError::InlineHex(InlineCodePointScanError::InvalidHexDigit(Span::new(src, 3, 4)));
+            assert_eq!(alloc::vec![TokenizeError::String(expected_error)], errors);
+            assert_eq!(alloc::vec![unknown(src, 0, 4), identifier(src, 5, 6)], tokens);
+
+            // newline escape whitespace
+            let src = "\"\\ \" x";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_error = StringLiteralScanError::UnknownWhitespace(Span::new(src, 3, 4));
+            assert_eq!(alloc::vec![TokenizeError::String(expected_error)], errors);
+            assert_eq!(alloc::vec![unknown(src, 0, 4), identifier(src, 5, 6)], tokens);
+        }
+
+        #[test]
+        fn vertical_identifier_resumes_past_closing_line() {
+            let src = "|a\\y b| x";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_error = IdentifierScanError::UnknownEscape(Span::new(src, 2, 4));
+            assert_eq!(alloc::vec![TokenizeError::Identifier(expected_error)], errors);
+            assert_eq!(alloc::vec![unknown(src, 0, 7), identifier(src, 8, 9)], tokens);
+        }
+
+        #[test]
+        fn end_of_file() {
+            let src = "x \"abc";
+            let (tokens, errors) = Lexer::new(src).tokenize_all_recovering();
+
+            let expected_error = StringLiteralScanError::EndOfFile(Span::new(src, 2, 6));
+            assert_eq!(alloc::vec![TokenizeError::String(expected_error)], errors);
+            assert_eq!(alloc::vec![identifier(src, 0, 1), unknown(src, 2, 6)], tokens);
+        }
+    }
+
+    mod streaming {
+        use super::*;
+
+        #[test]
+        fn yields_tokens_lazily() {
+            let src = "(a 1) ; done";
+            let mut lexer = Lexer::new(src);
+
+            let expected = TokenAll::Token(Token::Other(TokenChar {
+                inner: TokenCharVariant::OpenParenthesis,
+                span: Span::new(src, 0, 1),
+            }));
+            assert_eq!(Some(Ok(expected)), lexer.next_token());
+
+            // remaining source is yet to be scanned
+            assert_eq!(1, lexer.scanner.offset());
+
+            assert_eq!(4, lexer.count());
+        }
+
+        #[test]
+        fn whitespace_only_yields_none() {
+            let mut lexer = Lexer::new(" \n\t ");
+            assert_eq!(None, lexer.next_token());
+            assert_eq!(None, lexer.next_token());
+        }
+
+        #[test]
+        fn continues_after_error() {
+            let src = "#z a";
+            let mut lexer = Lexer::new(src);
+
+            assert_eq!(
+                Some(Err(TokenizeError::UnknownHashSyntax(Span::new(src, 0, 2)))),
+                lexer.next_token()
+            );
+
+            let expected = TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+                inner: "a".into(),
+                span: Span::new(src, 3, 4),
+            })));
+            assert_eq!(Some(Ok(expected)), lexer.next_token());
+            assert_eq!(None, lexer.next_token());
+        }
+
+        #[test]
+        fn resumes_at_offset() {
+            let src = "a bc D";
+            let mut lexer = Lexer::new_at(src, 1).with_fold_case(true);
+
+            let identifier = |inner: &'static str, start, end| {
+                TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+                    inner: inner.into(),
+                    span: Span::new(src, start, end),
+                })))
+            };
+
+            assert_eq!(Some(Ok(identifier("bc", 2, 4))), lexer.next_token());
+            assert_eq!(4, lexer.offset());
+            assert_eq!(Some(Ok(identifier("d", 5, 6))), lexer.next_token());
+            assert!(lexer.folds_case());
+        }
+
+        #[test]
+        fn fold_case_applies_to_subsequent_tokens() {
+            let src = "#!fold-case ABC";
+            let tokens = Lexer::new(src).skip(1).collect::<Result<Vec<_>, _>>().unwrap();
+
+            let expected = TokenAll::Token(Token::Identifier(Identifier::Simple(SimpleIdentifier {
+                inner: "abc".into(),
+                span: Span::new(src, 12, 15),
+            })));
+            assert_eq!(alloc::vec![expected], tokens);
+        }
+    }
+}
@@ -0,0 +1,674 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::*;
+
+impl Lexer<'_> {
+    /// Reads an already delimited lexeme spanning from `start` to `end` as a number.
+    ///
+    /// Lexemes starting with a `#` must be numbers, whilst others may still be
+    /// identifiers if they fail to scan as a number. See [`Self::is_numeric_lexeme`].
+    pub(super) fn scan_number(&self, start: usize, end: usize) -> Result<NumberLiteral, NumberLiteralScanError> {
+        let mut number_scanner = NumberScanner::new(self.scanner.src(), start, end);
+
+        let (radix, exactness) = number_scanner.scan_prefix()?;
+
+        let number_literal = match radix.unwrap_or(RadixMarker::Decimal) {
+            RadixMarker::Binary => NumberLiteral::Binary(number_scanner.scan_radix_number(exactness)?),
+            RadixMarker::Octal => NumberLiteral::Octal(number_scanner.scan_radix_number(exactness)?),
+            RadixMarker::Decimal => NumberLiteral::Decimal(number_scanner.scan_radix_number(exactness)?),
+            RadixMarker::Hexadecimal => NumberLiteral::Hexadecimal(number_scanner.scan_radix_number(exactness)?),
+        };
+
+        Ok(number_literal)
+    }
+
+    /// Whether a lexeme without a `#` prefix was intended to be a number, in which case a failed
+    /// number scan should be reported as such rather than falling back to an identifier.
+    ///
+    /// That is the case for lexemes beginning with a digit, optionally preceded by a sign and/or a
+    /// dot. Peculiar identifiers such as `+`, `...` and `->x` are thus not considered numeric,
+    /// neither are `+i` and `+inf.0`, but those are scanned successfully as numbers anyways.
+    pub(super) fn is_numeric_lexeme(lexeme: &str) -> bool {
+        let lexeme = lexeme.strip_prefix(['+', '-']).unwrap_or(lexeme);
+        let lexeme = lexeme.strip_prefix('.').unwrap_or(lexeme);
+
+        lexeme.starts_with(|char: char| char.is_ascii_digit())
+    }
+}
+
+/// Cursor over a single number lexeme.
+struct NumberScanner<'src> {
+    src: &'src str,
+    start: usize,
+    end: usize,
+    /// Absolute index of the next character
+    position: usize,
+}
+
+impl<'src> NumberScanner<'src> {
+    fn new(src: &'src str, start: usize, end: usize) -> Self {
+        Self { src, start, end, position: start }
+    }
+
+    fn rest(&self) -> &'src str {
+        &self.src[self.position..self.end]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += char.len_utf8();
+        Some(char)
+    }
+
+    fn next_if(&mut self, predicate: impl FnOnce(char) -> bool) -> Option<char> {
+        match self.peek() {
+            Some(char) if predicate(char) => self.next(),
+            _ => None,
+        }
+    }
+
+    /// Fails on the next character if any, or on the end of the lexeme if none.
+    fn unexpected(&self) -> NumberLiteralScanError {
+        match self.peek() {
+            Some(char) => {
+                let span = Span::new(self.src, self.position, self.position + char.len_utf8());
+                NumberLiteralScanError::UnexpectedCharacter(span)
+            }
+            None => NumberLiteralScanError::UnexpectedEnd(Span::new(self.src, self.start, self.end)),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), NumberLiteralScanError> {
+        match self.peek() {
+            Some(_) => Err(self.unexpected()),
+            None => Ok(()),
+        }
+    }
+
+    fn expect_imaginary_unit(&mut self) -> Result<(), NumberLiteralScanError> {
+        match self.next_if(|char| matches!(char, 'i' | 'I')) {
+            Some(_) => self.expect_end(),
+            None => Err(self.unexpected()),
+        }
+    }
+
+    /// Whether only an imaginary unit remains, used to detect `+i` and `-i`.
+    fn is_imaginary_unit_remaining(&self) -> bool {
+        self.rest().eq_ignore_ascii_case("i")
+    }
+
+    /// EBNF: `<Exactness> <Radix R> | <Radix R> <Exactness>`
+    fn scan_prefix(&mut self) -> Result<(Option<RadixMarker>, Option<Exactness>), NumberLiteralScanError> {
+        let mut radix = None;
+        let mut exactness = None;
+
+        while self.peek() == Some('#') {
+            let hash_index = self.position;
+            self.next();
+
+            let Some(prefix_char) = self.next() else {
+                let span = Span::new(self.src, hash_index, self.position);
+                return Err(NumberLiteralScanError::UnknownPrefix(span));
+            };
+
+            let span = Span::new(self.src, hash_index, self.position);
+
+            if let Some(radix_marker) = RadixMarker::from_char(prefix_char) {
+                if radix.replace(radix_marker).is_some() {
+                    return Err(NumberLiteralScanError::DuplicateRadix(span));
+                }
+            } else if let Some(exactness_marker) = Exactness::from_char(prefix_char) {
+                if exactness.replace(exactness_marker).is_some() {
+                    return Err(NumberLiteralScanError::DuplicateExactness(span));
+                }
+            } else {
+                return Err(NumberLiteralScanError::UnknownPrefix(span));
+            }
+        }
+
+        Ok((radix, exactness))
+    }
+
+    fn scan_radix_number<R: NumberRadix>(&mut self, exactness: Option<Exactness>) -> Result<Number<R>, NumberLiteralScanError> {
+        let inner = self.scan_complex()?;
+
+        Ok(Number {
+            prefix: Prefix { radix: PhantomData, exactness },
+            inner,
+            span: Span::new(self.src, self.start, self.end),
+        })
+    }
+
+    /// EBNF: see [`ComplexNumber`]
+    fn scan_complex<R: NumberRadix>(&mut self) -> Result<ComplexNumber<R>, NumberLiteralScanError> {
+        // `+i` and `-i`
+        if let Some(sign) = self.peek().and_then(Sign::from_char) {
+            self.next();
+
+            if self.is_imaginary_unit_remaining() {
+                self.next();
+                return Ok(ComplexNumber::RectangularValid { real: None, sign, imaginary: None });
+            }
+
+            // rewind sign, it is scanned as a part of the real number
+            self.position -= 1;
+        }
+
+        let first = self.scan_real()?;
+
+        let Some(char) = self.peek() else {
+            return Ok(ComplexNumber::Real(first));
+        };
+
+        match char {
+            '@' => {
+                self.next();
+                let phase = self.scan_real()?;
+                self.expect_end()?;
+
+                Ok(ComplexNumber::Polar { magnitude: first, phase })
+            }
+            'i' | 'I' => match first {
+                RealNumber::Number { sign: Some(sign), variant } => {
+                    self.expect_imaginary_unit()?;
+                    Ok(ComplexNumber::RectangularValid { real: None, sign, imaginary: Some(variant) })
+                }
+                RealNumber::NonNumber(non_number) => {
+                    self.expect_imaginary_unit()?;
+                    Ok(ComplexNumber::RectangularInvalid { real: None, imaginary: non_number })
+                }
+                // Imaginary part requires an explicit sign, `1i` is not a number
+                RealNumber::Number { sign: None, .. } => Err(self.unexpected()),
+            },
+            '+' | '-' => {
+                let sign = Sign::from_char(char).expect("matched sign character");
+                self.next();
+
+                if self.is_imaginary_unit_remaining() {
+                    self.next();
+                    return Ok(ComplexNumber::RectangularValid { real: Some(first), sign, imaginary: None });
+                }
+
+                // rewind sign, it is scanned as a part of the imaginary number
+                self.position -= 1;
+
+                let imaginary = self.scan_real()?;
+                self.expect_imaginary_unit()?;
+
+                match imaginary {
+                    RealNumber::Number { sign: Some(sign), variant } => {
+                        Ok(ComplexNumber::RectangularValid { real: Some(first), sign, imaginary: Some(variant) })
+                    }
+                    RealNumber::NonNumber(non_number) => Ok(ComplexNumber::RectangularInvalid { real: Some(first), imaginary: non_number }),
+                    RealNumber::Number { sign: None, .. } => unreachable!("imaginary part scanned with sign"),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// EBNF: `[<Sign>] <RealNumberVariant> | <NonNumber>`
+    fn scan_real<R: NumberRadix>(&mut self) -> Result<RealNumber<R>, NumberLiteralScanError> {
+        if let Some(non_number) = self.scan_non_number() {
+            return Ok(RealNumber::NonNumber(non_number));
+        }
+
+        let sign = self.scan_sign();
+        let variant = self.scan_unsigned_real()?;
+
+        Ok(RealNumber::Number { sign, variant })
+    }
+
+    fn scan_sign(&mut self) -> Option<Sign> {
+        let sign = self.peek().and_then(Sign::from_char)?;
+        self.next();
+        Some(sign)
+    }
+
+    /// EBNF: `+inf.0 | -inf.0 | +nan.0 | -nan.0`, case insensitive
+    fn scan_non_number(&mut self) -> Option<NonNumber> {
+        const NON_NUMBER_LENGTH: usize = "+inf.0".len();
+
+        let candidate = self.rest().get(..NON_NUMBER_LENGTH)?;
+        let sign = Sign::from_char(candidate.chars().next()?)?;
+
+        let variant = if candidate[1..].eq_ignore_ascii_case("inf.0") {
+            NonNumberVariant::Infinity
+        } else if candidate[1..].eq_ignore_ascii_case("nan.0") {
+            NonNumberVariant::Invalid
+        } else {
+            return None;
+        };
+
+        self.position += NON_NUMBER_LENGTH;
+
+        Some(NonNumber { sign, variant })
+    }
+
+    /// EBNF: `<Digit R>+ / <Digit R>+ | <R::Number>`
+    fn scan_unsigned_real<R: NumberRadix>(&mut self) -> Result<RealNumberVariant<R>, NumberLiteralScanError> {
+        let digits = self.scan_digits::<R>();
+
+        if !digits.is_empty() && self.next_if(|char| char == '/').is_some() {
+            let denominator = self.scan_digits::<R>();
+
+            if denominator.is_empty() {
+                return Err(self.unexpected());
+            }
+
+            return Ok(RealNumberVariant::Fraction { numerator: NonEmptyVec(digits), denominator: NonEmptyVec(denominator) });
+        }
+
+        R::scan_number(self, digits).map(RealNumberVariant::Number)
+    }
+
+    /// EBNF: `<Digit R>*`
+    fn scan_digits<R: Radix>(&mut self) -> Vec<R> {
+        let mut digits = Vec::new();
+
+        while let Some(digit) = self.peek().and_then(R::from_char) {
+            self.next();
+            digits.push(digit);
+        }
+
+        digits
+    }
+
+    /// EBNF: `<ExponentMarker> [<Sign>] <DecimalDigit>+`
+    fn scan_suffix(&mut self) -> Result<Option<Suffix>, NumberLiteralScanError> {
+        if self.next_if(ExponentMarker::is_marker).is_none() {
+            return Ok(None);
+        }
+
+        let sign = self.scan_sign();
+        let digits = self.scan_digits::<DecimalDigit>();
+
+        if digits.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        Ok(Some(Suffix { sign, digits: NonEmptyVec(digits) }))
+    }
+}
+
+/// Scanning of the radix specific [`Radix::Number`]
+trait NumberRadix: Radix {
+    /// `integer_digits` are the already scanned leading digits, may be empty.
+    fn scan_number(scanner: &mut NumberScanner, integer_digits: Vec<Self>) -> Result<Self::Number, NumberLiteralScanError>;
+}
+
+impl NumberRadix for DecimalDigit {
+    /// EBNF: see [`Decimal`]
+    fn scan_number(scanner: &mut NumberScanner, integer_digits: Vec<Self>) -> Result<Self::Number, NumberLiteralScanError> {
+        let variant = match scanner.next_if(|char| char == '.') {
+            Some(_) => {
+                let fraction_digits = scanner.scan_digits::<DecimalDigit>();
+
+                if integer_digits.is_empty() {
+                    if fraction_digits.is_empty() {
+                        return Err(scanner.unexpected());
+                    }
+
+                    DecimalVariant::Fraction { fraction_digits: NonEmptyVec(fraction_digits) }
+                } else {
+                    DecimalVariant::Both {
+                        digits: NonEmptyVec(integer_digits),
+                        fractional_digits: fraction_digits,
+                    }
+                }
+            }
+            None => {
+                if integer_digits.is_empty() {
+                    return Err(scanner.unexpected());
+                }
+
+                DecimalVariant::Integer(NonEmptyVec(integer_digits))
+            }
+        };
+
+        let suffix = scanner.scan_suffix()?;
+
+        Ok(Decimal { variant, suffix })
+    }
+}
+
+simple_number_radix!(BinaryDigit, OctalDigit, HexadecimalDigit);
+
+macro_rules! simple_number_radix {
+    ($($digit:ty),* $(,)?) => {
+        $(
+            impl NumberRadix for $digit {
+                /// EBNF: `<Digit R>+`
+                fn scan_number(scanner: &mut NumberScanner, integer_digits: Vec<Self>) -> Result<Self::Number, NumberLiteralScanError> {
+                    if integer_digits.is_empty() {
+                        return Err(scanner.unexpected());
+                    }
+
+                    Ok(NonEmptyVec(integer_digits))
+                }
+            }
+        )*
+    };
+}
+use simple_number_radix;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(src: &str) -> NumberLiteral {
+        match Lexer::new(src).tokenize_all().unwrap().remove(0) {
+            TokenAll::Token(Token::Number(number_literal)) => number_literal,
+            other => panic!("expected number token, got {:?}", other),
+        }
+    }
+
+    fn scan_error(src: &str) -> NumberLiteralScanError {
+        match Lexer::new(src).tokenize_all().unwrap_err() {
+            TokenizeError::Number(error) => error,
+            other => panic!("expected number error, got {:?}", other),
+        }
+    }
+
+    fn decimal(src: &str) -> Number<DecimalDigit> {
+        match scan(src) {
+            NumberLiteral::Decimal(number) => number,
+            other => panic!("expected decimal number, got {:?}", other),
+        }
+    }
+
+    fn digits<R: Radix>(digits: &str) -> NonEmptyVec<R> {
+        NonEmptyVec(digits.chars().map(|char| R::from_char(char).unwrap()).collect())
+    }
+
+    fn integer(sign: Option<Sign>, integer_digits: &str) -> RealNumber<DecimalDigit> {
+        RealNumber::Number {
+            sign,
+            variant: RealNumberVariant::Number(Decimal { variant: DecimalVariant::Integer(digits(integer_digits)), suffix: None }),
+        }
+    }
+
+    fn unsigned_integer_variant(integer_digits: &str) -> RealNumberVariant<DecimalDigit> {
+        let RealNumber::Number { variant, .. } = integer(None, integer_digits) else {
+            unreachable!()
+        };
+        variant
+    }
+
+    fn non_number(sign: Sign, variant: NonNumberVariant) -> NonNumber {
+        NonNumber { sign, variant }
+    }
+
+    mod real {
+        use super::*;
+
+        #[test]
+        fn integer() {
+            let number = decimal("42");
+            assert_eq!(super::integer(None, "42"), real(number));
+            assert_eq!(Span::new("42", 0, 2), number_span("42"));
+
+            assert_eq!(super::integer(Some(Sign::Plus), "1"), real(decimal("+1")));
+            assert_eq!(super::integer(Some(Sign::Minus), "10"), real(decimal("-10")));
+        }
+
+        #[test]
+        fn fraction() {
+            let expected = RealNumber::Number {
+                sign: Some(Sign::Minus),
+                variant: RealNumberVariant::Fraction { numerator: digits("1"), denominator: digits("2") },
+            };
+            assert_eq!(expected, real(decimal("-1/2")));
+        }
+
+        #[test]
+        fn decimal_variants() {
+            let expected_variant = |variant, suffix| RealNumber::Number {
+                sign: None,
+                variant: RealNumberVariant::Number(Decimal { variant, suffix }),
+            };
+
+            assert_eq!(
+                expected_variant(
+                    DecimalVariant::Both { digits: digits("1"), fractional_digits: digits::<DecimalDigit>("5").0 },
+                    None
+                ),
+                real(decimal("1.5"))
+            );
+            assert_eq!(
+                expected_variant(DecimalVariant::Both { digits: digits("1"), fractional_digits: Vec::new() }, None),
+                real(decimal("1."))
+            );
+            assert_eq!(
+                expected_variant(DecimalVariant::Fraction { fraction_digits: digits("25") }, None),
+                real(decimal(".25"))
+            );
+        }
+
+        #[test]
+        fn suffix() {
+            let suffix = |sign, suffix_digits| Some(Suffix { sign, digits: digits(suffix_digits) });
+
+            let expected = RealNumber::Number {
+                sign: None,
+                variant: RealNumberVariant::Number(Decimal {
+                    variant: DecimalVariant::Both { digits: digits("1"), fractional_digits: digits::<DecimalDigit>("5").0 },
+                    suffix: suffix(None, "10"),
+                }),
+            };
+            assert_eq!(expected, real(decimal("1.5e10")));
+
+            let expected = RealNumber::Number {
+                sign: Some(Sign::Minus),
+                variant: RealNumberVariant::Number(Decimal {
+                    variant: DecimalVariant::Integer(digits("2")),
+                    suffix: suffix(Some(Sign::Minus), "3"),
+                }),
+            };
+            assert_eq!(expected, real(decimal("-2E-3")));
+        }
+
+        #[test]
+        fn non_numbers() {
+            let cases = [
+                ("+inf.0", Sign::Plus, NonNumberVariant::Infinity),
+                ("-inf.0", Sign::Minus, NonNumberVariant::Infinity),
+                ("+nan.0", Sign::Plus, NonNumberVariant::Invalid),
+                ("-NAN.0", Sign::Minus, NonNumberVariant::Invalid),
+            ];
+
+            for (src, sign, variant) in cases {
+                assert_eq!(RealNumber::NonNumber(non_number(sign, variant)), real(decimal(src)));
+            }
+        }
+
+        fn real(number: Number<DecimalDigit>) -> RealNumber<DecimalDigit> {
+            match number.inner {
+                ComplexNumber::Real(real) => real,
+                other => panic!("expected real number, got {:?}", other),
+            }
+        }
+
+        fn number_span(src: &str) -> Span {
+            decimal(src).span
+        }
+    }
+
+    mod complex {
+        use super::*;
+
+        #[test]
+        fn polar() {
+            let expected = ComplexNumber::Polar { magnitude: integer(None, "1"), phase: integer(Some(Sign::Minus), "2") };
+            assert_eq!(expected, decimal("1@-2").inner);
+        }
+
+        #[test]
+        fn rectangular() {
+            let expected = ComplexNumber::RectangularValid {
+                real: Some(integer(None, "3")),
+                sign: Sign::Minus,
+                imaginary: Some(unsigned_integer_variant("4")),
+            };
+            assert_eq!(expected, decimal("3-4i").inner);
+        }
+
+        #[test]
+        fn imaginary_only() {
+            let expected = ComplexNumber::RectangularValid {
+                real: None,
+                sign: Sign::Plus,
+                imaginary: Some(unsigned_integer_variant("2")),
+            };
+            assert_eq!(expected, decimal("+2i").inner);
+        }
+
+        #[test]
+        fn imaginary_unit() {
+            let expected = ComplexNumber::RectangularValid { real: None, sign: Sign::Plus, imaginary: None };
+            assert_eq!(expected, decimal("+i").inner);
+
+            let expected = ComplexNumber::RectangularValid { real: None, sign: Sign::Minus, imaginary: None };
+            assert_eq!(expected, decimal("-I").inner);
+
+            let expected = ComplexNumber::RectangularValid { real: Some(integer(None, "1")), sign: Sign::Minus, imaginary: None };
+            assert_eq!(expected, decimal("1-i").inner);
+        }
+
+        #[test]
+        fn non_number_imaginary() {
+            let expected = ComplexNumber::RectangularInvalid {
+                real: Some(integer(None, "1")),
+                imaginary: non_number(Sign::Plus, NonNumberVariant::Infinity),
+            };
+            assert_eq!(expected, decimal("1+inf.0i").inner);
+
+            let expected = ComplexNumber::RectangularInvalid {
+                real: None,
+                imaginary: non_number(Sign::Minus, NonNumberVariant::Invalid),
+            };
+            assert_eq!(expected, decimal("-nan.0i").inner);
+        }
+    }
+
+    mod prefix {
+        use super::*;
+
+        #[test]
+        fn radixes() {
+            assert!(matches!(scan("#b101"), NumberLiteral::Binary(_)));
+            assert!(matches!(scan("#o17"), NumberLiteral::Octal(_)));
+            assert!(matches!(scan("#d19"), NumberLiteral::Decimal(_)));
+            assert!(matches!(scan("#xfF"), NumberLiteral::Hexadecimal(_)));
+            assert!(matches!(scan("#XA"), NumberLiteral::Hexadecimal(_)));
+        }
+
+        #[test]
+        fn hexadecimal_digits() {
+            let NumberLiteral::Hexadecimal(number) = scan("#x-1/Ae") else {
+                panic!()
+            };
+
+            let expected = ComplexNumber::Real(RealNumber::Number {
+                sign: Some(Sign::Minus),
+                variant: RealNumberVariant::Fraction { numerator: digits("1"), denominator: digits("Ae") },
+            });
+            assert_eq!(expected, number.inner);
+        }
+
+        #[test]
+        fn exactness_in_either_order() {
+            for src in ["#e#x10", "#x#e10", "#E#X10"] {
+                let NumberLiteral::Hexadecimal(number) = scan(src) else { panic!() };
+                assert_eq!(Some(Exactness::Exact), number.prefix.exactness);
+            }
+
+            assert_eq!(Some(Exactness::Inexact), decimal("#i1/3").prefix.exactness);
+            assert_eq!(None, decimal("1").prefix.exactness);
+        }
+
+        #[test]
+        fn unknown_prefix_error() {
+            let src = "#e#z1";
+            assert_eq!(NumberLiteralScanError::UnknownPrefix(Span::new(src, 2, 4)), scan_error(src));
+        }
+
+        #[test]
+        fn duplicate_prefix_error() {
+            let src = "#x#b1";
+            assert_eq!(NumberLiteralScanError::DuplicateRadix(Span::new(src, 2, 4)), scan_error(src));
+
+            let src = "#e#i1";
+            assert_eq!(NumberLiteralScanError::DuplicateExactness(Span::new(src, 2, 4)), scan_error(src));
+        }
+
+        #[test]
+        fn invalid_radix_digit_error() {
+            let src = "#b102";
+            assert_eq!(NumberLiteralScanError::UnexpectedCharacter(Span::new(src, 4, 5)), scan_error(src));
+
+            // decimal point only allowed in decimal radix
+            let src = "#x1.5";
+            assert_eq!(NumberLiteralScanError::UnexpectedCharacter(Span::new(src, 3, 4)), scan_error(src));
+        }
+
+        #[test]
+        fn missing_digits_error() {
+            let src = "#x";
+            assert_eq!(NumberLiteralScanError::UnexpectedEnd(Span::new(src, 0, 2)), scan_error(src));
+        }
+    }
+
+    mod disambiguation {
+        use super::*;
+
+        #[test]
+        fn peculiar_identifiers() {
+            for src in ["+", "-", "...", "->x", "+a", "-inf", "+.a", "+in"] {
+                let tokens = Lexer::new(src).tokenize_all().unwrap();
+                assert!(
+                    matches!(tokens[0], TokenAll::Token(Token::Identifier(Identifier::Peculiar(_)))),
+                    "{src} not an identifier"
+                );
+            }
+        }
+
+        #[test]
+        fn dot_is_not_a_number() {
+            let tokens = Lexer::new(".").tokenize_all().unwrap();
+            assert!(matches!(tokens[0], TokenAll::Token(Token::Other(_))));
+        }
+
+        #[test]
+        fn numeric_lexeme_errors() {
+            let src = "1+";
+            assert_eq!(NumberLiteralScanError::UnexpectedEnd(Span::new(src, 0, 2)), scan_error(src));
+
+            let src = "12abc";
+            assert_eq!(NumberLiteralScanError::UnexpectedCharacter(Span::new(src, 2, 3)), scan_error(src));
+
+            let src = "1e";
+            assert_eq!(NumberLiteralScanError::UnexpectedEnd(Span::new(src, 0, 2)), scan_error(src));
+
+            // imaginary part requires a sign
+            let src = "1i";
+            assert_eq!(NumberLiteralScanError::UnexpectedCharacter(Span::new(src, 1, 2)), scan_error(src));
+
+            let src = "1/";
+            assert_eq!(NumberLiteralScanError::UnexpectedEnd(Span::new(src, 0, 2)), scan_error(src));
+        }
+
+        #[test]
+        fn delimited_number() {
+            let src = "(1 2)";
+            let tokens = Lexer::new(src).tokenize_all().unwrap();
+            assert!(matches!(tokens[1], TokenAll::Token(Token::Number(_))));
+            assert!(matches!(tokens[2], TokenAll::Token(Token::Number(_))));
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use alloc::vec::Vec;
+
+use crate::*;
+
+impl<'src> Lexer<'src> {
+    /// `"` scanned
+    pub(super) fn scan_string(&mut self, start_index: usize) -> Result<(), StringLiteralScanError> {
+        let mut string_elements = StringElementCollector::new(self.scanner.src());
+
+        loop {
+            let Some((char_index, char)) = self.scanner.next() else {
+                let eof_span = self.scanner.span_to_end_of_file(start_index);
+                return Err(StringLiteralScanError::EndOfFile(eof_span));
+            };
+
+            match char {
+                '"' => {
+                    let string_elements = string_elements.finalize(char_index);
+
+                    let token = TokenAll::Token(Token::String(StringLiteral {
+                        inner: string_elements,
+                        // + 1 to include `"` in span
+                        span: self.scanner.span(start_index, char_index + 1),
+                    }));
+
+                    self.push_token_all(token);
+
+                    return Ok(());
+                }
+                '\\' => {
+                    let Some((char_nested_index, char_nested)) = self.scanner.next() else {
+                        let eof_span = self.scanner.span_to_end_of_file(start_index);
+                        return Err(StringLiteralScanError::EndOfFile(eof_span));
+                    };
+
+                    match char_nested {
+                        '"' => string_elements.push_string_escape(char_index, StringEscape::DoubleQuote),
+                        '\\' => string_elements.push_string_escape(char_index, StringEscape::Backslash),
+                        '|' => string_elements.push_string_escape(char_index, StringEscape::VerticalLine),
+                        'x' | 'X' => {
+                            let inline_code_point = self.scan_inline_hex(char_index)?;
+                            string_elements.push_inline_code_point(char_index, inline_code_point);
+                        }
+                        'a' => string_elements.push_mnemonic_escape(char_index, MnemonicEscape::Alarm),
+                        'b' => string_elements.push_mnemonic_escape(char_index, MnemonicEscape::Backspace),
+                        't' => string_elements.push_mnemonic_escape(char_index, MnemonicEscape::Tab),
+                        'n' => string_elements.push_mnemonic_escape(char_index, MnemonicEscape::Newline),
+                        'r' => string_elements.push_mnemonic_escape(char_index, MnemonicEscape::Return),
+                        '\r' => {
+                            // \r\n case handled by `maybe_update_line_ending` call in next loop iteration
+                            string_elements.push_newline_escape(char_index, LineEnding::Return, Vec::new());
+                        }
+                        '\n' => {
+                            string_elements.push_newline_escape(char_index, LineEnding::Newline, Vec::new());
+                        }
+                        ' ' => {
+                            self.scan_string_newline_escape(start_index, char_index, &mut string_elements, IntralineWhitespace::Space)?
+                        }
+                        '\t' => self.scan_string_newline_escape(start_index, char_index, &mut string_elements, IntralineWhitespace::Tab)?,
+                        _ => {
+                            // + 1 to include the unknown escape character
+                            let span = self.scanner.span(char_index, char_nested_index + 1);
+                            return Err(StringLiteralScanError::UnknownEscape(span));
+                        }
+                    }
+                }
+                '\n' => {
+                    string_elements.maybe_update_line_ending(char_index);
+                }
+                _ => {
+                    string_elements.maybe_begin_chars(char_index);
+                }
+            }
+        }
+    }
+
+    fn scan_string_newline_escape(
+        &mut self,
+        string_start_index: usize,
+        backslash_index: usize,
+        string_elements: &mut StringElementCollector,
+        first_whitespace_char: IntralineWhitespace,
+    ) -> Result<(), StringLiteralScanError> {
+        let mut leading_whitespace = alloc::vec![first_whitespace_char];
+
+        loop {
+            // Peeked so that an unknown character, possibly the closing `"`, is left for error
+            // recovery to resume at.
+            let Some((newline_escape_char_index, newline_escape_char)) = self.scanner.peek() else {
+                let eof_span = self.scanner.span_to_end_of_file(string_start_index);
+                return Err(StringLiteralScanError::EndOfFile(eof_span));
+            };
+
+            match newline_escape_char {
+                ' ' => leading_whitespace.push(IntralineWhitespace::Space),
+                '\t' => leading_whitespace.push(IntralineWhitespace::Tab),
+                '\r' => {
+                    self.scanner.next();
+                    string_elements.push_newline_escape(backslash_index, LineEnding::Return, leading_whitespace);
+                    break;
+                }
+                '\n' => {
+                    self.scanner.next();
+                    string_elements.push_newline_escape(backslash_index, LineEnding::Newline, leading_whitespace);
+                    break;
+                }
+                _ => {
+                    let span = self.scanner.span_char(newline_escape_char_index);
+                    return Err(StringLiteralScanError::UnknownWhitespace(span));
+                }
+            }
+
+            self.scanner.next();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+
+    use super::*;
+
+    fn expected_string_token<'src>(src: &'src str, start: usize, end: usize, element: StringElement<'src>) -> TokenAll<'src> {
+        expected_string_tokens(src, start, end, [element])
+    }
+
+    fn expected_string_tokens<'src>(
+        src: &'src str,
+        start: usize,
+        end: usize,
+        elements: impl IntoIterator<Item = StringElement<'src>>,
+    ) -> TokenAll<'src> {
+        TokenAll::Token(Token::String(StringLiteral {
+            inner: elements.into_iter().collect(),
+            span: Span::new(src, start, end),
+        }))
+    }
+
+    #[test]
+    fn valid_scan() {
+        let src = "\"abc\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 5, StringElement::Chars("abc"));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn mixed_string_elements() {
+        let src = "\"abc\\x64;e\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let string_elements = [
+            StringElement::Chars("abc"),
+            StringElement::InlineCodePoint(InlineCodePoint('d', Span::new(src, 4, 9))),
+            StringElement::Chars("e"),
+        ];
+        let expected = expected_string_tokens(src, 0, 11, string_elements);
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn backslash_escape() {
+        let src = r#""\\""#;
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::StringEscape(StringEscape::Backslash));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn vertical_line_escape() {
+        let src = r#""\|""#;
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::StringEscape(StringEscape::VerticalLine));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn double_quote_escape() {
+        let src = r#""\"""#;
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::StringEscape(StringEscape::DoubleQuote));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn mnemonic_escape() {
+        let src = "\"\\a\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::MnemonicEscape(MnemonicEscape::Alarm));
+        assert_eq!(&expected, comment);
+
+        let src = "\"\\b\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::MnemonicEscape(MnemonicEscape::Backspace));
+        assert_eq!(&expected, comment);
+
+        let src = "\"\\n\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::MnemonicEscape(MnemonicEscape::Newline));
+        assert_eq!(&expected, comment);
+
+        let src = "\"\\r\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::MnemonicEscape(MnemonicEscape::Return));
+        assert_eq!(&expected, comment);
+
+        let src = "\"\\t\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 4, StringElement::MnemonicEscape(MnemonicEscape::Tab));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn inline_hex_escape() {
+        // lower x
+        let src = "\"\\x61;\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let inlined_code_point = InlineCodePoint('a', Span::new(src, 1, 6));
+        let expected = expected_string_token(src, 0, 7, StringElement::InlineCodePoint(inlined_code_point));
+        assert_eq!(&expected, comment);
+
+        // upper X
+        let src = "\"\\X61;\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let inlined_code_point = InlineCodePoint('a', Span::new(src, 1, 6));
+        let expected = expected_string_token(src, 0, 7, StringElement::InlineCodePoint(inlined_code_point));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn unclosed_eof_error() {
+        // at first char
+        let src = r#"""#;
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 0, 1);
+        let expected_error = TokenizeError::String(StringLiteralScanError::EndOfFile(expected_span));
+        assert_eq!(expected_error, actual_error);
+
+        // at subsequent chars
+        let src = r#""abc"#;
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 0, 4);
+        let expected_error = TokenizeError::String(StringLiteralScanError::EndOfFile(expected_span));
+        assert_eq!(expected_error, actual_error);
+    }
+
+    #[test]
+    fn newline_escape() {
+        // keeps leading and trailing whitespace
+
+        // supports both \t and ' ' as first and subsequent chars
+        let src = "\"abc  \\ \t\n  def\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        let comment = &tokens[0];
+        let expected = expected_string_tokens(
+            src,
+            0,
+            16,
+            [
+                StringElement::Chars("abc  "),
+                StringElement::NewlineEscape(StringNewlineEscape {
+                    line_ending: LineEnding::Newline,
+                    leading_whitespace: alloc::vec![IntralineWhitespace::Space, IntralineWhitespace::Tab],
+                }),
+                StringElement::Chars("  def"),
+            ],
+        );
+        assert_eq!(&expected, comment);
+
+        // supports both \t and ' ' as first and subsequent chars
+        let src = "\"abc  \\\t \n  def\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        let comment = &tokens[0];
+        let expected = expected_string_tokens(
+            src,
+            0,
+            16,
+            [
+                StringElement::Chars("abc  "),
+                StringElement::NewlineEscape(StringNewlineEscape {
+                    line_ending: LineEnding::Newline,
+                    leading_whitespace: alloc::vec![IntralineWhitespace::Tab, IntralineWhitespace::Space],
+                }),
+                StringElement::Chars("  def"),
+            ],
+        );
+        assert_eq!(&expected, comment);
+
+        // without leading whitespace
+        // TODO: test \r and \r\n return too
+        let src = "\"a\\\nb\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+        let comment = &tokens[0];
+        let expected = expected_string_tokens(
+            src,
+            0,
+            6,
+            [
+                StringElement::Chars("a"),
+                StringElement::NewlineEscape(StringNewlineEscape {
+                    // TODO: test \r and \r\n return too
+                    line_ending: LineEnding::Newline,
+                    leading_whitespace: Default::default(),
+                }),
+                StringElement::Chars("b"),
+            ],
+        );
+        assert_eq!(&expected, comment);
+    }
+
+    // Make sure that the newline isn't simply checked for a possible
+    // CRLF of a newline escape and then discarded if not.
+    #[test]
+    fn newline_as_first_char() {
+        let src = "\"\n\"";
+        let tokens = Lexer::new(src).tokenize_all().unwrap();
+
+        let comment = &tokens[0];
+        let expected = expected_string_token(src, 0, 3, StringElement::Chars("\n"));
+        assert_eq!(&expected, comment);
+    }
+
+    #[test]
+    fn newline_escape_eof_error() {
+        let src = r#""\ "#;
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 0, 3);
+        let expected_error = TokenizeError::String(StringLiteralScanError::EndOfFile(expected_span));
+        assert_eq!(expected_error, actual_error);
+    }
+
+    #[test]
+    fn newline_escape_unknown_whitespace_error() {
+        let src = r#""\ a""#;
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 3, 4);
+        let expected_error = TokenizeError::String(StringLiteralScanError::UnknownWhitespace(expected_span));
+        assert_eq!(expected_error, actual_error);
+    }
+
+    #[test]
+    fn newline_escape_without_line_ending_error() {
+        // prematurely closing is an error when following the specs grammar
+        // (newline isn't optional)
+        let src = "\"\\ \"";
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 3, 4);
+        let expected_error = TokenizeError::String(StringLiteralScanError::UnknownWhitespace(expected_span));
+        assert_eq!(expected_error, actual_error);
+    }
+
+    #[test]
+    fn unknown_escape_error() {
+        let src = r#""\y""#;
+        let actual_error = Lexer::new(src).tokenize_all().unwrap_err();
+
+        let expected_span = Span::new(src, 1, 3);
+        let expected_error = TokenizeError::String(StringLiteralScanError::UnknownEscape(expected_span));
+        assert_eq!(expected_error, actual_error);
+    }
+
+    #[test]
+    fn value() {
+        fn value(src: &str) -> Cow<'_, str> {
+            match Lexer::new(src).tokenize_all().unwrap().pop() {
+                Some(TokenAll::Token(Token::String(string_literal))) => string_literal.value(),
+                other => panic!("expected string token, got {:?}", other),
+            }
+        }
+
+        assert!(matches!(value("\"\""), Cow::Borrowed("")));
+        assert!(matches!(value("\"abc\""), Cow::Borrowed("abc")));
+        assert_eq!("a\"b\\c|\x07\n\u{3BB}", value(r#""a\"b\\c\|\a\n\x3BB;""#));
+        assert_eq!("ab", value("\"a\\  \n  b\""));
+    }
+}
@@ -1,9 +1,9 @@
 //! Pluine Lexer
 //!
-//! Converts a UTF-8 string to a [`TokenStream`].
+//! Converts a UTF-8 string to [`Token`]s.
 //! The output should still be high-level enough for a simple formatter.
 //!
-//!//! ## Features
+//! ## Features
 //!
 //! (None are turned on by default.)
 //!
@@ -22,10 +22,14 @@
 //! which may in turn include the previously forbidden character. `\` (U+005C)
 //! belonging to the Po category being one such example.
 //!
-//! Pluine implements, therefore, unicode idedentifier support by allowing any character in the
+//! Pluine implements, therefore, unicode identifier support by allowing any character in the
 //! allowed Unicode general categories, *unless* it is also an ASCII character which was
 //! previously not permitted. Conversely, a character not part of the allowed unicode categories
-//! will still be permitted if it was an allowed ASCII character./!
+//! will still be permitted if it was an allowed ASCII character.
+//!
+//! Without the feature, non-ASCII identifier characters are rejected. Vertical identifiers
+//! (`|...|`) accept any character other than `|` and `\` in both cases, along with escapes such
+//! as `|\x3BB;|`.
 //!
 //! ## Token modelling
 //!
@@ -54,6 +58,12 @@ pub mod span;
 pub(crate) use pluine_lex_macros::Spanned;
 pub(crate) use span::{Span, Spanned};
 
+pub mod line_index;
+
+pub mod diagnostic;
+
+pub mod cst;
+
 mod lexer;
 pub use lexer::Lexer;
 
@@ -61,21 +71,39 @@ mod scanner;
 pub(crate) use scanner::Scanner;
 
 mod token;
-pub(crate) use token::{Token, TokenAll, TokenChar};
+pub use token::{Token, TokenAll, TokenChar, TokenCharVariant, UnknownToken};
 
 mod error;
-pub(crate) use error::TokenizeError;
+pub use error::TokenizeError;
+
+mod label;
+pub use label::{DatumLabel, DatumLabelVariant};
 
 mod comment;
-pub(crate) use comment::{Comment, NestedComment, SemicolonComment};
+pub use comment::{Comment, NestedComment, SectionComment, SemicolonComment};
+pub(crate) use comment::{NestedCommentContinuation, NestedCommentScanError, NestedCommentText};
 
 mod identifier;
+pub use identifier::Identifier;
 pub(crate) use identifier::*;
 
 mod primitive;
 pub(crate) use primitive::*;
+pub use primitive::{Boolean, CharacterLiteral, NumberLiteral, StringLiteral};
+
+/// Structure of a [`NumberLiteral`], for consumers evaluating it
+pub mod number {
+    pub use crate::{
+        misc::{NonEmptyVec, Sign},
+        primitive::{
+            BinaryDigit, ComplexNumber, Decimal, DecimalDigit, DecimalVariant, Exactness, HexadecimalDigit, NonNumber, NonNumberVariant,
+            Number, OctalDigit, Prefix, Radix, RealNumber, RealNumberVariant, Suffix,
+        },
+    };
+}
 
 mod misc;
 pub(crate) use misc::*;
+pub use misc::{Atmosphere, Directive, DirectiveVariant};
 
 mod private;
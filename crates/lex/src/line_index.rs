@@ -0,0 +1,300 @@
+//! Conversion of byte offsets and [`Span`]s to line and column positions.
+
+use alloc::vec::Vec;
+
+use crate::*;
+
+/// Unit in which [`LineColumn::column`] is counted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnUnit {
+    /// UTF-8 code units, equivalent to byte offsets within the line
+    Utf8,
+    /// UTF-16 code units, as used by the Language Server Protocol
+    Utf16,
+    /// Unicode scalar values, Rust `char`s that is
+    Char,
+}
+
+/// Zero-based line and column position.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LineColumn {
+    /// Zero-based line number
+    pub line: usize,
+    /// Zero-based column, counted in the requested [`ColumnUnit`]
+    pub column: usize,
+}
+
+/// Start and end positions of a [`Span`], end being exclusive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SpanLocation {
+    /// Position of the first character
+    pub start: LineColumn,
+    /// Position directly after the last character
+    pub end: LineColumn,
+}
+
+/// Line start offsets of a source string, built once and then queried for line and column
+/// positions.
+///
+/// Lines are terminated by every `LineEnding` variant: `\n`, `\r` and `\r\n`. The latter
+/// counts as a single line ending.
+///
+/// ```
+/// # use pluine_lex::line_index::{ColumnUnit, LineColumn, LineIndex};
+/// let line_index = LineIndex::new("(a\r\n λ b)");
+///
+/// let position = line_index.line_column(8, ColumnUnit::Char);
+/// assert_eq!(LineColumn { line: 1, column: 3 }, position);
+/// ```
+#[derive(Debug)]
+pub struct LineIndex<'src> {
+    src: &'src str,
+    /// Byte offset of the first character in each line, always starts with `0`.
+    line_starts: Vec<usize>,
+}
+
+impl<'src> LineIndex<'src> {
+    /// Scans `src` for line endings.
+    pub fn new(src: &'src str) -> Self {
+        let mut line_starts = alloc::vec![0];
+        let mut bytes = src.bytes().enumerate().peekable();
+
+        // Line endings are ASCII, so the following index is always on a char boundary.
+        while let Some((index, byte)) = bytes.next() {
+            match byte {
+                b'\n' => line_starts.push(index + 1),
+                b'\r' => match bytes.next_if(|(_, next_byte)| *next_byte == b'\n') {
+                    Some((newline_index, _)) => line_starts.push(newline_index + 1),
+                    None => line_starts.push(index + 1),
+                },
+                _ => {}
+            }
+        }
+
+        Self { src, line_starts }
+    }
+
+    /// Source string the index was built from
+    pub fn src(&self) -> &'src str {
+        self.src
+    }
+
+    /// Number of lines, at least one. A trailing line ending starts a new empty line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Zero-based line number containing `offset`.
+    ///
+    /// An offset pointing to a line ending belongs to the line it terminates.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is greater than the source length.
+    pub fn line(&self, offset: usize) -> usize {
+        assert!(offset <= self.src.len(), "offset {offset} out of bounds");
+
+        // `line_starts[0] == 0`, partition point is therefore at least 1
+        self.line_starts.partition_point(|line_start| *line_start <= offset) - 1
+    }
+
+    /// Line and column of `offset`.
+    ///
+    /// # Panics
+    ///
+    /// If `offset` is greater than the source length or not on a UTF-8 sequence boundary.
+    pub fn line_column(&self, offset: usize, unit: ColumnUnit) -> LineColumn {
+        let line = self.line(offset);
+        let line_prefix = &self.src[self.line_starts[line]..offset];
+
+        let column = match unit {
+            ColumnUnit::Utf8 => line_prefix.len(),
+            ColumnUnit::Utf16 => line_prefix.chars().map(char::len_utf16).sum(),
+            ColumnUnit::Char => line_prefix.chars().count(),
+        };
+
+        LineColumn { line, column }
+    }
+
+    /// Start and end positions of `span`, which must have been created from the same source.
+    ///
+    /// # Panics
+    ///
+    /// See [`Self::line_column`]
+    pub fn span_location(&self, span: Span, unit: ColumnUnit) -> SpanLocation {
+        SpanLocation {
+            start: self.line_column(span.start(), unit),
+            end: self.line_column(span.end(), unit),
+        }
+    }
+
+    /// Byte offset of a line and column, the inverse of [`Self::line_column`].
+    ///
+    /// `None` if the line does not exist, or if the column is past the line's end or within a
+    /// multi-unit character.
+    pub fn offset(&self, line_column: LineColumn, unit: ColumnUnit) -> Option<usize> {
+        let line_start = *self.line_starts.get(line_column.line)?;
+        let line_str = self.line_str(line_column.line)?;
+
+        let column_offset = match unit {
+            ColumnUnit::Utf8 => line_str.is_char_boundary(line_column.column).then_some(line_column.column)?,
+            ColumnUnit::Utf16 => {
+                let mut utf16_column = 0;
+                let mut char_indices = line_str.char_indices();
+
+                loop {
+                    if utf16_column == line_column.column {
+                        break char_indices.offset();
+                    }
+
+                    let (_, char) = char_indices.next()?;
+                    utf16_column += char.len_utf16();
+                }
+            }
+            ColumnUnit::Char => match line_str.char_indices().nth(line_column.column) {
+                Some((index, _)) => index,
+                None => (line_str.chars().count() == line_column.column).then_some(line_str.len())?,
+            },
+        };
+
+        if column_offset > line_str.len() {
+            return None;
+        }
+
+        Some(line_start + column_offset)
+    }
+
+    /// Contents of a zero-based line, line ending excluded.
+    pub fn line_str(&self, line: usize) -> Option<&'src str> {
+        let line_start = *self.line_starts.get(line)?;
+        let next_line_start = self.line_starts.get(line + 1).copied().unwrap_or(self.src.len());
+
+        let line_str = &self.src[line_start..next_line_start];
+        let line_str = line_str.strip_suffix('\n').unwrap_or(line_str);
+        Some(line_str.strip_suffix('\r').unwrap_or(line_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_line_column(line_index: &LineIndex, offset: usize, unit: ColumnUnit, line: usize, column: usize) {
+        assert_eq!(LineColumn { line, column }, line_index.line_column(offset, unit));
+    }
+
+    #[test]
+    fn single_line() {
+        let line_index = LineIndex::new("abc");
+
+        assert_eq!(1, line_index.line_count());
+        assert_line_column(&line_index, 0, ColumnUnit::Utf8, 0, 0);
+        assert_line_column(&line_index, 3, ColumnUnit::Utf8, 0, 3);
+    }
+
+    #[test]
+    fn empty_source() {
+        let line_index = LineIndex::new("");
+
+        assert_eq!(1, line_index.line_count());
+        assert_line_column(&line_index, 0, ColumnUnit::Char, 0, 0);
+        assert_eq!(Some(""), line_index.line_str(0));
+    }
+
+    #[test]
+    fn line_endings() {
+        let src = "a\nb\rc\r\nd";
+        let line_index = LineIndex::new(src);
+
+        assert_eq!(4, line_index.line_count());
+        assert_line_column(&line_index, 2, ColumnUnit::Utf8, 1, 0);
+        assert_line_column(&line_index, 4, ColumnUnit::Utf8, 2, 0);
+        assert_line_column(&line_index, 7, ColumnUnit::Utf8, 3, 0);
+    }
+
+    #[test]
+    fn line_ending_belongs_to_terminated_line() {
+        let line_index = LineIndex::new("ab\r\ncd");
+
+        assert_line_column(&line_index, 2, ColumnUnit::Utf8, 0, 2);
+        assert_line_column(&line_index, 3, ColumnUnit::Utf8, 0, 3);
+        assert_line_column(&line_index, 4, ColumnUnit::Utf8, 1, 0);
+    }
+
+    #[test]
+    fn trailing_line_ending() {
+        let src = "a\n";
+        let line_index = LineIndex::new(src);
+
+        assert_eq!(2, line_index.line_count());
+        assert_line_column(&line_index, 2, ColumnUnit::Utf8, 1, 0);
+    }
+
+    #[test]
+    fn column_units() {
+        // λ: 2 UTF-8 units, 1 UTF-16 unit. 💯: 4 UTF-8 units, 2 UTF-16 units.
+        let src = "x\nλ💯y";
+        let line_index = LineIndex::new(src);
+        let y_offset = src.find('y').unwrap();
+
+        assert_line_column(&line_index, y_offset, ColumnUnit::Utf8, 1, 6);
+        assert_line_column(&line_index, y_offset, ColumnUnit::Utf16, 1, 3);
+        assert_line_column(&line_index, y_offset, ColumnUnit::Char, 1, 2);
+    }
+
+    #[test]
+    fn span_location() {
+        let src = "(a\n  \"λ\")";
+        let line_index = LineIndex::new(src);
+
+        let expected = SpanLocation {
+            start: LineColumn { line: 1, column: 2 },
+            end: LineColumn { line: 1, column: 5 },
+        };
+        assert_eq!(expected, line_index.span_location(Span::new(src, 5, 9), ColumnUnit::Char));
+    }
+
+    #[test]
+    fn offset_roundtrip() {
+        let src = "a\r\nλ💯y\rz";
+        let line_index = LineIndex::new(src);
+
+        for (offset, _) in src.char_indices().chain([(src.len(), ' ')]) {
+            for unit in [ColumnUnit::Utf8, ColumnUnit::Utf16, ColumnUnit::Char] {
+                let line_column = line_index.line_column(offset, unit);
+
+                // line ending offsets are past the end of `line_str`
+                if LineEnding::is_line_ending(src[offset..].chars().next().unwrap_or(' ')) && line_column.column > 0 {
+                    continue;
+                }
+
+                assert_eq!(Some(offset), line_index.offset(line_column, unit), "{unit:?} {line_column:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn offset_invalid() {
+        let line_index = LineIndex::new("λ💯\nb");
+
+        // within a multi-unit character
+        assert_eq!(None, line_index.offset(LineColumn { line: 0, column: 1 }, ColumnUnit::Utf8));
+        assert_eq!(None, line_index.offset(LineColumn { line: 0, column: 2 }, ColumnUnit::Utf16));
+        // past line end
+        assert_eq!(None, line_index.offset(LineColumn { line: 0, column: 3 }, ColumnUnit::Char));
+        assert_eq!(None, line_index.offset(LineColumn { line: 1, column: 2 }, ColumnUnit::Utf8));
+        // line does not exist
+        assert_eq!(None, line_index.offset(LineColumn { line: 2, column: 0 }, ColumnUnit::Utf8));
+    }
+
+    #[test]
+    fn line_str() {
+        let line_index = LineIndex::new("a\r\nb\rc\n");
+
+        assert_eq!(Some("a"), line_index.line_str(0));
+        assert_eq!(Some("b"), line_index.line_str(1));
+        assert_eq!(Some("c"), line_index.line_str(2));
+        assert_eq!(Some(""), line_index.line_str(3));
+        assert_eq!(None, line_index.line_str(4));
+    }
+}
@@ -1,22 +1,60 @@
+use thiserror::Error;
+
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+/// Tokens without meaning to the datum syntax
+#[derive(Debug, PartialEq, Spanned)]
 pub enum Atmosphere<'src> {
+    /// See [`Comment`]
     Comment(Comment<'src>),
+    /// See [`Directive`]
     Directive(Directive),
 }
 
-#[derive(Debug, PartialEq)]
+/// EBNF: `#! <DirectiveVariant>`
+#[derive(Debug, PartialEq, Spanned)]
 pub struct Directive {
-    inner: DirectiveVariant,
-    span: Span,
+    pub(crate) inner: DirectiveVariant,
+    #[span]
+    pub(crate) span: Span,
+}
+
+impl Directive {
+    /// Which directive this is
+    pub fn variant(&self) -> DirectiveVariant {
+        self.inner
+    }
 }
 
-// TODO: case-insensitive directives? spec seems to imply that they should be
-#[derive(Debug, PartialEq)]
+/// Directive names are matched case-insensitively, as is the case for all other
+/// R7RS syntax apart from letters in identifiers, character names and escapes.
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum DirectiveVariant {
     /// EBNF: `#!fold-case`
+    ///
+    /// Subsequent identifiers and character names are case folded, see
+    /// [R7RS 2.1](https://standards.scheme.org/corrected-r7rs/r7rs-Z-H-4.html#TAG:__tex2page_sec_2.1).
     FoldCase,
     /// EBNF: `#!no-fold-case`
     NoFoldCase,
 }
+
+impl DirectiveVariant {
+    /// `name` excludes the leading `#!`
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("fold-case") {
+            Some(Self::FoldCase)
+        } else if name.eq_ignore_ascii_case("no-fold-case") {
+            Some(Self::NoFoldCase)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Error, Spanned)]
+pub enum DirectiveScanError {
+    /// Inner span points to the entire directive, `#!` included
+    #[error("unknown directive, expected '#!fold-case' or '#!no-fold-case'")]
+    Unknown(Span),
+}
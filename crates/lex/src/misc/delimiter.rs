@@ -0,0 +1,10 @@
+/// Characters terminating identifiers, numbers, booleans and character literals.
+///
+/// EBNF: `<Whitespace> | <VerticalLine> | ( | ) | " | ;`
+pub struct Delimiter;
+
+impl Delimiter {
+    pub(crate) fn is_delimiter(char: char) -> bool {
+        matches!(char, ' ' | '\t' | '\n' | '\r' | '|' | '(' | ')' | '"' | ';')
+    }
+}
@@ -12,6 +12,18 @@ mod mnemonic {
         /// EPNF: `\t`
         Tab,
     }
+
+    impl MnemonicEscape {
+        pub(crate) fn char(&self) -> char {
+            match self {
+                MnemonicEscape::Alarm => '\u{7}',
+                MnemonicEscape::Backspace => '\u{8}',
+                MnemonicEscape::Newline => '\n',
+                MnemonicEscape::Return => '\r',
+                MnemonicEscape::Tab => '\t',
+            }
+        }
+    }
 }
 pub(crate) use mnemonic::MnemonicEscape;
 
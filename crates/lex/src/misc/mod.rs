@@ -1,14 +1,18 @@
 mod non_empty;
-pub(crate) use non_empty::NonEmptyVec;
+pub use non_empty::NonEmptyVec;
 
 mod sign;
-pub(crate) use sign::Sign;
+pub use sign::Sign;
 
 mod escapes;
 pub(crate) use escapes::*;
 
+mod delimiter;
+pub(crate) use delimiter::Delimiter;
+
 mod whitespace;
 pub(crate) use whitespace::*;
 
 mod atmosphere;
-pub(crate) use atmosphere::Atmosphere;
+pub(crate) use atmosphere::DirectiveScanError;
+pub use atmosphere::{Atmosphere, Directive, DirectiveVariant};
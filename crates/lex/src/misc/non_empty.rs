@@ -1,5 +1,12 @@
 use alloc::vec::Vec;
 
 /// Used to tokenize <T>+, a list with at least one element.
-#[derive(Debug, PartialEq)]
-pub struct NonEmptyVec<T>(Vec<T>);
+#[derive(Debug, PartialEq, Clone)]
+pub struct NonEmptyVec<T>(pub(crate) Vec<T>);
+
+impl<T> NonEmptyVec<T> {
+    /// Elements, of which there is at least one
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+}
@@ -1,7 +1,18 @@
-#[derive(Debug, PartialEq)]
+/// Sign of a number or exponent
+#[derive(Debug, PartialEq, Clone)]
 pub enum Sign {
     /// +
     Plus,
     /// -
     Minus,
 }
+
+impl Sign {
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            '+' => Some(Self::Plus),
+            '-' => Some(Self::Minus),
+            _ => None,
+        }
+    }
+}
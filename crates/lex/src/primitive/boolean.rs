@@ -4,7 +4,14 @@ use crate::*;
 // XXX: #tRuE is also a valid representation
 #[derive(Debug, PartialEq, Spanned)]
 pub struct Boolean {
-    inner: bool,
+    pub(crate) inner: bool,
     #[span]
-    span: Span,
+    pub(crate) span: Span,
+}
+
+impl Boolean {
+    /// Boolean value
+    pub fn value(&self) -> bool {
+        self.inner
+    }
 }
@@ -1,14 +1,26 @@
-mod character {
-    use crate::*;
+use crate::*;
 
-    #[derive(Debug, PartialEq, Spanned)]
-    pub enum CharacterLiteral {
-        Simple(CharacterSimple),
-        CodePoint(CharacterCodePoint),
-        Name(CharacterName),
+/// EBNF: `#\<any char> | #\<CharacterName> | #\x<HexScalarValue>`
+#[derive(Debug, PartialEq, Spanned)]
+pub enum CharacterLiteral {
+    /// `#\a`
+    Simple(CharacterSimple),
+    /// `#\x3BB`
+    CodePoint(CharacterCodePoint),
+    /// `#\space`
+    Name(CharacterName),
+}
+
+impl CharacterLiteral {
+    /// Character the literal represents
+    pub fn value(&self) -> char {
+        match self {
+            CharacterLiteral::Simple(simple) => simple.inner,
+            CharacterLiteral::CodePoint(code_point) => code_point.inner,
+            CharacterLiteral::Name(name) => name.inner.char(),
+        }
     }
 }
-pub(crate) use character::CharacterLiteral;
 
 mod literal {
     use crate::*;
@@ -16,9 +28,9 @@ mod literal {
     /// EBNF-ish: `#\<any char>`
     #[derive(Debug, PartialEq, Spanned)]
     pub struct CharacterSimple {
-        inner: char,
+        pub(crate) inner: char,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 }
 pub(crate) use literal::CharacterSimple;
@@ -31,9 +43,9 @@ mod code_point {
     /// EBNF: `#\x <HexadecimalDigit>+ | #\X <HexadecimalDigit>+`
     #[derive(Debug, PartialEq, Spanned)]
     pub struct CharacterCodePoint {
-        inner: char,
+        pub(crate) inner: char,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 }
 pub(crate) use code_point::CharacterCodePoint;
@@ -63,11 +75,66 @@ mod name {
         Tab,
     }
 
+    impl CharacterNameVariant {
+        /// `name` excludes the leading `#\`, matched case-sensitively.
+        pub(crate) fn from_name(name: &str) -> Option<Self> {
+            let variant = match name {
+                "alarm" => Self::Alarm,
+                "backspace" => Self::Backspace,
+                "delete" => Self::Delete,
+                "escape" => Self::Escape,
+                "newline" => Self::Newline,
+                "null" => Self::Null,
+                "return" => Self::Return,
+                "space" => Self::Space,
+                "tab" => Self::Tab,
+                _ => return None,
+            };
+
+            Some(variant)
+        }
+
+        pub(crate) fn char(&self) -> char {
+            match self {
+                Self::Alarm => '\u{7}',
+                Self::Backspace => '\u{8}',
+                Self::Delete => '\u{7F}',
+                Self::Escape => '\u{1B}',
+                Self::Newline => '\n',
+                Self::Null => '\0',
+                Self::Return => '\r',
+                Self::Space => ' ',
+                Self::Tab => '\t',
+            }
+        }
+    }
+
+    /// EBNF: `#\<CharacterNameVariant>`
     #[derive(Debug, PartialEq, Spanned)]
     pub struct CharacterName {
-        inner: CharacterNameVariant,
+        pub(crate) inner: CharacterNameVariant,
         #[span]
-        span: Span,
+        pub(crate) span: Span,
     }
 }
 pub(crate) use name::{CharacterName, CharacterNameVariant};
+
+mod error {
+    use thiserror::Error;
+
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Error, Spanned)]
+    pub enum CharacterLiteralScanError {
+        /// Inner span points to the entire character literal
+        #[error("unknown character name, expected a single character, a hex scalar value or one of 'alarm', 'backspace', 'delete', 'escape', 'newline', 'null', 'return', 'space' or 'tab'")]
+        UnknownName(Span),
+        /// Inner span points to the entire character literal
+        #[error("invalid hex scalar value")]
+        CodePoint(#[from] InlineCodePointScanError),
+        /// Inner span points to the entire character literal
+        #[error("reached EOF, expected a character following '#\\'")]
+        EndOfFile(Span),
+    }
+}
+pub(crate) use error::CharacterLiteralScanError;
@@ -1,11 +1,17 @@
 mod boolean;
-pub(crate) use boolean::Boolean;
+pub use boolean::Boolean;
 
 mod character;
+pub use character::CharacterLiteral;
 pub(crate) use character::*;
 
 mod number;
 pub(crate) use number::*;
+pub use number::{
+    BinaryDigit, ComplexNumber, Decimal, DecimalDigit, DecimalVariant, Exactness, HexadecimalDigit, NonNumber, NonNumberVariant, Number,
+    NumberLiteral, OctalDigit, Prefix, Radix, RealNumber, RealNumberVariant, Suffix,
+};
 
 mod string;
+pub use string::StringLiteral;
 pub(crate) use string::*;
@@ -1,23 +1,39 @@
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+/// EBNF: `<complex R>`
+#[derive(Debug, PartialEq, Clone)]
 pub enum ComplexNumber<R: Radix> {
     /// EBNF: `<RealNumber>`
     Real(RealNumber<R>),
     /// EBNF: `<RealNumber> @ <RealNumber>`
-    Polar { magnitude: RealNumber<R>, phase: RealNumber<R> },
+    Polar {
+        /// Distance from zero
+        magnitude: RealNumber<R>,
+        /// Angle in radians
+        phase: RealNumber<R>,
+    },
     /// Number in the rectangular complex form where the imaginary component
     /// is a valid number (neither NaN nor Infinity).
     ///
     /// EBNF: `[<RealNumber>] <Sign> [<RealNumberVariant>] i`
+    ///
+    /// A missing imaginary variant denotes an imaginary part of one, e.g. `+i` or `1-i`.
     RectangularValid {
+        /// Real part, zero if absent
         real: Option<RealNumber<R>>,
+        /// Sign of the imaginary part
         sign: Sign,
-        imaginary: RealNumberVariant<R>,
+        /// Unsigned imaginary part, one if absent
+        imaginary: Option<RealNumberVariant<R>>,
     },
     /// Number in the rectangular complex form where the imaginary component
     /// is not a valid number.
     ///
     /// EBNF: `[<RealNumber>] <NonNumber> i`
-    RectangularInvalid { real: Option<RealNumber<R>>, imaginary: NonNumber },
+    RectangularInvalid {
+        /// Real part, zero if absent
+        real: Option<RealNumber<R>>,
+        /// Infinite or NaN imaginary part
+        imaginary: NonNumber,
+    },
 }
@@ -1,17 +1,37 @@
 use crate::*;
 
-#[derive(Debug, PartialEq, Spanned)]
+/// Number literal, grouped by radix
+#[derive(Debug, PartialEq, Clone, Spanned)]
 pub enum NumberLiteral {
+    /// `#b` prefixed
     Binary(Number<BinaryDigit>),
+    /// `#o` prefixed
     Octal(Number<OctalDigit>),
+    /// Optionally `#d` prefixed
     Decimal(Number<DecimalDigit>),
+    /// `#x` prefixed
     Hexadecimal(Number<HexadecimalDigit>),
 }
 
-#[derive(Debug, PartialEq, Spanned)]
+/// Number literal of a given radix
+///
+/// EBNF: `<Prefix R> <ComplexNumber R>`
+#[derive(Debug, PartialEq, Clone, Spanned)]
 pub struct Number<R: Radix> {
-    prefix: Prefix<R>,
-    inner: ComplexNumber<R>,
+    pub(crate) prefix: Prefix<R>,
+    pub(crate) inner: ComplexNumber<R>,
     #[span]
-    span: Span,
+    pub(crate) span: Span,
+}
+
+impl<R: Radix> Number<R> {
+    /// See [`Prefix`]
+    pub fn prefix(&self) -> &Prefix<R> {
+        &self.prefix
+    }
+
+    /// See [`ComplexNumber`]
+    pub fn complex(&self) -> &ComplexNumber<R> {
+        &self.inner
+    }
 }
@@ -3,27 +3,34 @@ use alloc::vec::Vec;
 use crate::*;
 
 /// e | E
-struct ExponentMarker;
+pub(crate) struct ExponentMarker;
+
+impl ExponentMarker {
+    pub(crate) fn is_marker(char: char) -> bool {
+        matches!(char, 'e' | 'E')
+    }
+}
 
 /// Used denote exponentiation
 ///
 /// EBNF: `<ExponentMarker> [<Sign>] <DecimalDigit>+`
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Suffix {
-    sign: Option<Sign>,
-    digits: NonEmptyVec<DecimalDigit>,
+    pub(crate) sign: Option<Sign>,
+    pub(crate) digits: NonEmptyVec<DecimalDigit>,
 }
 
 /// From the standard's <decimal 10>.
 ///
 /// EBNF: `<DecimalVariant> [<Suffix>]`
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Decimal {
-    variant: DecimalVariant,
-    suffix: Option<Suffix>,
+    pub(crate) variant: DecimalVariant,
+    pub(crate) suffix: Option<Suffix>,
 }
 
-#[derive(Debug, PartialEq)]
+/// See [`Decimal`]
+#[derive(Debug, PartialEq, Clone)]
 pub enum DecimalVariant {
     /// Integer digits only
     ///
@@ -32,12 +39,41 @@ pub enum DecimalVariant {
     /// Fraction digits only
     ///
     /// EBNF: `. <DecimalDigit>+`
-    Fraction { fraction_digits: NonEmptyVec<DecimalDigit> },
+    Fraction {
+        /// Digits after the point
+        fraction_digits: NonEmptyVec<DecimalDigit>,
+    },
     /// Both integer and fraction digits present
     ///
     /// EBNF: `<DecimalDigit>+ . <DecimalDigit>*`
     Both {
+        /// Digits before the point
         digits: NonEmptyVec<DecimalDigit>,
+        /// Possibly empty digits after the point
         fractional_digits: Vec<DecimalDigit>,
     },
 }
+
+impl Suffix {
+    /// Sign of the exponent, positive if absent
+    pub fn sign(&self) -> Option<&Sign> {
+        self.sign.as_ref()
+    }
+
+    /// Exponent digits
+    pub fn digits(&self) -> &NonEmptyVec<DecimalDigit> {
+        &self.digits
+    }
+}
+
+impl Decimal {
+    /// See [`DecimalVariant`]
+    pub fn variant(&self) -> &DecimalVariant {
+        &self.variant
+    }
+
+    /// See [`Suffix`]
+    pub fn suffix(&self) -> Option<&Suffix> {
+        self.suffix.as_ref()
+    }
+}
@@ -1,7 +1,5 @@
-use crate::*;
-
 /// type Digit2 = BinaryDigit;
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum BinaryDigit {
     /// 0
     Zero,
@@ -10,7 +8,7 @@ pub enum BinaryDigit {
 }
 
 /// type Digit8 = Octal;
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum OctalDigit {
     /// 0
     Zero,
@@ -31,7 +29,7 @@ pub enum OctalDigit {
 }
 
 /// type Digit10 = Decimal
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum DecimalDigit {
     /// 0
     Zero,
@@ -56,7 +54,7 @@ pub enum DecimalDigit {
 }
 
 /// type Digit16 = Hexadecimal
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum HexadecimalDigit {
     /// 0
     Zero,
@@ -92,6 +90,70 @@ pub enum HexadecimalDigit {
     F,
 }
 
+impl BinaryDigit {
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            '0' => Some(Self::Zero),
+            '1' => Some(Self::One),
+            _ => None,
+        }
+    }
+}
+
+impl OctalDigit {
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            '0' => Some(Self::Zero),
+            '1' => Some(Self::One),
+            '2' => Some(Self::Two),
+            '3' => Some(Self::Three),
+            '4' => Some(Self::Four),
+            '5' => Some(Self::Five),
+            '6' => Some(Self::Six),
+            '7' => Some(Self::Seven),
+            _ => None,
+        }
+    }
+}
+
+impl DecimalDigit {
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            '0' => Some(Self::Zero),
+            '1' => Some(Self::One),
+            '2' => Some(Self::Two),
+            '3' => Some(Self::Three),
+            '4' => Some(Self::Four),
+            '5' => Some(Self::Five),
+            '6' => Some(Self::Six),
+            '7' => Some(Self::Seven),
+            '8' => Some(Self::Eight),
+            '9' => Some(Self::Nine),
+            _ => None,
+        }
+    }
+}
+
 impl HexadecimalDigit {
-    pub(crate) const RADIX: u32 = 16;
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            '0' => Some(Self::Zero),
+            '1' => Some(Self::One),
+            '2' => Some(Self::Two),
+            '3' => Some(Self::Three),
+            '4' => Some(Self::Four),
+            '5' => Some(Self::Five),
+            '6' => Some(Self::Six),
+            '7' => Some(Self::Seven),
+            '8' => Some(Self::Eight),
+            '9' => Some(Self::Nine),
+            'a' | 'A' => Some(Self::A),
+            'b' | 'B' => Some(Self::B),
+            'c' | 'C' => Some(Self::C),
+            'd' | 'D' => Some(Self::D),
+            'e' | 'E' => Some(Self::E),
+            'f' | 'F' => Some(Self::F),
+            _ => None,
+        }
+    }
 }
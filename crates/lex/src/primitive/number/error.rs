@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+use crate::*;
+
+#[derive(Debug, PartialEq, Error, Spanned)]
+pub enum NumberLiteralScanError {
+    /// Inner span points to the `#` and the unknown prefix character
+    #[error("unknown number prefix, expected one of '#b', '#o', '#d', '#x', '#e' or '#i'")]
+    UnknownPrefix(Span),
+    /// Inner span points to the second radix prefix
+    #[error("radix prefix may only be provided once")]
+    DuplicateRadix(Span),
+    /// Inner span points to the second exactness prefix
+    #[error("exactness prefix may only be provided once")]
+    DuplicateExactness(Span),
+    /// Inner span points to the unexpected character
+    #[error("unexpected character in number literal")]
+    UnexpectedCharacter(Span),
+    /// Inner span points to the entire number literal
+    #[error("number literal ended unexpectedly")]
+    UnexpectedEnd(Span),
+}
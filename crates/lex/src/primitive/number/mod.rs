@@ -1,23 +1,28 @@
 mod core;
-pub(crate) use core::{Number, NumberLiteral};
+pub use core::{Number, NumberLiteral};
 
 mod prefix;
-pub(crate) use prefix::Prefix;
+pub(crate) use prefix::RadixMarker;
+pub use prefix::{Exactness, Prefix};
 
 mod complex;
-pub(crate) use complex::ComplexNumber;
+pub use complex::ComplexNumber;
 
 mod real;
-pub(crate) use real::{RealNumber, RealNumberVariant};
+pub use real::{RealNumber, RealNumberVariant};
 
 mod non_number;
-pub(crate) use non_number::NonNumber;
+pub use non_number::{NonNumber, NonNumberVariant};
 
 mod radix;
-pub(crate) use radix::Radix;
+pub use radix::Radix;
 
 mod decimal;
-pub(crate) use decimal::Decimal;
+pub(crate) use decimal::ExponentMarker;
+pub use decimal::{Decimal, DecimalVariant, Suffix};
+
+mod error;
+pub(crate) use error::NumberLiteralScanError;
 
 mod digit;
-pub(crate) use digit::{BinaryDigit, DecimalDigit, HexadecimalDigit, OctalDigit};
+pub use digit::{BinaryDigit, DecimalDigit, HexadecimalDigit, OctalDigit};
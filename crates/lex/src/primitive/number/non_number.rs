@@ -1,16 +1,29 @@
 use crate::*;
 
 /// Infinities (inf) and Not a Number (nan). Renamed from the standard's <infnan>
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct NonNumber {
-    sign: Sign,
-    variant: NonNumberVariant,
+    pub(crate) sign: Sign,
+    pub(crate) variant: NonNumberVariant,
 }
 
-#[derive(Debug, PartialEq)]
+/// See [`NonNumber`]
+#[derive(Debug, PartialEq, Clone)]
 pub enum NonNumberVariant {
     /// +inf.0 | -inf.0 | +INF.0 | -INF.0
     Infinity,
     /// +nan.0 | -nan.0 | +NAN.0 | -NAN.0
     Invalid,
 }
+
+impl NonNumber {
+    /// Mandatory sign
+    pub fn sign(&self) -> &Sign {
+        &self.sign
+    }
+
+    /// See [`NonNumberVariant`]
+    pub fn variant(&self) -> &NonNumberVariant {
+        &self.variant
+    }
+}
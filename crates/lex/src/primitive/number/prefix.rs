@@ -7,22 +7,63 @@ use core::marker::PhantomData;
 /// <Radix Decimal> = <empty> | #d | #D
 /// <Radix Hexadecimal> = #x | #X`
 /// ```
-struct RadixMarker<R>(PhantomData<R>);
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum RadixMarker {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl RadixMarker {
+    /// Character following the `#`
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            'b' | 'B' => Some(Self::Binary),
+            'o' | 'O' => Some(Self::Octal),
+            'd' | 'D' => Some(Self::Decimal),
+            'x' | 'X' => Some(Self::Hexadecimal),
+            _ => None,
+        }
+    }
+}
 
 /// <Radix R> <Exactness> | <Exactness> <Radix R>
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Prefix<R> {
-    radix: PhantomData<R>,
+    pub(crate) radix: PhantomData<R>,
     // NOTE: exactness can not be made public, it can only be determined by
     // looking at the entire number. 4/2 is for example an exact number, whilst
     // 4.0/2 is not
-    exactness: Option<Exactness>,
+    pub(crate) exactness: Option<Exactness>,
 }
 
-#[derive(Debug, PartialEq)]
-enum Exactness {
+impl<R> Prefix<R> {
+    /// Explicit `#e` or `#i` prefix, if any.
+    ///
+    /// The exactness of the literal as a whole is given by its value, as converted by
+    /// `pluine-number`.
+    pub fn explicit_exactness(&self) -> Option<Exactness> {
+        self.exactness
+    }
+}
+
+/// Explicit exactness prefix
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Exactness {
     /// #i | #I
     Inexact,
     /// #e | #E
     Exact,
 }
+
+impl Exactness {
+    /// Character following the `#`
+    pub(crate) fn from_char(char: char) -> Option<Self> {
+        match char {
+            'i' | 'I' => Some(Self::Inexact),
+            'e' | 'E' => Some(Self::Exact),
+            _ => None,
+        }
+    }
+}
@@ -1,21 +1,56 @@
 use crate::*;
 
-pub trait Radix: core::fmt::Debug + PartialEq + private::Sealed {
+/// Digit of a given radix, implemented by the digit enums only
+pub trait Radix: core::fmt::Debug + PartialEq + Clone + Sized + private::Sealed {
     /// Radix specific number representation in [`RealNumberVariant::Number`]
-    type Number: core::fmt::Debug + PartialEq;
+    type Number: core::fmt::Debug + PartialEq + Clone;
+
+    /// Number of distinct digits
+    const RADIX: u32;
+
+    /// Returns `None` if `char` is not a valid digit for the given radix.
+    fn from_char(char: char) -> Option<Self>;
+
+    /// Numeric value of the digit, lower than [`Self::RADIX`]
+    fn value(&self) -> u32;
 }
 
 private::impl_sealed_marker!(BinaryDigit, OctalDigit, DecimalDigit, HexadecimalDigit);
 
 impl Radix for DecimalDigit {
     type Number = Decimal;
+
+    const RADIX: u32 = 10;
+
+    fn from_char(char: char) -> Option<Self> {
+        DecimalDigit::from_char(char)
+    }
+
+    fn value(&self) -> u32 {
+        // digit variants are declared in ascending order, their discriminant being their value
+        *self as u32
+    }
 }
 
-simple_radix_number!(BinaryDigit, OctalDigit, HexadecimalDigit);
+simple_radix_number!(BinaryDigit: 2, OctalDigit: 8, HexadecimalDigit: 16);
 
 macro_rules! simple_radix_number {
-    ($($digit:ty),* $(,)?) => {
-        $(impl Radix for $digit { type Number = NonEmptyVec<$digit>; })*
+    ($($digit:ty: $radix:literal),* $(,)?) => {
+        $(
+            impl Radix for $digit {
+                type Number = NonEmptyVec<$digit>;
+
+                const RADIX: u32 = $radix;
+
+                fn from_char(char: char) -> Option<Self> {
+                    <$digit>::from_char(char)
+                }
+
+                fn value(&self) -> u32 {
+                    *self as u32
+                }
+            }
+        )*
     };
 }
 use simple_radix_number;
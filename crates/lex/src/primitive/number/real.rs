@@ -1,18 +1,29 @@
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+/// EBNF: `<real R>`
+#[derive(Debug, PartialEq, Clone)]
 pub enum RealNumber<R: Radix> {
-    Number { sign: Option<Sign>, variant: RealNumberVariant<R> },
+    /// EBNF: `[<Sign>] <RealNumberVariant>`
+    Number {
+        /// Explicit sign, positive if absent
+        sign: Option<Sign>,
+        /// Unsigned value
+        variant: RealNumberVariant<R>,
+    },
+    /// See [`NonNumber`]
     NonNumber(NonNumber),
 }
 
-#[derive(Debug, PartialEq)]
+/// EBNF: `<ureal R>`, excluding infinities and NaN
+#[derive(Debug, PartialEq, Clone)]
 pub enum RealNumberVariant<R: Radix> {
     /// Simple fraction representation
     ///
     /// EBNF: `<Digit R>+ / <Digit R>+`
     Fraction {
+        /// Digits before the slash
         numerator: NonEmptyVec<R>,
+        /// Digits after the slash, possibly all zeros
         denominator: NonEmptyVec<R>,
     },
     /// Number representation when not a simple fraction
@@ -1,4 +1,4 @@
-use alloc::vec::Vec;
+use alloc::{borrow::Cow, string::String, vec::Vec};
 
 use thiserror::Error;
 
@@ -12,6 +12,38 @@ pub struct StringLiteral<'src> {
     pub(crate) span: Span,
 }
 
+impl<'src> StringLiteral<'src> {
+    /// String contents with escapes resolved, borrowed from the source if escape free.
+    pub fn value(&self) -> Cow<'src, str> {
+        match self.inner.as_slice() {
+            [] => Cow::Borrowed(""),
+            [StringElement::Chars(chars)] => Cow::Borrowed(chars),
+            elements => {
+                let mut value = String::new();
+                let mut previous_element = None;
+
+                for element in elements {
+                    match element {
+                        StringElement::InlineCodePoint(inline_code_point) => value.push(inline_code_point.inner()),
+                        StringElement::NewlineEscape(_) => {}
+                        StringElement::MnemonicEscape(mnemonic_escape) => value.push(mnemonic_escape.char()),
+                        StringElement::StringEscape(string_escape) => value.push(string_escape.char()),
+                        // whitespace following the line ending of a newline escape is also skipped
+                        StringElement::Chars(chars) => match previous_element {
+                            Some(&StringElement::NewlineEscape(_)) => value.push_str(chars.trim_start_matches([' ', '\t'])),
+                            _ => value.push_str(chars),
+                        },
+                    }
+
+                    previous_element = Some(element);
+                }
+
+                Cow::Owned(value)
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum StringElement<'src> {
     InlineCodePoint(InlineCodePoint),
@@ -44,7 +76,17 @@ pub enum StringEscape {
     VerticalLine,
 }
 
-#[derive(Debug, PartialEq, Error)]
+impl StringEscape {
+    pub(crate) fn char(&self) -> char {
+        match self {
+            StringEscape::DoubleQuote => '"',
+            StringEscape::Backslash => '\\',
+            StringEscape::VerticalLine => '|',
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Error, Spanned)]
 pub enum StringLiteralScanError {
     #[error("invalid inline code point (inline hex escape)")]
     InlineHex(#[from] InlineCodePointScanError),
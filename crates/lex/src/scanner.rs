@@ -4,20 +4,27 @@ use crate::*;
 
 pub struct Scanner<'src> {
     src: &'src str,
+    /// Characters from `start` onwards, indexed relative to `start`
     char_iter: CharIndices<'src>,
+    start: usize,
 }
 
 impl Iterator for Scanner<'_> {
     type Item = (usize, char);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.char_iter.next()
+        self.char_iter.next().map(|(index, char)| (self.start + index, char))
     }
 }
 
 impl<'src> Scanner<'src> {
     pub fn new(src: &'src str) -> Self {
-        Self { src, char_iter: src.char_indices() }
+        Self::new_at(src, 0)
+    }
+
+    /// Scanner starting at byte index `start`, which must be on a UTF-8 sequence boundary.
+    pub fn new_at(src: &'src str, start: usize) -> Self {
+        Self { src, char_iter: src[start..].char_indices(), start }
     }
 
     /// Returns the inner source code string stored by the scanner
@@ -30,6 +37,29 @@ impl<'src> Scanner<'src> {
         self.next().map(|(_, char)| char)
     }
 
+    /// Returns the next index and character without advancing the scanner.
+    pub fn peek(&self) -> Option<(usize, char)> {
+        self.char_iter.clone().next().map(|(index, char)| (self.start + index, char))
+    }
+
+    /// Advances the scanner only if the next character equals `expected`.
+    ///
+    /// Returns the index of the consumed character.
+    pub fn next_if_eq(&mut self, expected: char) -> Option<usize> {
+        match self.peek() {
+            Some((index, char)) if char == expected => {
+                self.next();
+                Some(index)
+            }
+            _ => None,
+        }
+    }
+
+    /// Byte index of the next character to be scanned, `source.len()` if at EOF.
+    pub fn offset(&self) -> usize {
+        self.start + self.char_iter.offset()
+    }
+
     /// See [`Span::new`]
     pub fn span(&self, start: usize, end: usize) -> Span {
         Span::new(self.src, start, end)
@@ -45,6 +75,22 @@ impl<'src> Scanner<'src> {
         Span::new(self.src, start, start + 1)
     }
 
+    /// Forwards the internal iterator until the next character is a [`Delimiter`] or EOF.
+    ///
+    /// The delimiter itself is not consumed. Returned index points to the delimiter, or is
+    /// `source.len()` if EOF was reached.
+    pub fn scan_until_delimiter(&mut self) -> usize {
+        loop {
+            match self.peek() {
+                Some((index, char)) if Delimiter::is_delimiter(char) => return index,
+                Some(_) => {
+                    self.next();
+                }
+                None => return self.offset(),
+            }
+        }
+    }
+
     /// Forwards the internal iterator until it reaches a [`LineEnding`] or EOF.
     ///
     /// Returned tuple includes the index for the line ending or EOF, and the
@@ -63,7 +109,7 @@ impl<'src> Scanner<'src> {
 
                 loop {
                     let Some((current_index, current_char)) = self.next() else {
-                        let eof_index = self.char_iter.offset();
+                        let eof_index = self.offset();
                         return (eof_index, &self.src[start..eof_index]);
                     };
 
@@ -72,7 +118,7 @@ impl<'src> Scanner<'src> {
                     }
                 }
             }
-            None => (self.char_iter.offset(), ""),
+            None => (self.offset(), ""),
         }
     }
 }
@@ -81,6 +127,34 @@ impl<'src> Scanner<'src> {
 mod tests {
     use super::*;
 
+    mod scan_until_delimiter {
+        use super::*;
+
+        #[test]
+        fn stops_before_delimiter() {
+            let mut scanner = Scanner::new("abc)");
+
+            assert_eq!(3, scanner.scan_until_delimiter());
+            assert_eq!(Some((3, ')')), scanner.next());
+        }
+
+        #[test]
+        fn eof_returns_source_length() {
+            let mut scanner = Scanner::new("abc");
+
+            assert_eq!(3, scanner.scan_until_delimiter());
+            assert_eq!(None, scanner.next());
+        }
+
+        #[test]
+        fn already_at_delimiter() {
+            let mut scanner = Scanner::new("a b");
+            scanner.next();
+
+            assert_eq!(1, scanner.scan_until_delimiter());
+        }
+    }
+
     mod scan_until_line_ending {
         use super::*;
 
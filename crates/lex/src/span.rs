@@ -37,6 +37,28 @@ impl Span {
 
         Span { start, end }
     }
+
+    /// Shortens the span to its first `len` bytes, which must end on a UTF-8 sequence boundary.
+    pub(crate) fn truncate(self, len: usize) -> Self {
+        debug_assert!(len > 0 && len <= self.end - self.start);
+
+        Span { start: self.start, end: self.start + len }
+    }
+
+    /// Smallest span covering both `self` and `other`, and anything in between.
+    pub fn join(self, other: Span) -> Self {
+        Span { start: self.start.min(other.start), end: self.end.max(other.end) }
+    }
+
+    /// Inclusive start byte offset
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Exclusive end byte offset
+    pub fn end(&self) -> usize {
+        self.end
+    }
 }
 
 /// Primarily end-to-end tests for `pluine_lex_macros`
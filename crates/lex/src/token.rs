@@ -1,23 +1,41 @@
 use crate::*;
 
-#[derive(Debug, PartialEq)]
+/// Token which is part of a datum, see [`TokenAll`] for the remaining ones.
+#[derive(Debug, PartialEq, Spanned)]
 pub enum Token<'src> {
+    /// See [`Identifier`]
     Identifier(Identifier<'src>),
+    /// See [`Boolean`]
     Boolean(Boolean),
+    /// See [`NumberLiteral`]
     Number(NumberLiteral),
+    /// See [`CharacterLiteral`]
     Character(CharacterLiteral),
+    /// See [`StringLiteral`]
     String(StringLiteral<'src>),
+    /// See [`TokenChar`]
     Other(TokenChar),
+    /// See [`DatumLabel`]
+    Label(DatumLabel),
 }
 
+/// Punctuation token, parentheses and abbreviation prefixes.
 #[derive(Debug, PartialEq, Spanned)]
 pub struct TokenChar {
-    inner: TokenCharVariant,
+    pub(crate) inner: TokenCharVariant,
     #[span]
-    span: Span,
+    pub(crate) span: Span,
+}
+
+impl TokenChar {
+    /// Which punctuation token this is
+    pub fn variant(&self) -> TokenCharVariant {
+        self.inner
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// See [`TokenChar`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum TokenCharVariant {
     /// `(`
     OpenParenthesis,
@@ -25,6 +43,8 @@ pub enum TokenCharVariant {
     CloseParenthesis,
     /// `#(`
     PoundOpenParenthesis,
+    /// `#u8(`
+    PoundU8OpenParenthesis,
     /// `.`
     Dot,
     /// `'`
@@ -37,8 +57,22 @@ pub enum TokenCharVariant {
     CommaAt,
 }
 
-#[derive(Debug, PartialEq)]
+/// Item scanned by the [`Lexer`]
+#[derive(Debug, PartialEq, Spanned)]
 pub enum TokenAll<'src> {
+    /// Whitespace excluded, as whitespace is not emitted
     InterToken(Atmosphere<'src>),
+    /// See [`Token`]
     Token(Token<'src>),
+    /// Only emitted by [`Lexer::tokenize_all_recovering`]
+    Unknown(UnknownToken<'src>),
+}
+
+/// Source region skipped after a [`TokenizeError`], spanning from the start of the invalid token
+/// up until the point where lexing was resumed.
+#[derive(Debug, PartialEq, Spanned)]
+pub struct UnknownToken<'src> {
+    pub(crate) inner: &'src str,
+    #[span]
+    pub(crate) span: Span,
 }
@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+/// Error returned by the numeric operations, the arguments being outside of their domain
+#[derive(Debug, PartialEq, Clone, Copy, Error)]
+pub enum NumberError {
+    /// Exact zero divisor
+    #[error("division by exact zero")]
+    DivisionByZero,
+    /// Non-real complex number given where a real number is required
+    #[error("real number expected")]
+    NotReal,
+    /// Infinities and NaN, which have no exact representation
+    #[error("rational number expected")]
+    NotRational,
+    /// Non-integer given where an integer, exact or not, is required
+    #[error("integer expected")]
+    NotInteger,
+    /// `exact-integer-sqrt` argument
+    #[error("exact non-negative integer expected")]
+    NotExactNonNegativeInteger,
+    /// Exact result which would not fit in memory, such as `(expt 10 (expt 10 10))`
+    #[error("exact result too large")]
+    Overflow,
+    /// Radix other than 2, 8, 10 or 16
+    #[error("unsupported radix {0}, expected 2, 8, 10 or 16")]
+    InvalidRadix(u32),
+    /// Inexact numbers are only written in radix 10
+    #[error("inexact numbers can only be written in radix 10")]
+    InexactRadix,
+}
@@ -0,0 +1,275 @@
+//! Integer division, `exact-integer-sqrt` and rational operations of R7RS 6.2.6
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+use crate::*;
+
+impl Number {
+    /// R7RS `floor/`, the quotient being rounded towards negative infinity.
+    pub fn floor_divide(&self, divisor: &Number) -> Result<(Number, Number), NumberError> {
+        integer_division(self, divisor, BigInt::div_mod_floor, |dividend, divisor| {
+            let quotient = (dividend / divisor).floor();
+            (quotient, dividend - divisor * quotient)
+        })
+    }
+
+    /// R7RS `truncate/`, the quotient being rounded towards zero.
+    pub fn truncate_divide(&self, divisor: &Number) -> Result<(Number, Number), NumberError> {
+        integer_division(self, divisor, BigInt::div_rem, |dividend, divisor| {
+            ((dividend / divisor).trunc(), dividend % divisor)
+        })
+    }
+
+    /// R7RS `gcd`, always non-negative
+    pub fn gcd(&self, other: &Number) -> Result<Number, NumberError> {
+        integer_operation(self, other, |left, right| left.gcd(right))
+    }
+
+    /// R7RS `lcm`, always non-negative
+    pub fn lcm(&self, other: &Number) -> Result<Number, NumberError> {
+        integer_operation(self, other, |left, right| left.lcm(right))
+    }
+
+    /// R7RS `numerator`, in lowest terms
+    pub fn numerator(&self) -> Result<Number, NumberError> {
+        rational_part(self, |rational| rational.numer().clone())
+    }
+
+    /// R7RS `denominator`, in lowest terms and always positive
+    pub fn denominator(&self) -> Result<Number, NumberError> {
+        rational_part(self, |rational| rational.denom().clone())
+    }
+
+    /// R7RS `exact-integer-sqrt`, the square root along with the remainder
+    pub fn exact_integer_sqrt(&self) -> Result<(Number, Number), NumberError> {
+        match self {
+            Number::Real(Real::Integer(integer)) if !integer.is_negative() => {
+                let root = integer.sqrt();
+                let remainder = integer - &root * &root;
+                Ok((Number::from(root), Number::from(remainder)))
+            }
+            _ => Err(NumberError::NotExactNonNegativeInteger),
+        }
+    }
+
+    /// R7RS `rationalize`, the simplest rational differing from the number by no more than the
+    /// tolerance. Inexact if either argument is.
+    pub fn rationalize(&self, tolerance: &Number) -> Result<Number, NumberError> {
+        let (value, tolerance) = (self.as_real()?, tolerance.as_real()?);
+        let exact = value.is_exact() && tolerance.is_exact();
+
+        let (Some(value), Some(tolerance)) = (value.to_rational(), tolerance.to_rational()) else {
+            let result = match (value, tolerance) {
+                (value, _) if value.is_nan() => f64::NAN,
+                (_, tolerance) if tolerance.is_nan() => f64::NAN,
+                (value, tolerance) if value.is_infinite() && tolerance.is_infinite() => f64::NAN,
+                (value, _) if value.is_infinite() => value.to_f64(),
+                // any finite value is within an infinite tolerance of zero
+                _ => 0.0,
+            };
+            return Ok(Number::from(result));
+        };
+
+        let tolerance = tolerance.abs();
+        let simplest = Real::from_rational(simplest_rational(&value - &tolerance, &value + &tolerance));
+
+        Ok(Number::Real(match exact {
+            true => simplest,
+            false => simplest.to_inexact(),
+        }))
+    }
+
+    /// R7RS `floor`
+    pub fn floor(&self) -> Result<Number, NumberError> {
+        self.as_real().map(|real| Number::Real(real.floor()))
+    }
+
+    /// R7RS `ceiling`
+    pub fn ceiling(&self) -> Result<Number, NumberError> {
+        self.as_real().map(|real| Number::Real(real.ceiling()))
+    }
+
+    /// R7RS `truncate`
+    pub fn truncate(&self) -> Result<Number, NumberError> {
+        self.as_real().map(|real| Number::Real(real.truncate()))
+    }
+
+    /// R7RS `round`, to even when halfway between two integers
+    pub fn round(&self) -> Result<Number, NumberError> {
+        self.as_real().map(|real| Number::Real(real.round()))
+    }
+}
+
+/// Integer part of both operands, exact if both are.
+fn integers<'a>(left: &'a Number, right: &'a Number) -> Result<(&'a Real, &'a Real), NumberError> {
+    match (left.as_real()?, right.as_real()?) {
+        (left, right) if left.is_integer() && right.is_integer() => Ok((left, right)),
+        _ => Err(NumberError::NotInteger),
+    }
+}
+
+fn integer_division(
+    dividend: &Number,
+    divisor: &Number,
+    exact: impl Fn(&BigInt, &BigInt) -> (BigInt, BigInt),
+    inexact: impl Fn(f64, f64) -> (f64, f64),
+) -> Result<(Number, Number), NumberError> {
+    let (dividend, divisor) = integers(dividend, divisor)?;
+    if divisor.is_zero() {
+        return Err(NumberError::DivisionByZero);
+    }
+
+    match (dividend, divisor) {
+        (Real::Integer(dividend), Real::Integer(divisor)) => {
+            let (quotient, remainder) = exact(dividend, divisor);
+            Ok((Number::from(quotient), Number::from(remainder)))
+        }
+        _ => {
+            let (quotient, remainder) = inexact(dividend.to_f64(), divisor.to_f64());
+            Ok((Number::from(quotient), Number::from(remainder)))
+        }
+    }
+}
+
+fn integer_operation(left: &Number, right: &Number, operation: impl Fn(&BigInt, &BigInt) -> BigInt) -> Result<Number, NumberError> {
+    let (left, right) = integers(left, right)?;
+    let exact = left.is_exact() && right.is_exact();
+
+    let (Some(left), Some(right)) = (left.to_rational(), right.to_rational()) else {
+        return Err(NumberError::NotInteger);
+    };
+    let result = Real::Integer(operation(&left.to_integer(), &right.to_integer()));
+
+    Ok(Number::Real(match exact {
+        true => result,
+        false => result.to_inexact(),
+    }))
+}
+
+fn rational_part(number: &Number, part: impl Fn(&BigRational) -> BigInt) -> Result<Number, NumberError> {
+    let real = number.as_real()?;
+    let rational = real.to_rational().ok_or(NumberError::NotRational)?;
+    let result = Real::Integer(part(&rational));
+
+    Ok(Number::Real(match real.is_exact() {
+        true => result,
+        false => result.to_inexact(),
+    }))
+}
+
+/// Simplest rational within the inclusive bounds, the one with the smallest denominator and then
+/// numerator in absolute value.
+fn simplest_rational(low: BigRational, high: BigRational) -> BigRational {
+    if low.is_positive() {
+        simplest_positive_rational(low, high)
+    } else if high.is_negative() {
+        -simplest_positive_rational(-high, -low)
+    } else {
+        BigRational::zero()
+    }
+}
+
+/// Stern-Brocot search for `0 < low <= high`, collecting the continued fraction terms before
+/// folding them.
+fn simplest_positive_rational(mut low: BigRational, mut high: BigRational) -> BigRational {
+    let mut terms = Vec::new();
+
+    let last = loop {
+        let floor = low.floor();
+        if floor == low {
+            break floor;
+        }
+        if floor < high.floor() {
+            break floor + BigRational::one();
+        }
+
+        // both bounds share their integer part, continue with the reciprocals of the fractions
+        terms.push(floor.clone());
+        (low, high) = ((&high - &floor).recip(), (&low - &floor).recip());
+    };
+
+    terms.into_iter().rev().fold(last, |value, term| term + value.recip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rational(numerator: i64, denominator: i64) -> Number {
+        Number::from(BigRational::new(numerator.into(), denominator.into()))
+    }
+
+    fn pair(left: i64, right: i64) -> (Number, Number) {
+        (Number::from(left), Number::from(right))
+    }
+
+    #[test]
+    fn integer_division() {
+        assert_eq!(Ok(pair(-3, 1)), Number::from(-5).floor_divide(&Number::from(2)));
+        assert_eq!(Ok(pair(-2, -1)), Number::from(-5).truncate_divide(&Number::from(2)));
+        assert_eq!(Ok(pair(-3, -1)), Number::from(5).floor_divide(&Number::from(-2)));
+
+        let (quotient, remainder) = Number::from(-5.0).floor_divide(&Number::from(2)).unwrap();
+        assert!(!quotient.is_exact() && !remainder.is_exact());
+        assert_eq!((quotient, remainder), pair(-3, 1));
+
+        assert_eq!(Err(NumberError::DivisionByZero), Number::from(1).floor_divide(&Number::from(0)));
+        assert_eq!(Err(NumberError::NotInteger), Number::from(1.5).truncate_divide(&Number::from(1)));
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(Ok(Number::from(4)), Number::from(32).gcd(&Number::from(-36)));
+        assert_eq!(Ok(Number::from(288)), Number::from(32).lcm(&Number::from(-36)));
+        assert!(!Number::from(32.0).lcm(&Number::from(-36)).unwrap().is_exact());
+    }
+
+    #[test]
+    fn numerator_and_denominator() {
+        assert_eq!(Ok(Number::from(3)), rational(6, 4).numerator());
+        assert_eq!(Ok(Number::from(2)), rational(6, 4).denominator());
+        assert_eq!(Ok(Number::from(1)), Number::from(0).denominator());
+
+        let denominator = Number::from(0.5).denominator().unwrap();
+        assert!(!denominator.is_exact());
+        assert_eq!(Number::from(2), denominator);
+    }
+
+    #[test]
+    fn exact_integer_sqrt() {
+        assert_eq!(Ok(pair(2, 0)), Number::from(4).exact_integer_sqrt());
+        assert_eq!(Ok(pair(2, 1)), Number::from(5).exact_integer_sqrt());
+
+        let large = Number::from(BigInt::from(10).pow(40) + 1);
+        assert_eq!(
+            Ok((Number::from(BigInt::from(10).pow(20)), Number::from(1))),
+            large.exact_integer_sqrt()
+        );
+
+        assert_eq!(Err(NumberError::NotExactNonNegativeInteger), Number::from(-1).exact_integer_sqrt());
+        assert_eq!(Err(NumberError::NotExactNonNegativeInteger), Number::from(4.0).exact_integer_sqrt());
+    }
+
+    #[test]
+    fn rationalize() {
+        assert_eq!(Ok(rational(1, 3)), rational(3, 10).rationalize(&rational(1, 10)));
+        assert_eq!(Ok(rational(-1, 3)), rational(-3, 10).rationalize(&rational(1, 10)));
+        assert_eq!(Ok(Number::from(0)), rational(1, 10).rationalize(&rational(1, 5)));
+        assert_eq!(Ok(Number::from(2)), rational(5, 2).rationalize(&rational(1, 2)));
+
+        let inexact = Number::from(0.3).rationalize(&rational(1, 10)).unwrap();
+        assert!(!inexact.is_exact());
+        assert_eq!(Number::from(1.0 / 3.0), inexact);
+
+        assert_eq!(Ok(Number::from(0.0)), Number::from(3).rationalize(&Number::from(f64::INFINITY)));
+    }
+
+    #[test]
+    fn rounding_requires_reals() {
+        assert_eq!(Ok(Number::from(2)), rational(5, 2).round());
+        assert_eq!(Err(NumberError::NotReal), Number::rectangular(Real::from(1), Real::from(1)).floor());
+    }
+}
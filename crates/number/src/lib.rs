@@ -0,0 +1,27 @@
+//! Pluine numeric tower.
+//!
+//! [`Number`]s are exact integers of arbitrary precision, exact rationals, inexact reals and
+//! complex numbers, as described in R7RS 6.2. Exactness is tracked by the representation: exact
+//! values only become inexact when combined with inexact ones or converted with
+//! [`Number::to_inexact`]. Number literals of the lexer are converted with
+//! [`Number::try_from`], honoring their `#e` and `#i` prefixes.
+
+mod error;
+pub use error::NumberError;
+
+mod real;
+pub use real::Real;
+
+mod number;
+pub use number::{Complex, Number};
+
+mod literal;
+
+mod integer;
+
+mod transcendental;
+
+mod write;
+
+pub use num_bigint::BigInt;
+pub use num_rational::BigRational;
@@ -0,0 +1,181 @@
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+use pluine_lex::{
+    number::{
+        BinaryDigit, ComplexNumber, Decimal, DecimalDigit, DecimalVariant, Exactness, HexadecimalDigit, NonEmptyVec, NonNumber,
+        NonNumberVariant, OctalDigit, Radix, RealNumber, RealNumberVariant, Sign,
+    },
+    NumberLiteral,
+};
+
+use crate::*;
+
+/// Largest decimal exponent of exact literals, `#e1e100000` having a hundred thousand digits.
+const MAX_EXACT_EXPONENT: u32 = 100_000;
+
+/// Value of the literal, its `#e` or `#i` prefix taking precedence over its implicit exactness.
+///
+/// Fails for exact infinities and NaN such as `#e+inf.0`, fractions with a zero denominator and
+/// exact exponents above a hundred thousand.
+impl TryFrom<&NumberLiteral> for Number {
+    type Error = NumberError;
+
+    fn try_from(literal: &NumberLiteral) -> Result<Self, Self::Error> {
+        match literal {
+            NumberLiteral::Binary(number) => complex(number.complex(), number.prefix().explicit_exactness()),
+            NumberLiteral::Octal(number) => complex(number.complex(), number.prefix().explicit_exactness()),
+            NumberLiteral::Decimal(number) => complex(number.complex(), number.prefix().explicit_exactness()),
+            NumberLiteral::Hexadecimal(number) => complex(number.complex(), number.prefix().explicit_exactness()),
+        }
+    }
+}
+
+fn complex<R: RadixNumber>(complex: &ComplexNumber<R>, exactness: Option<Exactness>) -> Result<Number, NumberError> {
+    let number = match complex {
+        ComplexNumber::Real(real_number) => Number::Real(real(real_number, exactness)?),
+        ComplexNumber::Polar { magnitude, phase } => Number::polar(real(magnitude, exactness)?, real(phase, exactness)?),
+        ComplexNumber::RectangularValid { real: real_number, sign, imaginary } => {
+            let imaginary = match imaginary {
+                Some(imaginary) => variant(imaginary, exactness)?,
+                None => Real::from(1),
+            };
+            let imaginary = match sign {
+                Sign::Plus => imaginary,
+                Sign::Minus => -&imaginary,
+            };
+
+            Number::rectangular(real_part(real_number.as_ref(), exactness)?, imaginary)
+        }
+        ComplexNumber::RectangularInvalid { real: real_number, imaginary } => {
+            Number::rectangular(real_part(real_number.as_ref(), exactness)?, non_number(imaginary, exactness)?)
+        }
+    };
+
+    match exactness {
+        Some(Exactness::Exact) => number.to_exact(),
+        Some(Exactness::Inexact) => Ok(number.to_inexact()),
+        None => Ok(number),
+    }
+}
+
+/// Zero when absent
+fn real_part<R: RadixNumber>(real_number: Option<&RealNumber<R>>, exactness: Option<Exactness>) -> Result<Real, NumberError> {
+    real_number.map_or(Ok(Real::from(0)), |real_number| real(real_number, exactness))
+}
+
+fn real<R: RadixNumber>(real: &RealNumber<R>, exactness: Option<Exactness>) -> Result<Real, NumberError> {
+    match real {
+        RealNumber::Number { sign: Some(Sign::Minus), variant: real_variant } => Ok(-&variant(real_variant, exactness)?),
+        RealNumber::Number { variant: real_variant, .. } => variant(real_variant, exactness),
+        RealNumber::NonNumber(non_number_value) => non_number(non_number_value, exactness),
+    }
+}
+
+fn non_number(non_number: &NonNumber, exactness: Option<Exactness>) -> Result<Real, NumberError> {
+    if exactness == Some(Exactness::Exact) {
+        return Err(NumberError::NotRational);
+    }
+
+    let value = match (non_number.variant(), non_number.sign()) {
+        (NonNumberVariant::Infinity, Sign::Plus) => f64::INFINITY,
+        (NonNumberVariant::Infinity, Sign::Minus) => f64::NEG_INFINITY,
+        (NonNumberVariant::Invalid, _) => f64::NAN,
+    };
+
+    Ok(Real::Float(value))
+}
+
+fn variant<R: RadixNumber>(variant: &RealNumberVariant<R>, exactness: Option<Exactness>) -> Result<Real, NumberError> {
+    match variant {
+        RealNumberVariant::Fraction { numerator, denominator } => {
+            let denominator = digits_value(denominator);
+            if denominator == BigInt::from(0) {
+                return Err(NumberError::DivisionByZero);
+            }
+
+            Ok(Real::from_rational(BigRational::new(digits_value(numerator), denominator)))
+        }
+        RealNumberVariant::Number(number) => R::number_value(number, exactness),
+    }
+}
+
+/// Value of the radix specific [`Radix::Number`]
+trait RadixNumber: Radix {
+    fn number_value(number: &Self::Number, exactness: Option<Exactness>) -> Result<Real, NumberError>;
+}
+
+impl RadixNumber for DecimalDigit {
+    fn number_value(decimal: &Decimal, exactness: Option<Exactness>) -> Result<Real, NumberError> {
+        let (digits, fraction_digits): (&[DecimalDigit], &[DecimalDigit]) = match decimal.variant() {
+            DecimalVariant::Integer(digits) => (digits.as_slice(), &[]),
+            DecimalVariant::Fraction { fraction_digits } => (&[], fraction_digits.as_slice()),
+            DecimalVariant::Both { digits, fractional_digits } => (digits.as_slice(), fractional_digits),
+        };
+
+        let implicitly_exact = matches!(decimal.variant(), DecimalVariant::Integer(_)) && decimal.suffix().is_none();
+        if exactness == Some(Exactness::Inexact) || (exactness.is_none() && !implicitly_exact) {
+            // correctly rounded by the standard library, however large the exponent
+            let text = |digits: &[DecimalDigit]| {
+                digits
+                    .iter()
+                    .filter_map(|digit| char::from_digit(digit.value(), 10))
+                    .collect::<String>()
+            };
+            let mut float = format!("0{}.{}0", text(digits), text(fraction_digits));
+
+            if let Some(suffix) = decimal.suffix() {
+                let sign = if suffix.sign() == Some(&Sign::Minus) { "-" } else { "" };
+                float.push_str(&format!("e{sign}{}", text(suffix.digits().as_slice())));
+            }
+
+            return Ok(Real::Float(float.parse().unwrap_or(f64::NAN)));
+        }
+
+        let numerator = digits_value_of(digits.iter().chain(fraction_digits), 10);
+        let mut rational = BigRational::new(
+            numerator,
+            BigInt::from(10).pow(u32::try_from(fraction_digits.len()).map_err(|_| NumberError::Overflow)?),
+        );
+
+        if let Some(suffix) = decimal.suffix() {
+            let exponent = digits_value(suffix.digits());
+            let exponent = u32::try_from(&exponent)
+                .ok()
+                .filter(|exponent| *exponent <= MAX_EXACT_EXPONENT)
+                .ok_or(NumberError::Overflow)?;
+            let scale = BigRational::from_integer(BigInt::from(10).pow(exponent));
+
+            match suffix.sign() {
+                Some(Sign::Minus) => rational /= scale,
+                _ => rational *= scale,
+            }
+        }
+
+        Ok(Real::from_rational(rational))
+    }
+}
+
+simple_radix_number!(BinaryDigit, OctalDigit, HexadecimalDigit);
+
+macro_rules! simple_radix_number {
+    ($($digit:ty),* $(,)?) => {
+        $(
+            impl RadixNumber for $digit {
+                fn number_value(digits: &NonEmptyVec<$digit>, _: Option<Exactness>) -> Result<Real, NumberError> {
+                    Ok(Real::Integer(digits_value(digits)))
+                }
+            }
+        )*
+    };
+}
+use simple_radix_number;
+
+fn digits_value<R: Radix>(digits: &NonEmptyVec<R>) -> BigInt {
+    digits_value_of(digits.as_slice(), R::RADIX)
+}
+
+fn digits_value_of<'a, R: Radix + 'a>(digits: impl IntoIterator<Item = &'a R>, radix: u32) -> BigInt {
+    let digits = digits.into_iter().map(|digit| digit.value() as u8).collect::<Vec<_>>();
+    // `from_radix_be` rejects empty inputs
+    BigUint::from_radix_be(&digits, radix).map(BigInt::from).unwrap_or_default()
+}
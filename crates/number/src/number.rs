@@ -0,0 +1,314 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+use num_bigint::BigInt;
+use num_traits::Zero;
+
+use crate::*;
+
+/// Scheme number, see R7RS 6.2
+///
+/// Comparisons are numeric as with [`Real`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    /// See [`Real`]
+    Real(Real),
+    /// See [`Complex`]
+    Complex(Complex),
+}
+
+/// Non-real complex number in rectangular form, built by [`Number::rectangular`]
+///
+/// Its imaginary part is never an exact zero, and both parts share the same exactness.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Complex {
+    real: Real,
+    imaginary: Real,
+}
+
+impl Complex {
+    /// Real part
+    pub fn real(&self) -> &Real {
+        &self.real
+    }
+
+    /// Imaginary part
+    pub fn imaginary(&self) -> &Real {
+        &self.imaginary
+    }
+}
+
+impl Number {
+    /// R7RS `make-rectangular`, real when the imaginary part is an exact zero.
+    pub fn rectangular(real: Real, imaginary: Real) -> Number {
+        if imaginary.is_exact_zero() {
+            return Number::Real(real);
+        }
+
+        match real.is_exact() == imaginary.is_exact() {
+            true => Number::Complex(Complex { real, imaginary }),
+            false => Number::Complex(Complex { real: real.to_inexact(), imaginary: imaginary.to_inexact() }),
+        }
+    }
+
+    /// R7RS `make-polar`, the angle being in radians.
+    pub fn polar(magnitude: Real, angle: Real) -> Number {
+        if angle.is_exact_zero() {
+            return Number::Real(magnitude);
+        }
+
+        let (magnitude, angle) = (magnitude.to_f64(), angle.to_f64());
+        Number::rectangular(Real::Float(magnitude * angle.cos()), Real::Float(magnitude * angle.sin()))
+    }
+
+    /// R7RS `real-part`
+    pub fn real_part(&self) -> Real {
+        match self {
+            Number::Real(real) => real.clone(),
+            Number::Complex(complex) => complex.real.clone(),
+        }
+    }
+
+    /// R7RS `imag-part`, an exact zero for reals
+    pub fn imaginary_part(&self) -> Real {
+        match self {
+            Number::Real(_) => Real::Integer(BigInt::zero()),
+            Number::Complex(complex) => complex.imaginary.clone(),
+        }
+    }
+
+    /// R7RS `magnitude`, the absolute value of reals
+    pub fn magnitude(&self) -> Real {
+        match self {
+            Number::Real(real) => real.abs(),
+            Number::Complex(complex) => Real::Float(complex.real.to_f64().hypot(complex.imaginary.to_f64())),
+        }
+    }
+
+    /// R7RS `angle`, in radians
+    pub fn angle(&self) -> Real {
+        match self {
+            Number::Real(real) if real.is_exact() && real.signum() != Some(std::cmp::Ordering::Less) => Real::Integer(BigInt::zero()),
+            _ => Real::Float(self.imaginary_part().to_f64().atan2(self.real_part().to_f64())),
+        }
+    }
+
+    /// The real, or [`NumberError::NotReal`]
+    pub fn as_real(&self) -> Result<&Real, NumberError> {
+        match self {
+            Number::Real(real) => Ok(real),
+            Number::Complex(_) => Err(NumberError::NotReal),
+        }
+    }
+
+    /// Whether the number is exact
+    pub fn is_exact(&self) -> bool {
+        match self {
+            Number::Real(real) => real.is_exact(),
+            Number::Complex(complex) => complex.real.is_exact(),
+        }
+    }
+
+    /// R7RS `exact-integer?`
+    pub fn is_exact_integer(&self) -> bool {
+        matches!(self, Number::Real(Real::Integer(_)))
+    }
+
+    /// R7RS `integer?`
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Number::Real(real) if real.is_integer())
+    }
+
+    /// R7RS `rational?`
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Number::Real(real) if real.is_rational())
+    }
+
+    /// R7RS `real?`
+    pub fn is_real(&self) -> bool {
+        matches!(self, Number::Real(_))
+    }
+
+    /// R7RS `nan?`, true if either part is NaN
+    pub fn is_nan(&self) -> bool {
+        self.parts().iter().any(|part| part.is_nan())
+    }
+
+    /// R7RS `infinite?`, true if either part is infinite
+    pub fn is_infinite(&self) -> bool {
+        self.parts().iter().any(|part| part.is_infinite())
+    }
+
+    /// R7RS `finite?`, true if both parts are finite
+    pub fn is_finite(&self) -> bool {
+        self.parts().iter().all(|part| part.is_finite())
+    }
+
+    /// R7RS `zero?`
+    pub fn is_zero(&self) -> bool {
+        self.parts().iter().all(|part| part.is_zero())
+    }
+
+    /// R7RS `exact`
+    pub fn to_exact(&self) -> Result<Number, NumberError> {
+        match self {
+            Number::Real(real) => real.to_exact().map(Number::Real),
+            Number::Complex(complex) => Ok(Number::rectangular(complex.real.to_exact()?, complex.imaginary.to_exact()?)),
+        }
+    }
+
+    /// R7RS `inexact`
+    pub fn to_inexact(&self) -> Number {
+        match self {
+            Number::Real(real) => Number::Real(real.to_inexact()),
+            Number::Complex(complex) => Number::rectangular(complex.real.to_inexact(), complex.imaginary.to_inexact()),
+        }
+    }
+
+    /// Division, which fails on an exact zero divisor only.
+    pub fn divide(&self, divisor: &Number) -> Result<Number, NumberError> {
+        if let (Number::Real(dividend), Number::Real(divisor)) = (self, divisor) {
+            return dividend.divide(divisor).map(Number::Real);
+        }
+
+        // (a + bi) / (c + di) = ((ac + bd) + (bc - ad)i) / (c² + d²)
+        let [a, b] = self.parts();
+        let [c, d] = divisor.parts();
+        let denominator = &(&c * &c) + &(&d * &d);
+        let real = &(&a * &c) + &(&b * &d);
+        let imaginary = &(&b * &c) - &(&a * &d);
+
+        Ok(Number::rectangular(real.divide(&denominator)?, imaginary.divide(&denominator)?))
+    }
+
+    /// R7RS `square`
+    pub fn square(&self) -> Number {
+        self * self
+    }
+
+    /// Real and imaginary parts
+    pub(crate) fn parts(&self) -> [Real; 2] {
+        [self.real_part(), self.imaginary_part()]
+    }
+}
+
+impl Add for &Number {
+    type Output = Number;
+
+    fn add(self, other: &Number) -> Number {
+        match (self, other) {
+            (Number::Real(left), Number::Real(right)) => Number::Real(left + right),
+            _ => {
+                let ([a, b], [c, d]) = (self.parts(), other.parts());
+                Number::rectangular(&a + &c, &b + &d)
+            }
+        }
+    }
+}
+
+impl Sub for &Number {
+    type Output = Number;
+
+    fn sub(self, other: &Number) -> Number {
+        self + &-other
+    }
+}
+
+impl Mul for &Number {
+    type Output = Number;
+
+    fn mul(self, other: &Number) -> Number {
+        match (self, other) {
+            (Number::Real(left), Number::Real(right)) => Number::Real(left * right),
+            _ => {
+                // (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+                let ([a, b], [c, d]) = (self.parts(), other.parts());
+                Number::rectangular(&(&a * &c) - &(&b * &d), &(&a * &d) + &(&b * &c))
+            }
+        }
+    }
+}
+
+impl Neg for &Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        match self {
+            Number::Real(real) => Number::Real(-real),
+            Number::Complex(complex) => Number::Complex(Complex { real: -&complex.real, imaginary: -&complex.imaginary }),
+        }
+    }
+}
+
+impl From<Real> for Number {
+    fn from(real: Real) -> Self {
+        Number::Real(real)
+    }
+}
+
+real_from!(i64, f64, BigInt, num_rational::BigRational);
+
+macro_rules! real_from {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl From<$type> for Number {
+                fn from(value: $type) -> Self {
+                    Number::Real(value.into())
+                }
+            }
+        )*
+    };
+}
+use real_from;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complex(real: impl Into<Real>, imaginary: impl Into<Real>) -> Number {
+        Number::rectangular(real.into(), imaginary.into())
+    }
+
+    #[test]
+    fn rectangular_normalization() {
+        assert!(complex(1, 0).is_real());
+        assert!(!complex(1, 0.0).is_real());
+        assert!(!complex(1, 2.0).is_exact());
+        assert!(complex(1, 2).is_exact());
+    }
+
+    #[test]
+    fn complex_arithmetic() {
+        let i = complex(0, 1);
+        assert_eq!(Number::from(-1), &i * &i);
+        assert_eq!(complex(1, 1), &Number::from(1) + &i);
+        assert_eq!(Ok(complex(0, -1)), Number::from(1).divide(&i));
+        assert_eq!(Ok(complex(1, 2)), (&complex(1, 2) * &complex(3, -1)).divide(&complex(3, -1)));
+        assert_eq!(Err(NumberError::DivisionByZero), i.divide(&Number::from(0)));
+    }
+
+    #[test]
+    fn polar_form() {
+        assert_eq!(Number::from(2), Number::polar(Real::from(2), Real::from(0)));
+
+        let number = Number::polar(Real::from(2.0), Real::from(std::f64::consts::FRAC_PI_2));
+        assert!((number.imaginary_part().to_f64() - 2.0).abs() < 1e-12);
+        assert!((number.magnitude().to_f64() - 2.0).abs() < 1e-12);
+        assert!((number.angle().to_f64() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn predicates() {
+        assert!(Number::from(2.0).is_integer());
+        assert!(!Number::from(2.0).is_exact_integer());
+        assert!(!Number::from(f64::INFINITY).is_rational());
+        assert!(complex(f64::NAN, 1.0).is_nan());
+        assert!(complex(0.0, 0.0).is_zero());
+    }
+
+    #[test]
+    fn exactness_conversion() {
+        assert_eq!(Ok(complex(1, 2)), complex(1.0, 2.0).to_exact());
+        assert!(!complex(1, 2).to_inexact().is_exact());
+        assert_eq!(Err(NumberError::NotRational), complex(1.0, f64::INFINITY).to_exact());
+    }
+}
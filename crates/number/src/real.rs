@@ -0,0 +1,411 @@
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::*;
+
+/// Real number, exact or inexact
+///
+/// Comparisons are numeric, `1` being equal to `1.0` and NaN being equal to nothing.
+/// [`Real::is_exact`] tells both apart.
+#[derive(Debug, Clone)]
+pub enum Real {
+    /// Exact integer of arbitrary precision
+    Integer(BigInt),
+    /// Exact rational with a denominator greater than one, see [`Real::from_rational`]
+    Rational(BigRational),
+    /// Inexact real, as an IEEE 754 double
+    Float(f64),
+}
+
+impl Real {
+    /// Exact rational, integers being stored as such
+    pub fn from_rational(rational: BigRational) -> Self {
+        match rational.is_integer() {
+            true => Real::Integer(rational.to_integer()),
+            false => Real::Rational(rational),
+        }
+    }
+
+    /// Whether the real is exact
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Real::Float(_))
+    }
+
+    /// `2` and `2.0` are integers, `2.5` and `+inf.0` are not.
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Real::Integer(_) => true,
+            Real::Rational(rational) => rational.is_integer(),
+            Real::Float(float) => float.is_finite() && float.fract() == 0.0,
+        }
+    }
+
+    /// Every real but infinities and NaN is rational.
+    pub fn is_rational(&self) -> bool {
+        self.is_finite()
+    }
+
+    /// Whether the real is finite
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Real::Float(float) => float.is_finite(),
+            _ => true,
+        }
+    }
+
+    /// Whether the real is `+inf.0` or `-inf.0`
+    pub fn is_infinite(&self) -> bool {
+        matches!(self, Real::Float(float) if float.is_infinite())
+    }
+
+    /// Whether the real is NaN
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Real::Float(float) if float.is_nan())
+    }
+
+    /// Exact or inexact zero
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Real::Integer(integer) => integer.is_zero(),
+            Real::Rational(rational) => rational.is_zero(),
+            Real::Float(float) => *float == 0.0,
+        }
+    }
+
+    /// Zero which is exact, which never makes a complex number real when inexact
+    pub fn is_exact_zero(&self) -> bool {
+        self.is_exact() && self.is_zero()
+    }
+
+    /// Sign of the real, `None` for NaN. Inexact zeros compare equal to zero regardless of their
+    /// sign.
+    pub fn signum(&self) -> Option<Ordering> {
+        self.partial_cmp(&Real::Integer(BigInt::zero()))
+    }
+
+    /// Exact value of the real, `None` for infinities and NaN
+    pub fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Real::Integer(integer) => Some(BigRational::from_integer(integer.clone())),
+            Real::Rational(rational) => Some(rational.clone()),
+            Real::Float(float) => BigRational::from_float(*float),
+        }
+    }
+
+    /// Nearest double, infinite for exact values exceeding its range
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Real::Integer(integer) => integer.to_f64().unwrap_or(f64::NAN),
+            Real::Rational(rational) => rational.to_f64().unwrap_or(f64::NAN),
+            Real::Float(float) => *float,
+        }
+    }
+
+    /// R7RS `exact`, the conversion of a double being precise: `(exact 0.1)` isn't `1/10`.
+    pub fn to_exact(&self) -> Result<Real, NumberError> {
+        match self {
+            Real::Float(_) => self.to_rational().map(Real::from_rational).ok_or(NumberError::NotRational),
+            exact => Ok(exact.clone()),
+        }
+    }
+
+    /// R7RS `inexact`
+    pub fn to_inexact(&self) -> Real {
+        Real::Float(self.to_f64())
+    }
+
+    /// Division, which fails on an exact zero divisor only.
+    pub fn divide(&self, divisor: &Real) -> Result<Real, NumberError> {
+        if divisor.is_exact_zero() {
+            return Err(NumberError::DivisionByZero);
+        }
+
+        Ok(match (self.exact_rational(), divisor.exact_rational()) {
+            (Some(dividend), Some(divisor)) => Real::from_rational(dividend / divisor),
+            _ => Real::Float(self.to_f64() / divisor.to_f64()),
+        })
+    }
+
+    /// Absolute value
+    pub fn abs(&self) -> Real {
+        match self {
+            Real::Integer(integer) => Real::Integer(integer.abs()),
+            Real::Rational(rational) => Real::Rational(rational.abs()),
+            Real::Float(float) => Real::Float(float.abs()),
+        }
+    }
+
+    /// Largest integer not greater than the real
+    pub fn floor(&self) -> Real {
+        self.round_with(BigRational::floor, f64::floor)
+    }
+
+    /// Smallest integer not smaller than the real
+    pub fn ceiling(&self) -> Real {
+        self.round_with(BigRational::ceil, f64::ceil)
+    }
+
+    /// Integer closest to the real whose absolute value is not larger
+    pub fn truncate(&self) -> Real {
+        self.round_with(BigRational::trunc, f64::trunc)
+    }
+
+    /// Closest integer, rounding to even when halfway between two integers
+    pub fn round(&self) -> Real {
+        self.round_with(
+            |rational| {
+                let floor = rational.floor();
+                let half = BigRational::new(BigInt::one(), BigInt::from(2));
+
+                match (rational - &floor).cmp(&half) {
+                    Ordering::Less => floor,
+                    Ordering::Greater => floor + BigRational::one(),
+                    Ordering::Equal if floor.to_integer().is_even() => floor,
+                    Ordering::Equal => floor + BigRational::one(),
+                }
+            },
+            f64::round_ties_even,
+        )
+    }
+
+    /// Larger of both reals, inexact if either is.
+    pub fn max(&self, other: &Real) -> Real {
+        self.pick(other, Ordering::Greater)
+    }
+
+    /// Smaller of both reals, inexact if either is.
+    pub fn min(&self, other: &Real) -> Real {
+        self.pick(other, Ordering::Less)
+    }
+
+    fn pick(&self, other: &Real, ordering: Ordering) -> Real {
+        let picked = match self.partial_cmp(other) {
+            None if self.is_nan() => self,
+            None => other,
+            Some(order) if order == ordering => self,
+            Some(_) => other,
+        };
+
+        match self.is_exact() && other.is_exact() {
+            true => picked.clone(),
+            false => picked.to_inexact(),
+        }
+    }
+
+    fn round_with(&self, rational: impl Fn(&BigRational) -> BigRational, float: impl Fn(f64) -> f64) -> Real {
+        match self {
+            Real::Integer(_) => self.clone(),
+            Real::Rational(value) => Real::from_rational(rational(value)),
+            Real::Float(value) => Real::Float(float(*value)),
+        }
+    }
+
+    /// Rational of exact reals only
+    pub(crate) fn exact_rational(&self) -> Option<BigRational> {
+        match self {
+            Real::Float(_) => None,
+            exact => exact.to_rational(),
+        }
+    }
+
+    /// Applies the operation exactly when both operands are exact.
+    fn combine(
+        &self,
+        other: &Real,
+        integer: impl Fn(&BigInt, &BigInt) -> BigInt,
+        rational: impl Fn(BigRational, BigRational) -> BigRational,
+        float: impl Fn(f64, f64) -> f64,
+    ) -> Real {
+        if let (Real::Integer(left), Real::Integer(right)) = (self, other) {
+            return Real::Integer(integer(left, right));
+        }
+
+        match (self.exact_rational(), other.exact_rational()) {
+            (Some(left), Some(right)) => Real::from_rational(rational(left, right)),
+            _ => Real::Float(float(self.to_f64(), other.to_f64())),
+        }
+    }
+}
+
+impl Add for &Real {
+    type Output = Real;
+
+    fn add(self, other: &Real) -> Real {
+        self.combine(
+            other,
+            |left, right| left + right,
+            |left, right| left + right,
+            |left, right| left + right,
+        )
+    }
+}
+
+impl Sub for &Real {
+    type Output = Real;
+
+    fn sub(self, other: &Real) -> Real {
+        self.combine(
+            other,
+            |left, right| left - right,
+            |left, right| left - right,
+            |left, right| left - right,
+        )
+    }
+}
+
+impl Mul for &Real {
+    type Output = Real;
+
+    fn mul(self, other: &Real) -> Real {
+        self.combine(
+            other,
+            |left, right| left * right,
+            |left, right| left * right,
+            |left, right| left * right,
+        )
+    }
+}
+
+impl Neg for &Real {
+    type Output = Real;
+
+    fn neg(self) -> Real {
+        match self {
+            Real::Integer(integer) => Real::Integer(-integer),
+            Real::Rational(rational) => Real::Rational(-rational),
+            Real::Float(float) => Real::Float(-float),
+        }
+    }
+}
+
+impl PartialEq for Real {
+    fn eq(&self, other: &Real) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+/// Exact comparison, doubles being compared by their exact value: `(< 1/3 0.3333333333333333)`
+/// holds.
+impl PartialOrd for Real {
+    fn partial_cmp(&self, other: &Real) -> Option<Ordering> {
+        match (self, other) {
+            (Real::Integer(left), Real::Integer(right)) => Some(left.cmp(right)),
+            (Real::Float(left), Real::Float(right)) => left.partial_cmp(right),
+            (Real::Float(float), exact) => compare_float(*float, exact),
+            (exact, Real::Float(float)) => compare_float(*float, exact).map(Ordering::reverse),
+            _ => Some(self.exact_rational().cmp(&other.exact_rational())),
+        }
+    }
+}
+
+fn compare_float(float: f64, exact: &Real) -> Option<Ordering> {
+    match BigRational::from_float(float) {
+        Some(float) => exact.exact_rational().map(|exact| float.cmp(&exact)),
+        None if float.is_nan() => None,
+        None if float > 0.0 => Some(Ordering::Greater),
+        None => Some(Ordering::Less),
+    }
+}
+
+impl From<i64> for Real {
+    fn from(integer: i64) -> Self {
+        Real::Integer(integer.into())
+    }
+}
+
+impl From<BigInt> for Real {
+    fn from(integer: BigInt) -> Self {
+        Real::Integer(integer)
+    }
+}
+
+impl From<BigRational> for Real {
+    fn from(rational: BigRational) -> Self {
+        Real::from_rational(rational)
+    }
+}
+
+impl From<f64> for Real {
+    fn from(float: f64) -> Self {
+        Real::Float(float)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rational(numerator: i64, denominator: i64) -> Real {
+        Real::from_rational(BigRational::new(numerator.into(), denominator.into()))
+    }
+
+    #[test]
+    fn rationals_are_normalized() {
+        assert!(matches!(rational(4, 2), Real::Integer(_)));
+        assert!(matches!(rational(1, 3), Real::Rational(_)));
+        assert!(rational(6, -4).is_exact());
+    }
+
+    #[test]
+    fn exactness_contagion() {
+        let third = rational(1, 3);
+        assert!((&third + &third).is_exact());
+        assert_eq!(rational(2, 3), &third + &third);
+        assert!(!(&third + &Real::from(1.0)).is_exact());
+        assert_eq!(Real::from(1), &rational(1, 10) * &Real::from(10));
+    }
+
+    #[test]
+    fn exact_division() {
+        assert_eq!(Ok(rational(1, 3)), Real::from(1).divide(&Real::from(3)));
+        assert_eq!(Err(NumberError::DivisionByZero), Real::from(1.5).divide(&Real::from(0)));
+        assert_eq!(Ok(Real::from(f64::INFINITY)), Real::from(1).divide(&Real::from(0.0)));
+    }
+
+    #[test]
+    fn numeric_comparison() {
+        assert_eq!(Real::from(1), Real::from(1.0));
+        assert!(rational(1, 3) > Real::from(0.3333333333333333));
+        assert!(Real::from(f64::INFINITY) > Real::from(BigInt::from(10).pow(400)));
+        assert_eq!(None, Real::from(f64::NAN).partial_cmp(&Real::from(1)));
+    }
+
+    #[test]
+    fn exact_and_inexact_conversion() {
+        assert_eq!(Ok(rational(1, 2)), Real::from(0.5).to_exact());
+        assert_ne!(Ok(rational(1, 10)), Real::from(0.1).to_exact());
+        assert_eq!(Err(NumberError::NotRational), Real::from(f64::NAN).to_exact());
+        assert!(matches!(rational(1, 4).to_inexact(), Real::Float(0.25)));
+    }
+
+    #[test]
+    fn rounding() {
+        for (value, floor, ceiling, truncate, round) in [
+            (rational(7, 2), 3, 4, 3, 4),
+            (rational(5, 2), 2, 3, 2, 2),
+            (rational(-7, 2), -4, -3, -3, -4),
+            (rational(-1, 3), -1, 0, 0, 0),
+        ] {
+            assert_eq!(Real::from(floor), value.floor());
+            assert_eq!(Real::from(ceiling), value.ceiling());
+            assert_eq!(Real::from(truncate), value.truncate());
+            assert_eq!(Real::from(round), value.round());
+        }
+
+        assert!(matches!(Real::from(2.5).round(), Real::Float(2.0)));
+        assert!(matches!(Real::from(-3.5).round(), Real::Float(-4.0)));
+    }
+
+    #[test]
+    fn min_max_contagion() {
+        assert!(matches!(Real::from(3).max(&Real::from(1.0)), Real::Float(3.0)));
+        assert!(matches!(Real::from(3).min(&Real::from(4)), Real::Integer(_)));
+    }
+}
@@ -0,0 +1,264 @@
+//! Transcendental functions, square roots and exponentiation of R7RS 6.2.6
+
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::*;
+
+/// Largest number of bits of an exact power, about eight megabytes.
+const MAX_POWER_BITS: u64 = 1 << 26;
+
+impl Number {
+    /// R7RS `exp`
+    pub fn exp(&self) -> Number {
+        self.apply(f64::exp, Complex64::exp)
+    }
+
+    /// R7RS `log` with one argument, complex for negative reals
+    pub fn ln(&self) -> Number {
+        match self {
+            Number::Real(real) if real.signum() != Some(std::cmp::Ordering::Less) => Number::from(real.to_f64().ln()),
+            _ => from_complex64(to_complex64(self).ln()),
+        }
+    }
+
+    /// R7RS `log` with two arguments
+    pub fn log(&self, base: &Number) -> Result<Number, NumberError> {
+        self.ln().divide(&base.ln())
+    }
+
+    /// R7RS `sin`
+    pub fn sin(&self) -> Number {
+        self.apply(f64::sin, Complex64::sin)
+    }
+
+    /// R7RS `cos`
+    pub fn cos(&self) -> Number {
+        self.apply(f64::cos, Complex64::cos)
+    }
+
+    /// R7RS `tan`
+    pub fn tan(&self) -> Number {
+        self.apply(f64::tan, Complex64::tan)
+    }
+
+    /// R7RS `asin`, complex outside of `[-1, 1]`
+    pub fn asin(&self) -> Number {
+        self.apply_within_unit(f64::asin, Complex64::asin)
+    }
+
+    /// R7RS `acos`, complex outside of `[-1, 1]`
+    pub fn acos(&self) -> Number {
+        self.apply_within_unit(f64::acos, Complex64::acos)
+    }
+
+    /// R7RS `atan` with one argument
+    pub fn atan(&self) -> Number {
+        self.apply(f64::atan, Complex64::atan)
+    }
+
+    /// R7RS `atan` with two arguments, the angle of the point `(x, y)`
+    pub fn atan2(&self, x: &Number) -> Result<Number, NumberError> {
+        Ok(Number::from(self.as_real()?.to_f64().atan2(x.as_real()?.to_f64())))
+    }
+
+    /// R7RS `sqrt`, exact for exact perfect squares such as `-4` or `1/9`
+    pub fn sqrt(&self) -> Number {
+        if let Number::Real(real) = self {
+            if let Some(rational) = real.exact_rational() {
+                if let Some(root) = exact_sqrt(&rational.abs()) {
+                    return match rational.is_negative() {
+                        true => Number::rectangular(Real::from(0), root),
+                        false => Number::Real(root),
+                    };
+                }
+            }
+
+            let float = real.to_f64();
+            if float >= 0.0 || float.is_nan() {
+                return Number::from(float.sqrt());
+            }
+        }
+
+        from_complex64(to_complex64(self).sqrt())
+    }
+
+    /// R7RS `expt`, exact for exact bases raised to exact integers
+    pub fn expt(&self, exponent: &Number) -> Result<Number, NumberError> {
+        if let Number::Real(Real::Integer(exponent)) = exponent {
+            if self.is_exact() {
+                return exact_power(self, exponent);
+            }
+
+            if let Some(exponent) = exponent.to_i32() {
+                return Ok(match self {
+                    Number::Real(real) => Number::from(real.to_f64().powi(exponent)),
+                    Number::Complex(_) => from_complex64(to_complex64(self).powi(exponent)),
+                });
+            }
+        }
+
+        if self.is_zero() {
+            return match exponent.real_part().signum() {
+                Some(std::cmp::Ordering::Greater) => Ok(Number::from(0.0)),
+                _ => Err(NumberError::DivisionByZero),
+            };
+        }
+
+        match (self, exponent) {
+            (Number::Real(base), Number::Real(exponent)) if base.signum() == Some(std::cmp::Ordering::Greater) => {
+                Ok(Number::from(base.to_f64().powf(exponent.to_f64())))
+            }
+            _ => Ok(from_complex64((to_complex64(exponent) * to_complex64(self).ln()).exp())),
+        }
+    }
+
+    fn apply(&self, real: impl Fn(f64) -> f64, complex: impl Fn(Complex64) -> Complex64) -> Number {
+        match self {
+            Number::Real(value) => Number::from(real(value.to_f64())),
+            Number::Complex(_) => from_complex64(complex(to_complex64(self))),
+        }
+    }
+
+    fn apply_within_unit(&self, real: impl Fn(f64) -> f64, complex: impl Fn(Complex64) -> Complex64) -> Number {
+        match self {
+            Number::Real(value) if (-1.0..=1.0).contains(&value.to_f64()) => Number::from(real(value.to_f64())),
+            _ => from_complex64(complex(to_complex64(self))),
+        }
+    }
+}
+
+fn to_complex64(number: &Number) -> Complex64 {
+    Complex64::new(number.real_part().to_f64(), number.imaginary_part().to_f64())
+}
+
+fn from_complex64(complex: Complex64) -> Number {
+    Number::rectangular(Real::Float(complex.re), Real::Float(complex.im))
+}
+
+/// Square root of a non-negative rational, if both its numerator and denominator are perfect
+/// squares
+fn exact_sqrt(rational: &BigRational) -> Option<Real> {
+    let root = |integer: &BigInt| Some(integer.sqrt()).filter(|root| root * root == *integer);
+    Some(Real::from_rational(BigRational::new(
+        root(rational.numer())?,
+        root(rational.denom())?,
+    )))
+}
+
+fn exact_power(base: &Number, exponent: &BigInt) -> Result<Number, NumberError> {
+    if exponent.is_zero() {
+        return Ok(Number::from(1));
+    }
+    if base.is_zero() {
+        return match exponent.is_positive() {
+            true => Ok(Number::from(0)),
+            false => Err(NumberError::DivisionByZero),
+        };
+    }
+
+    // bases of magnitude one only cycle
+    let magnitude_one = matches!(base, Number::Real(real) if real.abs() == Real::from(1));
+    let exponent_value = match magnitude_one {
+        true => exponent.magnitude() % 4u32 + 4u32,
+        false => exponent.magnitude().clone(),
+    };
+
+    let base_bits = match base {
+        Number::Real(real) => real
+            .to_rational()
+            .map_or(0, |rational| rational.numer().bits().max(rational.denom().bits())),
+        Number::Complex(_) => base.magnitude().to_f64().log2().abs().ceil() as u64 + 1,
+    };
+    let exponent_value = exponent_value
+        .to_u64()
+        .filter(|exponent| magnitude_one || base_bits.saturating_mul(*exponent) <= MAX_POWER_BITS)
+        .ok_or(NumberError::Overflow)?;
+
+    // exponentiation by squaring
+    let mut result = Number::from(1);
+    let mut square = base.clone();
+    let mut remaining = exponent_value;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = &result * &square;
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            square = square.square();
+        }
+    }
+
+    match exponent.is_negative() {
+        true => Number::from(BigInt::one()).divide(&result),
+        false => Ok(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(expected: f64, number: Number) -> bool {
+        (number.real_part().to_f64() - expected).abs() < 1e-12
+    }
+
+    #[test]
+    fn exact_square_roots() {
+        assert_eq!(Number::from(3), Number::from(9).sqrt());
+        assert_eq!(
+            Number::from(BigRational::new(2.into(), 3.into())),
+            Number::from(BigRational::new(4.into(), 9.into())).sqrt()
+        );
+        assert_eq!(Number::rectangular(Real::from(0), Real::from(2)), Number::from(-4).sqrt());
+        assert!(!Number::from(2).sqrt().is_exact());
+        assert!(!Number::from(-2.0).sqrt().is_real());
+    }
+
+    #[test]
+    fn exact_powers() {
+        assert_eq!(Ok(Number::from(1024)), Number::from(2).expt(&Number::from(10)));
+        assert_eq!(
+            Ok(Number::from(BigRational::new(1.into(), 8.into()))),
+            Number::from(2).expt(&Number::from(-3))
+        );
+        assert_eq!(
+            Ok(Number::from(-1)),
+            Number::from(-1).expt(&Number::from(BigInt::from(10).pow(30) + 1))
+        );
+        assert_eq!(
+            Ok(Number::from(-1)),
+            Number::rectangular(Real::from(0), Real::from(1)).expt(&Number::from(2))
+        );
+        assert_eq!(Ok(Number::from(1)), Number::from(0).expt(&Number::from(0)));
+        assert_eq!(Err(NumberError::DivisionByZero), Number::from(0).expt(&Number::from(-1)));
+        assert_eq!(
+            Err(NumberError::Overflow),
+            Number::from(10).expt(&Number::from(BigInt::from(10).pow(10)))
+        );
+    }
+
+    #[test]
+    fn inexact_powers() {
+        assert!(close(2.0, Number::from(4).expt(&Number::from(0.5)).unwrap()));
+        assert!(close(0.25, Number::from(2.0).expt(&Number::from(-2)).unwrap()));
+        assert!(!Number::from(-8)
+            .expt(&Number::from(BigRational::new(1.into(), 3.into())))
+            .unwrap()
+            .is_real());
+        assert_eq!(Ok(Number::from(0.0)), Number::from(0).expt(&Number::from(0.5)));
+    }
+
+    #[test]
+    fn transcendental_functions() {
+        assert!(close(1.0, Number::from(0).exp()));
+        assert!(close(1.0, Number::from(std::f64::consts::E).ln()));
+        assert!(close(3.0, Number::from(8).log(&Number::from(2)).unwrap()));
+        assert!(close(std::f64::consts::FRAC_PI_2, Number::from(1).asin()));
+        assert!(!Number::from(2).asin().is_real());
+        assert!(!Number::from(-1).ln().is_real());
+        assert!(close(std::f64::consts::FRAC_PI_4, Number::from(1).atan2(&Number::from(1)).unwrap()));
+    }
+}
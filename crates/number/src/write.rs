@@ -0,0 +1,170 @@
+//! Written representation of numbers, as by R7RS `number->string` and `string->number`
+
+use std::fmt::{self, Display};
+
+use pluine_lex::{span::Spanned, Lexer, Token, TokenAll};
+
+use crate::*;
+
+impl Number {
+    /// R7RS `number->string`, inexact numbers being written in radix 10 only.
+    pub fn to_string_radix(&self, radix: u32) -> Result<String, NumberError> {
+        if !matches!(radix, 2 | 8 | 10 | 16) {
+            return Err(NumberError::InvalidRadix(radix));
+        }
+        if radix != 10 && !self.is_exact() {
+            return Err(NumberError::InexactRadix);
+        }
+
+        Ok(match self {
+            Number::Real(real) => write_real(real, radix),
+            Number::Complex(complex) => {
+                let imaginary = write_real(complex.imaginary(), radix);
+                let sign = if imaginary.starts_with(['+', '-']) { "" } else { "+" };
+                let real = match complex.real().is_exact_zero() {
+                    true => String::new(),
+                    false => write_real(complex.real(), radix),
+                };
+                format!("{real}{sign}{imaginary}i")
+            }
+        })
+    }
+
+    /// R7RS `string->number`, `None` if `src` isn't a single number literal.
+    ///
+    /// The radix applies unless overridden by a prefix, as in `(string->number "#x10" 2)`.
+    pub fn parse(src: &str, radix: u32) -> Option<Number> {
+        let prefix = match radix {
+            2 => "#b",
+            8 => "#o",
+            10 => "",
+            16 => "#x",
+            _ => return None,
+        };
+
+        let src = match has_radix_prefix(src) {
+            true => src.to_string(),
+            false => format!("{prefix}{src}"),
+        };
+
+        match Lexer::new(&src).tokenize_all().ok()?.as_slice() {
+            // the atmosphere isn't part of a number
+            [TokenAll::Token(Token::Number(literal))] if literal.span().end() - literal.span().start() == src.len() => {
+                Number::try_from(literal).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+fn has_radix_prefix(src: &str) -> bool {
+    // prefixes are at most two, `#e#x` or `#x#e`
+    src.as_bytes()
+        .chunks(2)
+        .take(2)
+        .take_while(|chunk| chunk[0] == b'#')
+        .any(|chunk| matches!(chunk.get(1), Some(b'b' | b'B' | b'o' | b'O' | b'd' | b'D' | b'x' | b'X')))
+}
+
+fn write_real(real: &Real, radix: u32) -> String {
+    match real {
+        Real::Integer(integer) => integer.to_str_radix(radix),
+        Real::Rational(rational) => format!("{}/{}", rational.numer().to_str_radix(radix), rational.denom().to_str_radix(radix)),
+        Real::Float(float) if float.is_nan() => "+nan.0".to_string(),
+        Real::Float(float) if float.is_infinite() && *float > 0.0 => "+inf.0".to_string(),
+        Real::Float(float) if float.is_infinite() => "-inf.0".to_string(),
+        // shortest representation reading back to the same double, with a point or an exponent
+        Real::Float(float) => format!("{float:?}"),
+    }
+}
+
+/// Radix 10, as by `write`
+impl Display for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Real(real) => real.fmt(formatter),
+            Number::Complex(_) => formatter.write_str(&self.to_string_radix(10).map_err(|_| fmt::Error)?),
+        }
+    }
+}
+
+/// Radix 10, as by `write`
+impl Display for Real {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&write_real(self, 10))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(src: &str) -> Number {
+        Number::parse(src, 10).unwrap_or_else(|| panic!("{src} is not a number"))
+    }
+
+    #[test]
+    fn literal_values() {
+        for (src, written) in [
+            ("42", "42"),
+            ("-4/6", "-2/3"),
+            ("#x-1F", "-31"),
+            ("1.5", "1.5"),
+            (".5e1", "5.0"),
+            ("1e400", "+inf.0"),
+            ("-inf.0", "-inf.0"),
+            ("+nan.0", "+nan.0"),
+            ("1+2i", "1+2i"),
+            ("-i", "-1i"),
+            ("1.5-2i", "1.5-2.0i"),
+            ("+inf.0i", "0.0+inf.0i"),
+            ("1@0", "1"),
+            ("123456789012345678901234567890", "123456789012345678901234567890"),
+        ] {
+            assert_eq!(written, read(src).to_string(), "{src}");
+        }
+    }
+
+    #[test]
+    fn exactness_prefixes() {
+        assert_eq!("1/10", read("#e0.1").to_string());
+        assert_eq!("100", read("#e1e2").to_string());
+        assert_eq!("1/100", read("#e1e-2").to_string());
+        assert_eq!("0.5", read("#i1/2").to_string());
+        assert_eq!("31.0", read("#i#x1F").to_string());
+        assert_eq!("1.0+1.0i", read("#i1+i").to_string());
+        assert!(read("#e1.5+2.5i").is_exact());
+
+        assert_eq!(None, Number::parse("#e+inf.0", 10));
+        assert_eq!(None, Number::parse("1/0", 10));
+        assert_eq!(None, Number::parse("#e1e1000000", 10));
+    }
+
+    #[test]
+    fn parse_radix() {
+        assert_eq!(Some(Number::from(5)), Number::parse("101", 2));
+        assert_eq!(Some(Number::from(255)), Number::parse("ff", 16));
+        assert_eq!(Some(Number::from(16)), Number::parse("#x10", 2));
+        assert_eq!(Some(Number::from(255)), Number::parse("#e#xff", 10));
+        assert_eq!(None, Number::parse("12", 2));
+        assert_eq!(None, Number::parse("1 2", 10));
+        assert_eq!(None, Number::parse(" 1", 10));
+        assert_eq!(None, Number::parse("abc", 10));
+    }
+
+    #[test]
+    fn write_radix() {
+        assert_eq!(Ok("-ff".to_string()), Number::from(-255).to_string_radix(16));
+        assert_eq!(Ok("101/11".to_string()), read("5/3").to_string_radix(2));
+        assert_eq!(Err(NumberError::InexactRadix), Number::from(1.5).to_string_radix(2));
+        assert_eq!(Err(NumberError::InvalidRadix(3)), Number::from(1).to_string_radix(3));
+    }
+
+    #[test]
+    fn written_floats_read_back() {
+        for float in [0.1, 1e21, 1e-7, -0.0, 123.0, f64::MAX, 5e-324] {
+            let number = Number::from(float);
+            assert_eq!(Some(number.clone()), Number::parse(&number.to_string(), 10), "{float}");
+        }
+    }
+}
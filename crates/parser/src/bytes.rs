@@ -1,7 +1,62 @@
-/// EBNF-ish: `<Byte> = <any exact integer between 0 and 255>`
-// TODO: parse inner bytes as Number and make sure that it can be reduced to an u8, checking that
-// it is not inexact  too. for example, 4/2 is a valid byte, but not 4.0/2
-pub struct Byte(u8);
+use pluine_lex::span::{Span, Spanned};
+use pluine_number::{Number, Real};
 
-/// EBNF: `#u8(<Byte>)`
-pub struct ByteVector(Vec<Byte>);
+use crate::*;
+
+/// Bytevector element, read from any exact integer literal between 0 and 255.
+///
+/// `4/2` and `#xFF` are for example valid bytes, whilst `2.0` is inexact, `1/2` is not an
+/// integer and `256` is out of bounds.
+#[derive(Debug, PartialEq)]
+pub struct Byte {
+    pub(crate) value: u8,
+    pub(crate) span: Span,
+}
+
+impl Byte {
+    /// Validates a bytevector element.
+    pub(crate) fn from_datum(datum: &Datum) -> Result<Self, ReadError> {
+        let Datum::Simple(SimpleDatum::Number(number)) = datum else {
+            return Err(ReadError::ExpectedByte(datum.span()));
+        };
+
+        let span = number.span();
+
+        match Number::try_from(number).map_err(|error| ReadError::InvalidByte(error, span))? {
+            Number::Real(Real::Integer(integer)) => match u8::try_from(&integer) {
+                Ok(value) => Ok(Self { value, span }),
+                Err(_) => Err(ReadError::ByteOutOfRange(span)),
+            },
+            _ => Err(ReadError::ByteNotExactInteger(span)),
+        }
+    }
+
+    /// Value of the byte
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// Span of the number literal
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// EBNF: `#u8( <Byte>* )`
+#[derive(Debug, PartialEq)]
+pub struct ByteVector {
+    pub(crate) bytes: Vec<Byte>,
+    pub(crate) span: Span,
+}
+
+impl ByteVector {
+    /// Bytevector elements
+    pub fn bytes(&self) -> &[Byte] {
+        &self.bytes
+    }
+
+    /// Values of the bytevector elements
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.bytes.iter().map(Byte::value).collect()
+    }
+}
@@ -0,0 +1,182 @@
+use std::borrow::Cow;
+
+use pluine_lex::{
+    span::{Span, Spanned},
+    Boolean, CharacterLiteral, NumberLiteral, StringLiteral,
+};
+
+use crate::*;
+
+/// External representation of a Scheme value, as returned by `read`.
+///
+/// EBNF: `<SimpleDatum> | <CompoundDatum>`
+#[derive(Debug, PartialEq)]
+pub enum Datum<'src> {
+    /// See [`SimpleDatum`]
+    Simple(SimpleDatum<'src>),
+    /// See [`CompoundDatum`]
+    Compound(CompoundDatum<'src>),
+    /// See [`Labeled`]
+    Labeled(Labeled<'src>),
+    /// See [`Reference`]
+    Reference(Reference),
+}
+
+impl Datum<'_> {
+    /// Source region of the datum, abbreviation prefixes included.
+    pub fn span(&self) -> Span {
+        match self {
+            Datum::Simple(simple_datum) => simple_datum.span(),
+            Datum::Compound(compound_datum) => compound_datum.span(),
+            Datum::Labeled(labeled) => labeled.span,
+            Datum::Reference(reference) => reference.span,
+        }
+    }
+}
+
+/// Datum which may be referred to by a [`Reference`] within the same top-level datum.
+///
+/// EBNF: `# <DecimalDigit>+ = <Datum>`
+#[derive(Debug, PartialEq)]
+pub struct Labeled<'src> {
+    pub(crate) label: u64,
+    pub(crate) datum: Box<Datum<'src>>,
+    pub(crate) span: Span,
+}
+
+impl<'src> Labeled<'src> {
+    /// Label number
+    pub fn label(&self) -> u64 {
+        self.label
+    }
+
+    /// Labeled datum, never a [`Reference`] to the label itself
+    pub fn datum(&self) -> &Datum<'src> {
+        &self.datum
+    }
+}
+
+/// Stands for the [`Labeled`] datum with the same label, see [`DatumGraph`](crate::DatumGraph)
+/// for resolving references.
+///
+/// Always preceded by its definition, possibly an enclosing one, which makes the datum cyclic.
+///
+/// EBNF: `# <DecimalDigit>+ #`
+#[derive(Debug, PartialEq)]
+pub struct Reference {
+    pub(crate) label: u64,
+    pub(crate) span: Span,
+}
+
+impl Reference {
+    /// Label number
+    pub fn label(&self) -> u64 {
+        self.label
+    }
+}
+
+/// EBNF: `<Boolean> | <Number> | <Character> | <String> | <Symbol> | <ByteVector>`
+#[derive(Debug, PartialEq)]
+pub enum SimpleDatum<'src> {
+    /// `#t` or `#false`
+    Boolean(Boolean),
+    /// `42`, `#x-1/2`, `+inf.0`...
+    Number(NumberLiteral),
+    /// `#\a`, `#\space`, `#\x3BB`...
+    Character(CharacterLiteral),
+    /// `"abc"`
+    String(StringLiteral<'src>),
+    /// See [`Symbol`]
+    Symbol(Symbol<'src>),
+    /// See [`ByteVector`]
+    ByteVector(ByteVector),
+}
+
+impl SimpleDatum<'_> {
+    /// Span of the literal
+    pub fn span(&self) -> Span {
+        match self {
+            SimpleDatum::Boolean(boolean) => boolean.span(),
+            SimpleDatum::Number(number) => number.span(),
+            SimpleDatum::Character(character) => character.span(),
+            SimpleDatum::String(string) => string.span(),
+            SimpleDatum::Symbol(symbol) => symbol.span,
+            SimpleDatum::ByteVector(byte_vector) => byte_vector.span,
+        }
+    }
+}
+
+/// Identifier read as a datum.
+///
+/// Symbols introduced by expanding an abbreviation, such as `quote` in `'a`, span the
+/// abbreviation prefix.
+#[derive(Debug, PartialEq)]
+pub struct Symbol<'src> {
+    pub(crate) name: Cow<'src, str>,
+    pub(crate) span: Span,
+}
+
+impl<'src> Symbol<'src> {
+    /// See [`pluine_lex::Identifier::name`]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// EBNF: `<List> | <Vector>`
+#[derive(Debug, PartialEq)]
+pub enum CompoundDatum<'src> {
+    /// See [`List`]
+    List(List<'src>),
+    /// See [`Vector`]
+    Vector(Vector<'src>),
+}
+
+impl CompoundDatum<'_> {
+    /// Span from the opening to the closing parenthesis, abbreviation prefixes included.
+    pub fn span(&self) -> Span {
+        match self {
+            CompoundDatum::List(list) => list.span,
+            CompoundDatum::Vector(vector) => vector.span,
+        }
+    }
+}
+
+/// Proper or dotted list.
+///
+/// EBNF: `( <Datum>* ) | ( <Datum>+ . <Datum> ) | <Abbreviation>`
+///
+/// Abbreviations are expanded into two element lists, `'a` being read as `(quote a)`. The same
+/// applies to `` ` ``, `,` and `,@` with `quasiquote`, `unquote` and `unquote-splicing`.
+#[derive(Debug, PartialEq)]
+pub struct List<'src> {
+    pub(crate) elements: Vec<Datum<'src>>,
+    pub(crate) tail: Option<Box<Datum<'src>>>,
+    pub(crate) span: Span,
+}
+
+impl<'src> List<'src> {
+    /// Elements preceding the dot, if any. Empty for `()`.
+    pub fn elements(&self) -> &[Datum<'src>] {
+        &self.elements
+    }
+
+    /// Datum following the dot, `None` for proper lists.
+    pub fn tail(&self) -> Option<&Datum<'src>> {
+        self.tail.as_deref()
+    }
+}
+
+/// EBNF: `#( <Datum>* )`
+#[derive(Debug, PartialEq)]
+pub struct Vector<'src> {
+    pub(crate) elements: Vec<Datum<'src>>,
+    pub(crate) span: Span,
+}
+
+impl<'src> Vector<'src> {
+    /// Vector elements
+    pub fn elements(&self) -> &[Datum<'src>] {
+        &self.elements
+    }
+}
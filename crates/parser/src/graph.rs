@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use pluine_lex::span::Span;
+
+use crate::*;
+
+/// Index of a [`Node`] within its [`DatumGraph`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct NodeId(pub(crate) usize);
+
+/// Node of a [`DatumGraph`], compound nodes refer to their elements by [`NodeId`].
+#[derive(Debug, PartialEq)]
+pub enum Node<'src> {
+    /// See [`SimpleDatum`]
+    Simple(SimpleDatum<'src>),
+    /// See [`List`]
+    List {
+        /// Elements preceding the dot, if any
+        elements: Vec<NodeId>,
+        /// Datum following the dot, `None` for proper lists
+        tail: Option<NodeId>,
+    },
+    /// See [`Vector`]
+    Vector {
+        /// Vector elements
+        elements: Vec<NodeId>,
+    },
+}
+
+/// Datum with its datum labels resolved, shared and cyclic structures included.
+///
+/// Each [`Reference`] is replaced by the node of its [`Labeled`] datum:
+///
+/// ```
+/// # use pluine_parser::{DatumGraph, Node, Reader};
+/// let src = "#0=(a . #0#)";
+/// let datum = Reader::read_all(src).unwrap().remove(0);
+/// let graph = DatumGraph::new(src, datum);
+///
+/// let Node::List { tail: Some(tail), .. } = graph.node(graph.root()) else {
+///     panic!("expected a dotted list");
+/// };
+/// assert_eq!(graph.root(), *tail);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct DatumGraph<'src> {
+    src: &'src str,
+    nodes: Vec<Node<'src>>,
+    /// Span of each node, indexed by `NodeId`
+    spans: Vec<Span>,
+    root: NodeId,
+}
+
+/// Graph node under construction
+enum Slot<'src> {
+    Node(Node<'src>),
+    /// Compound datum, elements not yet added
+    Pending,
+    Reference(u64),
+}
+
+impl<'src> DatumGraph<'src> {
+    /// Resolves the labels of a datum read from `src`.
+    ///
+    /// `src` is kept for writing number literals as they were written.
+    pub fn new(src: &'src str, datum: Datum<'src>) -> Self {
+        let mut builder = GraphBuilder {
+            slots: Vec::new(),
+            spans: Vec::new(),
+            labels: HashMap::new(),
+            pending: Vec::new(),
+        };
+
+        let root = builder.reserve(datum);
+
+        // Explicit stack rather than recursion, deeply nested data is common in generated code.
+        while let Some((id, datum)) = builder.pending.pop() {
+            let node = match datum {
+                Datum::Compound(CompoundDatum::List(list)) => Node::List {
+                    elements: list.elements.into_iter().map(|element| builder.reserve(element)).collect(),
+                    tail: list.tail.map(|tail| builder.reserve(*tail)),
+                },
+                Datum::Compound(CompoundDatum::Vector(vector)) => Node::Vector {
+                    elements: vector.elements.into_iter().map(|element| builder.reserve(element)).collect(),
+                },
+                _ => unreachable!("only compound data are pending"),
+            };
+
+            builder.slots[id.0] = Slot::Node(node);
+        }
+
+        builder.finish(src, root)
+    }
+
+    /// Node of the outermost datum
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    /// See [`Node`]
+    pub fn node(&self, id: NodeId) -> &Node<'src> {
+        &self.nodes[id.0]
+    }
+
+    /// Source region of the node's datum, label definitions excluded.
+    pub fn span(&self, id: NodeId) -> Span {
+        self.spans[id.0]
+    }
+
+    pub(crate) fn src(&self) -> &'src str {
+        self.src
+    }
+
+    /// Compound node elements, tail last.
+    pub fn children(&self, id: NodeId) -> impl DoubleEndedIterator<Item = NodeId> + '_ {
+        let (elements, tail): (&[NodeId], Option<NodeId>) = match self.node(id) {
+            Node::Simple(_) => (&[], None),
+            Node::List { elements, tail } => (elements, *tail),
+            Node::Vector { elements } => (elements, None),
+        };
+
+        elements.iter().copied().chain(tail)
+    }
+}
+
+struct GraphBuilder<'src> {
+    slots: Vec<Slot<'src>>,
+    spans: Vec<Span>,
+    labels: HashMap<u64, NodeId>,
+    /// Compound data whose elements are yet to be reserved
+    pending: Vec<(NodeId, Datum<'src>)>,
+}
+
+impl<'src> GraphBuilder<'src> {
+    /// Allocates the slot of a datum, registering the labels it is defined by.
+    fn reserve(&mut self, mut datum: Datum<'src>) -> NodeId {
+        let mut labels = Vec::new();
+
+        while let Datum::Labeled(labeled) = datum {
+            labels.push(labeled.label);
+            datum = *labeled.datum;
+        }
+
+        let id = NodeId(self.slots.len());
+        self.spans.push(datum.span());
+
+        let slot = match datum {
+            Datum::Simple(simple_datum) => Slot::Node(Node::Simple(simple_datum)),
+            Datum::Reference(reference) => Slot::Reference(reference.label),
+            compound_datum => {
+                self.pending.push((id, compound_datum));
+                Slot::Pending
+            }
+        };
+        self.slots.push(slot);
+
+        for label in labels {
+            self.labels.insert(label, id);
+        }
+
+        id
+    }
+
+    /// Removes reference slots, pointing their users to the labeled nodes instead.
+    fn finish(self, src: &'src str, root: NodeId) -> DatumGraph<'src> {
+        // Final node index of each non-reference slot
+        let mut node_indexes = Vec::with_capacity(self.slots.len());
+        let mut node_count = 0;
+
+        for slot in &self.slots {
+            node_indexes.push(node_count);
+            if !matches!(slot, Slot::Reference(_)) {
+                node_count += 1;
+            }
+        }
+
+        // Final node of each slot, references following their labels
+        let targets: Vec<NodeId> = (0..self.slots.len())
+            .map(|mut index| {
+                // references to references are followed, the reader rejects circular ones
+                while let Slot::Reference(label) = self.slots[index] {
+                    index = self.labels[&label].0;
+                }
+
+                NodeId(node_indexes[index])
+            })
+            .collect();
+        let resolve = |id: NodeId| targets[id.0];
+
+        let mut nodes = Vec::with_capacity(node_count);
+        let mut spans = Vec::with_capacity(node_count);
+
+        for (slot, span) in self.slots.into_iter().zip(self.spans) {
+            let node = match slot {
+                Slot::Reference(_) => continue,
+                Slot::Pending => unreachable!("all pending data are built"),
+                Slot::Node(Node::Simple(simple_datum)) => Node::Simple(simple_datum),
+                Slot::Node(Node::List { elements, tail }) => Node::List {
+                    elements: elements.into_iter().map(resolve).collect(),
+                    tail: tail.map(resolve),
+                },
+                Slot::Node(Node::Vector { elements }) => Node::Vector { elements: elements.into_iter().map(resolve).collect() },
+            };
+
+            nodes.push(node);
+            spans.push(span);
+        }
+
+        DatumGraph { src, nodes, spans, root: resolve(root) }
+    }
+}
@@ -1,27 +1,20 @@
 //! Pluine Parser.
+//!
+//! Reads [`Datum`]s from source code using the [`Reader`], the first step preceding macro
+//! expansion and evaluation. Datum labels are resolved by [`DatumGraph`], which also writes data
+//! back as do the R7RS `write` procedures. Errors implement
+//! [`ToDiagnostic`](pluine_lex::diagnostic::ToDiagnostic) for reporting.
 
 mod bytes;
-pub(crate) use bytes::{Byte, ByteVector};
+pub use bytes::{Byte, ByteVector};
 
-mod datum {
-    use crate::*;
+mod datum;
+pub use datum::{CompoundDatum, Datum, Labeled, List, Reference, SimpleDatum, Symbol, Vector};
 
-    pub enum Datum {
-        Simple(),
-        Compound(),
-        // /// EBNF: `# <DecimalDigit>+ = <Datum>`
-        // Labeled(NonEmptyVec<Datum>, Box<Datum>),
-        // /// EBNF: `# <DecimalDigit>+ #`
-        // Reference(NonEmptyVec<DecimalDigit>),
-    }
+mod graph;
+pub use graph::{DatumGraph, Node, NodeId};
 
-    pub enum SimpleDatum {
-        Boolean,
-        Number,
-        Character,
-        String,
-        Identifier,
-        ByteVector,
-    }
-}
-pub(crate) use datum::Datum;
+mod reader;
+pub use reader::{ReadError, Reader};
+
+mod write;
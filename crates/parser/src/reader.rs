@@ -0,0 +1,611 @@
+use std::borrow::Cow;
+
+use pluine_lex::{
+    diagnostic::{Diagnostic, ToDiagnostic},
+    span::{Span, Spanned},
+    Atmosphere, Comment, DatumLabelVariant, Lexer, Token, TokenAll, TokenCharVariant, TokenizeError,
+};
+use pluine_number::NumberError;
+use thiserror::Error;
+
+use crate::*;
+
+/// Reads [`Datum`]s from a source string, as does the R7RS `read` procedure.
+///
+/// ```
+/// # use pluine_parser::{CompoundDatum, Datum, Reader};
+/// let mut reader = Reader::new("'(a . b) #;(ignored) #(1 2)");
+///
+/// let Some(Ok(Datum::Compound(CompoundDatum::List(quotation)))) = reader.next() else {
+///     panic!("expected a list");
+/// };
+/// assert_eq!(2, quotation.elements().len());
+///
+/// assert!(matches!(
+///     reader.next(),
+///     Some(Ok(Datum::Compound(CompoundDatum::Vector(_))))
+/// ));
+/// assert!(reader.next().is_none());
+/// ```
+///
+/// Reading stops after the first error, all subsequent calls to `next` return `None`.
+pub struct Reader<'src> {
+    lexer: Lexer<'src>,
+    /// Enclosing data of the datum currently being read, avoids recursion.
+    frames: Vec<Frame<'src>>,
+    /// Labels defined within the current top-level datum, and whether their datum was read
+    labels: Vec<(u64, bool)>,
+    finished: bool,
+}
+
+/// Datum being read, awaiting its remaining elements.
+enum Frame<'src> {
+    List {
+        open: Span,
+        elements: Vec<Datum<'src>>,
+        /// Span of the `.`, if encountered
+        dot: Option<Span>,
+        tail: Option<Box<Datum<'src>>>,
+    },
+    Vector {
+        open: Span,
+        elements: Vec<Datum<'src>>,
+    },
+    ByteVector {
+        open: Span,
+        bytes: Vec<Byte>,
+    },
+    Abbreviation {
+        prefix: Span,
+        symbol: &'static str,
+    },
+    Label {
+        prefix: Span,
+        label: u64,
+    },
+    DatumComment(Span),
+}
+
+impl<'src> Reader<'src> {
+    /// Reader starting at the beginning of `src`
+    pub fn new(src: &'src str) -> Self {
+        Self::from_lexer(Lexer::new(src))
+    }
+
+    /// Reader of the tokens of `lexer`, such as one resuming at a given offset.
+    pub fn from_lexer(lexer: Lexer<'src>) -> Self {
+        Self { lexer, frames: Vec::new(), labels: Vec::new(), finished: false }
+    }
+
+    /// Underlying lexer, positioned right after the last datum read.
+    pub fn lexer(&self) -> &Lexer<'src> {
+        &self.lexer
+    }
+
+    /// Reads every datum in `src`, stopping at the first error.
+    pub fn read_all(src: &'src str) -> Result<Vec<Datum<'src>>, ReadError> {
+        Self::new(src).collect()
+    }
+
+    /// Reads the next top-level datum, `None` once the end of the source is reached.
+    pub fn read(&mut self) -> Option<Result<Datum<'src>, ReadError>> {
+        if self.finished {
+            return None;
+        }
+
+        let result = self.read_datum().transpose();
+
+        if !matches!(result, Some(Ok(_))) {
+            self.finished = true;
+        }
+
+        result
+    }
+
+    fn read_datum(&mut self) -> Result<Option<Datum<'src>>, ReadError> {
+        loop {
+            let Some(token) = self.lexer.next_token().transpose()? else {
+                return match self.frames.last() {
+                    None => Ok(None),
+                    Some(Frame::List { open, .. } | Frame::Vector { open, .. } | Frame::ByteVector { open, .. }) => {
+                        Err(ReadError::UnclosedList(*open))
+                    }
+                    Some(Frame::Abbreviation { prefix, .. } | Frame::Label { prefix, .. } | Frame::DatumComment(prefix)) => {
+                        Err(ReadError::MissingDatum(*prefix))
+                    }
+                };
+            };
+
+            let datum = match token {
+                TokenAll::InterToken(Atmosphere::Comment(Comment::Section(section_comment))) => {
+                    self.frames.push(Frame::DatumComment(section_comment.span()));
+                    continue;
+                }
+                TokenAll::InterToken(_) | TokenAll::Unknown(_) => continue,
+                TokenAll::Token(Token::Boolean(boolean)) => Datum::Simple(SimpleDatum::Boolean(boolean)),
+                TokenAll::Token(Token::Number(number)) => Datum::Simple(SimpleDatum::Number(number)),
+                TokenAll::Token(Token::Character(character)) => Datum::Simple(SimpleDatum::Character(character)),
+                TokenAll::Token(Token::String(string)) => Datum::Simple(SimpleDatum::String(string)),
+                TokenAll::Token(Token::Identifier(identifier)) => {
+                    Datum::Simple(SimpleDatum::Symbol(Symbol { name: identifier.name(), span: identifier.span() }))
+                }
+                TokenAll::Token(Token::Label(label)) => match label.variant() {
+                    DatumLabelVariant::Definition => {
+                        self.define_label(label.label(), label.span())?;
+                        continue;
+                    }
+                    DatumLabelVariant::Reference => {
+                        if !self.labels.iter().any(|(defined, _)| *defined == label.label()) {
+                            return Err(ReadError::UndefinedLabel(label.span()));
+                        }
+
+                        Datum::Reference(Reference { label: label.label(), span: label.span() })
+                    }
+                },
+                TokenAll::Token(Token::Other(token_char)) if token_char.variant() == TokenCharVariant::CloseParenthesis => {
+                    self.close(token_char.span())?
+                }
+                TokenAll::Token(Token::Other(token_char)) => {
+                    let span = token_char.span();
+
+                    let frame = match token_char.variant() {
+                        TokenCharVariant::OpenParenthesis => Frame::List { open: span, elements: Vec::new(), dot: None, tail: None },
+                        TokenCharVariant::PoundOpenParenthesis => Frame::Vector { open: span, elements: Vec::new() },
+                        TokenCharVariant::PoundU8OpenParenthesis => Frame::ByteVector { open: span, bytes: Vec::new() },
+                        TokenCharVariant::Apostophe => Frame::Abbreviation { prefix: span, symbol: "quote" },
+                        TokenCharVariant::GraveAccent => Frame::Abbreviation { prefix: span, symbol: "quasiquote" },
+                        TokenCharVariant::Comma => Frame::Abbreviation { prefix: span, symbol: "unquote" },
+                        TokenCharVariant::CommaAt => Frame::Abbreviation { prefix: span, symbol: "unquote-splicing" },
+                        TokenCharVariant::Dot => {
+                            self.read_dot(span)?;
+                            continue;
+                        }
+                        TokenCharVariant::CloseParenthesis => unreachable!("matched by the previous arm"),
+                    };
+
+                    self.frames.push(frame);
+                    continue;
+                }
+            };
+
+            if let Some(datum) = self.complete(datum)? {
+                return Ok(Some(datum));
+            }
+        }
+    }
+
+    /// Labels may only be defined once per top-level datum.
+    fn define_label(&mut self, label: u64, prefix: Span) -> Result<(), ReadError> {
+        if self.labels.iter().any(|(defined, _)| *defined == label) {
+            return Err(ReadError::DuplicateLabel(prefix));
+        }
+
+        self.labels.push((label, false));
+        self.frames.push(Frame::Label { prefix, label });
+
+        Ok(())
+    }
+
+    /// Dots are only valid within lists, after at least one element and at most once.
+    fn read_dot(&mut self, span: Span) -> Result<(), ReadError> {
+        match self.frames.last_mut() {
+            Some(Frame::List { elements, dot: dot @ None, .. }) if !elements.is_empty() => {
+                *dot = Some(span);
+                Ok(())
+            }
+            _ => Err(ReadError::UnexpectedDot(span)),
+        }
+    }
+
+    /// Pops the innermost list, vector or bytevector, returning it as a datum.
+    fn close(&mut self, close: Span) -> Result<Datum<'src>, ReadError> {
+        match self.frames.pop() {
+            Some(Frame::List { dot: Some(dot), tail: None, .. }) => Err(ReadError::MissingDatum(dot)),
+            Some(Frame::List { open, elements, tail, .. }) => {
+                let list = List { elements, tail, span: open.join(close) };
+                Ok(Datum::Compound(CompoundDatum::List(list)))
+            }
+            Some(Frame::Vector { open, elements }) => {
+                let vector = Vector { elements, span: open.join(close) };
+                Ok(Datum::Compound(CompoundDatum::Vector(vector)))
+            }
+            Some(Frame::ByteVector { open, bytes }) => {
+                let byte_vector = ByteVector { bytes, span: open.join(close) };
+                Ok(Datum::Simple(SimpleDatum::ByteVector(byte_vector)))
+            }
+            Some(Frame::Abbreviation { prefix, .. } | Frame::Label { prefix, .. } | Frame::DatumComment(prefix)) => {
+                Err(ReadError::MissingDatum(prefix))
+            }
+            None => Err(ReadError::UnexpectedCloseParenthesis(close)),
+        }
+    }
+
+    /// Adds a datum to its enclosing frame, returning it if it was a top-level datum.
+    fn complete(&mut self, mut datum: Datum<'src>) -> Result<Option<Datum<'src>>, ReadError> {
+        loop {
+            match self.frames.last_mut() {
+                None => {
+                    self.labels.clear();
+                    return Ok(Some(datum));
+                }
+                Some(Frame::List { dot: Some(_), tail: Some(_), .. }) => return Err(ReadError::ExpectedCloseParenthesis(datum.span())),
+                Some(Frame::List { dot: Some(_), tail, .. }) => *tail = Some(Box::new(datum)),
+                Some(Frame::List { elements, .. } | Frame::Vector { elements, .. }) => elements.push(datum),
+                Some(Frame::ByteVector { bytes, .. }) => bytes.push(Byte::from_datum(&datum)?),
+                Some(Frame::Abbreviation { prefix, symbol }) => {
+                    let span = prefix.join(datum.span());
+                    let symbol = Symbol { name: Cow::Borrowed(*symbol), span: *prefix };
+
+                    self.frames.pop();
+                    datum = Datum::Compound(CompoundDatum::List(List {
+                        elements: vec![Datum::Simple(SimpleDatum::Symbol(symbol)), datum],
+                        tail: None,
+                        span,
+                    }));
+                    continue;
+                }
+                Some(Frame::Label { prefix, label }) => {
+                    let label = *label;
+                    let labels = &mut self.labels;
+
+                    if let Datum::Reference(reference) = &datum {
+                        if labels.iter().any(|(defined, read)| *defined == reference.label && !read) {
+                            return Err(ReadError::CircularLabel(reference.span));
+                        }
+                    }
+
+                    if let Some((_, read)) = labels.iter_mut().find(|(defined, _)| *defined == label) {
+                        *read = true;
+                    }
+
+                    let span = prefix.join(datum.span());
+                    self.frames.pop();
+                    datum = Datum::Labeled(Labeled { label, datum: Box::new(datum), span });
+                    continue;
+                }
+                Some(Frame::DatumComment(_)) => {
+                    self.frames.pop();
+
+                    // labels are scoped to the outermost datum, commented out or not
+                    if self.frames.is_empty() {
+                        self.labels.clear();
+                    }
+                }
+            }
+
+            return Ok(None);
+        }
+    }
+}
+
+impl<'src> Iterator for Reader<'src> {
+    type Item = Result<Datum<'src>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read()
+    }
+}
+
+/// Error returned by the [`Reader`]
+#[derive(Debug, PartialEq, Error)]
+pub enum ReadError {
+    /// Lexer error
+    #[error(transparent)]
+    Tokenize(#[from] TokenizeError),
+    /// Inner span points to the opening parenthesis of the innermost unclosed list, vector or
+    /// bytevector
+    #[error("end of file reached, no closing ')' found")]
+    UnclosedList(Span),
+    /// Inner span points to the closing parenthesis
+    #[error("unexpected ')', no list or vector to close")]
+    UnexpectedCloseParenthesis(Span),
+    /// Inner span points to the dot
+    #[error("unexpected '.', dots may only precede the last element of a non-empty list")]
+    UnexpectedDot(Span),
+    /// Inner span points to the abbreviation prefix, datum comment or dot
+    #[error("expected a datum")]
+    MissingDatum(Span),
+    /// Inner span points to the second datum following a dot
+    #[error("expected ')', a dot must be followed by exactly one datum")]
+    ExpectedCloseParenthesis(Span),
+    /// Inner span points to the reference
+    #[error("reference to a label not previously defined within the same top-level datum")]
+    UndefinedLabel(Span),
+    /// Inner span points to the second definition
+    #[error("label already defined within the same top-level datum")]
+    DuplicateLabel(Span),
+    /// Inner span points to the reference, such as `#0#` in `#0=#0#`
+    #[error("label refers to itself without an enclosing list or vector")]
+    CircularLabel(Span),
+    /// Inner span points to the bytevector element
+    #[error("expected a byte, bytevectors may only contain number literals")]
+    ExpectedByte(Span),
+    /// Inner span points to the number literal
+    #[error("bytevector element is not an exact integer")]
+    ByteNotExactInteger(Span),
+    /// Inner span points to the number literal
+    #[error("bytevector element out of range, bytes must be integers between 0 and 255")]
+    ByteOutOfRange(Span),
+    /// Inner span points to the number literal, such as `#e+inf.0` which has no exact value
+    #[error("invalid bytevector element, {0}")]
+    InvalidByte(NumberError, Span),
+}
+
+/// Error codes:
+///
+/// | Code  | Error                                    |
+/// |-------|------------------------------------------|
+/// | P0101 | `ReadError::UnclosedList`                |
+/// | P0102 | `ReadError::UnexpectedCloseParenthesis`  |
+/// | P0103 | `ReadError::UnexpectedDot`               |
+/// | P0104 | `ReadError::MissingDatum`                |
+/// | P0105 | `ReadError::ExpectedCloseParenthesis`    |
+/// | P0201 | `ReadError::UndefinedLabel`              |
+/// | P0202 | `ReadError::DuplicateLabel`              |
+/// | P0203 | `ReadError::CircularLabel`               |
+/// | P0301 | `ReadError::ExpectedByte`                |
+/// | P0302 | `ReadError::ByteNotExactInteger`         |
+/// | P0303 | `ReadError::ByteOutOfRange`              |
+/// | P0304 | `ReadError::InvalidByte`                 |
+///
+/// Lexer errors keep their `L` codes.
+impl ToDiagnostic for ReadError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, span, label) = match self {
+            ReadError::Tokenize(error) => return error.to_diagnostic(),
+            ReadError::UnclosedList(span) => ("P0101", span, "unclosed list"),
+            ReadError::UnexpectedCloseParenthesis(span) => ("P0102", span, "unmatched ')'"),
+            ReadError::UnexpectedDot(span) => ("P0103", span, "unexpected '.'"),
+            ReadError::MissingDatum(span) => ("P0104", span, "expected a datum after this"),
+            ReadError::ExpectedCloseParenthesis(span) => ("P0105", span, "expected ')'"),
+            ReadError::UndefinedLabel(span) => ("P0201", span, "undefined label"),
+            ReadError::DuplicateLabel(span) => ("P0202", span, "label redefined"),
+            ReadError::CircularLabel(span) => ("P0203", span, "circular reference"),
+            ReadError::ExpectedByte(span) => ("P0301", span, "not a number"),
+            ReadError::ByteNotExactInteger(span) => ("P0302", span, "not an exact integer"),
+            ReadError::ByteOutOfRange(span) => ("P0303", span, "not an integer between 0 and 255"),
+            ReadError::InvalidByte(_, span) => ("P0304", span, "invalid number"),
+        };
+
+        Diagnostic::error(code, self.to_string()).with_primary_label(*span, Some(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `datum` back as an S-expression, literals as written in `src`.
+    fn write(src: &str, datum: &Datum) -> String {
+        let write_elements = |elements: &[Datum]| elements.iter().map(|element| write(src, element)).collect::<Vec<_>>().join(" ");
+
+        match datum {
+            Datum::Simple(SimpleDatum::Symbol(symbol)) => symbol.name().to_string(),
+            Datum::Simple(simple_datum) => src[simple_datum.span().start()..simple_datum.span().end()].to_string(),
+            Datum::Compound(CompoundDatum::List(list)) => match list.tail() {
+                Some(tail) => format!("({} . {})", write_elements(list.elements()), write(src, tail)),
+                None => format!("({})", write_elements(list.elements())),
+            },
+            Datum::Compound(CompoundDatum::Vector(vector)) => format!("#({})", write_elements(vector.elements())),
+            Datum::Labeled(labeled) => format!("#{}={}", labeled.label(), write(src, labeled.datum())),
+            Datum::Reference(reference) => format!("#{}#", reference.label()),
+        }
+    }
+
+    fn read_all(src: &str) -> Vec<String> {
+        Reader::read_all(src).unwrap().iter().map(|datum| write(src, datum)).collect()
+    }
+
+    fn read_error(src: &str) -> ReadError {
+        Reader::read_all(src).unwrap_err()
+    }
+
+    fn span(start: usize, end: usize) -> Span {
+        // spans can only be created by the lexer, a single datum of the expected range is read
+        let src = format!("{}{}", " ".repeat(start), "a".repeat(end - start));
+        Reader::read_all(&src).unwrap()[0].span()
+    }
+
+    #[test]
+    fn simple_data() {
+        assert_eq!(
+            vec!["#t", "-1/2", "#\\x", "\"a b\"", "abc", "a b"],
+            read_all("#t -1/2 #\\x \"a b\" abc |a b|")
+        );
+    }
+
+    #[test]
+    fn simple_data_values() {
+        let data = Reader::read_all("#false \"a\\tb\"").unwrap();
+        let [Datum::Simple(SimpleDatum::Boolean(boolean)), Datum::Simple(SimpleDatum::String(string))] = data.as_slice() else {
+            panic!("expected a boolean and a string");
+        };
+
+        assert!(!boolean.value());
+        assert_eq!("a\tb", string.value());
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            vec!["()", "(a (b c) ())", "(a . b)", "(a b . (c))"],
+            read_all("() (a (b c) ()) (a . b) (a b . (c))")
+        );
+    }
+
+    #[test]
+    fn vectors() {
+        assert_eq!(vec!["#()", "#(1 #(2) (3))"], read_all("#() #(1 #(2) (3))"));
+    }
+
+    #[test]
+    fn bytevectors() {
+        let src = "#u8() #u8(0 4/2 #xFF #e1.0 #;x 255)";
+        assert_eq!(vec!["#u8()", "#u8(0 4/2 #xFF #e1.0 #;x 255)"], read_all(src));
+
+        let data = Reader::read_all(src).unwrap();
+        let [_, Datum::Simple(SimpleDatum::ByteVector(byte_vector))] = data.as_slice() else {
+            panic!("expected two bytevectors");
+        };
+        assert_eq!(vec![0, 2, 255, 1, 255], byte_vector.to_vec());
+        assert_eq!(span(12, 15), byte_vector.bytes()[1].span());
+    }
+
+    #[test]
+    fn abbreviations() {
+        assert_eq!(
+            vec![
+                "(quote a)",
+                "(quasiquote (a (unquote b) (unquote-splicing c)))",
+                "(quote (quote a))"
+            ],
+            read_all("'a `(a ,b ,@c) ''a")
+        );
+    }
+
+    #[test]
+    fn abbreviation_spans() {
+        let src = "'(a)";
+        let data = Reader::read_all(src).unwrap();
+        let [Datum::Compound(CompoundDatum::List(quotation))] = data.as_slice() else {
+            panic!("expected a list");
+        };
+
+        assert_eq!(span(0, 4), quotation.span);
+        assert_eq!(span(0, 1), quotation.elements()[0].span());
+        assert_eq!(span(1, 4), quotation.elements()[1].span());
+    }
+
+    #[test]
+    fn atmosphere_is_skipped() {
+        assert_eq!(vec!["(a c)", "d"], read_all("; comment\n(a #| b |# #;(b) c) #;#;x y #!fold-case D"));
+    }
+
+    #[test]
+    fn datum_comment_at_end_of_list() {
+        assert_eq!(vec!["(a)"], read_all("(a #;b)"));
+    }
+
+    #[test]
+    fn deeply_nested() {
+        // NOTE: depth limited by the derived, recursive, `Drop` of the resulting datum
+        let src = format!("{}{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert_eq!(1, Reader::read_all(&src).unwrap().len());
+    }
+
+    #[test]
+    fn empty_source() {
+        assert!(read_all(" ; nothing").is_empty());
+    }
+
+    #[test]
+    fn unclosed_list_error() {
+        assert_eq!(ReadError::UnclosedList(span(3, 5)), read_error("(a #(b"));
+    }
+
+    #[test]
+    fn unexpected_close_parenthesis_error() {
+        assert_eq!(ReadError::UnexpectedCloseParenthesis(span(3, 4)), read_error("(a))"));
+    }
+
+    #[test]
+    fn unexpected_dot_error() {
+        assert_eq!(ReadError::UnexpectedDot(span(1, 2)), read_error("(. a)"));
+        assert_eq!(ReadError::UnexpectedDot(span(7, 8)), read_error("(a . b . c)"));
+        assert_eq!(ReadError::UnexpectedDot(span(4, 5)), read_error("#(a . b)"));
+        assert_eq!(ReadError::UnexpectedDot(span(0, 1)), read_error(". a"));
+    }
+
+    #[test]
+    fn missing_datum_error() {
+        assert_eq!(ReadError::MissingDatum(span(3, 4)), read_error("(a .)"));
+        assert_eq!(ReadError::MissingDatum(span(3, 4)), read_error("(a ')"));
+        assert_eq!(ReadError::MissingDatum(span(0, 2)), read_error("#;"));
+        assert_eq!(ReadError::MissingDatum(span(0, 2)), read_error(",@"));
+    }
+
+    #[test]
+    fn expected_close_parenthesis_error() {
+        assert_eq!(ReadError::ExpectedCloseParenthesis(span(7, 10)), read_error("(a . b (c))"));
+    }
+
+    #[test]
+    fn datum_labels() {
+        assert_eq!(
+            vec!["#0=(a . #0#)", "(#1=(x) #1# #2=#1#)"],
+            read_all("#0=(a . #0#) (#1=(x) #1# #2=#1#)")
+        );
+    }
+
+    #[test]
+    fn undefined_label_error() {
+        assert_eq!(ReadError::UndefinedLabel(span(0, 3)), read_error("#0#"));
+        assert_eq!(ReadError::UndefinedLabel(span(1, 4)), read_error("(#0# #0=a)"));
+        // labels are scoped to their top-level datum
+        assert_eq!(ReadError::UndefinedLabel(span(5, 8)), read_error("#0=a #0#"));
+        assert_eq!(ReadError::UndefinedLabel(span(9, 12)), read_error("#;#0=a b #0#"));
+    }
+
+    #[test]
+    fn duplicate_label_error() {
+        assert_eq!(ReadError::DuplicateLabel(span(6, 9)), read_error("(#0=a #0=b)"));
+    }
+
+    #[test]
+    fn circular_label_error() {
+        assert_eq!(ReadError::CircularLabel(span(3, 6)), read_error("#0=#0#"));
+        assert_eq!(ReadError::CircularLabel(span(6, 9)), read_error("#0=#1=#0#"));
+    }
+
+    #[test]
+    fn byte_errors() {
+        assert_eq!(ReadError::ExpectedByte(span(4, 5)), read_error("#u8(a)"));
+        assert_eq!(ReadError::ExpectedByte(span(6, 9)), read_error("#u8(1 (2))"));
+        assert_eq!(ReadError::ByteNotExactInteger(span(4, 7)), read_error("#u8(2.0)"));
+        assert_eq!(ReadError::ByteNotExactInteger(span(4, 7)), read_error("#u8(1.0)"));
+        assert_eq!(ReadError::ByteNotExactInteger(span(4, 7)), read_error("#u8(#i1)"));
+        assert_eq!(ReadError::ByteNotExactInteger(span(4, 7)), read_error("#u8(1/2)"));
+        assert_eq!(ReadError::ByteOutOfRange(span(4, 7)), read_error("#u8(256)"));
+        assert_eq!(ReadError::ByteOutOfRange(span(4, 6)), read_error("#u8(-1)"));
+        assert_eq!(
+            ReadError::InvalidByte(NumberError::DivisionByZero, span(4, 7)),
+            read_error("#u8(1/0)")
+        );
+        assert_eq!(
+            ReadError::InvalidByte(NumberError::NotRational, span(4, 12)),
+            read_error("#u8(#e+inf.0)")
+        );
+        assert_eq!(
+            ReadError::InvalidByte(NumberError::Overflow, span(4, 17)),
+            read_error("#u8(#e1e100000000)")
+        );
+        assert_eq!(ReadError::UnclosedList(span(0, 4)), read_error("#u8(1"));
+        assert_eq!(ReadError::UnexpectedDot(span(6, 7)), read_error("#u8(1 . 2)"));
+    }
+
+    #[test]
+    fn tokenize_error() {
+        assert!(matches!(
+            read_error("(a #z)"),
+            ReadError::Tokenize(TokenizeError::UnknownHashSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn reading_stops_after_error() {
+        let mut reader = Reader::new("a ) b");
+
+        assert!(matches!(reader.next(), Some(Ok(_))));
+        assert!(matches!(reader.next(), Some(Err(ReadError::UnexpectedCloseParenthesis(_)))));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn diagnostic_codes() {
+        assert_eq!("P0101", read_error("(").to_diagnostic().code);
+        assert_eq!("P0105", read_error("(a . b c)").to_diagnostic().code);
+        assert_eq!("P0201", read_error("#0#").to_diagnostic().code);
+        assert_eq!("P0303", read_error("#u8(256)").to_diagnostic().code);
+        assert_eq!("L0801", read_error("#z").to_diagnostic().code);
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use pluine_lex::{span::Spanned, Identifier, Lexer, Token, TokenAll};
+
+use crate::*;
+
+impl DatumGraph<'_> {
+    /// Writes the datum as does the R7RS `write` procedure, labeling only nodes which are part of
+    /// a cycle.
+    ///
+    /// ```
+    /// # use pluine_parser::{DatumGraph, Reader};
+    /// let src = "(#0=(x) . #1=(#0# . #1#))";
+    /// let graph = DatumGraph::new(src, Reader::read_all(src).unwrap().remove(0));
+    ///
+    /// assert_eq!("((x) . #0=((x) . #0#))", graph.write());
+    /// ```
+    pub fn write(&self) -> String {
+        self.write_labeled(&self.cyclic_nodes())
+    }
+
+    /// Writes the datum as does the R7RS `write-shared` procedure, labeling every compound node
+    /// reached more than once.
+    ///
+    /// Simple data are never labeled, they are written at each occurrence.
+    pub fn write_shared(&self) -> String {
+        self.write_labeled(&self.shared_nodes())
+    }
+
+    /// Writes the datum as does the R7RS `write-simple` procedure, without any label.
+    ///
+    /// Returns `None` for cyclic data, whose output would be infinite.
+    pub fn write_simple(&self) -> Option<String> {
+        self.cyclic_nodes().is_empty().then(|| self.write_labeled(&HashSet::new()))
+    }
+
+    /// Compound nodes reachable from themselves
+    fn cyclic_nodes(&self) -> HashSet<NodeId> {
+        enum Visit {
+            Enter(NodeId),
+            Exit(NodeId),
+        }
+
+        // Nodes absent are unvisited, `false` while on the current path, `true` once exited
+        let mut exited = HashMap::new();
+        let mut cyclic = HashSet::new();
+        let mut visits = vec![Visit::Enter(self.root())];
+
+        while let Some(visit) = visits.pop() {
+            match visit {
+                Visit::Enter(id) => match exited.get(&id) {
+                    None => {
+                        exited.insert(id, false);
+                        visits.push(Visit::Exit(id));
+                        visits.extend(self.children(id).rev().map(Visit::Enter));
+                    }
+                    Some(false) => {
+                        cyclic.insert(id);
+                    }
+                    Some(true) => (),
+                },
+                Visit::Exit(id) => {
+                    exited.insert(id, true);
+                }
+            }
+        }
+
+        cyclic
+    }
+
+    /// Compound nodes reachable through more than one path
+    fn shared_nodes(&self) -> HashSet<NodeId> {
+        let mut visited = HashSet::new();
+        let mut shared = HashSet::new();
+        let mut ids = vec![self.root()];
+
+        while let Some(id) = ids.pop() {
+            if !visited.insert(id) {
+                if !matches!(self.node(id), Node::Simple(_)) {
+                    shared.insert(id);
+                }
+                continue;
+            }
+
+            ids.extend(self.children(id).rev());
+        }
+
+        shared
+    }
+
+    /// `labeled` nodes are defined as `#n=` when first written, and referred to as `#n#`
+    /// afterwards. Labels are numbered in order of appearance.
+    fn write_labeled(&self, labeled: &HashSet<NodeId>) -> String {
+        enum Task {
+            Node(NodeId),
+            Text(&'static str),
+        }
+
+        let mut output = String::new();
+        let mut labels = HashMap::new();
+        let mut tasks = vec![Task::Node(self.root())];
+
+        while let Some(task) = tasks.pop() {
+            let id = match task {
+                Task::Node(id) => id,
+                Task::Text(text) => {
+                    output.push_str(text);
+                    continue;
+                }
+            };
+
+            if labeled.contains(&id) {
+                if let Some(label) = labels.get(&id) {
+                    write!(output, "#{label}#").expect("writing to a string never fails");
+                    continue;
+                }
+
+                let label = labels.len();
+                labels.insert(id, label);
+                write!(output, "#{label}=").expect("writing to a string never fails");
+            }
+
+            let (opening, elements, tail) = match self.node(id) {
+                Node::Simple(simple_datum) => {
+                    self.write_simple_datum(&mut output, simple_datum);
+                    continue;
+                }
+                Node::Vector { elements } => ("#(", elements.clone(), None),
+                Node::List { elements, tail } => {
+                    let mut elements = elements.clone();
+                    let mut tail = *tail;
+
+                    // `(a . (b . c))` is written `(a b . c)`, unless a label must be defined in between
+                    while let Some(Node::List { elements: tail_elements, tail: tail_tail }) =
+                        tail.filter(|tail| !labeled.contains(tail)).map(|tail| self.node(tail))
+                    {
+                        elements.extend(tail_elements);
+                        tail = *tail_tail;
+                    }
+
+                    ("(", elements, tail)
+                }
+            };
+
+            output.push_str(opening);
+            tasks.push(Task::Text(")"));
+
+            if let Some(tail) = tail {
+                tasks.push(Task::Node(tail));
+                tasks.push(Task::Text(" . "));
+            }
+
+            for (index, element) in elements.into_iter().enumerate().rev() {
+                tasks.push(Task::Node(element));
+                if index > 0 {
+                    tasks.push(Task::Text(" "));
+                }
+            }
+        }
+
+        output
+    }
+
+    fn write_simple_datum(&self, output: &mut String, simple_datum: &SimpleDatum) {
+        match simple_datum {
+            SimpleDatum::Boolean(boolean) => output.push_str(if boolean.value() { "#t" } else { "#f" }),
+            SimpleDatum::Number(number) => output.push_str(&self.src()[number.span().start()..number.span().end()]),
+            SimpleDatum::Character(character) => write_character(output, character.value()),
+            SimpleDatum::String(string) => {
+                output.push('"');
+                for char in string.value().chars() {
+                    write_escaped(output, char, '"');
+                }
+                output.push('"');
+            }
+            SimpleDatum::ByteVector(byte_vector) => {
+                let bytes = byte_vector.bytes().iter().map(|byte| byte.value().to_string()).collect::<Vec<_>>();
+                write!(output, "#u8({})", bytes.join(" ")).expect("writing to a string never fails");
+            }
+            SimpleDatum::Symbol(symbol) => {
+                if is_bare_symbol(symbol.name()) {
+                    output.push_str(symbol.name());
+                } else {
+                    output.push('|');
+                    for char in symbol.name().chars() {
+                        write_escaped(output, char, '|');
+                    }
+                    output.push('|');
+                }
+            }
+        }
+    }
+}
+
+fn write_character(output: &mut String, char: char) {
+    let name = match char {
+        '\u{7}' => "alarm",
+        '\u{8}' => "backspace",
+        '\u{7F}' => "delete",
+        '\u{1B}' => "escape",
+        '\n' => "newline",
+        '\0' => "null",
+        '\r' => "return",
+        ' ' => "space",
+        '\t' => "tab",
+        _ if char.is_control() || char.is_whitespace() => {
+            write!(output, "#\\x{:x}", char as u32).expect("writing to a string never fails");
+            return;
+        }
+        _ => {
+            output.push_str("#\\");
+            output.push(char);
+            return;
+        }
+    };
+
+    output.push_str("#\\");
+    output.push_str(name);
+}
+
+/// Escapes a string or vertical identifier element, `delimiter` being either `"` or `|`.
+fn write_escaped(output: &mut String, char: char, delimiter: char) {
+    match char {
+        '\\' => output.push_str("\\\\"),
+        '\u{7}' => output.push_str("\\a"),
+        '\u{8}' => output.push_str("\\b"),
+        '\t' => output.push_str("\\t"),
+        '\n' => output.push_str("\\n"),
+        '\r' => output.push_str("\\r"),
+        _ if char == delimiter => {
+            output.push('\\');
+            output.push(char);
+        }
+        _ if char.is_control() => write!(output, "\\x{:x};", char as u32).expect("writing to a string never fails"),
+        _ => output.push(char),
+    }
+}
+
+/// Whether `name` reads back as the same symbol without vertical lines
+fn is_bare_symbol(name: &str) -> bool {
+    let Ok(tokens) = Lexer::new(name).tokenize_all() else {
+        return false;
+    };
+
+    match tokens.as_slice() {
+        [TokenAll::Token(Token::Identifier(identifier))] => !matches!(identifier, Identifier::Vertical(_)) && identifier.name() == name,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(src: &str) -> DatumGraph<'_> {
+        DatumGraph::new(src, Reader::read_all(src).unwrap().remove(0))
+    }
+
+    #[test]
+    fn write_simple_data() {
+        let src = r#"(#t #false -1/2 #x1F #\a #\x7 #\space #\x85 "a\"b\\c\n\x1;" abc |a b| || |1+| |a\|b| #u8(#x10 2))"#;
+
+        assert_eq!(
+            Some(r#"(#t #f -1/2 #x1F #\a #\alarm #\space #\x85 "a\"b\\c\n\x1;" abc |a b| || |1+| |a\|b| #u8(16 2))"#.to_string()),
+            graph(src).write_simple()
+        );
+    }
+
+    #[test]
+    fn write_dotted_lists() {
+        assert_eq!("(a b c)", graph("(a . (b . (c . ())))").write());
+        assert_eq!("(a b . c)", graph("(a . (b . c))").write());
+        assert_eq!("#((a) ())", graph("#((a . ()) ())").write());
+    }
+
+    #[test]
+    fn write_labels_cycles_only() {
+        assert_eq!("#0=(a b . #0#)", graph("#0=(a b . #0#)").write());
+        assert_eq!("#0=#(1 #0#)", graph("#0=#(1 #0#)").write());
+        assert_eq!("((x) (x))", graph("(#0=(x) #0#)").write());
+    }
+
+    #[test]
+    fn write_shared_labels_shared_nodes() {
+        assert_eq!("(#0=(x) #0#)", graph("(#0=(x) #0#)").write_shared());
+        assert_eq!("#0=(#1=(a) #1# . #0#)", graph("#5=(#3=(a) #3# . #5#)").write_shared());
+        assert_eq!("(a a)", graph("(#0=a #0#)").write_shared());
+    }
+
+    #[test]
+    fn write_labeled_tail() {
+        assert_eq!("(a . #0=(b . #0#))", graph("(a . #0=(b . #0#))").write());
+    }
+
+    #[test]
+    fn write_simple_rejects_cycles() {
+        assert_eq!(None, graph("#0=(a . #0#)").write_simple());
+        assert_eq!(Some("((x) (x))".to_string()), graph("(#0=(x) #0#)").write_simple());
+    }
+
+    #[test]
+    fn references_to_references() {
+        assert_eq!("(#0=(a) #0# #0#)", graph("(#1=(a) #0=#1# #0#)").write_shared());
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let src = format!("{}{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert_eq!(src, graph(&src).write());
+    }
+}